@@ -1,20 +1,65 @@
+use std::collections::HashMap;
+use std::io::{Read as _, Write as _};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use anyhow::{Context, Result, bail};
 use clap::{Args, Subcommand};
 use pubky::PublicKey;
-use reqwest::{Client as HttpClient, Method, Url};
+use rand::Rng;
+use reqwest::{Client as HttpClient, Method, StatusCode, Url};
 use serde::Deserialize;
+use serde::Serialize;
 use serde::de::DeserializeOwned;
+use tracing::Instrument;
+
+/// Default admin URL when it's not set by `--admin-url`, the config file,
+/// or $PUBKY_ADMIN_URL.
+const DEFAULT_ADMIN_URL: &str = "http://127.0.0.1:6288";
+
+/// How `admin` commands should print their results, set globally via
+/// `pubky-cli --output`. `List`'s own `--json` flag predates this and keeps
+/// its distinct per-account NDJSON shape; this governs the one-shot results
+/// (`info`, `generate-token`, enable/disable, storage put/delete) that used
+/// to only ever print a human sentence.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
 
 #[derive(Args, Debug, Clone)]
 pub struct ConnectionArgs {
-    /// Base URL of the admin server (e.g. http://127.0.0.1:6288)
-    #[arg(long, default_value = "http://127.0.0.1:6288")]
-    pub admin_url: String,
-    /// Admin password; falls back to $PUBKY_ADMIN_PASSWORD or an interactive prompt.
-    #[arg(long, env = "PUBKY_ADMIN_PASSWORD")]
+    /// Base URL of the admin server (e.g. http://127.0.0.1:6288). Falls
+    /// back to $PUBKY_ADMIN_URL, then the config file's `admin-url`, then
+    /// http://127.0.0.1:6288.
+    #[arg(long)]
+    pub admin_url: Option<String>,
+    /// Admin password; falls back to $PUBKY_ADMIN_PASSWORD, then the config
+    /// file's `admin-password`, then an interactive prompt.
+    #[arg(long)]
     pub password: Option<String>,
+    /// Pin the admin server's TLS certificate to this SHA-256 fingerprint
+    /// (colon-separated hex) instead of the on-disk TOFU cache. Falls back
+    /// to $PUBKY_ADMIN_FINGERPRINT, then the config file's
+    /// `admin-fingerprint`. Only meaningful for `https://` admin URLs.
+    #[arg(long)]
+    pub fingerprint: Option<String>,
+    /// Give up on a single request after this many seconds.
+    #[arg(long, default_value_t = 120)]
+    pub timeout: u64,
+    /// How many times to retry a failed request before giving up (GETs
+    /// retry on connection errors, timeouts, and 5xx responses;
+    /// non-idempotent requests only retry on a pre-send connection error).
+    #[arg(long, default_value_t = 3)]
+    pub retries: u32,
+    /// Ignore any cached session ticket for this admin server and force a
+    /// fresh `--password` login.
+    #[arg(long)]
+    pub no_cache: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -29,6 +74,24 @@ pub enum Command {
         #[command(flatten)]
         connection: ConnectionArgs,
     },
+    /// Delete this admin server's cached session ticket, if any.
+    Logout {
+        #[command(flatten)]
+        connection: ConnectionArgs,
+    },
+    /// Fetch the same summary as `info`, but in Prometheus text exposition
+    /// format, for scraping into existing monitoring.
+    Metrics {
+        #[command(flatten)]
+        connection: ConnectionArgs,
+        /// Also POST the metrics to a Prometheus Pushgateway at this base
+        /// URL, e.g. http://127.0.0.1:9091
+        #[arg(long)]
+        push_gateway: Option<String>,
+        /// Pushgateway job label; only used with --push-gateway.
+        #[arg(long, default_value = "pubky_homeserver")]
+        job: String,
+    },
     /// Manage users (enable/disable).
     User {
         #[command(flatten)]
@@ -47,38 +110,102 @@ pub enum Command {
 
 #[derive(Subcommand, Debug)]
 pub enum UserCommand {
-    /// Disable a user by public key.
-    Disable { pubky: String },
-    /// Enable a user by public key.
-    Enable { pubky: String },
+    /// Disable a user by public key, or every key in `--from-file`.
+    Disable {
+        pubky: Option<String>,
+        /// Newline-separated public keys to disable; `-` reads from stdin.
+        /// Mutually exclusive with the positional `pubky`.
+        #[arg(long, conflicts_with = "pubky")]
+        from_file: Option<String>,
+    },
+    /// Enable a user by public key, or every key in `--from-file`.
+    Enable {
+        pubky: Option<String>,
+        /// Newline-separated public keys to enable; `-` reads from stdin.
+        /// Mutually exclusive with the positional `pubky`.
+        #[arg(long, conflicts_with = "pubky")]
+        from_file: Option<String>,
+    },
+    /// Page through registered accounts.
+    List {
+        /// Accounts per page.
+        #[arg(long, default_value_t = 100)]
+        limit: u32,
+        /// Opaque pagination cursor returned as `next-cursor:` by a
+        /// previous page; omit to start from the beginning.
+        #[arg(long)]
+        cursor: Option<String>,
+        /// Only list disabled accounts.
+        #[arg(long, conflicts_with = "enabled_only")]
+        disabled_only: bool,
+        /// Only list enabled accounts.
+        #[arg(long)]
+        enabled_only: bool,
+        /// Transparently follow `next-cursor` and print every account
+        /// instead of a single page.
+        #[arg(long)]
+        all: bool,
+        /// Print each account as JSON instead of plain text.
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
 pub enum StorageCommand {
+    /// List the immediate children of a WebDAV path (PROPFIND, Depth: 1).
+    List { pubky: String, path: String },
+    /// Download a WebDAV entry; writes to `--out` or, if omitted, stdout.
+    Get {
+        pubky: String,
+        path: String,
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Upload a local file to a WebDAV entry; path must start with /pub/
+    Put { pubky: String, path: String, file: PathBuf },
     /// Delete a WebDAV entry; path must start with /pub/
     Delete { pubky: String, path: String },
 }
 
-pub async fn run(command: Command) -> Result<()> {
+pub async fn run(command: Command, output: OutputFormat) -> Result<()> {
     match command {
         Command::Info { connection } => {
-            info(connection).await?;
+            info(connection, output).await?;
         }
         Command::GenerateToken { connection } => {
-            generate_signup_token(connection).await?;
+            generate_signup_token(connection, output).await?;
+        }
+        Command::Logout { connection } => {
+            logout(connection)?;
+        }
+        Command::Metrics { connection, push_gateway, job } => {
+            metrics(connection, push_gateway, job).await?;
         }
         Command::User { connection, action } => {
-            let client = connection.into_client()?;
+            let client = connection.into_client().await?;
             match action {
-                UserCommand::Disable { pubky } => disable_user(&client, &pubky).await?,
-                UserCommand::Enable { pubky } => enable_user(&client, &pubky).await?,
+                UserCommand::Disable { pubky, from_file } => {
+                    bulk_user_op(&client, pubky, from_file, output, UserAction::Disable).await?
+                }
+                UserCommand::Enable { pubky, from_file } => {
+                    bulk_user_op(&client, pubky, from_file, output, UserAction::Enable).await?
+                }
+                UserCommand::List { limit, cursor, disabled_only, enabled_only, all, json } => {
+                    list_users(&client, limit, cursor, disabled_only, enabled_only, all, json).await?
+                }
             }
         }
         Command::Storage { connection, action } => {
-            let client = connection.into_client()?;
+            let client = connection.into_client().await?;
             match action {
+                StorageCommand::List { pubky, path } => list_entries(&client, &pubky, &path, output).await?,
+                StorageCommand::Get { pubky, path, out } => get_entry(&client, &pubky, &path, out).await?,
+                StorageCommand::Put { pubky, path, file } => {
+                    put_entry(&client, &pubky, &path, &file, output).await?
+                }
                 StorageCommand::Delete { pubky, path } => {
-                    delete_entry(&client, &pubky, &path).await?
+                    delete_entry(&client, &pubky, &path, output).await?
                 }
             }
         }
@@ -88,23 +215,144 @@ pub async fn run(command: Command) -> Result<()> {
 }
 
 impl ConnectionArgs {
-    fn into_client(self) -> Result<AdminHttpClient> {
-        let password = match self.password {
-            Some(password) => password,
-            None => rpassword::prompt_password("Admin password (input hidden): ")?,
+    fn normalize_admin_url(&self, config: &crate::config::Config) -> Result<Url> {
+        let raw = self
+            .admin_url
+            .clone()
+            .or_else(|| config.admin_url())
+            .unwrap_or_else(|| DEFAULT_ADMIN_URL.to_string());
+
+        Url::parse(&raw)
+            .or_else(|_| Url::parse(&format!("http://{}", raw)))
+            .context("Failed to parse admin URL")
+    }
+
+    async fn into_client(self) -> Result<AdminHttpClient> {
+        let config = crate::config::effective_config()?;
+        let base_url = self.normalize_admin_url(&config)?;
+        let fingerprint = self.fingerprint.or_else(|| config.admin_fingerprint());
+        let password = self.password.or_else(|| config.admin_password());
+
+        let ticket = if self.no_cache {
+            None
+        } else {
+            cached_ticket(base_url.as_str())?.filter(|t| !t.is_expired())
         };
 
-        AdminHttpClient::new(&self.admin_url, password)
+        AdminHttpClient::new(
+            base_url,
+            password,
+            fingerprint.as_deref(),
+            Duration::from_secs(self.timeout),
+            self.retries,
+            self.no_cache,
+            ticket,
+        )
+        .await
     }
 }
 
+/// Deletes the cached session ticket for this admin server, if any, so the
+/// next command re-authenticates with `--password` from scratch.
+fn logout(connection: ConnectionArgs) -> Result<()> {
+    let config = crate::config::effective_config()?;
+    let base_url = connection.normalize_admin_url(&config)?;
+    forget_ticket(base_url.as_str())?;
+    println!("Logged out of {}", base_url);
+    Ok(())
+}
+
+/// `~/.local/share/pubky-cli/admin-tickets.json` — one short-lived bearer
+/// ticket per admin server, keyed by its normalized URL, so a scripted
+/// sequence of `admin` commands only has to send the password once.
+fn ticket_cache_path() -> PathBuf {
+    let base = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))
+        .unwrap_or_else(std::env::temp_dir);
+    base.join("pubky-cli").join("admin-tickets.json")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AdminTicket {
+    ticket: String,
+    expires_at: u64,
+}
+
+impl AdminTicket {
+    fn is_expired(&self) -> bool {
+        now_unix() >= self.expires_at
+    }
+}
+
+fn now_unix() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+type TicketCache = HashMap<String, AdminTicket>;
+
+fn load_ticket_cache() -> Result<TicketCache> {
+    let path = ticket_cache_path();
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse ticket cache: {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(TicketCache::new()),
+        Err(e) => Err(e).with_context(|| format!("Failed to read ticket cache: {}", path.display())),
+    }
+}
+
+fn save_ticket_cache(cache: &TicketCache) -> Result<()> {
+    let path = ticket_cache_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let json = serde_json::to_vec(cache).context("Failed to serialize ticket cache")?;
+    std::fs::write(&path, json).with_context(|| format!("Failed to write ticket cache: {}", path.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("Failed to set permissions on ticket cache: {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+fn cached_ticket(admin_url: &str) -> Result<Option<AdminTicket>> {
+    Ok(load_ticket_cache()?.get(admin_url).cloned())
+}
+
+fn remember_ticket(admin_url: &str, ticket: &AdminTicket) -> Result<()> {
+    let mut cache = load_ticket_cache()?;
+    cache.insert(admin_url.to_string(), ticket.clone());
+    save_ticket_cache(&cache)
+}
+
+fn forget_ticket(admin_url: &str) -> Result<()> {
+    let mut cache = load_ticket_cache()?;
+    if cache.remove(admin_url).is_some() {
+        save_ticket_cache(&cache)?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+struct LoginResponse {
+    ticket: String,
+    expires_in: u64,
+}
+
 struct AdminHttpClient {
     client: HttpClient,
     base_url: Url,
-    password: String,
+    password: Mutex<Option<String>>,
+    ticket: Mutex<Option<AdminTicket>>,
+    no_cache: bool,
+    retries: u32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 struct AdminInfoResponse {
     num_users: u64,
     num_disabled_users: u64,
@@ -113,17 +361,52 @@ struct AdminInfoResponse {
     num_unused_signup_codes: u64,
 }
 
+#[derive(Debug, Deserialize, serde::Serialize)]
+struct AdminUserSummary {
+    pubkey: String,
+    disabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdminUserListResponse {
+    users: Vec<AdminUserSummary>,
+    next_cursor: Option<String>,
+}
+
+/// Base delay for the exponential backoff in [`AdminHttpClient::request`];
+/// doubled per attempt and capped at [`MAX_BACKOFF`], with up to 50% jitter
+/// added so a fleet of CLIs retrying in lockstep doesn't hammer the
+/// homeserver in sync.
+const BASE_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_BACKOFF: Duration = Duration::from_secs(4);
+
 impl AdminHttpClient {
-    fn new(admin_url: &str, password: String) -> Result<Self> {
-        let base_url = Url::parse(admin_url)
-            .or_else(|_| Url::parse(&format!("http://{}", admin_url)))
-            .context("Failed to parse admin URL")?;
+    #[allow(clippy::too_many_arguments)]
+    async fn new(
+        base_url: Url,
+        password: Option<String>,
+        fingerprint: Option<&str>,
+        timeout: Duration,
+        retries: u32,
+        no_cache: bool,
+        ticket: Option<AdminTicket>,
+    ) -> Result<Self> {
+        let client = crate::tls_pin::build_http_client(base_url.as_str(), fingerprint, timeout).await?;
 
-        Ok(Self {
-            client: HttpClient::new(),
+        let this = Self {
+            client,
             base_url,
-            password,
-        })
+            password: Mutex::new(password),
+            ticket: Mutex::new(ticket),
+            no_cache,
+            retries,
+        };
+
+        if this.ticket.lock().unwrap().is_none() {
+            this.login().await?;
+        }
+
+        Ok(this)
     }
 
     fn endpoint(&self, path: &str) -> Result<Url> {
@@ -131,87 +414,506 @@ impl AdminHttpClient {
         Ok(self.base_url.join(trimmed)?)
     }
 
-    async fn request(&self, method: Method, path: &str) -> Result<reqwest::Response> {
-        let url = self.endpoint(path)?;
-        let response = self
+    /// Trades the admin password for a short-lived bearer ticket, prompting
+    /// for the password interactively if none was given yet. Called once
+    /// up front on a cache miss, and again on a 401 mid-session.
+    async fn login(&self) -> Result<()> {
+        let password = {
+            let mut guard = self.password.lock().unwrap();
+            if guard.is_none() {
+                *guard = Some(rpassword::prompt_password("Admin password (input hidden): ")?);
+            }
+            guard.clone().unwrap()
+        };
+
+        let response: LoginResponse = self
             .client
-            .request(method, url)
-            .header("X-Admin-Password", &self.password)
+            .post(self.endpoint("login")?)
+            .json(&serde_json::json!({ "password": password }))
             .send()
-            .await?;
+            .await
+            .context("Failed to reach admin login endpoint")?
+            .error_for_status()
+            .context("Admin login rejected")?
+            .json()
+            .await
+            .context("Failed to parse admin login response")?;
+
+        let ticket = AdminTicket {
+            ticket: response.ticket,
+            expires_at: now_unix().saturating_add(response.expires_in),
+        };
+
+        if !self.no_cache {
+            remember_ticket(self.base_url.as_str(), &ticket)?;
+        }
+        *self.ticket.lock().unwrap() = Some(ticket);
 
-        Ok(response.error_for_status()?)
+        Ok(())
+    }
+
+    /// Sends one request, retrying up to `self.retries` times with
+    /// exponential backoff and jitter. GETs (`idempotent = true`) retry on
+    /// connection errors, timeouts, and 5xx responses. Non-idempotent
+    /// requests (POST/DELETE) only retry on a pre-send connection error —
+    /// a timeout or 5xx there may mean the operation already took effect,
+    /// so retrying it silently could double-apply it. A 401 triggers one
+    /// transparent re-login (the cached ticket may have expired server-side
+    /// without us knowing) before falling back to the retry/backoff path.
+    async fn request(&self, method: Method, path: &str, idempotent: bool) -> Result<reqwest::Response> {
+        self.request_with(method, path, idempotent, &[], None).await
+    }
+
+    /// As [`Self::request`], plus extra headers and/or a request body, for
+    /// verbs that need more than bearer auth (`PROPFIND`'s `Depth` header,
+    /// `PUT`'s body). Wrapped in a span recording the target `admin_url`,
+    /// method/path, and — once the retry loop settles — the final
+    /// response status, attempt count, and latency, for OTLP export.
+    #[tracing::instrument(
+        skip(self, headers, body),
+        fields(
+            admin_url = %self.base_url,
+            %method,
+            path,
+            attempts = tracing::field::Empty,
+            status = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+        )
+    )]
+    async fn request_with(
+        &self,
+        method: Method,
+        path: &str,
+        idempotent: bool,
+        headers: &[(&str, &str)],
+        body: Option<Vec<u8>>,
+    ) -> Result<reqwest::Response> {
+        let started_at = std::time::Instant::now();
+        let url = self.endpoint(path)?;
+        let mut attempt = 0u32;
+        let mut relogged_in = false;
+
+        let outcome = loop {
+            let ticket = self
+                .ticket
+                .lock()
+                .unwrap()
+                .clone()
+                .context("No admin session ticket; this is a bug")?;
+
+            let mut builder = self
+                .client
+                .request(method.clone(), url.clone())
+                .bearer_auth(&ticket.ticket);
+            for (name, value) in headers {
+                builder = builder.header(*name, *value);
+            }
+            if let Some(body) = &body {
+                builder = builder.body(body.clone());
+            }
+
+            let result = builder
+                .send()
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|response| Ok(response.error_for_status()?));
+
+            if !relogged_in && is_unauthorized(&result) {
+                relogged_in = true;
+                self.login().await?;
+                continue;
+            }
+
+            let retryable = match &result {
+                Ok(_) => false,
+                Err(e) => is_retryable(e, idempotent),
+            };
+
+            if !retryable || attempt >= self.retries {
+                break result;
+            }
+
+            attempt += 1;
+            tokio::time::sleep(backoff_with_jitter(attempt)).await;
+        };
+
+        let span = tracing::Span::current();
+        span.record("attempts", attempt + 1);
+        span.record("latency_ms", started_at.elapsed().as_millis() as u64);
+        if let Ok(response) = &outcome {
+            span.record("status", response.status().as_u16());
+        }
+
+        outcome.with_context(|| format!("{method} {path} failed after {} attempt(s)", attempt + 1))
     }
 
     async fn get_json<T>(&self, path: &str) -> Result<T>
     where
         T: DeserializeOwned,
     {
-        Ok(self.request(Method::GET, path).await?.json().await?)
+        Ok(self.request(Method::GET, path, true).await?.json().await?)
     }
 
     async fn get_text(&self, path: &str) -> Result<String> {
-        Ok(self.request(Method::GET, path).await?.text().await?)
+        Ok(self.request(Method::GET, path, true).await?.text().await?)
     }
 
     async fn post_empty(&self, path: &str) -> Result<()> {
-        self.request(Method::POST, path).await?;
+        self.request(Method::POST, path, false).await?;
         Ok(())
     }
 
     async fn delete(&self, path: &str) -> Result<()> {
-        self.request(Method::DELETE, path).await?;
+        self.request(Method::DELETE, path, false).await?;
+        Ok(())
+    }
+
+    /// Issues a `PROPFIND` (`Depth: 1`) against `path` and returns the raw
+    /// multistatus XML body for [`parse_multistatus`] to pick apart.
+    async fn propfind(&self, path: &str) -> Result<String> {
+        let method = Method::from_bytes(b"PROPFIND").expect("PROPFIND is a valid HTTP method token");
+        Ok(self
+            .request_with(method, path, true, &[("Depth", "1")], None)
+            .await?
+            .text()
+            .await?)
+    }
+
+    /// Returns the raw streaming response for `path` so the caller can
+    /// forward it chunk-by-chunk instead of buffering the whole body.
+    async fn get_stream(&self, path: &str) -> Result<reqwest::Response> {
+        self.request(Method::GET, path, true).await
+    }
+
+    /// `PUT`s `body` to `path`. Idempotent (re-uploading the same bytes has
+    /// the same effect), so it's safe to retry under the normal policy.
+    async fn put_bytes(&self, path: &str, body: Vec<u8>) -> Result<()> {
+        self.request_with(Method::PUT, path, true, &[], Some(body)).await?;
+        Ok(())
+    }
+}
+
+/// Whether `result` failed with a 401, meaning the bearer ticket was
+/// rejected (expired or revoked server-side) and `request` should re-login
+/// once before applying the normal retry/backoff policy.
+fn is_unauthorized(result: &Result<reqwest::Response>) -> bool {
+    match result {
+        Err(e) => e.downcast_ref::<reqwest::Error>().and_then(|e| e.status()) == Some(StatusCode::UNAUTHORIZED),
+        Ok(_) => false,
+    }
+}
+
+/// Whether an error from `request` is worth retrying. `idempotent` gates
+/// timeouts and 5xx: those are only safely retryable when the call has no
+/// side effect to double-apply.
+fn is_retryable(error: &anyhow::Error, idempotent: bool) -> bool {
+    let Some(reqwest_err) = error.downcast_ref::<reqwest::Error>() else {
+        return false;
+    };
+
+    if reqwest_err.is_connect() {
+        return true;
+    }
+    if !idempotent {
+        return false;
+    }
+
+    reqwest_err.is_timeout() || reqwest_err.status().is_some_and(|status| status.is_server_error())
+}
+
+fn backoff_with_jitter(attempt: u32) -> Duration {
+    let exponential = BASE_BACKOFF.saturating_mul(1u32 << attempt.min(8)).min(MAX_BACKOFF);
+    let jitter_fraction: f64 = rand::rngs::OsRng.gen_range(0.0..0.5);
+    exponential.mul_f64(1.0 + jitter_fraction)
+}
+
+async fn info(connection: ConnectionArgs, output: OutputFormat) -> Result<()> {
+    let client = connection.into_client().await?;
+    let span = tracing::info_span!("admin.info", admin_url = %client.base_url);
+
+    async {
+        let info: AdminInfoResponse = client.get_json("info").await?;
+
+        match output {
+            OutputFormat::Json => println!("{}", serde_json::to_string(&info)?),
+            OutputFormat::Text => {
+                println!("Users: {}", info.num_users);
+                println!("Disabled users: {}", info.num_disabled_users);
+                println!("Disk usage (MB): {:.2}", info.total_disk_used_mb);
+                println!("Signup codes: {}", info.num_signup_codes);
+                println!("Unused signup codes: {}", info.num_unused_signup_codes);
+            }
+        }
+
         Ok(())
     }
+    .instrument(span)
+    .await
 }
 
-async fn info(connection: ConnectionArgs) -> Result<()> {
-    let client = connection.into_client()?;
+/// Renders `info` in Prometheus text exposition format: one `# HELP`/`#
+/// TYPE` pair and one gauge line per counter in the `/info` JSON (users,
+/// disabled users, signup codes, unused signup codes). `total_disk_used_mb`
+/// is not exported.
+fn render_prometheus(info: &AdminInfoResponse) -> String {
+    let mut out = String::new();
+    let gauge = |out: &mut String, name: &str, help: &str, value: f64| {
+        out.push_str(&format!("# HELP {name} {help}\n"));
+        out.push_str(&format!("# TYPE {name} gauge\n"));
+        out.push_str(&format!("{name} {value}\n"));
+    };
+
+    gauge(&mut out, "pubky_users_total", "Total registered users", info.num_users as f64);
+    gauge(
+        &mut out,
+        "pubky_disabled_users_total",
+        "Users currently disabled by an admin",
+        info.num_disabled_users as f64,
+    );
+    gauge(
+        &mut out,
+        "pubky_signup_codes_total",
+        "Signup codes ever generated",
+        info.num_signup_codes as f64,
+    );
+    gauge(
+        &mut out,
+        "pubky_unused_signup_codes_total",
+        "Signup codes generated but not yet redeemed",
+        info.num_unused_signup_codes as f64,
+    );
+
+    out
+}
+
+async fn metrics(connection: ConnectionArgs, push_gateway: Option<String>, job: String) -> Result<()> {
+    let client = connection.into_client().await?;
     let info: AdminInfoResponse = client.get_json("info").await?;
+    let exposition = render_prometheus(&info);
 
-    println!("Users: {}", info.num_users);
-    println!("Disabled users: {}", info.num_disabled_users);
-    println!("Disk usage (MB): {:.2}", info.total_disk_used_mb);
-    println!("Signup codes: {}", info.num_signup_codes);
-    println!("Unused signup codes: {}", info.num_unused_signup_codes);
+    print!("{}", exposition);
+
+    if let Some(push_gateway) = push_gateway {
+        let base = Url::parse(&push_gateway)
+            .or_else(|_| Url::parse(&format!("http://{}", push_gateway)))
+            .context("Failed to parse --push-gateway URL")?;
+        let endpoint = base
+            .join(&format!("metrics/job/{}", job))
+            .context("Failed to build Pushgateway endpoint")?;
+
+        HttpClient::new()
+            .post(endpoint)
+            .header("Content-Type", "text/plain; version=0.0.4")
+            .body(exposition)
+            .send()
+            .await?
+            .error_for_status()
+            .context("Pushgateway rejected the metrics push")?;
+
+        println!("Pushed metrics to {} (job={})", push_gateway, job);
+    }
 
     Ok(())
 }
 
-async fn generate_signup_token(connection: ConnectionArgs) -> Result<()> {
-    let client = connection.into_client()?;
-    let token = client.get_text("generate_signup_token").await?;
+async fn generate_signup_token(connection: ConnectionArgs, output: OutputFormat) -> Result<()> {
+    let client = connection.into_client().await?;
+    let span = tracing::info_span!("admin.generate_signup_token", admin_url = %client.base_url);
 
-    println!("{}", token);
+    async {
+        let token = client.get_text("generate_signup_token").await?;
+        match output {
+            OutputFormat::Json => println!("{}", serde_json::to_string(&serde_json::json!({ "token": token }))?),
+            OutputFormat::Text => println!("{}", token),
+        }
+        Ok(())
+    }
+    .instrument(span)
+    .await
+}
 
-    Ok(())
+#[derive(Debug, Clone, Copy)]
+enum UserAction {
+    Disable,
+    Enable,
 }
 
-async fn disable_user(client: &AdminHttpClient, pubky: &str) -> Result<()> {
+impl UserAction {
+    /// The `users/<pubkey>/<verb>` path segment this action POSTs to.
+    fn verb(self) -> &'static str {
+        match self {
+            UserAction::Disable => "disable",
+            UserAction::Enable => "enable",
+        }
+    }
+}
+
+#[tracing::instrument(skip(client), fields(admin_url = %client.base_url, %pubky))]
+async fn set_user_enabled(client: &AdminHttpClient, pubky: &str, action: UserAction) -> Result<()> {
     let public_key = PublicKey::from_str(pubky)?;
     client
-        .post_empty(&format!("users/{}/disable", public_key))
+        .post_empty(&format!("users/{}/{}", public_key, action.verb()))
         .await?;
+    Ok(())
+}
 
-    println!("Disabled user {}", public_key);
+/// Per-key outcome of a bulk `admin user disable`/`enable`, printed as a
+/// JSON array in `--output json` or summarized as text lines.
+#[derive(Debug, Serialize)]
+struct UserOpResult {
+    pubkey: String,
+    success: bool,
+    error: Option<String>,
+}
 
-    Ok(())
+/// Resolves `pubky`/`from_file` (clap's `conflicts_with` guarantees at most
+/// one is set) to the list of keys to act on. `from_file` of `-` reads from
+/// stdin instead of a file.
+fn resolve_keys(pubky: Option<String>, from_file: Option<String>) -> Result<Vec<String>> {
+    match (pubky, from_file) {
+        (Some(key), None) => Ok(vec![key]),
+        (None, Some(path)) => {
+            let contents = if path == "-" {
+                let mut buf = String::new();
+                std::io::stdin().read_to_string(&mut buf).context("Failed to read stdin")?;
+                buf
+            } else {
+                std::fs::read_to_string(&path).with_context(|| format!("Failed to read {path}"))?
+            };
+
+            Ok(contents.lines().map(str::trim).filter(|line| !line.is_empty()).map(str::to_string).collect())
+        }
+        (None, None) => bail!("Provide a pubky or --from-file"),
+        (Some(_), Some(_)) => unreachable!("clap's conflicts_with prevents both pubky and --from-file"),
+    }
 }
 
-async fn enable_user(client: &AdminHttpClient, pubky: &str) -> Result<()> {
-    let public_key = PublicKey::from_str(pubky)?;
-    client
-        .post_empty(&format!("users/{}/enable", public_key))
-        .await?;
+/// Runs `action` against every key from `pubky`/`from_file` (see
+/// [`resolve_keys`]), via the retry-aware client, continuing past a
+/// per-key failure instead of aborting so one bad key in a batch doesn't
+/// block the rest. Returns an error (after reporting every result) if any
+/// key failed, so scripted callers see a non-zero exit.
+async fn bulk_user_op(
+    client: &AdminHttpClient,
+    pubky: Option<String>,
+    from_file: Option<String>,
+    output: OutputFormat,
+    action: UserAction,
+) -> Result<()> {
+    let keys = resolve_keys(pubky, from_file)?;
 
-    println!("Enabled user {}", public_key);
+    let mut results = Vec::with_capacity(keys.len());
+    for key in keys {
+        let outcome = set_user_enabled(client, &key, action).await;
+        let success = outcome.is_ok();
+        let error = outcome.err().map(|e| format!("{e:#}"));
+        results.push(UserOpResult { pubkey: key, success, error });
+    }
+
+    let failed = results.iter().filter(|r| !r.success).count();
+
+    match output {
+        OutputFormat::Json => println!("{}", serde_json::to_string(&results)?),
+        OutputFormat::Text => {
+            for result in &results {
+                match &result.error {
+                    None => println!("{}: {}d", result.pubkey, action.verb()),
+                    Some(error) => println!("{}: FAILED to {} ({error})", result.pubkey, action.verb()),
+                }
+            }
+            if results.len() > 1 {
+                println!("{}/{} succeeded", results.len() - failed, results.len());
+            }
+        }
+    }
+
+    if failed > 0 {
+        bail!("{failed} of {} key(s) failed to {}", results.len(), action.verb());
+    }
 
     Ok(())
 }
 
-async fn delete_entry(client: &AdminHttpClient, pubky: &str, path: &str) -> Result<()> {
-    let public_key = PublicKey::from_str(pubky)?;
+/// Fetches one page of `admin user list`, honoring `--disabled-only`/
+/// `--enabled-only`, and returns it along with the cursor for the next
+/// page (if any).
+async fn fetch_user_page(
+    client: &AdminHttpClient,
+    limit: u32,
+    cursor: Option<&str>,
+    disabled_only: bool,
+    enabled_only: bool,
+) -> Result<AdminUserListResponse> {
+    let mut path = format!("users?limit={limit}");
+    if let Some(cursor) = cursor {
+        path.push_str(&format!("&cursor={}", urlencoding_component(cursor)));
+    }
+    if disabled_only {
+        path.push_str("&disabled=true");
+    } else if enabled_only {
+        path.push_str("&disabled=false");
+    }
+
+    client.get_json(&path).await
+}
+
+/// Minimal percent-encoding for a cursor token in a query string; cursors
+/// are opaque so this only needs to cover the characters that would
+/// otherwise break query-string parsing.
+fn urlencoding_component(raw: &str) -> String {
+    raw.chars()
+        .map(|c| match c {
+            'A'..='Z' | 'a'..='z' | '0'..='9' | '-' | '_' | '.' | '~' => c.to_string(),
+            _ => c.encode_utf8(&mut [0u8; 4]).bytes().map(|b| format!("%{:02X}", b)).collect(),
+        })
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn list_users(
+    client: &AdminHttpClient,
+    limit: u32,
+    cursor: Option<String>,
+    disabled_only: bool,
+    enabled_only: bool,
+    all: bool,
+    json: bool,
+) -> Result<()> {
+    let mut cursor = cursor;
+    let mut printed = 0usize;
+
+    loop {
+        let page = fetch_user_page(client, limit, cursor.as_deref(), disabled_only, enabled_only).await?;
 
+        for user in &page.users {
+            if json {
+                println!("{}", serde_json::to_string(user)?);
+            } else {
+                println!("{} ({})", user.pubkey, if user.disabled { "disabled" } else { "enabled" });
+            }
+        }
+        printed += page.users.len();
+
+        match page.next_cursor {
+            Some(next) if all => cursor = Some(next),
+            Some(next) => {
+                println!("next-cursor: {next}");
+                break;
+            }
+            None => break,
+        }
+    }
+
+    if all {
+        println!("Listed {} account(s).", printed);
+    }
+
+    Ok(())
+}
+
+/// Normalizes `path` to start with `/` and rejects anything outside
+/// `/pub/`, shared by every `admin storage` subcommand.
+fn normalize_and_validate_path(path: &str) -> Result<String> {
     let normalized_path = if path.starts_with('/') {
         path.to_string()
     } else {
@@ -222,10 +924,256 @@ async fn delete_entry(client: &AdminHttpClient, pubky: &str, path: &str) -> Resu
         bail!("entry path must start with /pub/");
     }
 
+    Ok(normalized_path)
+}
+
+#[tracing::instrument(skip(client), fields(admin_url = %client.base_url, %pubky, %path))]
+async fn delete_entry(client: &AdminHttpClient, pubky: &str, path: &str, output: OutputFormat) -> Result<()> {
+    let public_key = PublicKey::from_str(pubky)?;
+    let normalized_path = normalize_and_validate_path(path)?;
+
     let endpoint = format!("webdav/{}{}", public_key, normalized_path);
     client.delete(&endpoint).await?;
 
-    println!("Deleted entry {}{}", public_key, normalized_path);
+    match output {
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string(&serde_json::json!({
+                "pubkey": public_key.to_string(),
+                "path": normalized_path,
+                "deleted": true,
+            }))?
+        ),
+        OutputFormat::Text => println!("Deleted entry {}{}", public_key, normalized_path),
+    }
 
     Ok(())
 }
+
+/// A WebDAV child as printed by `admin storage list --output json`.
+#[derive(Serialize)]
+struct JsonWebdavEntry<'a> {
+    name: &'a str,
+    size: Option<u64>,
+    last_modified: Option<&'a str>,
+    is_collection: bool,
+}
+
+async fn list_entries(client: &AdminHttpClient, pubky: &str, path: &str, output: OutputFormat) -> Result<()> {
+    let public_key = PublicKey::from_str(pubky)?;
+    let normalized_path = normalize_and_validate_path(path)?;
+
+    let endpoint = format!("webdav/{}{}", public_key, normalized_path);
+    let xml = client.propfind(&endpoint).await?;
+    let entries = parse_multistatus(&xml);
+    let requested = endpoint.trim_matches('/');
+
+    let children: Vec<_> = entries.iter().filter(|entry| entry.href.trim_matches('/') != requested).collect();
+
+    match output {
+        OutputFormat::Json => {
+            let json_entries: Vec<_> = children
+                .iter()
+                .map(|entry| JsonWebdavEntry {
+                    name: &entry.name,
+                    size: entry.size,
+                    last_modified: entry.last_modified.as_deref(),
+                    is_collection: entry.is_collection,
+                })
+                .collect();
+            println!("{}", serde_json::to_string(&json_entries)?);
+        }
+        OutputFormat::Text => {
+            println!("{:<40} {:>12} {:<30}", "NAME", "SIZE", "LAST-MODIFIED");
+            for entry in children {
+                let name = if entry.is_collection { format!("{}/", entry.name) } else { entry.name.clone() };
+                println!(
+                    "{:<40} {:>12} {:<30}",
+                    name,
+                    entry.size.map(|s| s.to_string()).unwrap_or_default(),
+                    entry.last_modified.clone().unwrap_or_default(),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn get_entry(client: &AdminHttpClient, pubky: &str, path: &str, out: Option<PathBuf>) -> Result<()> {
+    let public_key = PublicKey::from_str(pubky)?;
+    let normalized_path = normalize_and_validate_path(path)?;
+
+    let endpoint = format!("webdav/{}{}", public_key, normalized_path);
+    let mut response = client.get_stream(&endpoint).await?;
+
+    let mut sink: Box<dyn std::io::Write> = match &out {
+        Some(out_path) => Box::new(
+            std::fs::File::create(out_path)
+                .with_context(|| format!("Failed to create {}", out_path.display()))?,
+        ),
+        None => Box::new(std::io::stdout()),
+    };
+
+    let mut written = 0u64;
+    while let Some(chunk) = response.chunk().await? {
+        sink.write_all(&chunk)?;
+        written += chunk.len() as u64;
+    }
+    sink.flush()?;
+
+    if let Some(out_path) = &out {
+        eprintln!("Wrote {} byte(s) to {}", written, out_path.display());
+    }
+
+    Ok(())
+}
+
+async fn put_entry(
+    client: &AdminHttpClient,
+    pubky: &str,
+    path: &str,
+    file: &Path,
+    output: OutputFormat,
+) -> Result<()> {
+    let public_key = PublicKey::from_str(pubky)?;
+    let normalized_path = normalize_and_validate_path(path)?;
+
+    let endpoint = format!("webdav/{}{}", public_key, normalized_path);
+    let data = std::fs::read(file).with_context(|| format!("Failed to read {}", file.display()))?;
+    let len = data.len();
+
+    client.put_bytes(&endpoint, data).await?;
+
+    match output {
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string(&serde_json::json!({
+                "pubkey": public_key.to_string(),
+                "path": normalized_path,
+                "bytes_written": len,
+            }))?
+        ),
+        OutputFormat::Text => println!("Uploaded {} byte(s) to {}{}", len, public_key, normalized_path),
+    }
+
+    Ok(())
+}
+
+/// One child of a PROPFIND'd WebDAV collection.
+struct WebdavEntry {
+    href: String,
+    name: String,
+    size: Option<u64>,
+    last_modified: Option<String>,
+    is_collection: bool,
+}
+
+/// Picks `<response>` entries and their `href`/`getcontentlength`/
+/// `getlastmodified`/`resourcetype` out of a WebDAV multistatus body.
+/// Deliberately not a general XML parser (no namespaces, no nesting beyond
+/// one level of `<response>`) — WebDAV PROPFIND replies are flat enough
+/// that a small tag scanner is simpler than pulling in an XML crate for
+/// this one call site.
+fn parse_multistatus(xml: &str) -> Vec<WebdavEntry> {
+    extract_elements(xml, "response")
+        .into_iter()
+        .filter_map(|block| {
+            let href = extract_elements(block, "href").into_iter().next()?;
+            let href = percent_decode_component(href.trim());
+            let size = extract_elements(block, "getcontentlength")
+                .into_iter()
+                .next()
+                .and_then(|s| s.trim().parse::<u64>().ok());
+            let last_modified = extract_elements(block, "getlastmodified")
+                .into_iter()
+                .next()
+                .map(|s| s.trim().to_string());
+            let is_collection = extract_elements(block, "resourcetype")
+                .into_iter()
+                .next()
+                .is_some_and(|s| s.to_lowercase().contains("collection"));
+            let name = href.trim_end_matches('/').rsplit('/').next().unwrap_or("").to_string();
+
+            Some(WebdavEntry { href, name, size, last_modified, is_collection })
+        })
+        .collect()
+}
+
+/// One open or close tag found by [`next_tag`], with its namespace prefix
+/// (e.g. `d:` in `<d:response>`) already stripped off.
+struct XmlTag<'a> {
+    local_name: &'a str,
+    is_closing: bool,
+    self_closing: bool,
+    start: usize,
+    end: usize,
+}
+
+fn next_tag(xml: &str, from: usize) -> Option<XmlTag<'_>> {
+    let start = xml[from..].find('<')? + from;
+    let end = xml[start..].find('>')? + start;
+    let inner = &xml[start + 1..end];
+    let is_closing = inner.starts_with('/');
+    let self_closing = inner.ends_with('/');
+    let name_part = inner.trim_start_matches('/').trim_end_matches('/');
+    let name_end = name_part.find(|c: char| c.is_whitespace()).unwrap_or(name_part.len());
+    let full_name = &name_part[..name_end];
+    let local_name = full_name.rsplit(':').next().unwrap_or(full_name);
+
+    Some(XmlTag { local_name, is_closing, self_closing, start, end })
+}
+
+/// Returns the inner content of every (non-nested) element whose local
+/// name is `local_name`, in document order.
+fn extract_elements<'a>(xml: &'a str, local_name: &str) -> Vec<&'a str> {
+    let mut out = Vec::new();
+    let mut pos = 0;
+
+    while let Some(tag) = next_tag(xml, pos) {
+        if tag.is_closing || tag.local_name != local_name {
+            pos = tag.end + 1;
+            continue;
+        }
+        if tag.self_closing {
+            out.push("");
+            pos = tag.end + 1;
+            continue;
+        }
+
+        let content_start = tag.end + 1;
+        let mut search_pos = content_start;
+        let mut content_end = xml.len();
+        pos = xml.len();
+        while let Some(closing) = next_tag(xml, search_pos) {
+            if closing.is_closing && closing.local_name == local_name {
+                content_end = closing.start;
+                pos = closing.end + 1;
+                break;
+            }
+            search_pos = closing.end + 1;
+        }
+        out.push(&xml[content_start..content_end]);
+    }
+
+    out
+}
+
+/// Reverses [`urlencoding_component`]'s `%XX` escaping.
+fn percent_decode_component(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Ok(byte) = u8::from_str_radix(&raw[i + 1..i + 3], 16) {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
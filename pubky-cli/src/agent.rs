@@ -0,0 +1,327 @@
+//! Background session agent: a local process that holds resumed sessions
+//! in memory behind a Unix socket, so `user get/publish/delete/session`
+//! can reuse an already-unlocked identity across many invocations instead
+//! of repeating the recovery-file handshake every time. `user list` reads
+//! public storage directly and does not use the agent. Modeled on an
+//! ssh-agent/distant-manager style daemon.
+//!
+//! The agent never receives a recovery file, passphrase, or raw keypair —
+//! `agent add` signs in locally (in the calling process, via the usual
+//! recovery-file prompt) and hands the agent only the resulting session's
+//! pubkey and cookie, which it resumes via `Pubky::resume_session`. Holding
+//! only resumable session cookies (rather than signing keys) keeps the
+//! daemon's blast radius to "can act as an already-open session", not
+//! "can derive the identity's private key".
+//!
+//! Every request/response is a single newline-delimited JSON value.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use pubky::PublicKey;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+
+use crate::util::{build_pubky, build_signer, load_keypair_from_recovery_file, runtime_dir};
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Start the agent in the foreground, listening on the session socket.
+    Start,
+    /// Ask a running agent to drop every held session and shut down.
+    Stop,
+    /// Sign in with a recovery file and hand the resulting session to a
+    /// running agent, so later `user` commands can reuse it.
+    Add {
+        /// Path to the user's recovery file.
+        recovery_file: PathBuf,
+        /// Use the public network (default) or local testnet configuration.
+        #[arg(long)]
+        testnet: bool,
+    },
+    /// List the pubkeys currently held by a running agent.
+    List,
+    /// Drop one held identity from a running agent, without stopping it.
+    Lock {
+        /// Pubky to drop.
+        pubkey: String,
+    },
+}
+
+pub async fn run(command: Command) -> Result<()> {
+    match command {
+        Command::Start => start().await?,
+        Command::Stop => print_reply(send_request(AgentRequest::Stop).await?),
+        Command::Add { recovery_file, testnet } => add(recovery_file, testnet).await?,
+        Command::List => print_reply(send_request(AgentRequest::ListIdentities).await?),
+        Command::Lock { pubkey } => print_reply(send_request(AgentRequest::Lock { pubkey }).await?),
+    }
+
+    Ok(())
+}
+
+/// `$XDG_RUNTIME_DIR/pubky/agent.sock`.
+fn socket_path() -> PathBuf {
+    runtime_dir().join("agent.sock")
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) enum AgentRequest {
+    /// Hold a session this process already signed in to, so later requests
+    /// for `pubkey` can reuse it.
+    AddSession { pubkey: String, cookie: String, testnet: bool },
+    ListIdentities,
+    Lock { pubkey: String },
+    Stop,
+    Get { pubkey: String, path: String },
+    Publish { pubkey: String, path: String, data: Vec<u8> },
+    List { pubkey: String, path: String },
+    Delete { pubkey: String, path: String },
+    SessionInfo { pubkey: String },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) enum AgentResponse {
+    Ok,
+    Identities(Vec<String>),
+    Data(Vec<u8>),
+    Entries(Vec<String>),
+    Info(String),
+    Error(String),
+}
+
+async fn start() -> Result<()> {
+    let path = socket_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create agent socket directory: {}", parent.display()))?;
+    }
+    // A socket left behind by a crashed agent would otherwise refuse to
+    // rebind; a live agent would already have been reached via `Stop`.
+    let _ = std::fs::remove_file(&path);
+
+    let listener = UnixListener::bind(&path)
+        .with_context(|| format!("Failed to bind agent socket: {}", path.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("Failed to set permissions on agent socket: {}", path.display()))?;
+    }
+
+    println!("pubky-cli agent listening on {}", path.display());
+
+    // Handled on a single sequential loop rather than one task per
+    // connection: a local session-caching agent is low-throughput by
+    // nature, and this keeps the held sessions in a plain `HashMap`
+    // instead of behind an `Arc<Mutex<_>>`.
+    let mut sessions: HashMap<String, pubky::PubkySession> = HashMap::new();
+
+    let config_path = crate::config::resolve_path();
+    let mut config = crate::config::Config::load(&config_path)?;
+    let mut config_mtime = std::fs::metadata(&config_path).ok().and_then(|m| m.modified().ok());
+
+    loop {
+        if let Some((reloaded, mtime)) = crate::config::reload_if_changed(&config_path, config_mtime)? {
+            let changes = crate::config::describe_changes(&config, &reloaded);
+            if !changes.is_empty() {
+                println!("Config reloaded: {}", changes.join(", "));
+            }
+            config = reloaded;
+            config_mtime = Some(mtime);
+        }
+
+        let (stream, _) = listener.accept().await.context("Failed to accept agent connection")?;
+        match handle_connection(stream, &mut sessions).await {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => eprintln!("Agent connection error: {e}"),
+        }
+    }
+
+    // Drop every held session before the process exits. This frees the
+    // sessions but does not overwrite their memory: the cookies are not
+    // zeroized.
+    for (_, session) in sessions.drain() {
+        drop(session);
+    }
+    let _ = std::fs::remove_file(&path);
+    println!("Agent stopped.");
+
+    Ok(())
+}
+
+async fn handle_connection(stream: UnixStream, sessions: &mut HashMap<String, pubky::PubkySession>) -> Result<bool> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    let mut line = String::new();
+    reader.read_line(&mut line).await.context("Failed to read agent request")?;
+    if line.trim().is_empty() {
+        return Ok(true);
+    }
+
+    let request: AgentRequest =
+        serde_json::from_str(line.trim()).context("Failed to parse agent request")?;
+    let (response, keep_running) = dispatch(request, sessions).await;
+
+    let mut payload = serde_json::to_vec(&response).context("Failed to serialize agent response")?;
+    payload.push(b'\n');
+    writer.write_all(&payload).await.context("Failed to write agent response")?;
+
+    Ok(keep_running)
+}
+
+async fn dispatch(
+    request: AgentRequest,
+    sessions: &mut HashMap<String, pubky::PubkySession>,
+) -> (AgentResponse, bool) {
+    match request {
+        AgentRequest::AddSession { pubkey, cookie, testnet } => match resume(&pubkey, &cookie, testnet).await {
+            Ok(session) => {
+                sessions.insert(pubkey, session);
+                (AgentResponse::Ok, true)
+            }
+            Err(e) => (AgentResponse::Error(e.to_string()), true),
+        },
+        AgentRequest::ListIdentities => {
+            (AgentResponse::Identities(sessions.keys().cloned().collect()), true)
+        }
+        AgentRequest::Lock { pubkey } => {
+            sessions.remove(&pubkey);
+            (AgentResponse::Ok, true)
+        }
+        AgentRequest::Stop => (AgentResponse::Ok, false),
+        AgentRequest::Get { pubkey, path } => match sessions.get(&pubkey) {
+            Some(session) => match session.storage().get(path).await {
+                Ok(response) => match response.bytes().await {
+                    Ok(bytes) => (AgentResponse::Data(bytes.to_vec()), true),
+                    Err(e) => (AgentResponse::Error(e.to_string()), true),
+                },
+                Err(e) => (AgentResponse::Error(e.to_string()), true),
+            },
+            None => (no_held_session(&pubkey), true),
+        },
+        AgentRequest::Publish { pubkey, path, data } => match sessions.get(&pubkey) {
+            Some(session) => match session.storage().put(path, reqwest::Body::from(data)).await {
+                Ok(_) => (AgentResponse::Ok, true),
+                Err(e) => (AgentResponse::Error(e.to_string()), true),
+            },
+            None => (no_held_session(&pubkey), true),
+        },
+        AgentRequest::Delete { pubkey, path } => match sessions.get(&pubkey) {
+            Some(session) => match session.storage().delete(path).await {
+                Ok(_) => (AgentResponse::Ok, true),
+                Err(e) => (AgentResponse::Error(e.to_string()), true),
+            },
+            None => (no_held_session(&pubkey), true),
+        },
+        AgentRequest::List { pubkey, path } => match sessions.get(&pubkey) {
+            Some(session) => match path.parse::<pubky::PubkyResource>() {
+                Ok(resource) => match session.storage().list(resource) {
+                    Ok(builder) => match builder.send().await {
+                        Ok(entries) => (
+                            AgentResponse::Entries(entries.iter().map(|e| e.to_pubky_url()).collect()),
+                            true,
+                        ),
+                        Err(e) => (AgentResponse::Error(e.to_string()), true),
+                    },
+                    Err(e) => (AgentResponse::Error(e.to_string()), true),
+                },
+                Err(_) => (AgentResponse::Error(format!("Invalid list path: {}", path)), true),
+            },
+            None => (no_held_session(&pubkey), true),
+        },
+        AgentRequest::SessionInfo { pubkey } => match sessions.get(&pubkey) {
+            Some(session) => (AgentResponse::Info(format!("{:#?}", session.info())), true),
+            None => (no_held_session(&pubkey), true),
+        },
+    }
+}
+
+fn no_held_session(pubkey: &str) -> AgentResponse {
+    AgentResponse::Error(format!("No held session for {}", pubkey))
+}
+
+async fn resume(pubkey: &str, cookie: &str, testnet: bool) -> Result<pubky::PubkySession> {
+    let public_key = PublicKey::from_str(pubkey).with_context(|| format!("Invalid pubkey: {}", pubkey))?;
+    let facade = build_pubky(testnet)?;
+    facade
+        .resume_session(&public_key, cookie)
+        .await
+        .context("Failed to resume session")
+}
+
+/// Signs in locally (the usual recovery-file/passphrase prompt) and hands
+/// the resulting session's pubkey and cookie to a running agent.
+async fn add(recovery_file: PathBuf, testnet: bool) -> Result<()> {
+    let keypair = load_keypair_from_recovery_file(&recovery_file)
+        .with_context(|| format!("Failed to load recovery file: {}", recovery_file.display()))?;
+    let pubkey = keypair.public_key();
+    println!("Loaded recovery file for Pubky {}", pubkey);
+
+    let signer = build_signer(testnet, keypair)?;
+    let session = signer.signin().await?;
+
+    let request = AgentRequest::AddSession {
+        pubkey: pubkey.to_string(),
+        cookie: session.cookie().to_string(),
+        testnet,
+    };
+    match send_request(request).await {
+        Ok(AgentResponse::Ok) => println!("Session for {} added to the agent.", pubkey),
+        Ok(AgentResponse::Error(e)) => anyhow::bail!("Agent rejected session: {}", e),
+        Ok(_) => anyhow::bail!("Unexpected agent response"),
+        Err(e) => anyhow::bail!("No agent running ({e}); start one with `pubky-cli agent start`"),
+    }
+
+    Ok(())
+}
+
+fn print_reply(response: AgentResponse) {
+    match response {
+        AgentResponse::Ok => println!("OK"),
+        AgentResponse::Identities(ids) if ids.is_empty() => println!("No identities held."),
+        AgentResponse::Identities(ids) => ids.iter().for_each(|id| println!("{id}")),
+        AgentResponse::Error(e) => eprintln!("Agent error: {e}"),
+        other => println!("{:?}", other),
+    }
+}
+
+/// Sends one request to a running agent and returns its response, erroring
+/// out (rather than falling back) when no agent is listening — used by the
+/// `agent` subcommands themselves, where "no agent running" should be a
+/// clear, reportable failure.
+async fn send_request(request: AgentRequest) -> Result<AgentResponse> {
+    let path = socket_path();
+    let stream = UnixStream::connect(&path)
+        .await
+        .with_context(|| format!("No agent listening at {}", path.display()))?;
+    request_response(stream, request).await
+}
+
+/// Like `send_request`, but treats "no agent is listening" as `None`
+/// instead of an error, so `user` commands can silently fall back to the
+/// direct recovery-file/signin path when no agent is running.
+pub(crate) async fn dispatch_if_running(request: AgentRequest) -> Option<AgentResponse> {
+    let stream = UnixStream::connect(socket_path()).await.ok()?;
+    request_response(stream, request).await.ok()
+}
+
+async fn request_response(stream: UnixStream, request: AgentRequest) -> Result<AgentResponse> {
+    let (reader, mut writer) = stream.into_split();
+
+    let mut payload = serde_json::to_vec(&request).context("Failed to serialize agent request")?;
+    payload.push(b'\n');
+    writer.write_all(&payload).await.context("Failed to write agent request")?;
+
+    let mut reader = BufReader::new(reader);
+    let mut line = String::new();
+    reader.read_line(&mut line).await.context("Failed to read agent response")?;
+    serde_json::from_str(line.trim()).context("Failed to parse agent response")
+}
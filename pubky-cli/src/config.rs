@@ -0,0 +1,169 @@
+//! Layered configuration: a `pubky-cli.toml` config file supplies the
+//! lowest-priority defaults, overridden by the existing `PUBKY_*`
+//! environment variables, overridden in turn by whatever a command's
+//! explicit flags resolve to. Modeled on Stalwart's TOML config, including
+//! picking up edits without a restart (see [`reload_if_changed`]).
+//!
+//! The config file's own location is itself layered the same way: the
+//! top-level `--config` flag (applied in `main` via `PUBKY_CLI_CONFIG_ENV`)
+//! beats the `PUBKY_CLI_CONFIG` environment variable, which beats
+//! [`default_config_path`].
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// `--config`/`PUBKY_CLI_CONFIG` is threaded to the rest of the binary via
+/// this environment variable rather than a parameter on every function that
+/// might need a setting, mirroring how `PUBKY_PKARR_BOOTSTRAP` and friends
+/// already reach `build_pubky_from_env` in `util.rs`.
+pub const PUBKY_CLI_CONFIG_ENV: &str = "PUBKY_CLI_CONFIG";
+
+/// Settings a `pubky-cli.toml` may supply. Every field is optional so a
+/// config file only needs to mention what it wants to override.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Config {
+    pub admin_url: Option<String>,
+    pub testnet: Option<bool>,
+    pub admin_password: Option<String>,
+    pub recovery_passphrase: Option<String>,
+    pub pkarr_bootstrap: Option<Vec<String>>,
+    pub pkarr_relays: Option<Vec<String>>,
+    pub admin_fingerprint: Option<String>,
+}
+
+impl Config {
+    /// Parses `path`, or returns an all-`None` config if no file is there —
+    /// a config file is optional, env vars and flags are enough on their
+    /// own.
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents)
+                .with_context(|| format!("Failed to parse config file: {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read config file: {}", path.display())),
+        }
+    }
+
+    pub fn admin_url(&self) -> Option<String> {
+        std::env::var("PUBKY_ADMIN_URL").ok().or_else(|| self.admin_url.clone())
+    }
+
+    pub fn admin_password(&self) -> Option<String> {
+        std::env::var("PUBKY_ADMIN_PASSWORD").ok().or_else(|| self.admin_password.clone())
+    }
+
+    pub fn recovery_passphrase(&self) -> Option<String> {
+        std::env::var("PUBKY_CLI_RECOVERY_PASSPHRASE")
+            .ok()
+            .or_else(|| self.recovery_passphrase.clone())
+    }
+
+    pub fn pkarr_bootstrap(&self) -> Option<Vec<String>> {
+        std::env::var("PUBKY_PKARR_BOOTSTRAP")
+            .ok()
+            .map(|raw| parse_csv(&raw))
+            .or_else(|| self.pkarr_bootstrap.clone())
+    }
+
+    pub fn pkarr_relays(&self) -> Option<Vec<String>> {
+        std::env::var("PUBKY_PKARR_RELAYS")
+            .ok()
+            .map(|raw| parse_csv(&raw))
+            .or_else(|| self.pkarr_relays.clone())
+    }
+
+    /// Pinned TLS fingerprint for the admin connection, e.g. when rolling
+    /// out a pin across a team without everyone re-confirming the TOFU
+    /// prompt individually. See `tls_pin`.
+    pub fn admin_fingerprint(&self) -> Option<String> {
+        std::env::var("PUBKY_ADMIN_FINGERPRINT")
+            .ok()
+            .or_else(|| self.admin_fingerprint.clone())
+    }
+
+    // `testnet` is deliberately not exposed as a layered accessor: every
+    // `--testnet` flag across the CLI is a plain `bool` with no way to tell
+    // "not passed" from "passed as false", so there's no layering point to
+    // plug a config/env default into without changing every such flag's
+    // type. The field is still parsed and kept on `Config` so a future pass
+    // that makes those flags tri-state has something to read from.
+}
+
+fn parse_csv(raw: &str) -> Vec<String> {
+    raw.split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// Resolves the config file path: `$PUBKY_CLI_CONFIG` (set by `main` when
+/// `--config` is passed) if present, else [`default_config_path`].
+pub fn resolve_path() -> PathBuf {
+    std::env::var_os(PUBKY_CLI_CONFIG_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(default_config_path)
+}
+
+/// `$XDG_CONFIG_HOME/pubky-cli/config.toml` (falling back to
+/// `~/.config/pubky-cli/config.toml`).
+pub fn default_config_path() -> PathBuf {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .unwrap_or_else(std::env::temp_dir);
+    base.join("pubky-cli").join("config.toml")
+}
+
+/// Loads whatever `resolve_path()` points at right now. Cheap enough (a
+/// small TOML file) to call fresh on every command invocation instead of
+/// caching it, which is what makes one-shot commands pick up config edits
+/// automatically; long-running commands use [`reload_if_changed`] instead
+/// so they can log what changed.
+pub fn effective_config() -> Result<Config> {
+    Config::load(&resolve_path())
+}
+
+/// Re-reads `path` if its mtime has advanced since `last_seen`, returning
+/// the new config and its mtime when it has. Used by long-running commands
+/// (`agent start`, `user watch`) to log edits to relays, bootstrap nodes,
+/// and the admin URL without restarting. Neither command rebuilds what it
+/// already has open: `agent start` applies new pkarr settings only to
+/// sessions added afterwards, and `user watch` keeps polling with the
+/// session it started with.
+pub fn reload_if_changed(path: &Path, last_seen: Option<SystemTime>) -> Result<Option<(Config, SystemTime)>> {
+    let metadata = match std::fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to stat config file: {}", path.display())),
+    };
+    let modified = metadata
+        .modified()
+        .with_context(|| format!("Failed to read mtime of {}", path.display()))?;
+
+    if Some(modified) == last_seen {
+        return Ok(None);
+    }
+
+    Ok(Some((Config::load(path)?, modified)))
+}
+
+/// Describes what changed between two reloadable-field snapshots, one line
+/// per changed field, for `agent start`/`user watch` to log on hot reload.
+pub fn describe_changes(old: &Config, new: &Config) -> Vec<String> {
+    let mut changes = Vec::new();
+    if old.admin_url != new.admin_url {
+        changes.push(format!("admin_url: {:?} -> {:?}", old.admin_url, new.admin_url));
+    }
+    if old.pkarr_bootstrap != new.pkarr_bootstrap {
+        changes.push(format!("pkarr_bootstrap: {:?} -> {:?}", old.pkarr_bootstrap, new.pkarr_bootstrap));
+    }
+    if old.pkarr_relays != new.pkarr_relays {
+        changes.push(format!("pkarr_relays: {:?} -> {:?}", old.pkarr_relays, new.pkarr_relays));
+    }
+    changes
+}
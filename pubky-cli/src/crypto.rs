@@ -0,0 +1,276 @@
+//! Transparent client-side content encryption for `user publish --encrypt`
+//! and `user get --decrypt`.
+//!
+//! Borrows the "encrypt before it leaves the client" shape used by
+//! Aerogramme: the content-encryption key is derived (HKDF-SHA256) from the
+//! account's recovery keypair plus a per-file random salt, then sealed with
+//! XChaCha20-Poly1305 in fixed-size records, so a homeserver operator who
+//! can read a published blob still can't read its contents. A small
+//! self-describing header (magic, version, salt, record size) is prepended
+//! so `get --decrypt` needs nothing beyond the same recovery keypair to
+//! recover the plaintext.
+//!
+//! Unlike wiky's RFC 8188 framing (see `wiky::encrypted_content`), records
+//! here carry no continuation delimiter byte: the short final record is
+//! only ever produced by [`Encryptor::finish`], so [`Decryptor`] already
+//! knows which record is last once the caller calls its own `finish`,
+//! without needing to inspect decrypted plaintext to find out.
+
+use anyhow::{anyhow, Result};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use hkdf::Hkdf;
+use rand::RngCore;
+use sha2::Sha256;
+
+const MAGIC: &[u8; 4] = b"PCE1";
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const TAG_LEN: usize = 16;
+const HEADER_LEN: usize = MAGIC.len() + 1 + SALT_LEN + 4;
+
+/// Plaintext bytes per sealed record. Bounds how much unsealed data
+/// [`Encryptor`]/[`Decryptor`] ever buffer at once, so streaming a large
+/// file doesn't require holding it all in memory.
+const DEFAULT_RECORD_SIZE: u32 = 64 * 1024;
+
+fn derive_key(ikm: &[u8], salt: &[u8]) -> [u8; 32] {
+    let hk = Hkdf::<Sha256>::new(Some(salt), ikm);
+    let mut key = [0u8; 32];
+    hk.expand(b"pubky-cli content-encryption key", &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    key
+}
+
+fn derive_nonce_base(ikm: &[u8], salt: &[u8]) -> [u8; NONCE_LEN] {
+    let hk = Hkdf::<Sha256>::new(Some(salt), ikm);
+    let mut base = [0u8; NONCE_LEN];
+    hk.expand(b"pubky-cli content-encryption nonce", &mut base)
+        .expect("24 bytes is a valid HKDF-SHA256 output length");
+    base
+}
+
+/// The per-record nonce is the HKDF-derived base XORed with the big-endian
+/// record sequence number, so no nonce ever needs to be stored alongside
+/// its record.
+fn record_nonce(base: &[u8; NONCE_LEN], index: u64) -> XNonce {
+    let mut nonce = *base;
+    let index_bytes = index.to_be_bytes();
+    for (byte, index_byte) in nonce[NONCE_LEN - 8..].iter_mut().zip(index_bytes) {
+        *byte ^= index_byte;
+    }
+    XNonce::clone_from_slice(&nonce)
+}
+
+/// Seals an entire buffer in one call. `user publish --encrypt` already
+/// reads the whole file into memory before uploading, so it uses this
+/// instead of driving [`Encryptor`] itself.
+pub fn seal(ikm: &[u8], plaintext: &[u8]) -> Vec<u8> {
+    let mut sealer = Encryptor::new(ikm);
+    let mut out = sealer.header().to_vec();
+    out.extend(sealer.push(plaintext));
+    out.extend(sealer.finish());
+    out
+}
+
+/// Inverse of [`seal`]. `user get --decrypt`'s non-streaming branch already
+/// has the whole object buffered, so it uses this instead of driving
+/// [`Decryptor`] itself.
+pub fn open(ikm: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+    let mut opener = Decryptor::new(ikm);
+    let mut out = opener.feed(ciphertext)?;
+    out.extend(opener.finish()?);
+    Ok(out)
+}
+
+/// Streaming sealer: feed it plaintext as it becomes available (e.g. from a
+/// chunked file read); it buffers at most one record's worth before
+/// emitting sealed bytes.
+pub struct Encryptor {
+    cipher: XChaCha20Poly1305,
+    nonce_base: [u8; NONCE_LEN],
+    header: Vec<u8>,
+    record_index: u64,
+    buffer: Vec<u8>,
+}
+
+impl Encryptor {
+    pub fn new(ikm: &[u8]) -> Self {
+        let mut salt = [0u8; SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(MAGIC);
+        header.push(VERSION);
+        header.extend_from_slice(&salt);
+        header.extend_from_slice(&DEFAULT_RECORD_SIZE.to_be_bytes());
+
+        Self {
+            cipher: XChaCha20Poly1305::new(derive_key(ikm, &salt).as_slice().into()),
+            nonce_base: derive_nonce_base(ikm, &salt),
+            header,
+            record_index: 0,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// The header to write once, before any sealed records it returns.
+    pub fn header(&self) -> &[u8] {
+        &self.header
+    }
+
+    /// Buffers `plaintext` and returns the bytes of every record that's now
+    /// full enough to seal.
+    pub fn push(&mut self, plaintext: &[u8]) -> Vec<u8> {
+        self.buffer.extend_from_slice(plaintext);
+
+        let mut out = Vec::new();
+        while self.buffer.len() >= DEFAULT_RECORD_SIZE as usize {
+            let record: Vec<u8> = self.buffer.drain(..DEFAULT_RECORD_SIZE as usize).collect();
+            out.extend(self.seal_record(&record));
+        }
+        out
+    }
+
+    /// Seals whatever remains buffered as the final (possibly empty or
+    /// short) record. Call exactly once, after the last `push`.
+    pub fn finish(mut self) -> Vec<u8> {
+        let record = std::mem::take(&mut self.buffer);
+        self.seal_record(&record)
+    }
+
+    fn seal_record(&mut self, record: &[u8]) -> Vec<u8> {
+        let nonce = record_nonce(&self.nonce_base, self.record_index);
+        self.record_index += 1;
+        // Fails only for a plaintext longer than the cipher's maximum
+        // message length, far above `DEFAULT_RECORD_SIZE`. The monotonic
+        // `record_index` counter above is what keeps nonces unique.
+        self.cipher
+            .encrypt(&nonce, record)
+            .expect("XChaCha20-Poly1305 seal cannot fail for a record this small")
+    }
+}
+
+/// Streaming opener, the inverse of [`Encryptor`]. Feed it ciphertext bytes
+/// as they arrive (e.g. from a download loop); it parses the header out of
+/// the first bytes fed to it and returns plaintext only for records it has
+/// fully reassembled, holding back any trailing partial record until the
+/// next `feed` or `finish`.
+pub struct Decryptor {
+    ikm: Vec<u8>,
+    state: DecryptorState,
+}
+
+enum DecryptorState {
+    AwaitingHeader(Vec<u8>),
+    Ready {
+        cipher: XChaCha20Poly1305,
+        nonce_base: [u8; NONCE_LEN],
+        record_size: usize,
+        record_index: u64,
+        buffer: Vec<u8>,
+    },
+}
+
+impl Decryptor {
+    pub fn new(ikm: &[u8]) -> Self {
+        Self { ikm: ikm.to_vec(), state: DecryptorState::AwaitingHeader(Vec::new()) }
+    }
+
+    /// Feeds more ciphertext, returning the plaintext of every record that
+    /// is now fully buffered.
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<Vec<u8>> {
+        match &mut self.state {
+            DecryptorState::AwaitingHeader(buffer) => buffer.extend_from_slice(bytes),
+            DecryptorState::Ready { buffer, .. } => buffer.extend_from_slice(bytes),
+        }
+
+        if let DecryptorState::AwaitingHeader(buffer) = &self.state {
+            if buffer.len() < HEADER_LEN {
+                return Ok(Vec::new());
+            }
+            self.state = self.parse_header()?;
+        }
+
+        self.drain_complete_records(false)
+    }
+
+    fn parse_header(&self) -> Result<DecryptorState> {
+        let DecryptorState::AwaitingHeader(buffer) = &self.state else {
+            unreachable!("parse_header only called while AwaitingHeader");
+        };
+
+        if buffer[0..MAGIC.len()] != *MAGIC {
+            return Err(anyhow!(
+                "object does not start with the pubky-cli content-encryption header; was it published with `user publish --encrypt`?"
+            ));
+        }
+        let version = buffer[MAGIC.len()];
+        if version != VERSION {
+            return Err(anyhow!("unsupported content-encryption version {version}"));
+        }
+
+        let salt_start = MAGIC.len() + 1;
+        let salt = &buffer[salt_start..salt_start + SALT_LEN];
+        let record_size_start = salt_start + SALT_LEN;
+        let record_size = u32::from_be_bytes(
+            buffer[record_size_start..record_size_start + 4]
+                .try_into()
+                .expect("4-byte slice"),
+        ) as usize;
+
+        Ok(DecryptorState::Ready {
+            cipher: XChaCha20Poly1305::new(derive_key(&self.ikm, salt).as_slice().into()),
+            nonce_base: derive_nonce_base(&self.ikm, salt),
+            record_size,
+            record_index: 0,
+            buffer: buffer[HEADER_LEN..].to_vec(),
+        })
+    }
+
+    /// Decrypts whatever ciphertext remains buffered as the final record.
+    /// Call exactly once, after the last `feed`.
+    pub fn finish(&mut self) -> Result<Vec<u8>> {
+        self.drain_complete_records(true)
+    }
+
+    fn drain_complete_records(&mut self, is_final_call: bool) -> Result<Vec<u8>> {
+        let DecryptorState::Ready { cipher, nonce_base, record_size, record_index, buffer } = &mut self.state
+        else {
+            return Ok(Vec::new());
+        };
+
+        let sealed_record_len = *record_size + TAG_LEN;
+        let mut out = Vec::new();
+
+        // A full sealed record is safe to open as soon as it arrives; only
+        // the last (possibly short) one needs `finish`'s confirmation that
+        // no more ciphertext is coming before it can be treated as final.
+        while buffer.len() >= sealed_record_len {
+            let record: Vec<u8> = buffer.drain(..sealed_record_len).collect();
+            out.extend(open_record(cipher, nonce_base, *record_index, &record)?);
+            *record_index += 1;
+        }
+
+        if is_final_call && !buffer.is_empty() {
+            let record = std::mem::take(buffer);
+            out.extend(open_record(cipher, nonce_base, *record_index, &record)?);
+            *record_index += 1;
+        }
+
+        Ok(out)
+    }
+}
+
+fn open_record(
+    cipher: &XChaCha20Poly1305,
+    nonce_base: &[u8; NONCE_LEN],
+    index: u64,
+    record: &[u8],
+) -> Result<Vec<u8>> {
+    let nonce = record_nonce(nonce_base, index);
+    cipher
+        .decrypt(&nonce, record)
+        .map_err(|_| anyhow!("authentication failed for record {index}; wrong recovery keypair or corrupted data"))
+}
@@ -1,14 +1,45 @@
 mod admin;
+mod agent;
+mod config;
+mod crypto;
+mod scenario;
+mod sftp;
+mod telemetry;
+mod tls_pin;
 mod tools;
 mod user;
 mod util;
 
+use std::path::PathBuf;
+
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
+    /// Path to a pubky-cli.toml config file supplying defaults (admin URL,
+    /// passwords, pkarr relays/bootstrap), layered under environment
+    /// variables and below explicit flags. A `testnet` key is parsed but
+    /// does not change any `--testnet` flag. Defaults to
+    /// $XDG_CONFIG_HOME/pubky-cli/config.toml.
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// OTLP/HTTP collector endpoint (e.g. http://localhost:4318) to export
+    /// `admin` command traces to. Falls back to
+    /// $OTEL_EXPORTER_OTLP_ENDPOINT. Unset, tracing stays local (stderr
+    /// only via `tracing_subscriber::fmt`).
+    #[arg(long, global = true)]
+    otlp_endpoint: Option<String>,
+
+    /// Output format for commands that support it (currently `admin`):
+    /// `text` for the usual human-readable lines, `json` to print results
+    /// (info summaries, enable/disable outcomes, storage confirmations) as
+    /// JSON for scripts to consume.
+    #[arg(long, global = true, value_enum, default_value = "text")]
+    output: admin::OutputFormat,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -30,17 +61,40 @@ enum Commands {
         #[command(subcommand)]
         action: tools::Command,
     },
+    /// Background session agent: holds signed-in sessions in memory behind
+    /// a Unix socket so `user` commands can reuse them across invocations.
+    Agent {
+        #[command(subcommand)]
+        action: agent::Command,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    match cli.command {
-        Commands::Admin { action } => admin::run(action).await?,
-        Commands::User { action } => user::run(action).await?,
-        Commands::Tools { action } => tools::run(action).await?,
+    let otlp_endpoint = cli
+        .otlp_endpoint
+        .clone()
+        .or_else(|| std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok());
+    telemetry::init(otlp_endpoint.as_deref())?;
+
+    if let Some(config_path) = &cli.config {
+        // Threaded to the rest of the binary via an env var (see
+        // `config::PUBKY_CLI_CONFIG_ENV`) rather than a parameter on every
+        // function that might need a setting, matching how the existing
+        // `PUBKY_PKARR_*` variables already reach `util::build_pubky_from_env`.
+        std::env::set_var(config::PUBKY_CLI_CONFIG_ENV, config_path);
     }
 
-    Ok(())
+    let result = match cli.command {
+        Commands::Admin { action } => admin::run(action, cli.output).await,
+        Commands::User { action } => user::run(action).await,
+        Commands::Tools { action } => tools::run(action).await,
+        Commands::Agent { action } => agent::run(action).await,
+    };
+
+    telemetry::shutdown();
+
+    result
 }
@@ -0,0 +1,263 @@
+//! Lua-scriptable driver for an in-process pubky testnet.
+//!
+//! `tools run-scenario` loads a `.lua` script and runs it against a fresh
+//! `pubky_testnet::Testnet`, exposing the same operations the Publar GUI's
+//! scenario buttons trigger as Lua globals: `add_homeserver()`,
+//! `add_client()`, `signup(client, homeserver)`, `put(client, path, bytes)`,
+//! `get(client, pubky, path)`, and `sleep(ms)`. Scripts register callbacks
+//! with `on(event_name, fn)`; the engine invokes the matching callback as it
+//! emits `"homeserver_started"`, `"client_signed_up"`, and
+//! `"record_published"` events, passing the relevant handle/pubky along.
+//! This lets a scenario ("spin up 3 homeservers, 10 clients, randomly
+//! publish, assert replication") be a real program instead of a canned
+//! preset, shared between the CLI and the GUI.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use mlua::{Function, Lua, RegistryKey};
+use pubky::{Keypair, PublicKey, PubkySession};
+use pubky_testnet::Testnet;
+
+struct HomeserverHandle {
+    public_key: PublicKey,
+}
+
+struct ClientHandle {
+    keypair: Keypair,
+    session: Option<Arc<PubkySession>>,
+}
+
+/// State shared by every Lua-exposed function: the testnet itself, the
+/// handles scripts address actors by, and the registered event callbacks.
+struct Engine {
+    testnet: Testnet,
+    homeservers: HashMap<String, HomeserverHandle>,
+    clients: HashMap<String, ClientHandle>,
+    handlers: HashMap<String, RegistryKey>,
+    next_id: usize,
+}
+
+impl Engine {
+    fn next_handle(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{prefix}{}", self.next_id)
+    }
+}
+
+/// Run a future from inside a Lua callback. Lua callbacks execute
+/// synchronously on whatever thread is driving the script's tokio task, so a
+/// bare `Handle::block_on` would panic; parking the wait on the blocking
+/// pool first is the standard way to bridge a sync callback back into the
+/// async network calls it needs to make.
+fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+    tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(fut))
+}
+
+/// Call `event`'s registered handler, if a script registered one, with `args`.
+fn emit(
+    lua: &Lua,
+    engine: &Rc<RefCell<Engine>>,
+    event: &str,
+    args: impl mlua::IntoLuaMulti,
+) -> mlua::Result<()> {
+    let func: Option<Function> = {
+        let eng = engine.borrow();
+        match eng.handlers.get(event) {
+            Some(key) => Some(lua.registry_value(key)?),
+            None => None,
+        }
+    };
+
+    match func {
+        Some(func) => func.call::<()>(args),
+        None => Ok(()),
+    }
+}
+
+/// Parse and run a scenario script against a fresh in-process testnet.
+pub async fn run_script(script: &Path) -> anyhow::Result<()> {
+    let source = std::fs::read_to_string(script)
+        .with_context(|| format!("Failed to read scenario script {}", script.display()))?;
+
+    let testnet = Testnet::new().await.context("Failed to create testnet")?;
+
+    let engine = Rc::new(RefCell::new(Engine {
+        testnet,
+        homeservers: HashMap::new(),
+        clients: HashMap::new(),
+        handlers: HashMap::new(),
+        next_id: 0,
+    }));
+
+    let lua = Lua::new();
+    register_globals(&lua, engine)?;
+
+    lua.load(&source)
+        .set_name(script.display().to_string())
+        .exec()
+        .map_err(|e| anyhow::anyhow!("Scenario script failed: {e}"))?;
+
+    Ok(())
+}
+
+fn register_globals(lua: &Lua, engine: Rc<RefCell<Engine>>) -> anyhow::Result<()> {
+    let globals = lua.globals();
+
+    {
+        let engine = engine.clone();
+        let lua_ref = lua.clone();
+        let add_homeserver = lua.create_function(move |_, ()| {
+            let (handle, public_key) = {
+                let mut eng = engine.borrow_mut();
+                let fut = eng.testnet.create_homeserver();
+                let homeserver = block_on(fut).map_err(|e| mlua::Error::external(e.to_string()))?;
+                let public_key = homeserver.public_key();
+                let handle = eng.next_handle("hs");
+                eng.homeservers.insert(
+                    handle.clone(),
+                    HomeserverHandle {
+                        public_key: public_key.clone(),
+                    },
+                );
+                (handle, public_key)
+            };
+
+            emit(&lua_ref, &engine, "homeserver_started", (handle.clone(), public_key.to_z32()))?;
+            Ok(handle)
+        })?;
+        globals.set("add_homeserver", add_homeserver)?;
+    }
+
+    {
+        let engine = engine.clone();
+        let add_client = lua.create_function(move |_, ()| {
+            let mut eng = engine.borrow_mut();
+            let handle = eng.next_handle("client");
+            eng.clients.insert(
+                handle.clone(),
+                ClientHandle {
+                    keypair: Keypair::random(),
+                    session: None,
+                },
+            );
+            Ok(handle)
+        })?;
+        globals.set("add_client", add_client)?;
+    }
+
+    {
+        let engine = engine.clone();
+        let lua_ref = lua.clone();
+        let signup = lua.create_function(move |_, (client, homeserver): (String, String)| {
+            let (keypair, homeserver_pk) = {
+                let eng = engine.borrow();
+                let client_handle = eng
+                    .clients
+                    .get(&client)
+                    .ok_or_else(|| mlua::Error::external(format!("unknown client handle {client}")))?;
+                let homeserver_handle = eng.homeservers.get(&homeserver).ok_or_else(|| {
+                    mlua::Error::external(format!("unknown homeserver handle {homeserver}"))
+                })?;
+                (client_handle.keypair.clone(), homeserver_handle.public_key.clone())
+            };
+
+            let session = {
+                let pubky = engine
+                    .borrow()
+                    .testnet
+                    .sdk()
+                    .context("Failed to create Pubky SDK from testnet")
+                    .map_err(|e| mlua::Error::external(e.to_string()))?;
+                block_on(pubky.signer(keypair).signup(&homeserver_pk, None))
+                    .map_err(|e| mlua::Error::external(e.to_string()))?
+            };
+
+            engine
+                .borrow_mut()
+                .clients
+                .get_mut(&client)
+                .expect("client handle checked above")
+                .session = Some(Arc::new(session));
+
+            emit(&lua_ref, &engine, "client_signed_up", (client.clone(), homeserver_pk.to_z32()))?;
+            Ok(())
+        })?;
+        globals.set("signup", signup)?;
+    }
+
+    {
+        let engine = engine.clone();
+        let lua_ref = lua.clone();
+        let put = lua.create_function(move |_, (client, path, data): (String, String, mlua::String)| {
+            let session = {
+                let eng = engine.borrow();
+                eng.clients
+                    .get(&client)
+                    .ok_or_else(|| mlua::Error::external(format!("unknown client handle {client}")))?
+                    .session
+                    .clone()
+                    .ok_or_else(|| mlua::Error::external(format!("client {client} has not signed up yet")))?
+            };
+
+            let bytes = data.as_bytes().to_vec();
+            block_on(session.storage().put(path.clone(), bytes))
+                .map_err(|e| mlua::Error::external(e.to_string()))?;
+
+            emit(&lua_ref, &engine, "record_published", (client.clone(), path))?;
+            Ok(())
+        })?;
+        globals.set("put", put)?;
+    }
+
+    {
+        let engine = engine.clone();
+        let lua_ref = lua.clone();
+        let get = lua.create_function(move |_, (client, pubky, path): (String, String, String)| {
+            if !engine.borrow().clients.contains_key(&client) {
+                return Err(mlua::Error::external(format!("unknown client handle {client}")));
+            }
+
+            let facade = engine
+                .borrow()
+                .testnet
+                .sdk()
+                .context("Failed to create Pubky SDK from testnet")
+                .map_err(|e| mlua::Error::external(e.to_string()))?;
+
+            let url = format!("pubky://{pubky}{path}");
+            let bytes = block_on(async {
+                let response = facade.public_storage().get(&url).await.context("Failed to fetch path")?;
+                response.bytes().await.context("Failed to read response body")
+            })
+            .map_err(|e: anyhow::Error| mlua::Error::external(e.to_string()))?;
+
+            lua_ref.create_string(&bytes)
+        })?;
+        globals.set("get", get)?;
+    }
+
+    {
+        let sleep = lua.create_function(move |_, ms: u64| {
+            block_on(tokio::time::sleep(tokio::time::Duration::from_millis(ms)));
+            Ok(())
+        })?;
+        globals.set("sleep", sleep)?;
+    }
+
+    {
+        let engine = engine.clone();
+        let on = lua.create_function(move |lua, (event, handler): (String, Function)| {
+            let key = lua.create_registry_value(handler)?;
+            engine.borrow_mut().handlers.insert(event, key);
+            Ok(())
+        })?;
+        globals.set("on", on)?;
+    }
+
+    Ok(())
+}
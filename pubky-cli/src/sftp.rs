@@ -0,0 +1,145 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use pubky::PubkySession;
+use sftp_server::{Backend, DirEntry, FileHandle, OpenFlags, Server};
+
+use crate::util::{build_signer, load_keypair_from_recovery_file};
+
+/// Presents a signed-in `PubkySession`'s storage as an SFTP filesystem, so
+/// standard tools (`sftp`, FUSE mounts, file managers) can read/write a
+/// homeserver without speaking the Pubky protocol. `open`/`read`/`write`/
+/// `readdir`/`remove` translate directly to `storage.get`/`put`/`list`/
+/// `delete`; `write` buffers into the handle and only issues the `put` on
+/// `close`, since `storage.put` takes a whole body rather than a byte range.
+struct PubkyBackend {
+    session: PubkySession,
+}
+
+impl PubkyBackend {
+    fn new(session: PubkySession) -> Self {
+        Self { session }
+    }
+}
+
+#[async_trait::async_trait]
+impl Backend for PubkyBackend {
+    async fn open(&self, path: &str, flags: OpenFlags) -> std::io::Result<FileHandle> {
+        if flags.is_write() {
+            return Ok(FileHandle::for_write(path));
+        }
+
+        let data = self
+            .session
+            .storage()
+            .get(path.to_string())
+            .await
+            .map_err(std::io::Error::other)?
+            .bytes()
+            .await
+            .map_err(std::io::Error::other)?;
+
+        Ok(FileHandle::for_read(path, data.to_vec()))
+    }
+
+    async fn read(&self, handle: &FileHandle, offset: u64, len: u32) -> std::io::Result<Vec<u8>> {
+        let data = handle.buffered_data();
+        let start = (offset as usize).min(data.len());
+        let end = start.saturating_add(len as usize).min(data.len());
+        Ok(data[start..end].to_vec())
+    }
+
+    async fn write(&self, handle: &mut FileHandle, offset: u64, data: &[u8]) -> std::io::Result<()> {
+        handle.write_at(offset, data);
+        Ok(())
+    }
+
+    async fn close(&self, handle: FileHandle) -> std::io::Result<()> {
+        if let Some((path, buffer)) = handle.into_pending_write() {
+            self.session
+                .storage()
+                .put(path, reqwest::Body::from(buffer))
+                .await
+                .map_err(std::io::Error::other)?;
+        }
+
+        Ok(())
+    }
+
+    async fn readdir(
+        &self,
+        path: &str,
+        cursor: Option<String>,
+        limit: Option<u16>,
+    ) -> std::io::Result<Vec<DirEntry>> {
+        let storage = self.session.storage();
+        let mut builder = storage
+            .list(path.to_string())
+            .map_err(std::io::Error::other)?
+            .shallow(true);
+
+        if let Some(limit) = limit {
+            builder = builder.limit(limit);
+        }
+        if let Some(cursor) = cursor.as_deref() {
+            builder = builder.cursor(cursor);
+        }
+
+        let listed = builder.send().await.map_err(std::io::Error::other)?;
+
+        Ok(listed
+            .iter()
+            .map(|entry| {
+                let url = entry.to_pubky_url();
+                let is_dir = url.ends_with('/');
+                let name = url
+                    .trim_end_matches('/')
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or(&url)
+                    .to_string();
+                DirEntry::new(name, is_dir)
+            })
+            .collect())
+    }
+
+    async fn remove(&self, path: &str) -> std::io::Result<()> {
+        self.session
+            .storage()
+            .delete(path.to_string())
+            .await
+            .map_err(std::io::Error::other)
+    }
+}
+
+/// Signs in and serves the resulting session's storage over an embedded
+/// SFTP/SSH server, so `sftp -P <port> localhost` can browse
+/// `pubky://<pubkey>/pub/` directly.
+pub async fn serve_sftp(recovery_file: PathBuf, bind_addr: SocketAddr, testnet: bool) -> Result<()> {
+    let keypair = load_keypair_from_recovery_file(&recovery_file)
+        .with_context(|| format!("Failed to load recovery file: {}", recovery_file.display()))?;
+    println!("Loaded recovery file for Pubky {}", keypair.public_key());
+
+    let signer = build_signer(testnet, keypair)?;
+    let session = signer.signin().await?;
+    let pubkey = session.info().public_key().clone();
+    println!("Signed in successfully. Session details:");
+    println!("{:#?}", session.info());
+
+    println!(
+        "Serving pubky://{}/pub/ over SFTP on {} (sftp -P {} localhost)",
+        pubkey,
+        bind_addr,
+        bind_addr.port()
+    );
+
+    let backend = PubkyBackend::new(session);
+
+    Server::bind(bind_addr)
+        .await
+        .with_context(|| format!("Failed to bind SFTP server on {}", bind_addr))?
+        .serve(backend)
+        .await
+        .context("SFTP server exited with an error")
+}
@@ -0,0 +1,51 @@
+//! Optional OpenTelemetry OTLP export for `admin` command tracing.
+//!
+//! By default `pubky-cli` just logs to stderr via `tracing_subscriber::fmt`
+//! (matching `publar`/`wiky`'s own `tracing_subscriber::fmt::init()`).
+//! Passing `--otlp-endpoint` (or setting `$OTEL_EXPORTER_OTLP_ENDPOINT`)
+//! layers a `tracing-opentelemetry` exporter on top instead, so the spans
+//! `admin` already emits around each operation and HTTP request ship to a
+//! collector — audit-grade traces of who ran what against which
+//! homeserver, without changing anything for operators who don't set it.
+
+use anyhow::{Context, Result};
+use opentelemetry::trace::TracerProvider as _;
+use tracing_subscriber::prelude::*;
+
+/// Initializes the global tracing subscriber. `otlp_endpoint` being `Some`
+/// adds the OTLP/HTTP exporter layer on top of the usual `fmt` layer;
+/// `None` installs only the `fmt` layer.
+pub fn init(otlp_endpoint: Option<&str>) -> Result<()> {
+    let fmt_layer = tracing_subscriber::fmt::layer().with_writer(std::io::stderr);
+
+    let Some(endpoint) = otlp_endpoint else {
+        tracing_subscriber::registry().with(fmt_layer).init();
+        return Ok(());
+    };
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()
+        .context("Failed to build OTLP span exporter")?;
+
+    let provider = opentelemetry_sdk::trace::TracerProvider::builder()
+        .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+        .build();
+    let tracer = provider.tracer("pubky-cli");
+    opentelemetry::global::set_tracer_provider(provider);
+
+    tracing_subscriber::registry()
+        .with(fmt_layer)
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .init();
+
+    Ok(())
+}
+
+/// Flushes any batched-but-unsent spans. Call once before the process
+/// exits, or the last admin command's span can be lost along with the
+/// exporter's export interval.
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
@@ -0,0 +1,295 @@
+//! TLS certificate fingerprint pinning for `admin` connections over HTTPS.
+//!
+//! The threat this protects against is a MITM presenting a certificate
+//! that's merely *valid* (e.g. issued by a captured or coerced CA) rather
+//! than specifically the operator's own. On first connection to a given
+//! admin URL we capture the leaf certificate's SHA-256 fingerprint, print
+//! it, and ask the operator to confirm it out-of-band before pinning it to
+//! `~/.config/pubky-admin/fingerprints.json`; every later connection to
+//! that URL is verified against the pinned value instead of the normal CA
+//! chain. A `--fingerprint` given explicitly is used as the pinned value in
+//! the same way, also in place of the CA chain.
+//!
+//! Plain `http://` admin URLs bypass this module entirely — there's no
+//! certificate to pin.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, SignatureScheme};
+use sha2::{Digest, Sha256};
+
+/// `~/.config/pubky-admin/fingerprints.json` — deliberately a different
+/// config directory than `pubky-cli`'s own (see `config::default_config_path`),
+/// since pinned certs are a property of the admin *servers* an operator
+/// talks to, not of this one CLI's settings.
+fn fingerprint_cache_path() -> PathBuf {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .unwrap_or_else(std::env::temp_dir);
+    base.join("pubky-admin").join("fingerprints.json")
+}
+
+type FingerprintCache = HashMap<String, String>;
+
+fn load_cache() -> Result<FingerprintCache> {
+    let path = fingerprint_cache_path();
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse fingerprint cache: {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(FingerprintCache::new()),
+        Err(e) => Err(e).with_context(|| format!("Failed to read fingerprint cache: {}", path.display())),
+    }
+}
+
+fn save_cache(cache: &FingerprintCache) -> Result<()> {
+    let path = fingerprint_cache_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let json = serde_json::to_vec_pretty(cache).context("Failed to serialize fingerprint cache")?;
+    std::fs::write(&path, json)
+        .with_context(|| format!("Failed to write fingerprint cache: {}", path.display()))
+}
+
+fn cached_fingerprint(admin_url: &str) -> Result<Option<String>> {
+    Ok(load_cache()?.get(admin_url).cloned())
+}
+
+fn remember_fingerprint(admin_url: &str, fingerprint: &str) -> Result<()> {
+    let mut cache = load_cache()?;
+    cache.insert(admin_url.to_string(), fingerprint.to_string());
+    save_cache(&cache)
+}
+
+fn format_fingerprint(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(":")
+}
+
+fn parse_fingerprint(formatted: &str) -> Result<Vec<u8>> {
+    formatted
+        .split(':')
+        .map(|part| u8::from_str_radix(part, 16).with_context(|| format!("Invalid fingerprint byte: {part}")))
+        .collect()
+}
+
+fn leaf_fingerprint(end_entity: &CertificateDer<'_>) -> Vec<u8> {
+    Sha256::digest(end_entity.as_ref()).to_vec()
+}
+
+/// Accepts a connection only if the leaf certificate's SHA-256 fingerprint
+/// matches `expected`; used once a fingerprint is pinned (via `--fingerprint`
+/// or a prior TOFU capture).
+#[derive(Debug)]
+struct PinnedVerifier {
+    expected: Vec<u8>,
+    provider: Arc<rustls::crypto::CryptoProvider>,
+}
+
+impl ServerCertVerifier for PinnedVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let actual = leaf_fingerprint(end_entity);
+        if actual == self.expected {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(format!(
+                "admin server certificate fingerprint {} does not match pinned fingerprint {}",
+                format_fingerprint(&actual),
+                format_fingerprint(&self.expected),
+            )))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// Always accepts the connection (never used for anything but the one-off
+/// TOFU probe) but records the leaf certificate's fingerprint so the caller
+/// can show it to the operator.
+#[derive(Debug)]
+struct CapturingVerifier {
+    captured: Arc<Mutex<Option<Vec<u8>>>>,
+    provider: Arc<rustls::crypto::CryptoProvider>,
+}
+
+impl ServerCertVerifier for CapturingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        *self.captured.lock().unwrap() = Some(leaf_fingerprint(end_entity));
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+fn client_config_with_verifier(verifier: Arc<dyn ServerCertVerifier>) -> rustls::ClientConfig {
+    rustls::ClientConfig::builder()
+        .dangerous()
+        .with_custom_certificate_verifier(verifier)
+        .with_no_client_auth()
+}
+
+/// Builds the `reqwest::Client` an `AdminHttpClient` should use for
+/// `admin_url`. For `http://` URLs this is just a plain client. For
+/// `https://` URLs: if `fingerprint` was given explicitly (`--fingerprint`
+/// or `$PUBKY_ADMIN_FINGERPRINT`), pin to it; otherwise consult the
+/// on-disk cache and, on a cache miss, run the TOFU capture-and-prompt flow
+/// in [`prompt_and_pin`].
+pub async fn build_http_client(
+    admin_url: &str,
+    fingerprint: Option<&str>,
+    timeout: Duration,
+) -> Result<reqwest::Client> {
+    if !admin_url.starts_with("https://") {
+        return reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .context("Failed to build HTTP client");
+    }
+
+    let pinned = match fingerprint {
+        Some(explicit) => explicit.to_string(),
+        None => match cached_fingerprint(admin_url)? {
+            Some(cached) => cached,
+            None => prompt_and_pin(admin_url).await?,
+        },
+    };
+
+    let expected = parse_fingerprint(&pinned)?;
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let verifier = Arc::new(PinnedVerifier { expected, provider });
+    let tls_config = client_config_with_verifier(verifier);
+
+    reqwest::Client::builder()
+        .use_preconfigured_tls(tls_config)
+        .timeout(timeout)
+        .build()
+        .context("Failed to build pinned-TLS HTTP client")
+}
+
+/// Connects once to `admin_url` with a verifier that accepts anything but
+/// records the leaf fingerprint, shows it to the operator, and — if they
+/// accept — persists and returns it. This is an ordinary async request
+/// (not `reqwest::blocking`) since it runs from inside `admin`'s already-
+/// running Tokio runtime.
+async fn prompt_and_pin(admin_url: &str) -> Result<String> {
+    let captured = Arc::new(Mutex::new(None));
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let verifier = Arc::new(CapturingVerifier { captured: captured.clone(), provider });
+    let tls_config = client_config_with_verifier(verifier);
+
+    let probe = reqwest::Client::builder()
+        .use_preconfigured_tls(tls_config)
+        .build()
+        .context("Failed to build TOFU probe client")?;
+
+    probe
+        .get(admin_url)
+        .send()
+        .await
+        .context("Failed to connect to admin server to capture its certificate")?;
+
+    let digest = captured
+        .lock()
+        .unwrap()
+        .take()
+        .context("TLS handshake completed but no certificate was captured")?;
+    let formatted = format_fingerprint(&digest);
+
+    println!("No pinned fingerprint on file for {admin_url}.");
+    println!("Server presented certificate fingerprint (SHA-256): {formatted}");
+    print!("Trust and pin this fingerprint? [y/N] ");
+    use std::io::Write;
+    std::io::stdout().flush().ok();
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer).context("Failed to read confirmation")?;
+    if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+        bail!("Refused to trust {admin_url}'s certificate fingerprint");
+    }
+
+    remember_fingerprint(admin_url, &formatted)?;
+    println!("Pinned. Future connections to {admin_url} will be verified against this fingerprint.");
+
+    Ok(formatted)
+}
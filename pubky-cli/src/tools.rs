@@ -1,12 +1,16 @@
 use std::fs::File;
 use std::io::{self, Write};
+use std::net::SocketAddr;
 use std::path::PathBuf;
 
 use anyhow::{Context, Result};
 use clap::{CommandFactory, Subcommand};
 use clap_complete::{Shell, generate};
 
-use crate::{Cli, util::create_recovery_file_on_disk};
+use crate::{
+    Cli, scenario, sftp,
+    util::{build_signer, create_recovery_file_on_disk, load_keypair_from_recovery_file},
+};
 
 #[derive(Subcommand, Debug)]
 pub enum Command {
@@ -26,6 +30,33 @@ pub enum Command {
         #[arg(long)]
         outfile: Option<PathBuf>,
     },
+    /// Run a Lua scenario script against a fresh in-process testnet.
+    RunScenario {
+        /// Path to the `.lua` scenario script to execute.
+        script: PathBuf,
+    },
+    /// Serve a signed-in session's storage over SFTP, e.g. `sftp -P <port> localhost`.
+    ServeSftp {
+        /// Path to the user's recovery file.
+        recovery_file: PathBuf,
+        /// Address to bind the embedded SSH/SFTP server to.
+        #[arg(long, default_value = "127.0.0.1:2222")]
+        bind_addr: SocketAddr,
+        /// Use the local testnet instead of the public network.
+        #[arg(long)]
+        testnet: bool,
+    },
+    /// Time a storage round-trip to measure upload/download throughput.
+    Bench {
+        /// Path to the user's recovery file.
+        recovery_file: PathBuf,
+        /// Size of the random payload to upload/download, in bytes.
+        #[arg(long, default_value_t = 16 * 1024 * 1024)]
+        size: usize,
+        /// Use the local testnet instead of the public network.
+        #[arg(long)]
+        testnet: bool,
+    },
 }
 
 pub async fn run(command: Command) -> Result<()> {
@@ -36,11 +67,106 @@ pub async fn run(command: Command) -> Result<()> {
         Command::Completions { shell, outfile } => {
             emit_completions(shell, outfile)?;
         }
+        Command::RunScenario { script } => {
+            scenario::run_script(&script).await?;
+        }
+        Command::ServeSftp {
+            recovery_file,
+            bind_addr,
+            testnet,
+        } => {
+            sftp::serve_sftp(recovery_file, bind_addr, testnet).await?;
+        }
+        Command::Bench {
+            recovery_file,
+            size,
+            testnet,
+        } => {
+            run_bench(recovery_file, size, testnet).await?;
+        }
     }
 
     Ok(())
 }
 
+async fn run_bench(recovery_file: PathBuf, size: usize, testnet: bool) -> Result<()> {
+    const BENCH_PATH: &str = "/pub/pubky-cli.bench";
+
+    let keypair = load_keypair_from_recovery_file(&recovery_file)
+        .with_context(|| format!("Failed to load recovery file: {}", recovery_file.display()))?;
+    println!("Loaded recovery file for Pubky {}", keypair.public_key());
+
+    let signer = build_signer(testnet, keypair)?;
+    let session = signer.signin().await?;
+    println!("Signed in successfully. Session details:");
+    println!("{:#?}", session.info());
+
+    let storage = session.storage();
+
+    println!("Generating {} byte(s) of random data...", size);
+    let data = generate_random_bytes(size);
+
+    let upload_started = std::time::Instant::now();
+    storage
+        .put(BENCH_PATH.to_string(), reqwest::Body::from(data.clone()))
+        .await
+        .with_context(|| format!("Failed to upload to {}", BENCH_PATH))?;
+    let upload_elapsed = upload_started.elapsed();
+
+    let download_started = std::time::Instant::now();
+    let downloaded = storage
+        .get(BENCH_PATH.to_string())
+        .await
+        .with_context(|| format!("Failed to download {}", BENCH_PATH))?
+        .bytes()
+        .await
+        .with_context(|| "Failed to read downloaded bytes")?;
+    let download_elapsed = download_started.elapsed();
+
+    storage
+        .delete(BENCH_PATH.to_string())
+        .await
+        .with_context(|| format!("Failed to clean up {}", BENCH_PATH))?;
+    session.signout().await.map_err(|(err, _)| err)?;
+
+    if downloaded.as_ref() != data.as_slice() {
+        anyhow::bail!("Downloaded data does not match uploaded data; integrity check failed");
+    }
+
+    let mib = size as f64 / (1024.0 * 1024.0);
+    let upload_mib_s = mib / upload_elapsed.as_secs_f64().max(f64::EPSILON);
+    let download_mib_s = mib / download_elapsed.as_secs_f64().max(f64::EPSILON);
+
+    println!("Upload:   {:.2} MiB/s ({:?})", upload_mib_s, upload_elapsed);
+    println!("Download: {:.2} MiB/s ({:?})", download_mib_s, download_elapsed);
+    println!(
+        "Integrity check passed. Round-trip latency: {:?}",
+        upload_elapsed + download_elapsed
+    );
+
+    Ok(())
+}
+
+/// xorshift64, seeded from the current time. Not cryptographically
+/// relevant - this data only exists to be pushed through the storage API.
+fn generate_random_bytes(len: usize) -> Vec<u8> {
+    let mut state = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64
+        | 1;
+
+    let mut bytes = Vec::with_capacity(len + 8);
+    while bytes.len() < len {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        bytes.extend_from_slice(&state.to_le_bytes());
+    }
+    bytes.truncate(len);
+    bytes
+}
+
 fn generate_recovery(output: PathBuf, passphrase: Option<String>) -> Result<()> {
     let passphrase = match passphrase {
         Some(pass) => pass,
@@ -1,11 +1,185 @@
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use anyhow::{Context, Result};
 use clap::Subcommand;
-use pubky::{PubkyResource, PublicKey};
+use pubky::{PubkyResource, PublicKey, PublicStorage};
+use serde::{Deserialize, Serialize};
 
-use crate::util::{build_pubky, build_signer, load_keypair_from_recovery_file};
+use crate::agent::{self, AgentRequest, AgentResponse};
+use crate::crypto;
+use crate::util::{
+    build_pubky, build_signer, load_keypair_from_recovery_file, remove_session_ticket,
+    save_session_ticket, signin_with_cache,
+};
+
+/// Chunks average ~1MiB, clamped so a single boundary-less run of bytes
+/// (e.g. all zeroes) can't produce a chunk smaller or larger than this.
+const CHUNK_MIN_SIZE: usize = 512 * 1024;
+const CHUNK_MAX_SIZE: usize = 4 * 1024 * 1024;
+const CHUNK_AVG_SIZE: usize = 1024 * 1024;
+const BUZHASH_WINDOW: usize = 64;
+
+const CHUNK_MANIFEST_VERSION: u32 = 1;
+
+/// Largest `data_path` object `get_data --output` will buffer in full to
+/// check whether it's a `ChunkManifest`. A manifest is a JSON list of
+/// chunk hashes, one per chunk of at least `CHUNK_MIN_SIZE` bytes, so this
+/// covers the manifest of any file up to roughly 120 GiB; anything bigger
+/// is treated as raw content, which goes straight to the streaming path
+/// `--output` exists for instead of being buffered.
+const CHUNK_MANIFEST_PROBE_MAX_BYTES: u64 = 16 * 1024 * 1024;
+
+const CATALOG_VERSION: u32 = 1;
+
+/// Recorded about one local file by `Push`, enough for `Pull` to tell
+/// whether its on-disk copy is already current. `hash` is blank for
+/// catalogs written before `Sync` started populating it, or for the
+/// no-catalog listing fallback; `Sync` then treats the entry as changed
+/// rather than risk skipping a real update.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CatalogEntry {
+    path: String,
+    size: u64,
+    modified_unix: u64,
+    #[serde(default)]
+    hash: String,
+}
+
+/// Self-describing manifest written to `<remote_path>/.catalog` by `Push`
+/// so a later `Pull` doesn't have to re-derive the tree from a listing.
+#[derive(Debug, Serialize, Deserialize)]
+struct Catalog {
+    version: u32,
+    entries: Vec<CatalogEntry>,
+}
+
+/// Ordered list of content-addressed chunk hashes that reassemble into the
+/// original file, written to `data_path` in place of the raw bytes by a
+/// `--chunked` publish.
+#[derive(Debug, Serialize, Deserialize)]
+struct ChunkManifest {
+    version: u32,
+    total_len: u64,
+    chunks: Vec<String>,
+}
+
+/// What kind of change `Watch` observed between two successive listings of
+/// the same entry.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ChangeKind {
+    Created,
+    Modified,
+    Deleted,
+}
+
+/// One change event emitted by `Watch`, either printed as a human-readable
+/// line or serialized as-is under `--json`.
+#[derive(Debug, Clone, Serialize)]
+struct ChangeEvent {
+    path: String,
+    kind: ChangeKind,
+    /// The entry's fingerprint after the change; `None` for `Deleted`.
+    fingerprint: Option<String>,
+    timestamp_unix: u64,
+}
+
+const SYNC_MANIFEST_VERSION: u32 = 1;
+
+/// One local file's state as of the last `Sync`, so a later run can skip
+/// rehashing it when its size and mtime haven't changed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SyncManifestEntry {
+    size: u64,
+    modified_unix: u64,
+    hash: String,
+}
+
+/// Cached at `<local_dir>/.pubky-sync`, keyed by slash-separated relative
+/// path.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncManifest {
+    version: u32,
+    #[serde(default)]
+    entries: HashMap<String, SyncManifestEntry>,
+}
+
+/// Which side of a `Sync` is authoritative.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SyncDirection {
+    Push,
+    Pull,
+}
+
+/// One `Search` match: a bare path for a `--path-only` glob hit, or a path
+/// plus the matched line for a content regex hit.
+#[derive(Debug, Clone, Serialize)]
+struct SearchHit {
+    path: String,
+    line_number: Option<usize>,
+    excerpt: Option<String>,
+}
+
+/// Pseudo-random per-byte table for a buzhash rolling hash. Seeded with a
+/// fixed constant so chunk boundaries (and therefore chunk hashes) are
+/// reproducible across runs and machines.
+fn buzhash_table() -> &'static [u32; 256] {
+    static TABLE: OnceLock<[u32; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = [0u32; 256];
+        let mut seed: u32 = 0x9e3779b9;
+        for slot in table.iter_mut() {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            *slot = seed;
+        }
+        table
+    })
+}
+
+/// Splits `data` into content-defined chunks using a rolling buzhash over a
+/// `BUZHASH_WINDOW`-byte window, declaring a boundary whenever the hash's
+/// low bits are zero (mirroring Proxmox's "merge known chunks" scheme so
+/// that a small edit only perturbs the chunk(s) around it). Returns the
+/// exclusive end offset of each chunk.
+fn find_chunk_boundaries(data: &[u8]) -> Vec<usize> {
+    let table = buzhash_table();
+    let mask = (CHUNK_AVG_SIZE - 1) as u32;
+    let mut boundaries = Vec::new();
+    let mut chunk_start = 0usize;
+    let mut hash: u32 = 0;
+
+    for (i, &byte) in data.iter().enumerate() {
+        let pos_in_chunk = i - chunk_start;
+        if pos_in_chunk < BUZHASH_WINDOW {
+            hash = hash.rotate_left(1) ^ table[byte as usize];
+        } else {
+            let outgoing = data[i - BUZHASH_WINDOW];
+            hash = hash.rotate_left(1)
+                ^ table[byte as usize]
+                ^ table[outgoing as usize].rotate_left(BUZHASH_WINDOW as u32 % 32);
+        }
+
+        let chunk_len = pos_in_chunk + 1;
+        let at_boundary = hash & mask == 0;
+        if chunk_len >= CHUNK_MAX_SIZE || (chunk_len >= CHUNK_MIN_SIZE && at_boundary) {
+            boundaries.push(i + 1);
+            chunk_start = i + 1;
+            hash = 0;
+        }
+    }
+
+    if chunk_start < data.len() {
+        boundaries.push(data.len());
+    }
+
+    boundaries
+}
 
 #[derive(Subcommand, Debug)]
 pub enum Command {
@@ -40,6 +214,10 @@ pub enum Command {
         /// Use the public network (default) or local testnet configuration.
         #[arg(long)]
         testnet: bool,
+        /// Reuse (and refresh) the cached session ticket instead of
+        /// signing out when done.
+        #[arg(long)]
+        use_session: bool,
     },
     /// Sign out of the homeserver for a given Pubky.
     Signout {
@@ -48,6 +226,27 @@ pub enum Command {
         /// Use the public network (default) or local testnet configuration.
         #[arg(long)]
         testnet: bool,
+        /// Reuse the cached session ticket for the handshake, if present.
+        #[arg(long)]
+        use_session: bool,
+    },
+    /// Sign in once and cache the session ticket at
+    /// `$XDG_RUNTIME_DIR/pubky/<pubkey>.session` for later `--use-session`
+    /// commands to reuse.
+    Login {
+        /// Path to the user's recovery file.
+        recovery_file: PathBuf,
+        /// Use the public network (default) or local testnet configuration.
+        #[arg(long)]
+        testnet: bool,
+    },
+    /// Invalidate and remove the cached session ticket for a Pubky.
+    Logout {
+        /// Path to the user's recovery file.
+        recovery_file: PathBuf,
+        /// Use the public network (default) or local testnet configuration.
+        #[arg(long)]
+        testnet: bool,
     },
     /// List storage entries at a Pubky URL.
     List {
@@ -90,6 +289,21 @@ pub enum Command {
         /// Use the public network (default) or local testnet configuration.
         #[arg(long)]
         testnet: bool,
+        /// Split the file into content-defined chunks and only upload the
+        /// ones the homeserver doesn't already have, writing a manifest to
+        /// `data_path` instead of the raw bytes. Resumes/deduplicates across
+        /// re-publishes of a changed file.
+        #[arg(long)]
+        chunked: bool,
+        /// Reuse (and refresh) the cached session ticket instead of
+        /// signing in and out again.
+        #[arg(long)]
+        use_session: bool,
+        /// Seal the file with a key derived from the recovery keypair
+        /// before uploading, so the homeserver only ever sees ciphertext.
+        /// Read back with `user get --decrypt`.
+        #[arg(long)]
+        encrypt: bool,
     },
     /// Delete data at a Pubky URL.
     Delete {
@@ -100,6 +314,10 @@ pub enum Command {
         /// Use the public network (default) or local testnet configuration.
         #[arg(long)]
         testnet: bool,
+        /// Reuse (and refresh) the cached session ticket instead of
+        /// signing in and out again.
+        #[arg(long)]
+        use_session: bool,
     },
     /// Get data to a Pubky URL from a file.
     Get {
@@ -110,6 +328,133 @@ pub enum Command {
         /// Use the public network (default) or local testnet configuration.
         #[arg(long)]
         testnet: bool,
+        /// Reuse (and refresh) the cached session ticket instead of
+        /// signing in and out again.
+        #[arg(long)]
+        use_session: bool,
+        /// Stream the response body to this file instead of printing it to
+        /// stdout, so binary files (images, archives) aren't corrupted.
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// With `--output`, resume a partial download by requesting only
+        /// the bytes missing from the existing file.
+        #[arg(long)]
+        resume: bool,
+        /// Open the object as data sealed by `user publish --encrypt`,
+        /// using a key derived from the recovery keypair. Fails clearly if
+        /// the object doesn't carry the expected header.
+        #[arg(long)]
+        decrypt: bool,
+    },
+    /// Mirror a local directory tree to a Pubky path, e.g. for backups.
+    Push {
+        /// Local directory to mirror.
+        local_dir: PathBuf,
+        /// Remote path to mirror into, e.g. "/pub/backup"
+        remote_path: String,
+        /// Path to the user's recovery file.
+        recovery_file: PathBuf,
+        /// Use the public network (default) or local testnet configuration.
+        #[arg(long)]
+        testnet: bool,
+    },
+    /// Mirror a Pubky path previously written by `Push` back to a local directory.
+    Pull {
+        /// Remote Pubky URL to mirror from, e.g. pubky://<pubky>/pub/backup
+        remote_path: String,
+        /// Local directory to mirror into.
+        local_dir: PathBuf,
+        /// Use the public network (default) or local testnet configuration.
+        #[arg(long)]
+        testnet: bool,
+    },
+    /// Poll a Pubky URL prefix and stream Created/Modified/Deleted events as
+    /// its listing changes, until interrupted with Ctrl-C.
+    Watch {
+        /// Pubky URL prefix to watch, e.g. pubky://<pubky>/pub/app/
+        prefix: String,
+        /// Path to the user's recovery file.
+        recovery_file: PathBuf,
+        /// Use the public network (default) or local testnet configuration.
+        #[arg(long)]
+        testnet: bool,
+        /// Shallow listing (does not recurse into nested directories).
+        #[arg(long)]
+        shallow: bool,
+        /// Milliseconds between successive listings.
+        #[arg(long, default_value_t = 2000)]
+        poll_ms: u64,
+        /// Suppress a second event for the same entry until this many
+        /// milliseconds have passed without a further change to it.
+        #[arg(long, default_value_t = 500)]
+        debounce_ms: u64,
+        /// Write one JSON object per event to stdout instead of a
+        /// human-readable line, so scripts can consume it.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Recursively reconcile a local directory with a Pubky storage prefix,
+    /// applying only the put/delete operations needed to bring one side in
+    /// line with the other.
+    Sync {
+        /// Local directory to reconcile.
+        local_dir: PathBuf,
+        /// Remote path to reconcile with, e.g. "/pub/app"
+        remote_path: String,
+        /// Path to the user's recovery file.
+        recovery_file: PathBuf,
+        /// Use the public network (default) or local testnet configuration.
+        #[arg(long)]
+        testnet: bool,
+        /// Upload local changes to remote; combine with `--delete` to also
+        /// prune remote entries missing locally.
+        #[arg(long)]
+        push: bool,
+        /// Download remote changes to local; combine with `--delete` to
+        /// also prune local files missing remotely.
+        #[arg(long)]
+        pull: bool,
+        /// Make remote an exact copy of local: like `--push`, but always
+        /// pruning remote entries missing locally.
+        #[arg(long)]
+        mirror: bool,
+        /// Prune entries missing on the source side. Ignored (and implied)
+        /// with `--mirror`.
+        #[arg(long)]
+        delete: bool,
+        /// Print the planned put/delete operations without performing them.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Recursively search a Pubky URL prefix by path glob or file content
+    /// regex, grep-style.
+    Search {
+        /// Glob (with `--path-only`) or regex (content search) to match.
+        pattern: String,
+        /// Pubky URL prefix to search under, e.g. pubky://<pubky>/pub/
+        prefix: String,
+        /// Path to the user's recovery file.
+        recovery_file: PathBuf,
+        /// Use the public network (default) or local testnet configuration.
+        #[arg(long)]
+        testnet: bool,
+        /// Match `pattern` as a glob against entry paths instead of
+        /// fetching and regex-matching their content.
+        #[arg(long)]
+        path_only: bool,
+        /// Stop after this many hits.
+        #[arg(long)]
+        max_results: Option<usize>,
+        /// Skip content matching for entries larger than this many bytes.
+        #[arg(long)]
+        max_size: Option<u64>,
+        /// Case-insensitive matching.
+        #[arg(long)]
+        ignore_case: bool,
+        /// Write one JSON object per hit to stdout instead of a
+        /// human-readable line.
+        #[arg(long)]
+        json: bool,
     },
 }
 
@@ -129,11 +474,21 @@ pub async fn run(command: Command) -> Result<()> {
         Command::Session {
             recovery_file,
             testnet,
-        } => fetch_session(recovery_file, testnet).await?,
+            use_session,
+        } => fetch_session(recovery_file, testnet, use_session).await?,
         Command::Signout {
             recovery_file,
             testnet,
-        } => signout_user(recovery_file, testnet).await?,
+            use_session,
+        } => signout_user(recovery_file, testnet, use_session).await?,
+        Command::Login {
+            recovery_file,
+            testnet,
+        } => login(recovery_file, testnet).await?,
+        Command::Logout {
+            recovery_file,
+            testnet,
+        } => logout(recovery_file, testnet).await?,
         Command::List {
             url,
             reverse,
@@ -152,17 +507,67 @@ pub async fn run(command: Command) -> Result<()> {
             file,
             recovery_file,
             testnet,
-        } => publish_data(data_path, file, recovery_file, testnet).await?,
+            chunked,
+            use_session,
+            encrypt,
+        } => publish_data(data_path, file, recovery_file, testnet, chunked, use_session, encrypt).await?,
         Command::Delete {
             data_path,
             recovery_file,
             testnet,
-        } => delete_data(data_path, recovery_file, testnet).await?,
+            use_session,
+        } => delete_data(data_path, recovery_file, testnet, use_session).await?,
         Command::Get {
             data_path,
             recovery_file,
             testnet,
-        } => get_data(data_path, recovery_file, testnet).await?,
+            use_session,
+            output,
+            resume,
+            decrypt,
+        } => get_data(data_path, recovery_file, testnet, use_session, output, resume, decrypt).await?,
+        Command::Push {
+            local_dir,
+            remote_path,
+            recovery_file,
+            testnet,
+        } => push_directory(local_dir, remote_path, recovery_file, testnet).await?,
+        Command::Pull {
+            remote_path,
+            local_dir,
+            testnet,
+        } => pull_directory(remote_path, local_dir, testnet).await?,
+        Command::Watch {
+            prefix,
+            recovery_file,
+            testnet,
+            shallow,
+            poll_ms,
+            debounce_ms,
+            json,
+        } => watch_prefix(prefix, recovery_file, testnet, shallow, poll_ms, debounce_ms, json).await?,
+        Command::Sync {
+            local_dir,
+            remote_path,
+            recovery_file,
+            testnet,
+            push,
+            pull,
+            mirror,
+            delete,
+            dry_run,
+        } => sync_directory(local_dir, remote_path, recovery_file, testnet, push, pull, mirror, delete, dry_run).await?,
+        Command::Search {
+            pattern,
+            prefix,
+            recovery_file,
+            testnet,
+            path_only,
+            max_results,
+            max_size,
+            ignore_case,
+            json,
+        } => search_prefix(pattern, prefix, recovery_file, testnet, path_only, max_results, max_size, ignore_case, json).await?,
     }
 
     Ok(())
@@ -209,37 +614,88 @@ async fn signin_user(recovery_file: PathBuf, testnet: bool, sync_publish: bool)
     Ok(())
 }
 
-async fn signout_user(recovery_file: PathBuf, testnet: bool) -> Result<()> {
+async fn signout_user(recovery_file: PathBuf, testnet: bool, use_session: bool) -> Result<()> {
     let keypair = load_keypair_from_recovery_file(&recovery_file)?;
     println!("Loaded recovery file for Pubky {}", keypair.public_key());
+    let pubkey = keypair.public_key();
 
-    let signer = build_signer(testnet, keypair)?;
-    let session = signer.signin().await?;
+    let session = signin_with_cache(testnet, keypair, use_session).await?;
     let user_pubkey = session.info().public_key().clone();
 
     session.signout().await.map_err(|(err, _)| err)?;
+    remove_session_ticket(&pubkey)?;
 
     println!("Signed out of homeserver for {}", user_pubkey);
 
     Ok(())
 }
 
-async fn fetch_session(recovery_file: PathBuf, testnet: bool) -> Result<()> {
+async fn fetch_session(recovery_file: PathBuf, testnet: bool, use_session: bool) -> Result<()> {
     let keypair = load_keypair_from_recovery_file(&recovery_file)?;
     println!("Loaded recovery file for Pubky {}", keypair.public_key());
+    let pubkey = keypair.public_key().to_string();
 
-    let signer = build_signer(testnet, keypair)?;
-    let session = signer.signin().await?;
+    let request = AgentRequest::SessionInfo { pubkey };
+    if let Some(AgentResponse::Info(info)) = agent::dispatch_if_running(request).await {
+        println!("Session information (from running agent, no signin needed):");
+        println!("{info}");
+        return Ok(());
+    }
+
+    let session = signin_with_cache(testnet, keypair, use_session).await?;
 
     println!("Session information:");
     println!("{:#?}", session.info());
 
+    if use_session {
+        println!("Session left open for reuse via --use-session.");
+    } else {
+        session.signout().await.map_err(|(err, _)| err)?;
+        println!("Session closed.");
+    }
+
+    Ok(())
+}
+
+/// Signs in once and caches the session ticket so later `--use-session`
+/// commands skip the recovery-file handshake.
+async fn login(recovery_file: PathBuf, testnet: bool) -> Result<()> {
+    let keypair = load_keypair_from_recovery_file(&recovery_file)
+        .with_context(|| format!("Failed to load recovery file: {}", recovery_file.display()))?;
+    println!("Loaded recovery file for Pubky {}", keypair.public_key());
+    let pubkey = keypair.public_key();
+
+    let signer = build_signer(testnet, keypair)?;
+    let session = signer.signin().await?;
+
+    let path = save_session_ticket(&pubkey, &session)?;
+    println!(
+        "Signed in successfully. Session ticket cached at {}",
+        path.display()
+    );
+
+    Ok(())
+}
+
+/// Invalidates and removes the cached session ticket for a Pubky.
+async fn logout(recovery_file: PathBuf, testnet: bool) -> Result<()> {
+    let keypair = load_keypair_from_recovery_file(&recovery_file)
+        .with_context(|| format!("Failed to load recovery file: {}", recovery_file.display()))?;
+    println!("Loaded recovery file for Pubky {}", keypair.public_key());
+    let pubkey = keypair.public_key();
+
+    let session = signin_with_cache(testnet, keypair, true).await?;
     session.signout().await.map_err(|(err, _)| err)?;
-    println!("Session closed.");
+    remove_session_ticket(&pubkey)?;
+
+    println!("Signed out and removed cached session ticket for {}", pubkey);
 
     Ok(())
 }
 
+// `list` has no recovery-file/identity argument at all — it only lists
+// public storage — so there's no pubkey to key an agent-held session by,
+// and this command is not wired to `pubky-cli agent`.
 async fn list_resources(
     url: String,
     reverse: bool,
@@ -309,48 +765,157 @@ async fn publish_data(
     file: PathBuf,
     recovery_file: PathBuf,
     testnet: bool,
+    chunked: bool,
+    use_session: bool,
+    encrypt: bool,
 ) -> Result<()> {
     // Load the recovery file and sign in to get a session
     let keypair = load_keypair_from_recovery_file(&recovery_file)
         .with_context(|| format!("Failed to load recovery file: {}", recovery_file.display()))?;
     println!("Loaded recovery file for Pubky {}", keypair.public_key());
-
-    let signer = build_signer(testnet, keypair)?;
-    let session = signer.signin().await?;
-    println!("Signed in successfully. Session details:");
-    println!("{:#?}", session.info());
+    let pubkey = keypair.public_key().to_string();
 
     // Read the file data
-    let data = tokio::fs::read(&file)
+    let mut data = tokio::fs::read(&file)
         .await
         .with_context(|| format!("Failed to read file: {}", file.display()))?;
 
+    if encrypt {
+        data = crypto::seal(&keypair.secret_key(), &data);
+        println!("Sealed {} with a key derived from the recovery keypair.", file.display());
+    }
+
+    // A running agent only holds a single resumed session, so chunked
+    // publishes (which read back the chunk directory first) still go
+    // through the direct path below.
+    if !chunked {
+        let request = AgentRequest::Publish { pubkey, path: data_path.clone(), data: data.clone() };
+        match agent::dispatch_if_running(request).await {
+            Some(AgentResponse::Ok) => {
+                println!("Data published successfully to {} (via running agent)", data_path);
+                return Ok(());
+            }
+            Some(AgentResponse::Error(e)) => {
+                println!("Agent couldn't publish ({e}); falling back to direct signin.");
+            }
+            _ => {}
+        }
+    }
+
+    let session = signin_with_cache(testnet, keypair, use_session).await?;
+    println!("Signed in successfully. Session details:");
+    println!("{:#?}", session.info());
+
     // Get the storage object from the session
     let storage = session.storage();
 
-    // Use the `put` method to upload the data
-    storage
-        .put(data_path.to_string(), reqwest::Body::from(data))
-        .await
-        .with_context(|| "Failed to publish data")?;
+    if chunked {
+        // Content-defined chunking: only upload the chunks the homeserver
+        // doesn't already hold, then publish a manifest in place of the
+        // raw bytes (see `find_chunk_boundaries`).
+        let chunks_dir = format!("{}.chunks", data_path);
 
-    println!("Data published successfully to {}", data_path);
+        let mut existing_hashes: HashSet<String> = HashSet::new();
+        if let Ok(builder) = storage.list(format!("{}/", chunks_dir)) {
+            if let Ok(entries) = builder.shallow(true).send().await {
+                for entry in entries {
+                    if let Some(hash) = entry.to_pubky_url().rsplit('/').next() {
+                        existing_hashes.insert(hash.to_string());
+                    }
+                }
+            }
+        }
 
-    // Sign out after publishing
-    session.signout().await.map_err(|(err, _)| err)?;
-    println!("Signed out successfully.");
+        let boundaries = find_chunk_boundaries(&data);
+        let mut chunk_hashes = Vec::with_capacity(boundaries.len());
+        let mut uploaded = 0usize;
+        let mut start = 0usize;
+
+        for end in boundaries {
+            let chunk = &data[start..end];
+            let hash = blake3::hash(chunk).to_hex().to_string();
+
+            if !existing_hashes.contains(&hash) {
+                let chunk_path = format!("{}/{}", chunks_dir, hash);
+                storage
+                    .put(chunk_path, reqwest::Body::from(chunk.to_vec()))
+                    .await
+                    .with_context(|| format!("Failed to upload chunk {}", hash))?;
+                uploaded += 1;
+            }
+
+            chunk_hashes.push(hash);
+            start = end;
+        }
+
+        let manifest = ChunkManifest {
+            version: CHUNK_MANIFEST_VERSION,
+            total_len: data.len() as u64,
+            chunks: chunk_hashes,
+        };
+        let manifest_json =
+            serde_json::to_vec(&manifest).context("Failed to serialize chunk manifest")?;
+
+        storage
+            .put(data_path.to_string(), reqwest::Body::from(manifest_json))
+            .await
+            .with_context(|| "Failed to publish chunk manifest")?;
+
+        println!(
+            "Published {} chunk(s) ({} new, {} already present) for {} ({} bytes)",
+            manifest.chunks.len(),
+            uploaded,
+            manifest.chunks.len() - uploaded,
+            data_path,
+            manifest.total_len
+        );
+    } else {
+        // Use the `put` method to upload the data
+        storage
+            .put(data_path.to_string(), reqwest::Body::from(data))
+            .await
+            .with_context(|| "Failed to publish data")?;
+
+        println!("Data published successfully to {}", data_path);
+    }
+
+    // Sign out after publishing, unless the session is being kept alive
+    // for reuse by a later `--use-session` command.
+    if use_session {
+        println!("Session left open for reuse via --use-session.");
+    } else {
+        session.signout().await.map_err(|(err, _)| err)?;
+        println!("Signed out successfully.");
+    }
 
     Ok(())
 }
 
-async fn delete_data(data_path: String, recovery_file: PathBuf, testnet: bool) -> Result<()> {
+async fn delete_data(
+    data_path: String,
+    recovery_file: PathBuf,
+    testnet: bool,
+    use_session: bool,
+) -> Result<()> {
     // Load the recovery file and sign in to get a session
     let keypair = load_keypair_from_recovery_file(&recovery_file)
         .with_context(|| format!("Failed to load recovery file: {}", recovery_file.display()))?;
     println!("Loaded recovery file for Pubky {}", keypair.public_key());
+    let pubkey = keypair.public_key().to_string();
 
-    let signer = build_signer(testnet, keypair)?;
-    let session = signer.signin().await?;
+    let request = AgentRequest::Delete { pubkey, path: data_path.clone() };
+    match agent::dispatch_if_running(request).await {
+        Some(AgentResponse::Ok) => {
+            println!("Data deleted successfully at {} (via running agent)", data_path);
+            return Ok(());
+        }
+        Some(AgentResponse::Error(e)) => {
+            println!("Agent couldn't delete ({e}); falling back to direct signin.");
+        }
+        _ => {}
+    }
+
+    let session = signin_with_cache(testnet, keypair, use_session).await?;
     println!("Signed in successfully. Session details:");
     println!("{:#?}", session.info());
 
@@ -365,15 +930,687 @@ async fn delete_data(data_path: String, recovery_file: PathBuf, testnet: bool) -
 
     println!("Data deleted successfully at {}", data_path);
 
-    // Sign out after deleting
+    // Sign out after deleting, unless the session is being kept alive for
+    // reuse by a later `--use-session` command.
+    if use_session {
+        println!("Session left open for reuse via --use-session.");
+    } else {
+        session.signout().await.map_err(|(err, _)| err)?;
+        println!("Signed out successfully.");
+    }
+
+    Ok(())
+}
+
+async fn get_data(
+    data_path: String,
+    recovery_file: PathBuf,
+    testnet: bool,
+    use_session: bool,
+    output: Option<PathBuf>,
+    resume: bool,
+    decrypt: bool,
+) -> Result<()> {
+    // Load the recovery file and sign in to get a session
+    let keypair = load_keypair_from_recovery_file(&recovery_file)
+        .with_context(|| format!("Failed to load recovery file: {}", recovery_file.display()))?;
+    println!("Loaded recovery file for Pubky {}", keypair.public_key());
+    let pubkey = keypair.public_key().to_string();
+
+    if decrypt && resume {
+        // Resuming mid-ciphertext would restart `crypto::Decryptor` at a byte
+        // offset that doesn't line up with a record boundary, and the agent
+        // protocol has no way to persist streaming decryption state across
+        // invocations either way — unsupported for now.
+        anyhow::bail!("--decrypt cannot be combined with --resume");
+    }
+
+    // Streaming `--output`/`--resume` downloads use range requests, which the
+    // agent protocol (a single buffered `Vec<u8>` response) can't express, so
+    // they always go through the direct path below. The in-memory fetch can
+    // use a running agent, but only when the body isn't a chunk manifest and
+    // no decryption is needed — reassembling chunks or deriving the content
+    // key both need the recovery keypair that only the direct path has handy.
+    if output.is_none() && !decrypt {
+        let request = AgentRequest::Get { pubkey: pubkey.clone(), path: data_path.clone() };
+        if let Some(AgentResponse::Data(body)) = agent::dispatch_if_running(request).await {
+            if serde_json::from_slice::<ChunkManifest>(&body).is_err() {
+                let data_text = String::from_utf8_lossy(&body);
+                println!("Data at {} (via running agent): {}", data_path, data_text);
+                return Ok(());
+            }
+        }
+    }
+
+    let secret_key = keypair.secret_key();
+    let session = signin_with_cache(testnet, keypair, use_session).await?;
+    println!("Signed in successfully. Session details:");
+    println!("{:#?}", session.info());
+
+    // Get the storage object from the session
+    let storage = session.storage();
+
+    if let Some(output_path) = output {
+        // Stream the body straight to disk instead of buffering a `String`,
+        // so large/binary files don't blow up memory or mangle bytes.
+        use tokio::io::AsyncWriteExt;
+
+        let mut decryptor = decrypt.then(|| crypto::Decryptor::new(&secret_key));
+
+        let resume_offset = if resume {
+            tokio::fs::metadata(&output_path)
+                .await
+                .map(|meta| meta.len())
+                .unwrap_or(0)
+        } else {
+            0
+        };
+
+        // Probe `data_path` unranged first: a `--chunked` publish leaves a
+        // `ChunkManifest` there instead of the raw bytes, and the only way
+        // to tell the two apart is to look at the object itself (see
+        // `CHUNK_MANIFEST_PROBE_MAX_BYTES`).
+        let probe = storage
+            .get(data_path.to_string())
+            .await
+            .with_context(|| format!("Failed to get {}", data_path))?;
+        let (manifest, small_raw_body) = if probe
+            .content_length()
+            .is_some_and(|len| len <= CHUNK_MANIFEST_PROBE_MAX_BYTES)
+        {
+            let body = probe
+                .bytes()
+                .await
+                .with_context(|| format!("Failed to read {}", data_path))?;
+            match serde_json::from_slice::<ChunkManifest>(&body) {
+                Ok(manifest) => (Some(manifest), None),
+                Err(_) => (None, Some(body)),
+            }
+        } else {
+            (None, None)
+        };
+
+        let mut file = if resume_offset > 0 {
+            tokio::fs::OpenOptions::new()
+                .append(true)
+                .open(&output_path)
+                .await
+                .with_context(|| format!("Failed to open {} for append", output_path.display()))?
+        } else {
+            tokio::fs::File::create(&output_path)
+                .await
+                .with_context(|| format!("Failed to create {}", output_path.display()))?
+        };
+
+        let started = std::time::Instant::now();
+        let mut written = 0u64;
+
+        if let Some(manifest) = manifest {
+            // The manifest doesn't record per-chunk sizes, so a resume
+            // offset has to be mapped onto chunk boundaries by reading each
+            // preceding chunk's Content-Length header (without downloading
+            // its body): whole chunks entirely below the offset are
+            // skipped, the chunk straddling it is range-requested for its
+            // remainder, and every chunk after that is downloaded in full.
+            let mut skip_bytes = resume_offset;
+            for hash in &manifest.chunks {
+                let chunk_path = format!("{}.chunks/{}", data_path, hash);
+
+                let mut response = if skip_bytes > 0 {
+                    let len = storage
+                        .get(chunk_path.clone())
+                        .await
+                        .with_context(|| format!("Failed to get chunk {}", hash))?
+                        .content_length()
+                        .with_context(|| {
+                            format!("Chunk {} has no Content-Length, cannot resume into a chunked file", hash)
+                        })?;
+                    if skip_bytes >= len {
+                        skip_bytes -= len;
+                        continue;
+                    }
+                    let response = storage
+                        .get(chunk_path.clone())
+                        .range(skip_bytes..)
+                        .await
+                        .with_context(|| format!("Failed to get chunk {}", hash))?;
+                    skip_bytes = 0;
+                    response
+                } else {
+                    storage
+                        .get(chunk_path.clone())
+                        .await
+                        .with_context(|| format!("Failed to get chunk {}", hash))?
+                };
+
+                while let Some(chunk) = response
+                    .chunk()
+                    .await
+                    .with_context(|| format!("Failed to read chunk {}", hash))?
+                {
+                    let plaintext = match &mut decryptor {
+                        Some(decryptor) => decryptor.feed(&chunk)?,
+                        None => chunk.to_vec(),
+                    };
+                    file.write_all(&plaintext)
+                        .await
+                        .with_context(|| format!("Failed to write to {}", output_path.display()))?;
+                    written += plaintext.len() as u64;
+                }
+            }
+        } else if let Some(body) = small_raw_body {
+            // Already buffered in full while probing for a manifest above
+            // (it was small enough), so resuming is just slicing rather
+            // than a second round-trip.
+            let remaining = &body[(resume_offset as usize).min(body.len())..];
+            let plaintext = match &mut decryptor {
+                Some(decryptor) => decryptor.feed(remaining)?,
+                None => remaining.to_vec(),
+            };
+            file.write_all(&plaintext)
+                .await
+                .with_context(|| format!("Failed to write to {}", output_path.display()))?;
+            written += plaintext.len() as u64;
+        } else {
+            // Too large to have been buffered above: re-request, applying
+            // `--resume`'s range directly to the raw body this time.
+            let mut request = storage.get(data_path.to_string());
+            if resume_offset > 0 {
+                request = request.range(resume_offset..);
+            }
+
+            let mut response = request
+                .await
+                .with_context(|| format!("Failed to get {}", data_path))?;
+
+            while let Some(chunk) = response
+                .chunk()
+                .await
+                .with_context(|| format!("Failed to read response body for {}", data_path))?
+            {
+                let plaintext = match &mut decryptor {
+                    Some(decryptor) => decryptor.feed(&chunk)?,
+                    None => chunk.to_vec(),
+                };
+                file.write_all(&plaintext)
+                    .await
+                    .with_context(|| format!("Failed to write to {}", output_path.display()))?;
+                written += plaintext.len() as u64;
+            }
+        }
+
+        if let Some(mut decryptor) = decryptor {
+            let plaintext = decryptor.finish()?;
+            file.write_all(&plaintext)
+                .await
+                .with_context(|| format!("Failed to write to {}", output_path.display()))?;
+            written += plaintext.len() as u64;
+        }
+        file.flush().await?;
+
+        let elapsed_secs = started.elapsed().as_secs_f64().max(f64::EPSILON);
+        let mib_per_sec = (written as f64 / (1024.0 * 1024.0)) / elapsed_secs;
+        if resume_offset > 0 {
+            println!(
+                "Resumed from byte {}, wrote {} more byte(s) to {} ({:.2} MiB/s)",
+                resume_offset,
+                written,
+                output_path.display(),
+                mib_per_sec
+            );
+        } else {
+            println!(
+                "Wrote {} byte(s) to {} ({:.2} MiB/s)",
+                written,
+                output_path.display(),
+                mib_per_sec
+            );
+        }
+    } else {
+        // Use the `get` method to get the data
+        let body = storage
+            .get(data_path.to_string())
+            .await
+            .with_context(|| "Failed to get data")?
+            .bytes()
+            .await
+            .with_context(|| "Transform data to bytes")?;
+
+        // If the body is a chunk manifest (written by `publish --chunked`),
+        // fetch each referenced chunk and reassemble the original file.
+        let data = match serde_json::from_slice::<ChunkManifest>(&body) {
+            Ok(manifest) => {
+                let mut reassembled = Vec::with_capacity(manifest.total_len as usize);
+                for hash in &manifest.chunks {
+                    let chunk_path = format!("{}.chunks/{}", data_path, hash);
+                    let chunk = storage
+                        .get(chunk_path)
+                        .await
+                        .with_context(|| format!("Failed to fetch chunk {}", hash))?
+                        .bytes()
+                        .await
+                        .with_context(|| format!("Failed to read chunk {}", hash))?;
+                    reassembled.extend_from_slice(&chunk);
+                }
+                reassembled
+            }
+            Err(_) => body.to_vec(),
+        };
+
+        let data = if decrypt {
+            crypto::open(&secret_key, &data)
+                .with_context(|| format!("Failed to decrypt {}", data_path))?
+        } else {
+            data
+        };
+        let data_text = String::from_utf8_lossy(&data);
+
+        println!("Data at {}: {}", data_path, data_text);
+    }
+
+    // Sign out after getting data, unless the session is being kept alive
+    // for reuse by a later `--use-session` command.
+    if use_session {
+        println!("Session left open for reuse via --use-session.");
+    } else {
+        session.signout().await.map_err(|(err, _)| err)?;
+        println!("Signed out successfully.");
+    }
+
+    Ok(())
+}
+
+async fn push_directory(
+    local_dir: PathBuf,
+    remote_path: String,
+    recovery_file: PathBuf,
+    testnet: bool,
+) -> Result<()> {
+    // Load the recovery file and sign in to get a session
+    let keypair = load_keypair_from_recovery_file(&recovery_file)
+        .with_context(|| format!("Failed to load recovery file: {}", recovery_file.display()))?;
+    println!("Loaded recovery file for Pubky {}", keypair.public_key());
+
+    let signer = build_signer(testnet, keypair)?;
+    let session = signer.signin().await?;
+    println!("Signed in successfully. Session details:");
+    println!("{:#?}", session.info());
+
+    let storage = session.storage();
+    let remote_root = remote_path.trim_end_matches('/').to_string();
+
+    let files = collect_local_files(&local_dir)
+        .with_context(|| format!("Failed to walk directory: {}", local_dir.display()))?;
+
+    let mut entries = Vec::with_capacity(files.len());
+    for file in &files {
+        let rel_path = relative_slash_path(&local_dir, file)
+            .with_context(|| format!("Failed to compute relative path for {}", file.display()))?;
+
+        let metadata = std::fs::metadata(file)
+            .with_context(|| format!("Failed to stat file: {}", file.display()))?;
+        let modified_unix = metadata
+            .modified()
+            .with_context(|| format!("Failed to read mtime for: {}", file.display()))?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let data = tokio::fs::read(file)
+            .await
+            .with_context(|| format!("Failed to read file: {}", file.display()))?;
+        let size = data.len() as u64;
+        let hash = blake3::hash(&data).to_hex().to_string();
+
+        let remote_file_path = format!("{}/{}", remote_root, rel_path);
+        storage
+            .put(remote_file_path.clone(), reqwest::Body::from(data))
+            .await
+            .with_context(|| format!("Failed to push file: {}", remote_file_path))?;
+
+        entries.push(CatalogEntry {
+            path: rel_path,
+            size,
+            modified_unix,
+            hash,
+        });
+    }
+
+    let catalog = Catalog {
+        version: CATALOG_VERSION,
+        entries,
+    };
+    let catalog_json = serde_json::to_vec(&catalog).context("Failed to serialize catalog")?;
+    let catalog_path = format!("{}/.catalog", remote_root);
+    storage
+        .put(catalog_path.clone(), reqwest::Body::from(catalog_json))
+        .await
+        .with_context(|| format!("Failed to publish catalog: {}", catalog_path))?;
+
+    println!(
+        "Pushed {} file(s) from {} to {}",
+        catalog.entries.len(),
+        local_dir.display(),
+        remote_root
+    );
+
     session.signout().await.map_err(|(err, _)| err)?;
     println!("Signed out successfully.");
 
     Ok(())
 }
 
-async fn get_data(data_path: String, recovery_file: PathBuf, testnet: bool) -> Result<()> {
-    // Load the recovery file and sign in to get a session
+async fn pull_directory(remote_path: String, local_dir: PathBuf, testnet: bool) -> Result<()> {
+    let facade = build_pubky(testnet)?;
+    let storage = facade.public_storage();
+    let remote_root = remote_path.trim_end_matches('/').to_string();
+
+    tokio::fs::create_dir_all(&local_dir)
+        .await
+        .with_context(|| format!("Failed to create directory: {}", local_dir.display()))?;
+
+    let catalog_path = format!("{}/.catalog", remote_root);
+    let catalog = match storage.get(catalog_path.clone()).await {
+        Ok(response) => {
+            let bytes = response
+                .bytes()
+                .await
+                .with_context(|| format!("Failed to read catalog: {}", catalog_path))?;
+            serde_json::from_slice::<Catalog>(&bytes).ok()
+        }
+        Err(_) => None,
+    };
+
+    let entries = match catalog {
+        Some(catalog) => catalog.entries,
+        None => {
+            println!(
+                "No catalog found at {}, falling back to directory listing",
+                catalog_path
+            );
+            let resource: PubkyResource = remote_root
+                .parse()
+                .with_context(|| format!("Pull URL must be pubky://<user>/<path>: {}", remote_root))?;
+            let listed = storage.list(resource)?.send().await?;
+            listed
+                .iter()
+                .filter_map(|entry| {
+                    let url = entry.to_pubky_url();
+                    url.strip_prefix(&format!("{}/", remote_root))
+                        .map(|rel| CatalogEntry {
+                            path: rel.to_string(),
+                            size: 0,
+                            modified_unix: 0,
+                            hash: String::new(),
+                        })
+                })
+                .filter(|entry| entry.path != ".catalog")
+                .collect()
+        }
+    };
+
+    let mut pulled = 0usize;
+    let mut skipped = 0usize;
+    for entry in &entries {
+        let local_path = local_dir.join(&entry.path);
+        if let Some(parent) = local_path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+
+        if entry_matches_disk(&local_path, entry) {
+            skipped += 1;
+            continue;
+        }
+
+        let remote_file_url = format!("{}/{}", remote_root, entry.path);
+        let data = storage
+            .get(remote_file_url.clone())
+            .await
+            .with_context(|| format!("Failed to fetch: {}", remote_file_url))?
+            .bytes()
+            .await
+            .with_context(|| format!("Failed to read: {}", remote_file_url))?;
+
+        tokio::fs::write(&local_path, &data)
+            .await
+            .with_context(|| format!("Failed to write: {}", local_path.display()))?;
+        pulled += 1;
+    }
+
+    println!(
+        "Pulled {} file(s), skipped {} already up to date, into {}",
+        pulled,
+        skipped,
+        local_dir.display()
+    );
+
+    Ok(())
+}
+
+/// Polls `prefix`'s listing every `poll_ms` and diffs it against the
+/// previous poll to emit `Created`/`Modified`/`Deleted` events, debouncing
+/// rapid repeat changes to the same entry, until Ctrl-C.
+///
+/// The listing API only exposes an entry's URL, not its ETag or
+/// size/mtime (unlike `Push`/`Pull`'s own `.catalog`, which is written by
+/// this same CLI), so a fingerprint is read off the headers of a plain
+/// `storage.get` of the entry instead of a cheaper HEAD-style call.
+async fn watch_prefix(
+    prefix: String,
+    recovery_file: PathBuf,
+    testnet: bool,
+    shallow: bool,
+    poll_ms: u64,
+    debounce_ms: u64,
+    json: bool,
+) -> Result<()> {
+    let keypair = load_keypair_from_recovery_file(&recovery_file)
+        .with_context(|| format!("Failed to load recovery file: {}", recovery_file.display()))?;
+    println!("Loaded recovery file for Pubky {}", keypair.public_key());
+
+    let session = signin_with_cache(testnet, keypair, true).await?;
+    let storage = session.storage();
+
+    println!("Watching {} (poll every {}ms, Ctrl-C to stop)...", prefix, poll_ms);
+
+    let mut snapshot: HashMap<String, String> = HashMap::new();
+    let mut pending: HashMap<String, (ChangeEvent, Instant)> = HashMap::new();
+    let mut interval = tokio::time::interval(Duration::from_millis(poll_ms.max(1)));
+
+    let config_path = crate::config::resolve_path();
+    let mut config = crate::config::Config::load(&config_path)?;
+    let mut config_mtime = std::fs::metadata(&config_path).ok().and_then(|m| m.modified().ok());
+
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                flush_pending(&mut pending, json);
+                println!("Stopped watching {}.", prefix);
+                break;
+            }
+            _ = interval.tick() => {
+                if let Some((reloaded, mtime)) = crate::config::reload_if_changed(&config_path, config_mtime)? {
+                    let changes = crate::config::describe_changes(&config, &reloaded);
+                    if !changes.is_empty() {
+                        println!("Config reloaded: {}", changes.join(", "));
+                    }
+                    config = reloaded;
+                    config_mtime = Some(mtime);
+                }
+
+                let resource: PubkyResource = match prefix.parse() {
+                    Ok(resource) => resource,
+                    Err(_) => {
+                        eprintln!("Watch prefix must be pubky://<user>/<path> or pubky<user>/<path>");
+                        break;
+                    }
+                };
+                let mut builder = storage.list(resource)?;
+                if shallow {
+                    builder = builder.shallow(true);
+                }
+
+                let entries = match builder.send().await {
+                    Ok(entries) => entries,
+                    Err(e) => {
+                        eprintln!("Failed to list {}: {}", prefix, e);
+                        continue;
+                    }
+                };
+
+                let mut current: HashMap<String, String> = HashMap::new();
+                for entry in &entries {
+                    let url = entry.to_pubky_url();
+                    let fingerprint = match storage.get(url.clone()).await {
+                        Ok(response) => entry_fingerprint(response.headers()),
+                        Err(_) => String::new(),
+                    };
+                    current.insert(url, fingerprint);
+                }
+
+                let now = unix_timestamp();
+                for (path, fingerprint) in &current {
+                    match snapshot.get(path) {
+                        None => queue_event(&mut pending, path, ChangeKind::Created, Some(fingerprint.clone()), now),
+                        Some(prev) if prev != fingerprint => {
+                            queue_event(&mut pending, path, ChangeKind::Modified, Some(fingerprint.clone()), now)
+                        }
+                        _ => {}
+                    }
+                }
+                for path in snapshot.keys() {
+                    if !current.contains_key(path) {
+                        queue_event(&mut pending, path, ChangeKind::Deleted, None, now);
+                    }
+                }
+
+                snapshot = current;
+                emit_ready(&mut pending, debounce_ms, json);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// The homeserver's ETag for the entry if it sends one, otherwise a
+/// `<content-length>:<last-modified>` pair — just enough to tell "changed"
+/// from "unchanged" without re-reading the whole body.
+fn entry_fingerprint(headers: &reqwest::header::HeaderMap) -> String {
+    if let Some(etag) = headers
+        .get(reqwest::header::ETAG)
+        .and_then(|v| v.to_str().ok())
+    {
+        return etag.to_string();
+    }
+
+    let size = headers
+        .get(reqwest::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("?");
+    let modified = headers
+        .get(reqwest::header::LAST_MODIFIED)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("?");
+    format!("{size}:{modified}")
+}
+
+/// Queues `path`'s event, resetting its debounce timer, so a burst of rapid
+/// changes to the same entry collapses into whatever its state is once the
+/// debounce window elapses.
+fn queue_event(
+    pending: &mut HashMap<String, (ChangeEvent, Instant)>,
+    path: &str,
+    kind: ChangeKind,
+    fingerprint: Option<String>,
+    timestamp_unix: u64,
+) {
+    let event = ChangeEvent {
+        path: path.to_string(),
+        kind,
+        fingerprint,
+        timestamp_unix,
+    };
+    pending.insert(path.to_string(), (event, Instant::now()));
+}
+
+/// Emits (and removes) every pending event whose debounce window has
+/// elapsed without a further change.
+fn emit_ready(pending: &mut HashMap<String, (ChangeEvent, Instant)>, debounce_ms: u64, json: bool) {
+    let debounce = Duration::from_millis(debounce_ms);
+    let ready: Vec<String> = pending
+        .iter()
+        .filter(|(_, (_, last_seen))| last_seen.elapsed() >= debounce)
+        .map(|(path, _)| path.clone())
+        .collect();
+
+    for path in ready {
+        if let Some((event, _)) = pending.remove(&path) {
+            print_event(&event, json);
+        }
+    }
+}
+
+/// Emits every still-debounced event immediately, so Ctrl-C doesn't drop
+/// whatever changed just before the watch stopped.
+fn flush_pending(pending: &mut HashMap<String, (ChangeEvent, Instant)>, json: bool) {
+    for (_, (event, _)) in pending.drain() {
+        print_event(&event, json);
+    }
+}
+
+fn print_event(event: &ChangeEvent, json: bool) {
+    if json {
+        match serde_json::to_string(event) {
+            Ok(line) => println!("{line}"),
+            Err(e) => eprintln!("Failed to serialize event: {e}"),
+        }
+        return;
+    }
+
+    match event.fingerprint.as_deref() {
+        Some(fingerprint) => println!("{:?} {} ({})", event.kind, event.path, fingerprint),
+        None => println!("{:?} {}", event.kind, event.path),
+    }
+}
+
+/// Reconciles `local_dir` with `remote_path`, in whichever direction is
+/// authoritative, applying only the put/delete operations needed to bring
+/// the other side in line. Local per-file hashes are cached at
+/// `<local_dir>/.pubky-sync` (keyed by mtime+size) so an unchanged file
+/// isn't rehashed on every run.
+async fn sync_directory(
+    local_dir: PathBuf,
+    remote_path: String,
+    recovery_file: PathBuf,
+    testnet: bool,
+    push: bool,
+    pull: bool,
+    mirror: bool,
+    delete: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let selected = [push, pull, mirror].iter().filter(|&&b| b).count();
+    if selected != 1 {
+        anyhow::bail!("Specify exactly one of --push, --pull, or --mirror");
+    }
+    let (direction, prune) = if mirror {
+        (SyncDirection::Push, true)
+    } else if push {
+        (SyncDirection::Push, delete)
+    } else {
+        (SyncDirection::Pull, delete)
+    };
+
     let keypair = load_keypair_from_recovery_file(&recovery_file)
         .with_context(|| format!("Failed to load recovery file: {}", recovery_file.display()))?;
     println!("Loaded recovery file for Pubky {}", keypair.public_key());
@@ -383,23 +1620,446 @@ async fn get_data(data_path: String, recovery_file: PathBuf, testnet: bool) -> R
     println!("Signed in successfully. Session details:");
     println!("{:#?}", session.info());
 
-    // Get the storage object from the session
     let storage = session.storage();
+    let remote_root = remote_path.trim_end_matches('/').to_string();
+
+    tokio::fs::create_dir_all(&local_dir)
+        .await
+        .with_context(|| format!("Failed to create directory: {}", local_dir.display()))?;
+
+    let manifest_path = local_dir.join(".pubky-sync");
+    let mut manifest = load_sync_manifest(&manifest_path).await;
+    let local_entries = local_fingerprints(&local_dir, &manifest_path, &mut manifest).await?;
+    let remote_entries = fetch_remote_catalog(&storage, &remote_root).await?;
+
+    match direction {
+        SyncDirection::Push => {
+            let mut to_put: Vec<&str> = Vec::new();
+            for (path, local) in &local_entries {
+                match remote_entries.get(path) {
+                    Some(remote) if !remote.hash.is_empty() && remote.hash == local.hash => {}
+                    _ => to_put.push(path.as_str()),
+                }
+            }
+            let to_delete: Vec<&str> = if prune {
+                remote_entries
+                    .keys()
+                    .filter(|path| !local_entries.contains_key(*path) && path.as_str() != ".catalog")
+                    .map(|path| path.as_str())
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            if dry_run {
+                for path in &to_put {
+                    println!("would put {}", path);
+                }
+                for path in &to_delete {
+                    println!("would delete {}", path);
+                }
+            } else {
+                for path in &to_put {
+                    let data = tokio::fs::read(local_dir.join(path))
+                        .await
+                        .with_context(|| format!("Failed to read file: {}", path))?;
+                    let remote_file_path = format!("{}/{}", remote_root, path);
+                    storage
+                        .put(remote_file_path.clone(), reqwest::Body::from(data))
+                        .await
+                        .with_context(|| format!("Failed to push file: {}", remote_file_path))?;
+                }
+                for path in &to_delete {
+                    let remote_file_path = format!("{}/{}", remote_root, path);
+                    storage
+                        .delete(remote_file_path.clone())
+                        .await
+                        .with_context(|| format!("Failed to delete: {}", remote_file_path))?;
+                }
+
+                let catalog = Catalog {
+                    version: CATALOG_VERSION,
+                    entries: local_entries
+                        .iter()
+                        .map(|(path, entry)| CatalogEntry {
+                            path: path.clone(),
+                            size: entry.size,
+                            modified_unix: entry.modified_unix,
+                            hash: entry.hash.clone(),
+                        })
+                        .collect(),
+                };
+                let catalog_json = serde_json::to_vec(&catalog).context("Failed to serialize catalog")?;
+                let catalog_path = format!("{}/.catalog", remote_root);
+                storage
+                    .put(catalog_path.clone(), reqwest::Body::from(catalog_json))
+                    .await
+                    .with_context(|| format!("Failed to publish catalog: {}", catalog_path))?;
+            }
+
+            println!(
+                "Sync (push{}): {} put, {} deleted, {} unchanged{}",
+                if prune { ", pruning" } else { "" },
+                to_put.len(),
+                to_delete.len(),
+                local_entries.len().saturating_sub(to_put.len()),
+                if dry_run { " (dry run)" } else { "" }
+            );
+        }
+        SyncDirection::Pull => {
+            let mut to_get: Vec<&str> = Vec::new();
+            for (path, remote) in &remote_entries {
+                if path == ".catalog" {
+                    continue;
+                }
+                match local_entries.get(path) {
+                    Some(local) if !remote.hash.is_empty() && remote.hash == local.hash => {}
+                    _ => to_get.push(path.as_str()),
+                }
+            }
+            let to_delete: Vec<&str> = if prune {
+                local_entries
+                    .keys()
+                    .filter(|path| !remote_entries.contains_key(*path))
+                    .map(|path| path.as_str())
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            if dry_run {
+                for path in &to_get {
+                    println!("would get {}", path);
+                }
+                for path in &to_delete {
+                    println!("would delete {}", path);
+                }
+            } else {
+                for path in &to_get {
+                    let remote_file_path = format!("{}/{}", remote_root, path);
+                    let data = storage
+                        .get(remote_file_path.clone())
+                        .await
+                        .with_context(|| format!("Failed to fetch: {}", remote_file_path))?
+                        .bytes()
+                        .await
+                        .with_context(|| format!("Failed to read: {}", remote_file_path))?;
 
-    // Use the `get` method to get the data
-    let data_text = storage
-        .get(data_path.to_string())
+                    let local_path = local_dir.join(path);
+                    if let Some(parent) = local_path.parent() {
+                        tokio::fs::create_dir_all(parent)
+                            .await
+                            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+                    }
+                    let modified_unix = unix_timestamp();
+                    let hash = blake3::hash(&data).to_hex().to_string();
+                    manifest.entries.insert(
+                        (*path).to_string(),
+                        SyncManifestEntry { size: data.len() as u64, modified_unix, hash },
+                    );
+                    tokio::fs::write(&local_path, &data)
+                        .await
+                        .with_context(|| format!("Failed to write: {}", local_path.display()))?;
+                }
+                for path in &to_delete {
+                    let local_path = local_dir.join(path);
+                    tokio::fs::remove_file(&local_path)
+                        .await
+                        .with_context(|| format!("Failed to remove: {}", local_path.display()))?;
+                    manifest.entries.remove(*path);
+                }
+            }
+
+            println!(
+                "Sync (pull{}): {} fetched, {} deleted, {} unchanged{}",
+                if prune { ", pruning" } else { "" },
+                to_get.len(),
+                to_delete.len(),
+                remote_entries.len().saturating_sub(to_get.len()).saturating_sub(1),
+                if dry_run { " (dry run)" } else { "" }
+            );
+        }
+    }
+
+    if !dry_run {
+        save_sync_manifest(&manifest_path, &manifest).await?;
+    }
+
+    session.signout().await.map_err(|(err, _)| err)?;
+    println!("Signed out successfully.");
+
+    Ok(())
+}
+
+/// Loads `<local_dir>/.pubky-sync`, or an empty manifest if this is the
+/// first sync.
+async fn load_sync_manifest(manifest_path: &Path) -> SyncManifest {
+    match tokio::fs::read(manifest_path).await {
+        Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_default(),
+        Err(_) => SyncManifest { version: SYNC_MANIFEST_VERSION, entries: HashMap::new() },
+    }
+}
+
+async fn save_sync_manifest(manifest_path: &Path, manifest: &SyncManifest) -> Result<()> {
+    let json = serde_json::to_vec(manifest).context("Failed to serialize sync manifest")?;
+    tokio::fs::write(manifest_path, json)
         .await
-        .with_context(|| "Failed to get data")?
-        .text()
+        .with_context(|| format!("Failed to write {}", manifest_path.display()))
+}
+
+/// Walks `local_dir`, reusing `manifest`'s cached hash for any file whose
+/// size and mtime still match it, and hashing (then caching) any file that
+/// doesn't. `.pubky-sync` itself is excluded.
+async fn local_fingerprints(
+    local_dir: &Path,
+    manifest_path: &Path,
+    manifest: &mut SyncManifest,
+) -> Result<HashMap<String, SyncManifestEntry>> {
+    let files = collect_local_files(local_dir)
+        .with_context(|| format!("Failed to walk directory: {}", local_dir.display()))?;
+
+    let mut current = HashMap::with_capacity(files.len());
+    for file in &files {
+        let rel_path = relative_slash_path(local_dir, file)
+            .with_context(|| format!("Failed to compute relative path for {}", file.display()))?;
+        if rel_path == manifest_path.file_name().and_then(|n| n.to_str()).unwrap_or(".pubky-sync") {
+            continue;
+        }
+
+        let metadata = std::fs::metadata(file)
+            .with_context(|| format!("Failed to stat file: {}", file.display()))?;
+        let size = metadata.len();
+        let modified_unix = metadata
+            .modified()
+            .with_context(|| format!("Failed to read mtime for: {}", file.display()))?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let hash = match manifest.entries.get(&rel_path) {
+            Some(cached) if cached.size == size && cached.modified_unix == modified_unix => cached.hash.clone(),
+            _ => {
+                let data = tokio::fs::read(file)
+                    .await
+                    .with_context(|| format!("Failed to read file: {}", file.display()))?;
+                blake3::hash(&data).to_hex().to_string()
+            }
+        };
+
+        let entry = SyncManifestEntry { size, modified_unix, hash };
+        manifest.entries.insert(rel_path.clone(), entry.clone());
+        current.insert(rel_path, entry);
+    }
+
+    Ok(current)
+}
+
+/// Fetches `<remote_root>/.catalog` and indexes its entries by path, or
+/// returns an empty map if there is no catalog yet (first sync).
+async fn fetch_remote_catalog(
+    storage: &PublicStorage,
+    remote_root: &str,
+) -> Result<HashMap<String, CatalogEntry>> {
+    let catalog_path = format!("{}/.catalog", remote_root);
+    let catalog = match storage.get(catalog_path.clone()).await {
+        Ok(response) => {
+            let bytes = response
+                .bytes()
+                .await
+                .with_context(|| format!("Failed to read catalog: {}", catalog_path))?;
+            serde_json::from_slice::<Catalog>(&bytes).ok()
+        }
+        Err(_) => None,
+    };
+
+    Ok(catalog
+        .map(|catalog| catalog.entries.into_iter().map(|entry| (entry.path.clone(), entry)).collect())
+        .unwrap_or_default())
+}
+
+/// Recursively lists `prefix` and matches each entry against `pattern`,
+/// either as a glob over the entry's path (`path_only`, listing-only and
+/// cheap) or as a regex scanned line-by-line over its fetched content.
+async fn search_prefix(
+    pattern: String,
+    prefix: String,
+    recovery_file: PathBuf,
+    testnet: bool,
+    path_only: bool,
+    max_results: Option<usize>,
+    max_size: Option<u64>,
+    ignore_case: bool,
+    json: bool,
+) -> Result<()> {
+    let keypair = load_keypair_from_recovery_file(&recovery_file)
+        .with_context(|| format!("Failed to load recovery file: {}", recovery_file.display()))?;
+    println!("Loaded recovery file for Pubky {}", keypair.public_key());
+
+    let session = signin_with_cache(testnet, keypair, true).await?;
+    let storage = session.storage();
+
+    let resource: PubkyResource = prefix
+        .parse()
+        .with_context(|| "Search prefix must be pubky://<user>/<path> or pubky<user>/<path>")?;
+    let entries = storage
+        .list(resource)?
+        .send()
         .await
-        .with_context(|| "Transform data to text")?;
+        .with_context(|| format!("Failed to list {}", prefix))?;
 
-    println!("Data at {}: {}", data_path, data_text);
+    let content_regex = if path_only {
+        None
+    } else {
+        Some(
+            regex::RegexBuilder::new(&pattern)
+                .case_insensitive(ignore_case)
+                .build()
+                .with_context(|| format!("Invalid search pattern: {}", pattern))?,
+        )
+    };
+
+    let mut hits: Vec<SearchHit> = Vec::new();
+    'entries: for entry in &entries {
+        let url = entry.to_pubky_url();
+
+        let Some(regex) = &content_regex else {
+            if glob_match(&pattern, &url, ignore_case) {
+                hits.push(SearchHit { path: url, line_number: None, excerpt: None });
+                if max_results.is_some_and(|max| hits.len() >= max) {
+                    break;
+                }
+            }
+            continue;
+        };
+
+        let response = match storage.get(url.clone()).await {
+            Ok(response) => response,
+            Err(_) => continue,
+        };
+        if let Some(max_size) = max_size {
+            if response.content_length().is_some_and(|len| len > max_size) {
+                continue;
+            }
+        }
+        let Ok(bytes) = response.bytes().await else { continue };
+        let text = String::from_utf8_lossy(&bytes);
+
+        for (line_number, line) in text.lines().enumerate() {
+            if regex.is_match(line) {
+                hits.push(SearchHit {
+                    path: url.clone(),
+                    line_number: Some(line_number + 1),
+                    excerpt: Some(line.trim().chars().take(120).collect()),
+                });
+                if max_results.is_some_and(|max| hits.len() >= max) {
+                    break 'entries;
+                }
+            }
+        }
+    }
+
+    for hit in &hits {
+        print_search_hit(hit, json);
+    }
+    println!("{} hit(s) found", hits.len());
 
-    // Sign out after getting data
     session.signout().await.map_err(|(err, _)| err)?;
     println!("Signed out successfully.");
 
     Ok(())
 }
+
+fn print_search_hit(hit: &SearchHit, json: bool) {
+    if json {
+        match serde_json::to_string(hit) {
+            Ok(line) => println!("{line}"),
+            Err(e) => eprintln!("Failed to serialize hit: {e}"),
+        }
+        return;
+    }
+
+    match (hit.line_number, hit.excerpt.as_deref()) {
+        (Some(line_number), Some(excerpt)) => println!("{}:{}: {}", hit.path, line_number, excerpt),
+        _ => println!("{}", hit.path),
+    }
+}
+
+/// Minimal shell-glob matcher supporting `*` (any run of characters,
+/// including none) and `?` (exactly one character); no character classes.
+fn glob_match(pattern: &str, text: &str, ignore_case: bool) -> bool {
+    if ignore_case {
+        glob_match_bytes(pattern.to_lowercase().as_bytes(), text.to_lowercase().as_bytes())
+    } else {
+        glob_match_bytes(pattern.as_bytes(), text.as_bytes())
+    }
+}
+
+fn glob_match_bytes(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => {
+            glob_match_bytes(rest, text) || (!text.is_empty() && glob_match_bytes(pattern, &text[1..]))
+        }
+        Some((b'?', rest)) => !text.is_empty() && glob_match_bytes(rest, &text[1..]),
+        Some((ch, rest)) => !text.is_empty() && text[0] == *ch && glob_match_bytes(rest, &text[1..]),
+    }
+}
+
+/// Recursively lists every regular file under `root`, in deterministic order.
+fn collect_local_files(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        for entry in std::fs::read_dir(&dir)
+            .with_context(|| format!("Failed to read directory: {}", dir.display()))?
+        {
+            let path = entry
+                .with_context(|| format!("Failed to read directory entry in: {}", dir.display()))?
+                .path();
+            if path.is_dir() {
+                stack.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Renders `file`'s path relative to `root` with `/` separators, regardless
+/// of the host OS, so catalog entries are portable.
+fn relative_slash_path(root: &Path, file: &Path) -> Result<String> {
+    let rel = file
+        .strip_prefix(root)
+        .with_context(|| format!("{} is not under {}", file.display(), root.display()))?;
+
+    Ok(rel
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
+/// Whether `local_path` already matches a catalog `entry`, so `Pull` can
+/// skip re-downloading it. Entries from the no-catalog fallback listing
+/// carry no size/mtime and never match, so they always re-download.
+fn entry_matches_disk(local_path: &Path, entry: &CatalogEntry) -> bool {
+    if entry.size == 0 && entry.modified_unix == 0 {
+        return false;
+    }
+
+    let Ok(metadata) = std::fs::metadata(local_path) else {
+        return false;
+    };
+    let local_modified_unix = metadata
+        .modified()
+        .ok()
+        .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    metadata.len() == entry.size && local_modified_unix == entry.modified_unix
+}
@@ -1,13 +1,20 @@
-use std::{env, path::Path, time::Duration};
+use std::{
+    env,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use anyhow::{Context, Result};
 use pubky::recovery_file::create_recovery_file;
-use pubky::{Keypair, Pubky, PubkyHttpClient, PubkySigner};
+use pubky::{Keypair, Pubky, PubkyHttpClient, PubkySession, PubkySigner, PublicKey};
+use serde::{Deserialize, Serialize};
 
-const PKARR_BOOTSTRAP_ENV: &str = "PUBKY_PKARR_BOOTSTRAP";
-const PKARR_RELAYS_ENV: &str = "PUBKY_PKARR_RELAYS";
 const PKARR_TIMEOUT_ENV: &str = "PUBKY_PKARR_TIMEOUT_MS";
 
+/// How long a cached session ticket is trusted before `login` must be
+/// re-run, mirroring a homeserver session's own expiry window.
+const SESSION_TICKET_TTL_SECS: u64 = 24 * 60 * 60;
+
 pub fn build_pubky(testnet: bool) -> Result<Pubky> {
     if let Some(facade) = build_pubky_from_env()? {
         return Ok(facade);
@@ -28,22 +35,18 @@ pub fn build_signer(testnet: bool, keypair: Keypair) -> Result<PubkySigner> {
 }
 
 fn build_pubky_from_env() -> Result<Option<Pubky>> {
-    let bootstrap_raw = env::var(PKARR_BOOTSTRAP_ENV).ok().filter(|s| !s.is_empty());
-    let relays_raw = env::var(PKARR_RELAYS_ENV).ok().filter(|s| !s.is_empty());
-
-    if bootstrap_raw.is_none() && relays_raw.is_none() {
+    // `PUBKY_PKARR_*` env vars win over the config file (see
+    // `config::Config::pkarr_bootstrap`/`pkarr_relays`), so this is read
+    // fresh on every call rather than cached, which is also what lets a
+    // long-running command pick up a config file edit without restarting.
+    let config = crate::config::effective_config()?;
+    let bootstrap_list = config.pkarr_bootstrap().unwrap_or_default();
+    let relays_list = config.pkarr_relays().unwrap_or_default();
+
+    if bootstrap_list.is_empty() && relays_list.is_empty() {
         return Ok(None);
     }
 
-    let bootstrap_list = bootstrap_raw
-        .as_ref()
-        .map(|raw| parse_csv(raw))
-        .unwrap_or_default();
-    let relays_list = relays_raw
-        .as_ref()
-        .map(|raw| parse_csv(raw))
-        .unwrap_or_default();
-
     let mut builder = PubkyHttpClient::builder();
     builder.pkarr(|pb| {
         pb.no_default_network();
@@ -72,21 +75,13 @@ fn build_pubky_from_env() -> Result<Option<Pubky>> {
     Ok(Some(Pubky::with_client(client)))
 }
 
-fn parse_csv(raw: &str) -> Vec<String> {
-    raw.split(',')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .map(|s| s.to_string())
-        .collect()
-}
-
 pub fn load_keypair_from_recovery_file(path: &Path) -> Result<Keypair> {
     let recovery_bytes = std::fs::read(path)
         .with_context(|| format!("Failed to read recovery file {}", path.display()))?;
 
-    let passphrase = match std::env::var("PUBKY_CLI_RECOVERY_PASSPHRASE") {
-        Ok(value) => value,
-        Err(_) => {
+    let passphrase = match crate::config::effective_config()?.recovery_passphrase() {
+        Some(value) => value,
+        None => {
             let prompt = format!(
                 "Enter the recovery file passphrase for {} (input hidden): ",
                 path.display()
@@ -108,3 +103,124 @@ pub fn create_recovery_file_on_disk(path: &Path, passphrase: &str) -> Result<Key
         .with_context(|| format!("Failed to write recovery file to {}", path.display()))?;
     Ok(keypair)
 }
+
+/// A cached homeserver session, written by `user login` (and by any
+/// `--use-session` command on a cache miss) so a batch of commands can
+/// share one authenticated session instead of re-running the recovery-file
+/// handshake on every invocation. Modeled on Proxmox's `AuthInfo` ticket
+/// cache under `$XDG_RUNTIME_DIR`.
+#[derive(Debug, Serialize, Deserialize)]
+struct SessionTicket {
+    session_cookie: String,
+    issued_at: u64,
+    ttl_secs: u64,
+}
+
+impl SessionTicket {
+    fn is_expired(&self) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        now >= self.issued_at.saturating_add(self.ttl_secs)
+    }
+}
+
+/// `$XDG_RUNTIME_DIR/pubky` (falling back to the OS temp dir), shared by the
+/// session ticket cache and by `pubky-cli agent`'s Unix socket.
+pub(crate) fn runtime_dir() -> PathBuf {
+    let base = env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(env::temp_dir);
+    base.join("pubky")
+}
+
+fn session_dir() -> PathBuf {
+    runtime_dir()
+}
+
+fn session_ticket_path(pubkey: &PublicKey) -> PathBuf {
+    session_dir().join(format!("{}.session", pubkey))
+}
+
+/// Writes `session`'s ticket to `$XDG_RUNTIME_DIR/pubky/<pubkey>.session`
+/// with owner-only permissions (0600), creating the directory if needed.
+pub fn save_session_ticket(pubkey: &PublicKey, session: &PubkySession) -> Result<PathBuf> {
+    let dir = session_dir();
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create session directory: {}", dir.display()))?;
+
+    let ticket = SessionTicket {
+        session_cookie: session.cookie().to_string(),
+        issued_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        ttl_secs: SESSION_TICKET_TTL_SECS,
+    };
+
+    let path = session_ticket_path(pubkey);
+    let json = serde_json::to_vec(&ticket).context("Failed to serialize session ticket")?;
+    std::fs::write(&path, json)
+        .with_context(|| format!("Failed to write session ticket: {}", path.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).with_context(
+            || format!("Failed to set permissions on session ticket: {}", path.display()),
+        )?;
+    }
+
+    Ok(path)
+}
+
+/// Removes any cached ticket for `pubkey`. A missing file is not an error.
+pub fn remove_session_ticket(pubkey: &PublicKey) -> Result<()> {
+    let path = session_ticket_path(pubkey);
+    match std::fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e).with_context(|| format!("Failed to remove session ticket: {}", path.display())),
+    }
+}
+
+/// Resumes `pubkey`'s cached, non-expired session if `use_session` is set
+/// and a ticket is present and still accepted by the homeserver; otherwise
+/// falls back to the full recovery-file handshake via `signin()` and, when
+/// `use_session` is set, caches the resulting ticket for next time.
+pub async fn signin_with_cache(
+    testnet: bool,
+    keypair: Keypair,
+    use_session: bool,
+) -> Result<PubkySession> {
+    let pubkey = keypair.public_key();
+
+    if use_session {
+        let path = session_ticket_path(&pubkey);
+        if let Ok(bytes) = std::fs::read(&path) {
+            if let Ok(ticket) = serde_json::from_slice::<SessionTicket>(&bytes) {
+                if !ticket.is_expired() {
+                    let facade = build_pubky(testnet)?;
+                    if let Ok(session) =
+                        facade.resume_session(&pubkey, &ticket.session_cookie).await
+                    {
+                        return Ok(session);
+                    }
+                }
+                // Cache miss or the homeserver no longer accepts the ticket;
+                // fall through and sign in fresh below.
+                let _ = remove_session_ticket(&pubkey);
+            }
+        }
+    }
+
+    let signer = build_signer(testnet, keypair)?;
+    let session = signer.signin().await?;
+
+    if use_session {
+        save_session_ticket(&pubkey, &session)?;
+    }
+
+    Ok(session)
+}
@@ -506,6 +506,80 @@ async fn user_get_data() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[serial]
+async fn user_publish_and_get_chunked_data() -> Result<()> {
+    let network = start_testnet().await?;
+    let passphrase = "demo-pass";
+    let env = user_env(passphrase, &network);
+    sleep(Duration::from_millis(500)).await;
+
+    let temp_dir = tempdir().context("create temp dir")?;
+    let recovery_path = temp_dir.path().join("user.recovery");
+    let recovery_str = recovery_path.to_string_lossy().to_string();
+    let homeserver_pk = network.homeserver().public_key().to_string();
+
+    // Generate recovery file
+    run_cli_dynamic(
+        &[
+            "tools",
+            "generate-recovery",
+            &recovery_str,
+            "--passphrase",
+            passphrase,
+        ],
+        env.clone(),
+    )
+    .await?;
+
+    // Signup user
+    run_cli_dynamic(
+        &["user", "signup", &homeserver_pk, &recovery_str, "--testnet"],
+        env.clone(),
+    )
+    .await?;
+
+    // Publish a multi-chunk file in chunked mode
+    let file_path = temp_dir.path().join("big.bin");
+    let file_content = vec![b'x'; 2 * 1024 * 1024];
+    std::fs::write(&file_path, &file_content).context("write test file")?;
+    let pubky_url = "/pub/app/big.bin";
+    let publish_output = run_cli_dynamic(
+        &[
+            "user",
+            "publish",
+            &pubky_url,
+            file_path.to_str().unwrap(),
+            &recovery_str,
+            "--testnet",
+            "--chunked",
+        ],
+        env.clone(),
+    )
+    .await?;
+    let publish_stdout = String::from_utf8_lossy(&publish_output.stdout);
+    assert!(
+        publish_stdout.contains("Published") && publish_stdout.contains("chunk(s)"),
+        "unexpected chunked publish output: {}",
+        publish_stdout
+    );
+
+    // Fetching reassembles the chunks transparently
+    let get_output = run_cli_dynamic(
+        &["user", "get", &pubky_url, &recovery_str, "--testnet"],
+        env.clone(),
+    )
+    .await?;
+    let get_stdout = String::from_utf8_lossy(&get_output.stdout);
+    assert!(
+        get_stdout.matches('x').count() >= file_content.len(),
+        "expected reassembled chunked data, got {} 'x' chars",
+        get_stdout.matches('x').count()
+    );
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 #[serial]
 async fn user_delete_data() -> Result<()> {
@@ -573,6 +647,107 @@ async fn user_delete_data() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[serial]
+async fn user_push_and_pull_directory() -> Result<()> {
+    let network = start_testnet().await?;
+    let passphrase = "demo-pass";
+    let env = user_env(passphrase, &network);
+    sleep(Duration::from_millis(500)).await;
+
+    let temp_dir = tempdir().context("create temp dir")?;
+    let recovery_path = temp_dir.path().join("user.recovery");
+    let recovery_str = recovery_path.to_string_lossy().to_string();
+    let homeserver_pk = network.homeserver().public_key().to_string();
+
+    // Generate recovery file
+    run_cli_dynamic(
+        &[
+            "tools",
+            "generate-recovery",
+            &recovery_str,
+            "--passphrase",
+            passphrase,
+        ],
+        env.clone(),
+    )
+    .await?;
+
+    // Signup user
+    run_cli_dynamic(
+        &["user", "signup", &homeserver_pk, &recovery_str, "--testnet"],
+        env.clone(),
+    )
+    .await?;
+
+    // Build a small local tree to push
+    let push_dir = temp_dir.path().join("push");
+    std::fs::create_dir_all(push_dir.join("nested")).context("create nested dir")?;
+    std::fs::write(push_dir.join("root.txt"), "root file").context("write root file")?;
+    std::fs::write(push_dir.join("nested/leaf.txt"), "leaf file").context("write leaf file")?;
+
+    let user_pubkey = {
+        let recovery_bytes = std::fs::read(&recovery_path).context("read recovery file")?;
+        recovery_file::decrypt_recovery_file(&recovery_bytes, passphrase)
+            .context("decrypt")?
+            .public_key()
+            .to_string()
+    };
+
+    let remote_path = "/pub/app/backup";
+    let push_output = run_cli_dynamic(
+        &[
+            "user",
+            "push",
+            push_dir.to_str().unwrap(),
+            remote_path,
+            &recovery_str,
+            "--testnet",
+        ],
+        env.clone(),
+    )
+    .await?;
+    let push_stdout = String::from_utf8_lossy(&push_output.stdout);
+    assert!(
+        push_stdout.contains("Pushed 2 file(s)"),
+        "unexpected push output: {}",
+        push_stdout
+    );
+
+    // Pull the catalog back into a fresh directory
+    let pull_dir = temp_dir.path().join("pull");
+    let remote_url = format!("pubky://{}{}", user_pubkey, remote_path);
+    let pull_output = run_cli_dynamic(
+        &[
+            "user",
+            "pull",
+            &remote_url,
+            pull_dir.to_str().unwrap(),
+            "--testnet",
+        ],
+        env.clone(),
+    )
+    .await?;
+    let pull_stdout = String::from_utf8_lossy(&pull_output.stdout);
+    assert!(
+        pull_stdout.contains("Pulled 2 file(s)"),
+        "unexpected pull output: {}",
+        pull_stdout
+    );
+
+    assert_eq!(
+        std::fs::read_to_string(pull_dir.join("root.txt")).context("read pulled root file")?,
+        "root file"
+    );
+    assert_eq!(
+        std::fs::read_to_string(pull_dir.join("nested/leaf.txt"))
+            .context("read pulled leaf file")?,
+        "leaf file"
+    );
+
+    Ok(())
+}
+
 const PASS_ENV: &str = "PUBKY_ADMIN_PASSWORD";
 const RECOVERY_PASS_ENV: &str = "PUBKY_CLI_RECOVERY_PASSPHRASE";
 const PKARR_BOOTSTRAP_ENV: &str = "PUBKY_PKARR_BOOTSTRAP";
@@ -0,0 +1,258 @@
+//! Derived-metric anomaly detection, evaluated once per tick alongside the
+//! bandwidth sampling effect in `main.rs` — the network-monitor equivalent
+//! of flood/anomaly detection, but over this app's own simulated metrics
+//! (connectivity flapping, storage growth, stuck operations, homeserver
+//! errors) rather than raw packets.
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use crate::components::network_visualization::{ConnectivityStatus, Node, NodeStatus};
+
+/// How far back a homeserver's `Unreachable` transitions are counted
+/// toward "flapping".
+const FLAP_WINDOW: Duration = Duration::from_secs(30);
+/// Transitions within `FLAP_WINDOW` at or above this count raise a
+/// `Flapping` alert.
+const FLAP_THRESHOLD: usize = 3;
+/// Storage growth at or above this rate raises a `StorageGrowth` alert.
+const STORAGE_GROWTH_THRESHOLD_BPS: f64 = 512.0 * 1024.0;
+/// How long a client's write/read may stay in flight before it's flagged
+/// as stuck.
+const STUCK_OP_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Severity {
+    Warning,
+    Critical,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum AlertKind {
+    /// A homeserver's connectivity repeatedly flipped to `Unreachable`
+    /// within `FLAP_WINDOW`.
+    Flapping,
+    /// A homeserver's tracked storage is growing faster than
+    /// `STORAGE_GROWTH_THRESHOLD_BPS`.
+    StorageGrowth,
+    /// A client's write or read has been in flight longer than
+    /// `STUCK_OP_TIMEOUT`.
+    StuckOperation,
+    /// A homeserver node entered `NodeStatus::Error`.
+    HomeserverError,
+}
+
+impl AlertKind {
+    fn severity(&self) -> Severity {
+        match self {
+            AlertKind::HomeserverError => Severity::Critical,
+            AlertKind::Flapping | AlertKind::StorageGrowth | AlertKind::StuckOperation => Severity::Warning,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Alert {
+    pub id: usize,
+    pub severity: Severity,
+    pub node_id: String,
+    pub kind: AlertKind,
+    pub message: String,
+    /// Seconds since this condition was first observed, recomputed each
+    /// `evaluate` call the same way `TestnetManager::last_seen_secs_ago`
+    /// exposes age rather than a raw `Instant`.
+    pub first_seen_secs_ago: u64,
+    /// How many times this exact (node, kind) condition has been observed
+    /// without clearing in between.
+    pub count: u32,
+}
+
+/// Tick-driven evaluator holding just enough history (recent unreachable
+/// transitions, last storage reading, in-flight op start times) to turn a
+/// node snapshot into a deduplicated alert list.
+pub struct AlertEngine {
+    next_id: usize,
+    active: HashMap<(String, AlertKind), (Alert, Instant)>,
+    /// (node_id, kind) pairs dismissed via `acknowledge` while their
+    /// condition is still active; cleared once the condition clears, so a
+    /// later fresh occurrence can alert again.
+    suppressed: HashSet<(String, AlertKind)>,
+    unreachable_events: HashMap<String, Vec<Instant>>,
+    was_unreachable: HashMap<String, bool>,
+    last_storage: HashMap<String, (u64, Instant)>,
+    pending_ops: HashMap<String, Instant>,
+}
+
+impl AlertEngine {
+    pub fn new() -> Self {
+        Self {
+            next_id: 0,
+            active: HashMap::new(),
+            suppressed: HashSet::new(),
+            unreachable_events: HashMap::new(),
+            was_unreachable: HashMap::new(),
+            last_storage: HashMap::new(),
+            pending_ops: HashMap::new(),
+        }
+    }
+
+    /// Record that a client's write/read just started, for `StuckOperation`
+    /// detection.
+    pub fn mark_op_started(&mut self, client_id: &str) {
+        self.pending_ops.insert(client_id.to_string(), Instant::now());
+    }
+
+    /// Record that a client's write/read finished (success or failure).
+    pub fn mark_op_finished(&mut self, client_id: &str) {
+        self.pending_ops.remove(client_id);
+    }
+
+    /// Dismiss an alert by id. The underlying condition is suppressed
+    /// until it clears, so it doesn't immediately reappear on the next
+    /// tick while still active.
+    pub fn acknowledge(&mut self, alert_id: usize) {
+        if let Some(key) = self
+            .active
+            .iter()
+            .find(|(_, (alert, _))| alert.id == alert_id)
+            .map(|(key, _)| key.clone())
+        {
+            self.active.remove(&key);
+            self.suppressed.insert(key);
+        }
+    }
+
+    fn update(&mut self, node_id: &str, kind: AlertKind, condition: bool, message: impl Fn() -> String) {
+        let key = (node_id.to_string(), kind);
+
+        if !condition {
+            self.active.remove(&key);
+            self.suppressed.remove(&key);
+            return;
+        }
+
+        if self.suppressed.contains(&key) {
+            return;
+        }
+
+        match self.active.get_mut(&key) {
+            Some((alert, _first_seen)) => {
+                alert.count += 1;
+                alert.message = message();
+            }
+            None => {
+                let id = self.next_id;
+                self.next_id += 1;
+                self.active.insert(
+                    key,
+                    (
+                        Alert {
+                            id,
+                            severity: kind.severity(),
+                            node_id: node_id.to_string(),
+                            kind,
+                            message: message(),
+                            first_seen_secs_ago: 0,
+                            count: 1,
+                        },
+                        Instant::now(),
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Re-evaluate every rule against the current node snapshot and return
+    /// the deduplicated, age-stamped alert list.
+    pub fn evaluate(&mut self, nodes: &[Node]) -> Vec<Alert> {
+        let now = Instant::now();
+
+        for node in nodes {
+            match node {
+                Node::Homeserver(h) => {
+                    let is_unreachable = matches!(h.connectivity_status, ConnectivityStatus::Unreachable { .. });
+                    let was_unreachable = self.was_unreachable.get(&h.id).copied().unwrap_or(false);
+                    if is_unreachable && !was_unreachable {
+                        let events = self.unreachable_events.entry(h.id.clone()).or_default();
+                        events.push(now);
+                    }
+                    self.was_unreachable.insert(h.id.clone(), is_unreachable);
+
+                    let flap_count = self
+                        .unreachable_events
+                        .get_mut(&h.id)
+                        .map(|events| {
+                            events.retain(|t| now.duration_since(*t) <= FLAP_WINDOW);
+                            events.len()
+                        })
+                        .unwrap_or(0);
+                    self.update(&h.id, AlertKind::Flapping, flap_count >= FLAP_THRESHOLD, || {
+                        format!(
+                            "{} flapped unreachable {} times in the last {}s",
+                            h.name,
+                            flap_count,
+                            FLAP_WINDOW.as_secs()
+                        )
+                    });
+
+                    if let Some(stats) = &h.storage_stats {
+                        let bytes = stats.total_size_bytes as u64;
+                        let growth_bps = match self.last_storage.get(&h.id) {
+                            Some((last_bytes, last_at)) => {
+                                let dt = now.duration_since(*last_at).as_secs_f64();
+                                if dt > 0.0 {
+                                    bytes.saturating_sub(*last_bytes) as f64 / dt
+                                } else {
+                                    0.0
+                                }
+                            }
+                            None => 0.0,
+                        };
+                        self.last_storage.insert(h.id.clone(), (bytes, now));
+
+                        self.update(
+                            &h.id,
+                            AlertKind::StorageGrowth,
+                            growth_bps >= STORAGE_GROWTH_THRESHOLD_BPS,
+                            || format!("{} storage is growing at {:.0} KB/s", h.name, growth_bps / 1024.0),
+                        );
+                    }
+
+                    self.update(&h.id, AlertKind::HomeserverError, h.status == NodeStatus::Error, || {
+                        format!("{} entered an error state", h.name)
+                    });
+                }
+                Node::Client(c) => {
+                    let stuck = self
+                        .pending_ops
+                        .get(&c.id)
+                        .is_some_and(|started_at| now.duration_since(*started_at) >= STUCK_OP_TIMEOUT);
+                    self.update(&c.id, AlertKind::StuckOperation, stuck, || {
+                        format!(
+                            "{} has been reading/writing for over {}s",
+                            c.name,
+                            STUCK_OP_TIMEOUT.as_secs()
+                        )
+                    });
+                }
+            }
+        }
+
+        let mut alerts: Vec<Alert> = self
+            .active
+            .values()
+            .map(|(alert, first_seen)| {
+                let mut alert = alert.clone();
+                alert.first_seen_secs_ago = now.duration_since(*first_seen).as_secs();
+                alert
+            })
+            .collect();
+
+        alerts.sort_by(|a, b| {
+            let rank = |s: Severity| if s == Severity::Critical { 0 } else { 1 };
+            rank(a.severity).cmp(&rank(b.severity)).then(b.count.cmp(&a.count))
+        });
+
+        alerts
+    }
+}
@@ -1,16 +1,68 @@
 use axum::{
-    extract::State,
-    response::Json,
-    routing::get,
+    extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Json},
+    routing::{get, post},
     Router,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
 use tower_http::cors::CorsLayer;
 
+/// A wiki page published for ActivityPub federation, keyed in `ApiState`
+/// by `(user_pubkey, page_id)` — the same pair the `wiky` desktop app
+/// identifies a page by as `selected_wiki_user_id`/`selected_wiki_page_id`.
+/// `wiky` is a separate binary with no process boundary into this server,
+/// so a page only shows up here once something calls `POST /ap/pages` to
+/// publish it; that call is the federation bridge this endpoint exists for.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WikiPageRecord {
+    pub user_pubkey: String,
+    pub page_id: String,
+    pub content: String,
+}
+
+/// One `Create`/`Announce` activity recorded in a user's outbox.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct OutboxActivity {
+    pub activity_type: String, // "Create" | "Announce"
+    pub actor: String,
+    pub object_id: String,
+}
+
 #[derive(Clone)]
 pub struct ApiState {
     pub homeserver_urls: Arc<Mutex<Vec<String>>>,
+    /// Pages published via `POST /ap/pages`, so they can be served back as
+    /// ActivityStreams `Article`s and looked up by WebFinger/actor URLs.
+    pub wiki_pages: Arc<Mutex<HashMap<(String, String), WikiPageRecord>>>,
+    /// Per-user outbox, keyed by `user_pubkey`, newest activity last.
+    pub outbox: Arc<Mutex<HashMap<String, Vec<OutboxActivity>>>>,
+    /// Scheme+host this server's own links are rendered under, e.g.
+    /// `"http://127.0.0.1:3030"`. ActivityPub ids must be stable absolute
+    /// URLs, so every handler below renders off this rather than relying
+    /// on the incoming request's `Host` header.
+    pub public_base_url: String,
+    /// Bearer token a `GET /ws/events` caller must present, so the push
+    /// stream isn't open to anyone who can reach the port.
+    pub auth_token: String,
+    /// Broadcasts `ApiEvent`s to every connected `/ws/events` socket.
+    /// `TestnetManager` holds a clone of the sender (see
+    /// `set_event_sender`) so `create_homeserver`/`write_to_homeserver`
+    /// can publish directly instead of the UI polling `GET /homeservers`.
+    pub events: broadcast::Sender<ApiEvent>,
+}
+
+/// One push event delivered over `/ws/events`, in place of polling
+/// `GET /homeservers` or re-fetching a wiki page on a timer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ApiEvent {
+    HomeserverCreated { http_url: String },
+    PathWritten { node_id: String, path: String },
 }
 
 #[derive(Serialize)]
@@ -23,21 +75,240 @@ async fn get_homeservers(State(state): State<ApiState>) -> Json<HomeserversRespo
     Json(HomeserversResponse { homeservers: urls })
 }
 
+fn actor_url(base: &str, pubkey: &str) -> String {
+    format!("{base}/ap/users/{pubkey}")
+}
+
+fn page_url(base: &str, pubkey: &str, page_id: &str) -> String {
+    format!("{base}/ap/users/{pubkey}/pages/{page_id}")
+}
+
+#[derive(Deserialize)]
+pub struct WebfingerQuery {
+    resource: String,
+}
+
+/// `GET /.well-known/webfinger?resource=acct:<pubkey>@host` — maps a Pubky
+/// public key to its ActivityStreams actor, the way Mastodon/Plume resolve
+/// a handle to a profile before following it.
+async fn webfinger(
+    State(state): State<ApiState>,
+    Query(query): Query<WebfingerQuery>,
+) -> impl IntoResponse {
+    let Some(acct) = query.resource.strip_prefix("acct:") else {
+        return (StatusCode::BAD_REQUEST, "resource must be an acct: URI").into_response();
+    };
+    let Some((pubkey, _host)) = acct.split_once('@') else {
+        return (StatusCode::BAD_REQUEST, "resource must be acct:<pubkey>@host").into_response();
+    };
+
+    Json(serde_json::json!({
+        "subject": query.resource,
+        "links": [{
+            "rel": "self",
+            "type": "application/activity+json",
+            "href": actor_url(&state.public_base_url, pubkey),
+        }]
+    }))
+    .into_response()
+}
+
+/// `GET /ap/users/:pubkey` — the ActivityStreams `Person` actor a Pubky
+/// public key resolves to.
+async fn get_actor(State(state): State<ApiState>, Path(pubkey): Path<String>) -> impl IntoResponse {
+    let id = actor_url(&state.public_base_url, &pubkey);
+    Json(serde_json::json!({
+        "@context": ["https://www.w3.org/ns/activitystreams"],
+        "id": id,
+        "type": "Person",
+        "preferredUsername": pubkey,
+        "inbox": format!("{id}/inbox"),
+        "outbox": format!("{id}/outbox"),
+    }))
+}
+
+/// `GET /ap/users/:pubkey/pages/:page_id` — a published wiki page as an
+/// ActivityStreams `Article`, at the stable URL remote followers store as
+/// the object id of the `Create`/`Announce` activity that introduced it.
+async fn get_page(
+    State(state): State<ApiState>,
+    Path((pubkey, page_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let record = state.wiki_pages.lock().unwrap().get(&(pubkey.clone(), page_id.clone())).cloned();
+    let Some(record) = record else {
+        return (StatusCode::NOT_FOUND, "no such page").into_response();
+    };
+
+    let id = page_url(&state.public_base_url, &pubkey, &page_id);
+    Json(serde_json::json!({
+        "@context": ["https://www.w3.org/ns/activitystreams"],
+        "id": id,
+        "type": "Article",
+        "attributedTo": actor_url(&state.public_base_url, &pubkey),
+        "content": record.content,
+        "url": id,
+    }))
+    .into_response()
+}
+
+/// `GET /ap/users/:pubkey/outbox` — an `OrderedCollection` of the
+/// `Create`/`Announce` activities recorded for this user, e.g. one
+/// `Announce` per fork of one of their pages.
+async fn get_outbox(State(state): State<ApiState>, Path(pubkey): Path<String>) -> impl IntoResponse {
+    let activities = state.outbox.lock().unwrap().get(&pubkey).cloned().unwrap_or_default();
+    let base = state.public_base_url.clone();
+    let items: Vec<_> = activities
+        .iter()
+        .map(|a| {
+            serde_json::json!({
+                "type": a.activity_type,
+                "actor": a.actor,
+                "object": a.object_id,
+            })
+        })
+        .collect();
+
+    Json(serde_json::json!({
+        "@context": ["https://www.w3.org/ns/activitystreams"],
+        "id": format!("{base}/ap/users/{pubkey}/outbox"),
+        "type": "OrderedCollection",
+        "totalItems": items.len(),
+        "orderedItems": items,
+    }))
+}
+
+/// Request body for `POST /ap/pages`: the federation bridge a wiki client
+/// (e.g. `wiky`'s Fork button) calls to publish a page and, when it was
+/// forked from another page, announce that fork to the source author's
+/// followers.
+#[derive(Deserialize)]
+pub struct PublishPageRequest {
+    pub user_pubkey: String,
+    pub page_id: String,
+    pub content: String,
+    pub forked_from: Option<ForkedFrom>,
+}
+
+#[derive(Deserialize)]
+pub struct ForkedFrom {
+    pub user_pubkey: String,
+    pub page_id: String,
+}
+
+/// `POST /ap/pages` — publish (or republish) a page, recording a `Create`
+/// in the author's own outbox and, for a fork, an `Announce` in the
+/// forked-from author's outbox pointing at the new page.
+async fn publish_page(
+    State(state): State<ApiState>,
+    Json(req): Json<PublishPageRequest>,
+) -> impl IntoResponse {
+    let base = state.public_base_url.clone();
+    let page_id_url = page_url(&base, &req.user_pubkey, &req.page_id);
+    let actor = actor_url(&base, &req.user_pubkey);
+
+    state.wiki_pages.lock().unwrap().insert(
+        (req.user_pubkey.clone(), req.page_id.clone()),
+        WikiPageRecord {
+            user_pubkey: req.user_pubkey.clone(),
+            page_id: req.page_id.clone(),
+            content: req.content.clone(),
+        },
+    );
+
+    state.outbox.lock().unwrap().entry(req.user_pubkey.clone()).or_default().push(OutboxActivity {
+        activity_type: "Create".to_string(),
+        actor: actor.clone(),
+        object_id: page_id_url.clone(),
+    });
+
+    if let Some(forked_from) = &req.forked_from {
+        let source_actor = actor_url(&base, &forked_from.user_pubkey);
+        state.outbox.lock().unwrap().entry(forked_from.user_pubkey.clone()).or_default().push(OutboxActivity {
+            activity_type: "Announce".to_string(),
+            actor: source_actor,
+            object_id: page_id_url.clone(),
+        });
+    }
+
+    (StatusCode::CREATED, Json(serde_json::json!({ "id": page_id_url })))
+}
+
+/// `GET /ws/events` — upgrades to a WebSocket that pushes `ApiEvent`s as
+/// JSON text frames, so a client can drop a polling loop in favor of
+/// reacting to live updates. Requires `Authorization: Bearer <token>`
+/// matching `ApiState::auth_token`; a missing or wrong token is rejected
+/// before the upgrade completes (following the websocket-server-with-
+/// auth-token model: authenticate once, up front, rather than per frame).
+async fn ws_events(State(state): State<ApiState>, headers: HeaderMap, ws: WebSocketUpgrade) -> impl IntoResponse {
+    let presented = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    if presented != Some(state.auth_token.as_str()) {
+        return (StatusCode::UNAUTHORIZED, "missing or invalid bearer token").into_response();
+    }
+
+    ws.on_upgrade(move |socket| stream_events(socket, state)).into_response()
+}
+
+/// Forward every `ApiEvent` broadcast by `TestnetManager` to this socket
+/// until the client disconnects or the sender is closed. A socket that
+/// falls behind skips the events it missed and keeps streaming.
+async fn stream_events(mut socket: WebSocket, state: ApiState) {
+    let mut events = state.events.subscribe();
+    loop {
+        match events.recv().await {
+            Ok(event) => {
+                let Ok(payload) = serde_json::to_string(&event) else { continue };
+                if socket.send(Message::Text(payload)).await.is_err() {
+                    break;
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
 pub fn create_router(state: ApiState) -> Router {
     Router::new()
         .route("/homeservers", get(get_homeservers))
+        .route("/.well-known/webfinger", get(webfinger))
+        .route("/ap/users/{pubkey}", get(get_actor))
+        .route("/ap/users/{pubkey}/pages/{page_id}", get(get_page))
+        .route("/ap/users/{pubkey}/outbox", get(get_outbox))
+        .route("/ap/pages", post(publish_page))
+        .route("/ws/events", get(ws_events))
         .layer(CorsLayer::permissive())
         .with_state(state)
 }
 
-pub async fn start_api_server(state: ApiState, port: u16) -> anyhow::Result<()> {
+/// Serve `state` on `port` until `shutdown` resolves, then drain in-flight
+/// requests and return. Callers trigger `shutdown` (e.g. by cancelling a
+/// `CancellationToken`) instead of aborting this future outright, so a
+/// restart never races a still-closing listener on the same port.
+pub async fn start_api_server(
+    state: ApiState,
+    port: u16,
+    shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+) -> anyhow::Result<()> {
     let app = create_router(state);
     let addr = format!("127.0.0.1:{}", port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
 
     println!("API server running on http://{}", addr);
-    println!("  GET http://{}/homeservers - List all homeserver URLs", addr);
+    println!("  GET  http://{}/homeservers - List all homeserver URLs", addr);
+    println!("  GET  http://{}/.well-known/webfinger?resource=acct:<pubkey>@host", addr);
+    println!("  GET  http://{}/ap/users/:pubkey - ActivityStreams Person actor", addr);
+    println!("  GET  http://{}/ap/users/:pubkey/pages/:page_id - ActivityStreams Article", addr);
+    println!("  POST http://{}/ap/pages - publish a page, announcing forks to the source outbox", addr);
+    println!("  GET  ws://{}/ws/events - push feed (Authorization: Bearer <auth_token>)", addr);
+
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown)
+        .await?;
 
-    axum::serve(listener, app).await?;
+    println!("API server on http://{} shut down gracefully", addr);
     Ok(())
 }
@@ -1,7 +1,18 @@
 use dioxus::prelude::*;
-use super::network_visualization::{Node, NodeStatus, ConnectivityStatus};
+use super::network_visualization::{Node, NodeStatus, ConnectivityStatus, NodeGroup};
+use super::sparkline::Sparkline;
+use super::directory_browser::DirectoryBrowser;
+use crate::routing::RoutingSummary;
+use crate::session_export::ExportFormat;
+use crate::directory_tree::TreeNode;
+use crate::alerts::{Alert, Severity};
+use crate::http_exchange::HttpExchangeCapture;
 
-fn format_bytes(bytes: usize) -> String {
+/// Swatch palette offered by the Group control, alongside the custom hex
+/// input for anything outside this set.
+const GROUP_COLORS: [&str; 6] = ["#c7ff00", "#60a5fa", "#f472b6", "#eab308", "#34d399", "#a78bfa"];
+
+pub(crate) fn format_bytes(bytes: usize) -> String {
     const KB: usize = 1024;
     const MB: usize = KB * 1024;
     const GB: usize = MB * 1024;
@@ -17,6 +28,95 @@ fn format_bytes(bytes: usize) -> String {
     }
 }
 
+/// Render a parsed `HttpExchange`'s headers one per line, for the Inspect
+/// panel's monospaced request/response blocks.
+fn format_headers(headers: &std::collections::BTreeMap<String, String>) -> String {
+    headers.iter().map(|(k, v)| format!("{k}: {v}\n")).collect()
+}
+
+fn format_body(body: &Option<String>) -> String {
+    match body {
+        Some(b) => format!("\n{b}"),
+        None => String::new(),
+    }
+}
+
+/// Flatten a `TreeNode` forest into every leaf's full path, for the
+/// "Read All" batch action.
+fn collect_leaf_paths(nodes: &[TreeNode], out: &mut Vec<String>) {
+    for node in nodes {
+        if node.leaf.is_some() {
+            out.push(node.path.clone());
+        }
+        collect_leaf_paths(&node.children, out);
+    }
+}
+
+/// Live health summary for the Diagnostics section, derived entirely from
+/// `event_log` entries that mention this node's id — there's no separate
+/// per-node metrics store, so the log doubles as one.
+#[derive(Default)]
+struct NodeDiagnostics {
+    last_successful_read_at: Option<String>,
+    success_count: usize,
+    error_count: usize,
+    avg_read_latency_ms: Option<f64>,
+}
+
+fn node_diagnostics(node_id: &str, event_log: &[EventLogEntry]) -> NodeDiagnostics {
+    use chrono::NaiveTime;
+
+    let mut diag = NodeDiagnostics::default();
+    let mut pending_read_start: Option<NaiveTime> = None;
+    let mut latencies_ms: Vec<f64> = Vec::new();
+    let read_start_prefix = format!("Reading data from {node_id}");
+
+    for entry in event_log {
+        if !entry.message.contains(node_id) {
+            continue;
+        }
+        let parsed_at = NaiveTime::parse_from_str(&entry.timestamp, "%H:%M:%S%.3f").ok();
+
+        if entry.message.starts_with(&read_start_prefix) {
+            pending_read_start = parsed_at;
+        } else if entry.message.starts_with("✓ Read data:") {
+            diag.success_count += 1;
+            diag.last_successful_read_at = Some(entry.timestamp.clone());
+            if let (Some(start), Some(end)) = (pending_read_start.take(), parsed_at) {
+                latencies_ms.push((end - start).num_milliseconds() as f64);
+            }
+        } else if entry.message.starts_with("✗ Read failed:") {
+            diag.error_count += 1;
+            pending_read_start = None;
+        } else if entry.message.starts_with("✓ Wrote data:") {
+            diag.success_count += 1;
+        } else if entry.message.starts_with("✗ Write failed:") {
+            diag.error_count += 1;
+        }
+    }
+
+    diag.avg_read_latency_ms = if latencies_ms.is_empty() {
+        None
+    } else {
+        Some(latencies_ms.iter().sum::<f64>() / latencies_ms.len() as f64)
+    };
+
+    diag
+}
+
+/// Status pill color for the Diagnostics section, combining the node's own
+/// status with what its recent read/write history looks like.
+fn diagnostics_pill_class(node: &Node, diag: &NodeDiagnostics) -> &'static str {
+    let node_errored = matches!(node.status(), NodeStatus::Error);
+    if node_errored || (diag.error_count > 0 && diag.success_count == 0) {
+        "w-2 h-2 rounded-full bg-red-500"
+    } else if diag.error_count > 0 {
+        "w-2 h-2 rounded-full bg-yellow-500"
+    } else {
+        "w-2 h-2 rounded-full bg-green-500"
+    }
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct EventLogEntry {
     pub id: usize,
@@ -25,18 +125,42 @@ pub struct EventLogEntry {
     pub event_type: EventType,
 }
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum EventType {
     Success,
     Error,
     Info,
 }
 
+impl EventType {
+    pub const ALL: [EventType; 3] = [EventType::Success, EventType::Error, EventType::Info];
+
+    fn label(&self) -> &'static str {
+        match self {
+            EventType::Success => "Success",
+            EventType::Error => "Error",
+            EventType::Info => "Info",
+        }
+    }
+}
+
 #[derive(Props, Clone, PartialEq)]
 pub struct ContextSidebarProps {
     pub selected_node: Option<Node>,
     pub all_nodes: Vec<Node>,
+    pub routing_summary: Option<RoutingSummary>,
     pub event_log: Vec<EventLogEntry>,
+    /// Total entries before filtering, for the "showing X of Y" count.
+    pub event_log_total: usize,
+    /// Current search query, shared across the event log and the node
+    /// graph — `on_search_change` updates it, and `event_log` here already
+    /// arrives pre-filtered to matches.
+    pub search_query: String,
+    pub on_search_change: EventHandler<String>,
+    /// Event types currently shown. `on_toggle_event_type` flips one type's
+    /// membership; `event_log` here already arrives filtered to this set.
+    pub event_type_filter: std::collections::HashSet<EventType>,
+    pub on_toggle_event_type: EventHandler<EventType>,
     pub is_writing: bool,
     pub is_reading: bool,
     pub sidebar_width: i32,
@@ -48,8 +172,45 @@ pub struct ContextSidebarProps {
     pub on_connect_client: EventHandler<(String, String)>, // (client_id, homeserver_id)
     pub on_write_data: EventHandler<(String, String, String)>, // (client_id, path, content)
     pub on_read_data: EventHandler<(String, String)>, // (client_id, path)
+    /// Tree built from the last `on_list_directory` response, already
+    /// nested and scoped to whatever prefix was last browsed.
+    pub directory_tree: Vec<TreeNode>,
+    pub on_list_directory: EventHandler<(String, String)>, // (client_id, prefix)
+    /// Paths with a "Read All" batch read currently in flight, so each
+    /// browser row can show its own spinner instead of one shared
+    /// `is_reading` boolean.
+    #[props(default)]
+    pub reading_paths: std::collections::HashSet<String>,
+    pub on_read_all: EventHandler<(String, Vec<String>)>, // (client_id, paths)
     pub on_resize_sidebar: EventHandler<i32>,
     pub on_resize_eventlog: EventHandler<i32>,
+    pub on_collapse: EventHandler<()>,
+    /// Export the current nodes/event log as a downloadable capture.
+    pub on_export: EventHandler<ExportFormat>,
+    /// Active anomaly alerts (flapping, storage growth, stuck ops,
+    /// homeserver errors), already deduplicated and age-stamped.
+    pub alerts: Vec<Alert>,
+    pub on_acknowledge_alert: EventHandler<usize>,
+    /// Request/response pair captured from the most recent client
+    /// write/read, rendered in the "Inspect" panel below Test Read/Write.
+    pub http_exchange: Option<HttpExchangeCapture>,
+    /// Empties the event log entirely.
+    pub on_clear_log: EventHandler<()>,
+    /// Every group currently in use, for the Group control's "existing
+    /// group" picker.
+    pub all_groups: Vec<NodeGroup>,
+    /// The selected node's current group, if any.
+    pub current_group: Option<NodeGroup>,
+    pub on_assign_group: EventHandler<(String, String, String, String)>, // (node_id, group_id, label, color)
+    pub on_clear_group: EventHandler<String>, // node_id
+    /// Export the whole graph (nodes, groups, event log) to a mergeable
+    /// JSON file.
+    pub on_export_graph: EventHandler<()>,
+    /// Import and merge a previously exported graph from a file path.
+    pub on_import_graph: EventHandler<String>,
+    /// Actively re-probe the selected node (e.g. a fresh connectivity test)
+    /// from the Diagnostics section's "Refresh" button.
+    pub on_refresh_diagnostics: EventHandler<String>,
 }
 
 #[component]
@@ -68,6 +229,57 @@ pub fn ContextSidebar(props: ContextSidebarProps) -> Element {
                 },
             }
 
+            // Collapse toggle: hides this panel, replaced in the parent by
+            // a thin re-open handle, for users who want full
+            // NetworkVisualization room.
+            button {
+                class: "absolute top-2 left-2 z-10 h-6 w-6 flex items-center justify-center rounded-md bg-zinc-900 hover:bg-zinc-800 text-zinc-400 border border-zinc-800 transition-all",
+                onclick: move |_| props.on_collapse.call(()),
+                title: "Collapse sidebar",
+                "»"
+            }
+
+            // Alerts banner: derived-metric anomalies (flapping, storage
+            // growth, stuck ops, homeserver errors), the way a network
+            // monitor surfaces flood/anomaly detection above its main view.
+            if !props.alerts.is_empty() {
+                div {
+                    class: "border-b border-zinc-800 max-h-40 overflow-auto",
+                    for alert in props.alerts.iter() {
+                        {
+                            let alert_id = alert.id;
+                            let meta = if alert.count > 1 {
+                                format!("{} · ×{} · {}s ago", alert.node_id, alert.count, alert.first_seen_secs_ago)
+                            } else {
+                                format!("{} · {}s ago", alert.node_id, alert.first_seen_secs_ago)
+                            };
+                            rsx! {
+                                div {
+                                    key: "{alert_id}",
+                                    class: "flex items-start gap-2 px-4 py-2 border-b border-zinc-900 last:border-b-0",
+                                    div {
+                                        class: match alert.severity {
+                                            Severity::Critical => "w-2 h-2 rounded-full bg-red-500 mt-1 flex-shrink-0",
+                                            Severity::Warning => "w-2 h-2 rounded-full bg-yellow-500 mt-1 flex-shrink-0",
+                                        },
+                                    }
+                                    div {
+                                        class: "flex-1 min-w-0",
+                                        p { class: "text-xs text-zinc-300", "{alert.message}" }
+                                        p { class: "text-[10px] text-zinc-600 mt-0.5", "{meta}" }
+                                    }
+                                    button {
+                                        class: "text-[10px] text-zinc-600 hover:text-zinc-400 flex-shrink-0",
+                                        onclick: move |_| props.on_acknowledge_alert.call(alert_id),
+                                        "dismiss"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
             // Top section: Node details (scrollable)
             div {
                 class: "flex-1 overflow-auto p-4",
@@ -119,6 +331,52 @@ pub fn ContextSidebar(props: ContextSidebarProps) -> Element {
                         }
                     }
 
+                    // Routing table (Kademlia k-buckets this node currently knows about)
+                    if let Some(summary) = &props.routing_summary {
+                        div {
+                            class: "mb-6 p-3 bg-zinc-900/50 rounded-lg border border-zinc-800",
+                            h3 {
+                                class: "text-xs font-medium text-zinc-400 mb-3",
+                                "Routing Table ({summary.buckets.iter().map(|b| b.peers.len()).sum::<usize>()} peers, {summary.buckets.len()} buckets)"
+                            }
+
+                            if summary.buckets.is_empty() {
+                                p {
+                                    class: "text-xs text-zinc-600",
+                                    "No peers discovered yet"
+                                }
+                            } else {
+                                div {
+                                    class: "space-y-1.5 max-h-48 overflow-auto",
+                                    for bucket in summary.buckets.iter() {
+                                        div {
+                                            key: "{bucket.index}",
+                                            class: "flex items-center justify-between gap-2",
+                                            span {
+                                                class: "text-[10px] font-mono text-zinc-600 w-10 flex-shrink-0",
+                                                "#{bucket.index}"
+                                            }
+                                            div {
+                                                class: "flex flex-wrap gap-1 justify-end",
+                                                for peer in bucket.peers.iter() {
+                                                    span {
+                                                        key: "{peer.label}",
+                                                        class: if peer.alive {
+                                                            "text-[10px] font-mono px-1.5 py-0.5 rounded bg-blue-500/10 text-blue-400"
+                                                        } else {
+                                                            "text-[10px] font-mono px-1.5 py-0.5 rounded bg-zinc-800 text-zinc-500"
+                                                        },
+                                                        "{peer.label}"
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
                     // Node-specific content
                     match node {
                         Node::Homeserver(homeserver) => rsx! {
@@ -161,23 +419,32 @@ pub fn ContextSidebar(props: ContextSidebarProps) -> Element {
                                     class: "flex items-center gap-2 mb-2",
                                     div {
                                         class: match &homeserver.connectivity_status {
-                                            ConnectivityStatus::Connected => "w-2 h-2 rounded-full bg-green-500",
+                                            ConnectivityStatus::DirectlyReachable { .. } => "w-2 h-2 rounded-full bg-green-500",
+                                            ConnectivityStatus::BehindNat { .. } => "w-2 h-2 rounded-full bg-blue-500",
                                             ConnectivityStatus::Testing => "w-2 h-2 rounded-full bg-yellow-500 animate-pulse",
-                                            ConnectivityStatus::Failed => "w-2 h-2 rounded-full bg-red-500",
+                                            ConnectivityStatus::Unreachable { .. } => "w-2 h-2 rounded-full bg-red-500",
                                             ConnectivityStatus::Unknown => "w-2 h-2 rounded-full bg-zinc-600",
                                         }
                                     }
                                     span {
                                         class: "text-sm text-zinc-300",
                                         match &homeserver.connectivity_status {
-                                            ConnectivityStatus::Connected => "Connected",
-                                            ConnectivityStatus::Testing => "Testing...",
-                                            ConnectivityStatus::Failed => "Failed",
-                                            ConnectivityStatus::Unknown => "Unknown",
+                                            ConnectivityStatus::DirectlyReachable { latency_ms } => format!("Directly reachable ({} ms)", latency_ms),
+                                            ConnectivityStatus::BehindNat { latency_ms } => format!("Behind NAT ({} ms)", latency_ms),
+                                            ConnectivityStatus::Testing => "Testing...".to_string(),
+                                            ConnectivityStatus::Unreachable { reason } => format!("Unreachable: {}", reason),
+                                            ConnectivityStatus::Unknown => "Unknown".to_string(),
                                         }
                                     }
                                 }
 
+                                if let Some(secs) = homeserver.last_seen_secs_ago {
+                                    div {
+                                        class: "text-xs text-zinc-500 mb-2",
+                                        "Last seen {secs}s ago"
+                                    }
+                                }
+
                                 // HTTP URL section
                                 if let Some(url) = &homeserver.http_url {
                                     div {
@@ -238,6 +505,55 @@ pub fn ContextSidebar(props: ContextSidebarProps) -> Element {
                                                 "{format_bytes(stats.total_size_bytes)}"
                                             }
                                         }
+                                        div {
+                                            class: "flex justify-between items-center",
+                                            span {
+                                                class: "text-xs text-zinc-500",
+                                                "Available"
+                                            }
+                                            span {
+                                                class: "text-xs font-mono text-zinc-300",
+                                                "{format_bytes(stats.available_bytes as usize)} / {format_bytes(stats.capacity_bytes as usize)}"
+                                            }
+                                        }
+                                        div {
+                                            class: "w-full h-1.5 bg-zinc-800 rounded-full overflow-hidden",
+                                            div {
+                                                class: "h-full bg-[#c7ff00]",
+                                                style: "width: {(100.0 * (stats.capacity_bytes.saturating_sub(stats.available_bytes)) as f64 / stats.capacity_bytes.max(1) as f64).min(100.0)}%",
+                                            }
+                                        }
+                                    }
+
+                                    {
+                                        let rates = homeserver.bandwidth_history.rates();
+                                        let read_values: Vec<f64> = rates.iter().map(|(r, _)| *r).collect();
+                                        let write_values: Vec<f64> = rates.iter().map(|(_, w)| *w).collect();
+                                        let (peak_read, peak_write) = homeserver.bandwidth_history.peak_rates();
+
+                                        rsx! {
+                                            div {
+                                                class: "mt-3 pt-3 border-t border-zinc-800 space-y-2",
+                                                div {
+                                                    class: "flex justify-between items-center",
+                                                    span { class: "text-xs text-zinc-500", "Read" }
+                                                    span {
+                                                        class: "text-xs font-mono text-zinc-300",
+                                                        "peak {format_bytes(peak_read as usize)}/s"
+                                                    }
+                                                }
+                                                Sparkline { values: read_values, width: 220, height: 32, stroke: "#38bdf8".to_string() }
+                                                div {
+                                                    class: "flex justify-between items-center",
+                                                    span { class: "text-xs text-zinc-500", "Write" }
+                                                    span {
+                                                        class: "text-xs font-mono text-zinc-300",
+                                                        "peak {format_bytes(peak_write as usize)}/s"
+                                                    }
+                                                }
+                                                Sparkline { values: write_values, width: 220, height: 32, stroke: "#c7ff00".to_string() }
+                                            }
+                                        }
                                     }
                                 }
                             }
@@ -247,6 +563,8 @@ pub fn ContextSidebar(props: ContextSidebarProps) -> Element {
                             let mut write_path = use_signal(|| String::from("/pub/publar/test.txt"));
                             let mut write_content = use_signal(|| String::from("Hello, Pubky!"));
                             let mut read_path = use_signal(|| String::from("/pub/publar/test.txt"));
+                            let mut inspect_expanded = use_signal(|| false);
+                            let mut browse_prefix = use_signal(|| String::from("/pub/"));
 
                             // Get available homeservers
                             let homeservers: Vec<_> = props.all_nodes.iter()
@@ -343,6 +661,72 @@ pub fn ContextSidebar(props: ContextSidebarProps) -> Element {
                                     }
                                 }
 
+                                // Content browser: lists tracked keys under a prefix, grouped
+                                // into a collapsible folder tree. Clicking an entry fills in
+                                // both read_path and write_path below, the way a file picker
+                                // fills a path field rather than requiring it typed by hand.
+                                if client.connected_homeserver.is_some() {
+                                    div {
+                                        class: "mb-6 p-3 bg-zinc-900/50 rounded-lg border border-zinc-800",
+                                        h3 {
+                                            class: "text-xs font-medium text-zinc-400 mb-3",
+                                            "Browse"
+                                        }
+                                        div {
+                                            class: "flex gap-2 mb-3",
+                                            input {
+                                                class: "flex-1 min-w-0 px-2 py-1.5 rounded-md bg-zinc-900 text-zinc-300 text-xs border border-zinc-800 focus:outline-none focus:border-zinc-600 font-mono",
+                                                r#type: "text",
+                                                value: "{browse_prefix}",
+                                                oninput: move |evt| browse_prefix.set(evt.value()),
+                                                placeholder: "/pub/"
+                                            }
+                                            {
+                                                let client_id = client.id.clone();
+                                                rsx! {
+                                                    button {
+                                                        class: "px-3 py-1.5 rounded-md bg-zinc-800 hover:bg-zinc-700 text-zinc-300 text-xs font-medium transition-all whitespace-nowrap",
+                                                        onclick: move |_| {
+                                                            props.on_list_directory.call((client_id.clone(), browse_prefix()));
+                                                        },
+                                                        "Browse"
+                                                    }
+                                                }
+                                            }
+                                        }
+                                        if props.directory_tree.is_empty() {
+                                            p {
+                                                class: "text-xs text-zinc-600 italic",
+                                                "No entries yet — write some data below, then Browse."
+                                            }
+                                        } else {
+                                            {
+                                                let client_id = client.id.clone();
+                                                let mut leaf_paths = Vec::new();
+                                                collect_leaf_paths(&props.directory_tree, &mut leaf_paths);
+                                                let count = leaf_paths.len();
+                                                rsx! {
+                                                    button {
+                                                        class: "mb-2 w-full px-3 py-1.5 rounded-md bg-zinc-800 hover:bg-zinc-700 text-zinc-300 text-xs font-medium transition-all",
+                                                        onclick: move |_| {
+                                                            props.on_read_all.call((client_id.clone(), leaf_paths.clone()));
+                                                        },
+                                                        "Read All ({count})"
+                                                    }
+                                                }
+                                            }
+                                            DirectoryBrowser {
+                                                nodes: props.directory_tree.clone(),
+                                                reading_paths: props.reading_paths.clone(),
+                                                on_select: move |path: String| {
+                                                    read_path.set(path.clone());
+                                                    write_path.set(path);
+                                                },
+                                            }
+                                        }
+                                    }
+                                }
+
                                 // Read/Write controls (only show if connected)
                                 if client.connected_homeserver.is_some() {
                                     div {
@@ -359,12 +743,23 @@ pub fn ContextSidebar(props: ContextSidebarProps) -> Element {
                                                     class: "block text-xs text-zinc-500 mb-1",
                                                     "Write Path"
                                                 }
-                                                input {
-                                                    class: "w-full px-2 py-1.5 mb-2 rounded-md bg-zinc-900 text-zinc-300 text-xs border border-zinc-800 focus:outline-none focus:border-zinc-600 font-mono",
-                                                    r#type: "text",
-                                                    value: "{write_path}",
-                                                    oninput: move |evt| write_path.set(evt.value()),
-                                                    placeholder: "/pub/publar/example.txt"
+                                                {
+                                                    let client_id = client.id.clone();
+                                                    rsx! {
+                                                        input {
+                                                            class: "w-full px-2 py-1.5 mb-2 rounded-md bg-zinc-900 text-zinc-300 text-xs border border-zinc-800 focus:outline-none focus:border-zinc-600 font-mono",
+                                                            r#type: "text",
+                                                            value: "{write_path}",
+                                                            oninput: move |evt| write_path.set(evt.value()),
+                                                            onkeydown: move |evt: KeyboardEvent| {
+                                                                let mods = evt.modifiers();
+                                                                if evt.key() == Key::Enter && !mods.shift() && !mods.ctrl() && !mods.alt() && !mods.meta() {
+                                                                    props.on_write_data.call((client_id.clone(), write_path(), write_content()));
+                                                                }
+                                                            },
+                                                            placeholder: "/pub/publar/example.txt"
+                                                        }
+                                                    }
                                                 }
 
                                                 label {
@@ -414,12 +809,23 @@ pub fn ContextSidebar(props: ContextSidebarProps) -> Element {
                                                     class: "block text-xs text-zinc-500 mb-1",
                                                     "Read Path"
                                                 }
-                                                input {
-                                                    class: "w-full px-2 py-1.5 mb-2 rounded-md bg-zinc-900 text-zinc-300 text-xs border border-zinc-800 focus:outline-none focus:border-zinc-600 font-mono",
-                                                    r#type: "text",
-                                                    value: "{read_path}",
-                                                    oninput: move |evt| read_path.set(evt.value()),
-                                                    placeholder: "/pub/publar/example.txt"
+                                                {
+                                                    let client_id = client.id.clone();
+                                                    rsx! {
+                                                        input {
+                                                            class: "w-full px-2 py-1.5 mb-2 rounded-md bg-zinc-900 text-zinc-300 text-xs border border-zinc-800 focus:outline-none focus:border-zinc-600 font-mono",
+                                                            r#type: "text",
+                                                            value: "{read_path}",
+                                                            oninput: move |evt| read_path.set(evt.value()),
+                                                            onkeydown: move |evt: KeyboardEvent| {
+                                                                let mods = evt.modifiers();
+                                                                if evt.key() == Key::Enter && !mods.shift() && !mods.ctrl() && !mods.alt() && !mods.meta() {
+                                                                    props.on_read_data.call((client_id.clone(), read_path()));
+                                                                }
+                                                            },
+                                                            placeholder: "/pub/publar/example.txt"
+                                                        }
+                                                    }
                                                 }
 
                                                 {
@@ -448,6 +854,262 @@ pub fn ContextSidebar(props: ContextSidebarProps) -> Element {
                                                     }
                                                 }
                                             }
+
+                                            // Inspect panel: the raw HTTP exchange behind the
+                                            // last write/read, collapsed by default since most
+                                            // users only want it when debugging.
+                                            if let Some(exchange) = &props.http_exchange {
+                                                div {
+                                                    class: "mt-4 pt-3 border-t border-zinc-800",
+                                                    button {
+                                                        class: "w-full flex items-center justify-between text-xs text-zinc-500 hover:text-zinc-300",
+                                                        onclick: move |_| inspect_expanded.set(!inspect_expanded()),
+                                                        span { "Inspect last HTTP exchange" }
+                                                        span { if inspect_expanded() { "▾" } else { "▸" } }
+                                                    }
+                                                    if inspect_expanded() {
+                                                        div {
+                                                            class: "mt-2 space-y-2",
+                                                            p {
+                                                                class: "text-xs text-zinc-500",
+                                                                "Request"
+                                                            }
+                                                            pre {
+                                                                class: "text-xs font-mono text-zinc-300 break-all whitespace-pre-wrap bg-zinc-900 p-2 rounded border border-zinc-800",
+                                                                "{exchange.request.verb} {exchange.request.path}\n{format_headers(&exchange.request.headers)}{format_body(&exchange.request.body)}"
+                                                            }
+                                                            p {
+                                                                class: "text-xs text-zinc-500",
+                                                                "Response"
+                                                            }
+                                                            pre {
+                                                                class: "text-xs font-mono text-zinc-300 break-all whitespace-pre-wrap bg-zinc-900 p-2 rounded border border-zinc-800",
+                                                                "{exchange.response.status.map(|s| s.to_string()).unwrap_or_default()}\n{format_headers(&exchange.response.headers)}{format_body(&exchange.response.body)}"
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    // Diagnostics: live health for the selected node, derived
+                    // from the event log rather than a separate metrics
+                    // store, so it stays in sync with whatever's already
+                    // being logged for writes/reads/connectivity.
+                    {
+                        let node_id = node.id().to_string();
+                        let diag = node_diagnostics(&node_id, &props.event_log);
+                        let pill_class = diagnostics_pill_class(node, &diag);
+                        let last_read_text = diag.last_successful_read_at.clone().unwrap_or_else(|| "never".to_string());
+                        let connection_text = format!("{:?}", node.status());
+                        let latency_text = diag
+                            .avg_read_latency_ms
+                            .map(|ms| format!("{ms:.0}ms"))
+                            .unwrap_or_else(|| "n/a".to_string());
+                        let mut diagnostics_expanded = use_signal(|| true);
+                        rsx! {
+                            div {
+                                class: "mb-6 p-3 bg-zinc-900/50 rounded-lg border border-zinc-800",
+                                button {
+                                    class: "w-full flex items-center justify-between",
+                                    onclick: move |_| diagnostics_expanded.set(!diagnostics_expanded()),
+                                    div {
+                                        class: "flex items-center gap-2",
+                                        div { class: "{pill_class}" }
+                                        h3 { class: "text-xs font-medium text-zinc-400", "Diagnostics" }
+                                    }
+                                    span {
+                                        class: "text-xs text-zinc-600",
+                                        if diagnostics_expanded() { "▾" } else { "▸" }
+                                    }
+                                }
+                                if diagnostics_expanded() {
+                                    div {
+                                        class: "mt-3 space-y-1.5",
+                                        div {
+                                            class: "flex items-center justify-between text-xs",
+                                            span { class: "text-zinc-500", "Last successful read" }
+                                            span {
+                                                class: "text-zinc-300 font-mono",
+                                                "{last_read_text}"
+                                            }
+                                        }
+                                        div {
+                                            class: "flex items-center justify-between text-xs",
+                                            span { class: "text-zinc-500", "Success / Error" }
+                                            span {
+                                                class: "text-zinc-300 font-mono",
+                                                "{diag.success_count} / {diag.error_count}"
+                                            }
+                                        }
+                                        div {
+                                            class: "flex items-center justify-between text-xs",
+                                            span { class: "text-zinc-500", "Avg read latency" }
+                                            span {
+                                                class: "text-zinc-300 font-mono",
+                                                "{latency_text}"
+                                            }
+                                        }
+                                        div {
+                                            class: "flex items-center justify-between text-xs",
+                                            span { class: "text-zinc-500", "Connection" }
+                                            span {
+                                                class: "text-zinc-300",
+                                                "{connection_text}"
+                                            }
+                                        }
+                                        button {
+                                            class: "w-full mt-1 px-3 py-1 rounded-md bg-zinc-900 hover:bg-zinc-800 text-zinc-400 text-[11px] border border-zinc-800 transition-all",
+                                            onclick: move |_| props.on_refresh_diagnostics.call(node_id.clone()),
+                                            "Refresh"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    // Group: assign the selected node to a named, colored
+                    // group, mirroring the grouping feature of flow editors.
+                    // Membership is tracked by the parent keyed off node id
+                    // (see `NodeGroup`), not stored on the node itself.
+                    {
+                        let node_id = node.id().to_string();
+                        let existing_label = props.current_group.as_ref().map(|g| g.label.clone()).unwrap_or_default();
+                        let existing_color = props.current_group.as_ref().map(|g| g.color.clone()).unwrap_or_else(|| GROUP_COLORS[0].to_string());
+                        let mut group_choice = use_signal(|| String::from("new"));
+                        let mut group_label = use_signal(move || existing_label.clone());
+                        let mut group_color = use_signal(move || existing_color.clone());
+
+                        rsx! {
+                            div {
+                                class: "mb-6 p-3 bg-zinc-900/50 rounded-lg border border-zinc-800",
+                                h3 {
+                                    class: "text-xs font-medium text-zinc-400 mb-3",
+                                    "Group"
+                                }
+
+                                if let Some(group) = &props.current_group {
+                                    div {
+                                        class: "flex items-center gap-2 mb-3",
+                                        div {
+                                            class: "w-2.5 h-2.5 rounded-full flex-shrink-0",
+                                            style: "background-color: {group.color};",
+                                        }
+                                        span {
+                                            class: "text-sm text-zinc-300",
+                                            "{group.label}"
+                                        }
+                                        {
+                                            let node_id = node_id.clone();
+                                            rsx! {
+                                                button {
+                                                    class: "ml-auto text-[10px] text-zinc-600 hover:text-zinc-400",
+                                                    onclick: move |_| props.on_clear_group.call(node_id.clone()),
+                                                    "clear"
+                                                }
+                                            }
+                                        }
+                                    }
+                                } else {
+                                    p {
+                                        class: "text-xs text-zinc-600 mb-3",
+                                        "Not in a group"
+                                    }
+                                }
+
+                                label {
+                                    class: "block text-xs text-zinc-500 mb-1",
+                                    "Group"
+                                }
+                                select {
+                                    class: "w-full px-2 py-1.5 mb-2 rounded-md bg-zinc-900 text-zinc-300 text-xs border border-zinc-800 focus:outline-none focus:border-zinc-600",
+                                    value: "{group_choice}",
+                                    onchange: move |evt| {
+                                        let value = evt.value();
+                                        if value != "new" {
+                                            if let Some(existing) = props.all_groups.iter().find(|g| g.id == value) {
+                                                group_label.set(existing.label.clone());
+                                                group_color.set(existing.color.clone());
+                                            }
+                                        }
+                                        group_choice.set(value);
+                                    },
+                                    option { value: "new", "+ New group" }
+                                    for group in props.all_groups.iter() {
+                                        option { key: "{group.id}", value: "{group.id}", "{group.label}" }
+                                    }
+                                }
+
+                                label {
+                                    class: "block text-xs text-zinc-500 mb-1",
+                                    "Label"
+                                }
+                                input {
+                                    class: "w-full px-2 py-1.5 mb-2 rounded-md bg-zinc-900 text-zinc-300 text-xs border border-zinc-800 focus:outline-none focus:border-zinc-600",
+                                    r#type: "text",
+                                    value: "{group_label}",
+                                    oninput: move |evt| group_label.set(evt.value()),
+                                    placeholder: "e.g. Region A",
+                                }
+
+                                label {
+                                    class: "block text-xs text-zinc-500 mb-1",
+                                    "Color"
+                                }
+                                div {
+                                    class: "flex items-center gap-1.5 mb-2",
+                                    for color in GROUP_COLORS.iter() {
+                                        {
+                                            let color = color.to_string();
+                                            let active = group_color() == color;
+                                            rsx! {
+                                                button {
+                                                    key: "{color}",
+                                                    class: if active {
+                                                        "w-5 h-5 rounded-full border-2 border-white"
+                                                    } else {
+                                                        "w-5 h-5 rounded-full border border-zinc-700"
+                                                    },
+                                                    style: "background-color: {color};",
+                                                    onclick: move |_| group_color.set(color.clone()),
+                                                }
+                                            }
+                                        }
+                                    }
+                                    input {
+                                        class: "w-20 px-2 py-1 rounded-md bg-zinc-900 text-zinc-300 text-xs border border-zinc-800 focus:outline-none focus:border-zinc-600 font-mono",
+                                        r#type: "text",
+                                        value: "{group_color}",
+                                        oninput: move |evt| group_color.set(evt.value()),
+                                        placeholder: "#c7ff00",
+                                    }
+                                }
+
+                                {
+                                    let node_id = node_id.clone();
+                                    rsx! {
+                                        button {
+                                            class: "w-full px-3 py-1.5 rounded-md text-xs font-medium transition-all",
+                                            style: "background-color: rgba(199, 255, 0, 0.1); color: #c7ff00; border: 1px solid rgba(199, 255, 0, 0.2);",
+                                            onclick: move |_| {
+                                                let choice = group_choice();
+                                                let label = group_label();
+                                                let group_id = if choice != "new" {
+                                                    choice
+                                                } else if label.trim().is_empty() {
+                                                    format!("group-{}", node_id)
+                                                } else {
+                                                    label.trim().to_lowercase().replace(' ', "-")
+                                                };
+                                                props.on_assign_group.call((node_id.clone(), group_id, label, group_color()));
+                                            },
+                                            "Assign to Group"
+                                        }
                                     }
                                 }
                             }
@@ -468,6 +1130,41 @@ pub fn ContextSidebar(props: ContextSidebarProps) -> Element {
                                 }
                             }
                         }
+
+                        // Export/import the whole graph (nodes, groups, event
+                        // log) as a mergeable JSON file, the way a flow editor
+                        // lets you copy a workspace out and paste it into
+                        // another without losing node ids to a full replace.
+                        button {
+                            class: "w-full px-3 py-1.5 rounded-md bg-zinc-900 hover:bg-zinc-800 text-zinc-300 text-xs font-medium border border-zinc-800 transition-all",
+                            onclick: move |_| props.on_export_graph.call(()),
+                            "Export Graph"
+                        }
+                        {
+                            let mut import_path = use_signal(String::new);
+                            rsx! {
+                                div {
+                                    class: "flex gap-2",
+                                    input {
+                                        class: "flex-1 min-w-0 px-2 py-1.5 rounded-md bg-zinc-900 text-zinc-300 text-xs border border-zinc-800 focus:outline-none focus:border-zinc-600 font-mono",
+                                        r#type: "text",
+                                        value: "{import_path}",
+                                        oninput: move |evt| import_path.set(evt.value()),
+                                        placeholder: "/path/to/graph-export.json",
+                                    }
+                                    button {
+                                        class: "px-3 py-1.5 rounded-md bg-zinc-900 hover:bg-zinc-800 text-zinc-300 text-xs font-medium border border-zinc-800 transition-all whitespace-nowrap",
+                                        onclick: move |_| {
+                                            if !import_path().trim().is_empty() {
+                                                props.on_import_graph.call(import_path());
+                                                import_path.set(String::new());
+                                            }
+                                        },
+                                        "Import Graph"
+                                    }
+                                }
+                            }
+                        }
                     }
                 }
             } else {
@@ -505,6 +1202,21 @@ pub fn ContextSidebar(props: ContextSidebarProps) -> Element {
             }
 
             // Bottom section: Event log (resizable height, scrollable)
+            {
+                // While paused, new events still arrive into `props.event_log`
+                // (the parent keeps buffering them) but rendering stays
+                // frozen on the snapshot taken the moment pause was toggled
+                // on, the way a flow-editor debug sidebar lets you inspect a
+                // burst of events without them scrolling out from under you.
+                let mut paused = use_signal(|| false);
+                let mut frozen_snapshot: Signal<Vec<EventLogEntry>> = use_signal(Vec::new);
+                let display_log = if paused() { frozen_snapshot() } else { props.event_log.clone() };
+                // Index into `rendered` (below) of the arrow-navigated entry,
+                // the way a prev/next documentation UI keeps a single
+                // focused item rather than relying on native DOM focus.
+                let mut focused_index: Signal<Option<usize>> = use_signal(|| None);
+                let rendered: Vec<EventLogEntry> = display_log.iter().rev().cloned().collect();
+                rsx! {
             div {
                 class: "border-t border-zinc-800 flex flex-col relative",
                 style: "height: {props.event_log_height}px;",
@@ -518,19 +1230,148 @@ pub fn ContextSidebar(props: ContextSidebarProps) -> Element {
                     },
                 }
 
-                // Header
+                // Header, with a query box searching both the event log
+                // below and node ids/status/type in the graph above.
                 div {
-                    class: "px-4 py-2 bg-zinc-900/50 border-b border-zinc-800",
-                    h3 {
-                        class: "text-xs font-medium text-zinc-400",
-                        "Event Log"
+                    class: "px-4 py-2 bg-zinc-900/50 border-b border-zinc-800 flex flex-col gap-2",
+                    div {
+                        class: "flex items-center gap-2",
+                        h3 {
+                            class: "text-xs font-medium text-zinc-400 flex-shrink-0",
+                            "Event Log"
+                        }
+                        input {
+                            class: "h-6 px-2 rounded-md bg-zinc-900 text-zinc-300 text-[11px] border border-zinc-800 flex-1 min-w-0",
+                            value: "{props.search_query}",
+                            oninput: move |evt| props.on_search_change.call(evt.value()),
+                            placeholder: "Fuzzy search events...",
+                        }
+                        button {
+                            class: if paused() {
+                                "h-6 px-2 rounded-md bg-[#c7ff00]/10 text-[#c7ff00] text-[11px] border border-[#c7ff00]/50 flex-shrink-0"
+                            } else {
+                                "h-6 px-2 rounded-md bg-zinc-900 hover:bg-zinc-800 text-zinc-400 text-[11px] border border-zinc-800 flex-shrink-0"
+                            },
+                            onclick: move |_| {
+                                if !paused() {
+                                    frozen_snapshot.set(props.event_log.clone());
+                                }
+                                paused.set(!paused());
+                            },
+                            title: "Pause new events (still buffered in the background)",
+                            if paused() { "Paused" } else { "Pause" }
+                        }
+                        button {
+                            class: "h-6 px-2 rounded-md bg-zinc-900 hover:bg-zinc-800 text-zinc-400 text-[11px] border border-zinc-800 flex-shrink-0",
+                            onclick: move |_| props.on_clear_log.call(()),
+                            title: "Clear event log",
+                            "Clear"
+                        }
+                        {
+                            let mut export_menu_open = use_signal(|| false);
+                            rsx! {
+                                div {
+                                    class: "relative flex-shrink-0",
+                                    button {
+                                        class: "h-6 px-2 rounded-md bg-zinc-900 hover:bg-zinc-800 text-zinc-400 text-[11px] border border-zinc-800",
+                                        onclick: move |_| export_menu_open.set(!export_menu_open()),
+                                        title: "Export session",
+                                        "Export ▾"
+                                    }
+                                    if export_menu_open() {
+                                        div {
+                                            class: "absolute right-0 top-7 z-20 w-28 rounded-md bg-zinc-900 border border-zinc-800 shadow-lg overflow-hidden",
+                                            button {
+                                                class: "w-full text-left px-2 py-1.5 text-[11px] text-zinc-300 hover:bg-zinc-800",
+                                                onclick: move |_| {
+                                                    export_menu_open.set(false);
+                                                    props.on_export.call(ExportFormat::Json);
+                                                },
+                                                "as JSON"
+                                            }
+                                            button {
+                                                class: "w-full text-left px-2 py-1.5 text-[11px] text-zinc-300 hover:bg-zinc-800",
+                                                onclick: move |_| {
+                                                    export_menu_open.set(false);
+                                                    props.on_export.call(ExportFormat::Csv);
+                                                },
+                                                "as CSV"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    div {
+                        class: "flex items-center gap-1.5",
+                        for event_type in EventType::ALL {
+                            {
+                                let active = props.event_type_filter.contains(&event_type);
+                                rsx! {
+                                    button {
+                                        key: "{event_type:?}",
+                                        class: if active {
+                                            "px-2 py-0.5 rounded-full text-[10px] font-medium border border-[#c7ff00]/50 bg-[#c7ff00]/10 text-[#c7ff00]"
+                                        } else {
+                                            "px-2 py-0.5 rounded-full text-[10px] font-medium border border-zinc-800 bg-zinc-900 text-zinc-500"
+                                        },
+                                        onclick: move |_| props.on_toggle_event_type.call(event_type),
+                                        "{event_type.label()}"
+                                    }
+                                }
+                            }
+                        }
+                        span {
+                            class: "text-[10px] text-zinc-600 ml-auto",
+                            "showing {display_log.len()} of {props.event_log_total}"
+                        }
                     }
                 }
 
-                // Event list (scrollable)
+                // Event list (scrollable). Focusable and arrow-navigable:
+                // Up/Down move `focused_index`, Enter copies the focused
+                // entry's message to the clipboard.
                 div {
-                    class: "flex-1 overflow-y-auto px-4 py-2",
-                    if props.event_log.is_empty() {
+                    class: "flex-1 overflow-y-auto px-4 py-2 focus:outline-none",
+                    tabindex: "0",
+                    onkeydown: move |evt: KeyboardEvent| {
+                        let mods = evt.modifiers();
+                        if mods.shift() || mods.ctrl() || mods.alt() || mods.meta() {
+                            return;
+                        }
+                        let len = rendered.len();
+                        if len == 0 {
+                            return;
+                        }
+                        match evt.key() {
+                            Key::ArrowUp | Key::ArrowLeft => {
+                                let next = match focused_index() {
+                                    Some(i) if i > 0 => i - 1,
+                                    _ => len - 1,
+                                };
+                                focused_index.set(Some(next));
+                            }
+                            Key::ArrowDown | Key::ArrowRight => {
+                                let next = match focused_index() {
+                                    Some(i) if i + 1 < len => i + 1,
+                                    _ => 0,
+                                };
+                                focused_index.set(Some(next));
+                            }
+                            Key::Enter => {
+                                if let Some(entry) = focused_index().and_then(|i| rendered.get(i)) {
+                                    let message = entry.message.clone();
+                                    spawn(async move {
+                                        let js = format!("navigator.clipboard.writeText({message:?});");
+                                        let _ = document::eval(&js).await;
+                                    });
+                                }
+                            }
+                            _ => {}
+                        }
+                    },
+                    if rendered.is_empty() {
                         div {
                             class: "h-full flex items-center justify-center",
                             p {
@@ -541,10 +1382,16 @@ pub fn ContextSidebar(props: ContextSidebarProps) -> Element {
                     } else {
                         div {
                             class: "space-y-2",
-                            for entry in props.event_log.iter().rev() {
+                            for (i, entry) in rendered.iter().enumerate() {
                                 div {
                                     key: "{entry.id}",
-                                    class: "text-xs",
+                                    tabindex: "0",
+                                    class: if focused_index() == Some(i) {
+                                        "text-xs rounded px-1 -mx-1 bg-zinc-900 ring-1 ring-zinc-700"
+                                    } else {
+                                        "text-xs"
+                                    },
+                                    onclick: move |_| focused_index.set(Some(i)),
                                     div {
                                         class: "flex items-start gap-2",
                                         div {
@@ -576,6 +1423,8 @@ pub fn ContextSidebar(props: ContextSidebarProps) -> Element {
                     }
                 }
             }
+                }
+            }
         }
     }
 }
@@ -0,0 +1,100 @@
+use dioxus::prelude::*;
+use std::collections::HashSet;
+use crate::directory_tree::TreeNode;
+use super::context_sidebar::format_bytes;
+
+#[derive(Props, Clone, PartialEq)]
+pub struct DirectoryBrowserProps {
+    pub nodes: Vec<TreeNode>,
+    pub on_select: EventHandler<String>,
+    /// Leaf paths with a "Read All" batch read currently in flight, for
+    /// each row's own spinner.
+    #[props(default)]
+    pub reading_paths: HashSet<String>,
+}
+
+/// Collapsible folder/leaf tree over a `directory_tree::TreeNode` forest,
+/// the way a file manager's sidebar renders nested directories. Clicking a
+/// leaf reports its full path via `on_select`.
+#[component]
+pub fn DirectoryBrowser(props: DirectoryBrowserProps) -> Element {
+    rsx! {
+        div {
+            class: "space-y-0.5",
+            for node in props.nodes.iter() {
+                DirectoryNode { node: node.clone(), on_select: props.on_select, reading_paths: props.reading_paths.clone() }
+            }
+        }
+    }
+}
+
+#[derive(Props, Clone, PartialEq)]
+struct DirectoryNodeProps {
+    node: TreeNode,
+    on_select: EventHandler<String>,
+    #[props(default)]
+    reading_paths: HashSet<String>,
+}
+
+#[component]
+fn DirectoryNode(props: DirectoryNodeProps) -> Element {
+    let mut expanded = use_signal(|| false);
+    let has_children = !props.node.children.is_empty();
+    let is_reading = props.reading_paths.contains(&props.node.path);
+
+    rsx! {
+        div {
+            div {
+                class: "flex items-center gap-1.5 px-1 py-1 rounded hover:bg-zinc-900 cursor-pointer",
+                onclick: move |_| {
+                    if has_children {
+                        expanded.set(!expanded());
+                    } else {
+                        props.on_select.call(props.node.path.clone());
+                    }
+                },
+                span {
+                    class: "text-zinc-600 text-xs w-3 shrink-0",
+                    if has_children {
+                        if expanded() { "▾" } else { "▸" }
+                    } else {
+                        ""
+                    }
+                }
+                span {
+                    class: if has_children { "text-xs text-zinc-300" } else { "text-xs text-zinc-400 font-mono" },
+                    "{props.node.name}"
+                }
+                if let Some(leaf) = &props.node.leaf {
+                    span {
+                        class: "ml-auto text-[10px] text-zinc-600 font-mono whitespace-nowrap",
+                        "{format_bytes(leaf.size_bytes)} · {leaf.modified_secs_ago}s ago"
+                    }
+                    if is_reading {
+                        span {
+                            class: "text-[10px] text-zinc-500 ml-1 shrink-0 animate-pulse",
+                            "reading…"
+                        }
+                    } else {
+                        button {
+                            class: "text-[10px] text-blue-400 hover:text-blue-300 ml-1 shrink-0",
+                            onclick: move |evt| {
+                                evt.stop_propagation();
+                                props.on_select.call(props.node.path.clone());
+                            },
+                            "use"
+                        }
+                    }
+                }
+            }
+            if has_children && expanded() {
+                div {
+                    class: "pl-4 border-l border-zinc-800 ml-1.5",
+                    for child in props.node.children.iter() {
+                        DirectoryNode { node: child.clone(), on_select: props.on_select, reading_paths: props.reading_paths.clone() }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,11 @@
 pub mod topbar;
 pub mod network_visualization;
 pub mod context_sidebar;
+pub mod sparkline;
+pub mod directory_browser;
 
 pub use topbar::Topbar;
-pub use network_visualization::NetworkVisualization;
+pub use network_visualization::{NetworkVisualization, NodeGroup};
 pub use context_sidebar::{ContextSidebar, EventLogEntry, EventType};
+pub use sparkline::Sparkline;
+pub use directory_browser::DirectoryBrowser;
@@ -1,8 +1,24 @@
 use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::context_sidebar::format_bytes;
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::JsCast;
 
+/// Format a bytes/sec rate for the edge bandwidth label, e.g. `"12.3 KB/s"`.
+fn format_rate(bps: f64) -> String {
+    const KB: f64 = 1024.0;
+    const MB: f64 = KB * 1024.0;
+    if bps >= MB {
+        format!("{:.1} MB/s", bps / MB)
+    } else if bps >= KB {
+        format!("{:.1} KB/s", bps / KB)
+    } else {
+        format!("{:.0} B/s", bps)
+    }
+}
+
 // Convert client coordinates to SVG user units using the CTM
 #[cfg(target_arch = "wasm32")]
 fn client_to_svg(el: &web_sys::SvgGraphicsElement, client_x: f64, client_y: f64) -> (f64, f64) {
@@ -103,6 +119,12 @@ pub struct Homeserver {
     pub public_key: Option<String>,
     pub connectivity_status: ConnectivityStatus,
     pub storage_stats: Option<StorageStats>,
+    /// Seconds since the background health poll last confirmed this
+    /// homeserver reachable. `None` until the first successful probe.
+    pub last_seen_secs_ago: Option<u64>,
+    /// Rolling window of recent read/write throughput, sampled on every UI
+    /// tick, backing the sidebar's bandwidth sparklines.
+    pub bandwidth_history: BandwidthHistory,
     pub x: f64,
     pub y: f64,
 }
@@ -136,30 +158,115 @@ pub struct Edge {
     pub from: String, // node id
     pub to: String,   // node id
     pub edge_type: EdgeType,
+    /// Smoothed bytes/sec over the last sampling window, kept by an
+    /// independent periodic effect rather than the write/read paths
+    /// themselves. `None` until the first sample.
+    pub bandwidth_bps: Option<f64>,
 }
 
 #[derive(Clone, PartialEq, Debug)]
 #[allow(dead_code)]
 pub enum EdgeType {
     Connection, // Client connected to Homeserver
+    /// Derived from a node's Kademlia routing table (see `routing.rs`):
+    /// one of the target's k-closest known peers, not a hand-drawn link.
+    DhtPeer,
 }
 
-#[derive(Clone, PartialEq, Debug)]
+/// Result of probing a homeserver's `http_url`, classified the way a
+/// peer-to-peer host would describe its own reachability rather than a
+/// plain connected/failed flag.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub enum ConnectivityStatus {
     Unknown,
     Testing,
-    Connected,
-    Failed,
+    /// Reachable, with round-trip latency typical of a direct socket.
+    DirectlyReachable { latency_ms: u64 },
+    /// Reachable, but the round trip is slow enough to suggest the path
+    /// went through a relay or a hole-punched route rather than a direct
+    /// connection.
+    BehindNat { latency_ms: u64 },
+    Unreachable { reason: String },
 }
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct StorageStats {
     pub total_keys: usize,
     pub total_size_bytes: usize,
+    /// Bytes still free out of `capacity_bytes`. This testnet enforces no
+    /// real disk quota, so writes are tracked against a fixed simulated
+    /// capacity, giving the sidebar's used/free bar a real partition to
+    /// chart rather than an open-ended byte count.
+    pub available_bytes: u64,
+    pub capacity_bytes: u64,
 }
 
-#[derive(Clone, PartialEq, Debug)]
+/// How many `BandwidthHistory` samples to keep — enough to show the last
+/// ~30s of activity at the 500ms tick `main.rs` samples on.
+const BANDWIDTH_HISTORY_LEN: usize = 60;
+
+/// One sample of a homeserver's cumulative read/write byte counters, taken
+/// on a UI tick.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BandwidthSample {
+    pub at: std::time::Instant,
+    pub bytes_read: u64,
+    pub bytes_written: u64,
+}
+
+/// Fixed-size ring buffer of recent `BandwidthSample`s for one homeserver,
+/// like a packet sniffer's bandwidth view rather than a single cumulative
+/// counter. Samples hold cumulative byte totals; `rates` differences
+/// adjacent samples into bytes/sec.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct BandwidthHistory {
+    samples: std::collections::VecDeque<BandwidthSample>,
+}
+
+impl BandwidthHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the current cumulative read/write totals, evicting the
+    /// oldest sample once the buffer exceeds `BANDWIDTH_HISTORY_LEN`.
+    pub fn push(&mut self, bytes_read: u64, bytes_written: u64) {
+        self.samples.push_back(BandwidthSample {
+            at: std::time::Instant::now(),
+            bytes_read,
+            bytes_written,
+        });
+        if self.samples.len() > BANDWIDTH_HISTORY_LEN {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Per-second (read_rate, write_rate) for each adjacent sample pair,
+    /// oldest first. Empty with fewer than two samples.
+    pub fn rates(&self) -> Vec<(f64, f64)> {
+        self.samples
+            .iter()
+            .zip(self.samples.iter().skip(1))
+            .map(|(a, b)| {
+                let dt = b.at.duration_since(a.at).as_secs_f64().max(0.001);
+                let read_rate = b.bytes_read.saturating_sub(a.bytes_read) as f64 / dt;
+                let write_rate = b.bytes_written.saturating_sub(a.bytes_written) as f64 / dt;
+                (read_rate, write_rate)
+            })
+            .collect()
+    }
+
+    /// Highest read/write rate seen across the current window, in
+    /// bytes/sec. `0.0` if there aren't enough samples yet to compute a rate.
+    pub fn peak_rates(&self) -> (f64, f64) {
+        self.rates()
+            .iter()
+            .fold((0.0_f64, 0.0_f64), |(peak_r, peak_w), (r, w)| (peak_r.max(*r), peak_w.max(*w)))
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub enum NodeStatus {
     Starting,
@@ -176,16 +283,87 @@ pub struct NetworkVisualizationProps {
     pub on_select: EventHandler<String>,
     pub on_node_move: EventHandler<(String, f64, f64)>,
     pub is_loading_scenario: bool,
+    /// Fired when a drag started on a node's outer ring (or Shift+drag on
+    /// the node itself) is dropped onto another node, as `(client_id,
+    /// homeserver_id)` — already normalized to the `Connection` invariant's
+    /// direction regardless of which end the drag started from.
+    pub on_connect: EventHandler<(String, String)>,
+    /// Fired from the hover tooltip's Start/Stop control, driving the
+    /// control-plane task for that homeserver (see `control_plane.rs`).
+    pub on_start: EventHandler<String>,
+    pub on_stop: EventHandler<String>,
+    /// Node ids matching the sidebar's current search query, drawn with an
+    /// extra highlight ring. Empty when there's no active query.
+    #[props(default)]
+    pub highlighted_ids: Vec<String>,
+    /// Group membership by node id, for the tinted ring drawn behind each
+    /// grouped node. Nodes absent from this map aren't in any group.
+    #[props(default)]
+    pub groups: std::collections::HashMap<String, NodeGroup>,
+}
+
+/// A user-defined group a node can be assigned to, the way a flow editor
+/// lets you cluster nodes with a shared label and color. Assignment is
+/// tracked by the parent app keyed by node id (see `NetworkVisualizationProps::groups`)
+/// rather than stored on `Node` itself, since membership is optional and
+/// orthogonal to everything else a node already carries.
+#[derive(Clone, PartialEq, Debug)]
+pub struct NodeGroup {
+    pub id: String,
+    pub label: String,
+    /// Hex color, e.g. `"#c7ff00"`.
+    pub color: String,
+}
+
+/// What a mouse-down on a node starts: repositioning it, or dragging out a
+/// new edge to drop onto another node.
+#[derive(Clone, PartialEq, Debug)]
+enum DragMode {
+    MoveNode { id: String, ox: f64, oy: f64 },
+    CreateEdge { from_id: String, cursor: (f64, f64) },
+}
+
+/// How far one arrow-key press nudges the focused node, in SVG units.
+const FOCUS_NUDGE: f64 = 10.0;
+
+/// A keyboard-driven edit to apply to whichever node currently has focus.
+/// Resolving this generically against `Node::position` (rather than a
+/// `match` per `Node` variant) is what lets a future `Node` kind pick up
+/// keyboard navigation for free.
+enum FocusOp {
+    Nudge { dx: f64, dy: f64 },
+}
+
+/// Look up `focused_id` in `nodes` and compute the new position `op`
+/// implies, without mutating anything — the caller reports the result
+/// through `on_node_move` the same way a mouse drag does.
+fn apply_focus_op(nodes: &[Node], focused_id: &str, op: FocusOp) -> Option<(f64, f64)> {
+    nodes.iter().find(|n| n.id() == focused_id).map(|n| {
+        let (x, y) = n.position();
+        match op {
+            FocusOp::Nudge { dx, dy } => (x + dx, y + dy),
+        }
+    })
 }
 
 #[component]
 pub fn NetworkVisualization(props: NetworkVisualizationProps) -> Element {
-    // Store: (node_id, offset_x, offset_y) - offset from mouse to node center in SVG coords
-    let mut dragging = use_signal(|| Option::<(String, f64, f64)>::None);
+    let mut dragging = use_signal(|| Option::<DragMode>::None);
     let mut panning = use_signal(|| Option::<(f64, f64)>::None);
     let mut pan_offset = use_signal(|| (0.0, 0.0));
     let mut zoom = use_signal(|| 1.0);
 
+    // Which node (if any) the cursor is currently over, and where to anchor
+    // its tooltip. Recomputed every `on_mouse_move` from this frame's actual
+    // node positions rather than relying on SVG `:hover`, which lags a frame
+    // behind while panning/zooming/dragging and flickers as a result.
+    let mut hovered = use_signal(|| Option::<String>::None);
+    let mut hover_pos = use_signal(|| (0.0_f64, 0.0_f64));
+
+    // Keyboard-focused node, independent of mouse selection so the two can
+    // be told apart visually (see the focus ring in the node loop below).
+    let mut focused_id = use_signal(|| Option::<String>::None);
+
     // Reference to the transformed <g> element for coordinate conversion
     #[cfg(target_arch = "wasm32")]
     let viewport_g = use_signal(|| Option::<web_sys::SvgGraphicsElement>::None);
@@ -200,6 +378,18 @@ pub fn NetworkVisualization(props: NetworkVisualizationProps) -> Element {
             let (sx, sy) = mouse_svg!(viewport_g, evt);
             let (cur_pan_x, cur_pan_y) = pan_offset();
 
+            // Hit-test this frame's cursor against this frame's node
+            // centers, topmost first (nodes are drawn in list order, so the
+            // last match in the list is painted on top).
+            let hit = props.nodes.iter().rev().find(|n| {
+                let (nx, ny) = n.position();
+                let (dx, dy) = (nx - sx, ny - sy);
+                (dx * dx + dy * dy).sqrt() <= 30.0
+            });
+            hovered.set(hit.map(|n| n.id().to_string()));
+            let anchor = evt.element_coordinates();
+            hover_pos.set((anchor.x, anchor.y));
+
             // Handle panning: deltas in SVG units
             if let Some((start_x, start_y)) = panning() {
                 let dx = sx - start_x;
@@ -209,16 +399,61 @@ pub fn NetworkVisualization(props: NetworkVisualizationProps) -> Element {
                 return;
             }
 
-            // Handle node dragging: all in SVG units
-            if let Some((ref node_id, offset_x, offset_y)) = dragging() {
-                let new_svg_x = sx + offset_x;
-                let new_svg_y = sy + offset_y;
-                on_node_move.call((node_id.clone(), new_svg_x, new_svg_y));
+            // Handle node dragging / edge creation: all in SVG units
+            match dragging() {
+                Some(DragMode::MoveNode { id, ox, oy }) => {
+                    let new_svg_x = sx + ox;
+                    let new_svg_y = sy + oy;
+                    on_node_move.call((id, new_svg_x, new_svg_y));
+                }
+                Some(DragMode::CreateEdge { from_id, .. }) => {
+                    dragging.set(Some(DragMode::CreateEdge { from_id, cursor: (sx, sy) }));
+                }
+                None => {}
             }
         }
     };
 
+    let on_connect = props.on_connect.clone();
+
     let on_mouse_up = move |_evt: MouseEvent| {
+        if let Some(DragMode::CreateEdge { from_id, cursor }) = dragging() {
+            let target = props.nodes.iter().find(|n| {
+                if n.id() == from_id {
+                    return false;
+                }
+                let (nx, ny) = n.position();
+                let (dx, dy) = (nx - cursor.0, ny - cursor.1);
+                (dx * dx + dy * dy).sqrt() <= 30.0
+            });
+
+            if let (Some(source), Some(target)) =
+                (props.nodes.iter().find(|n| n.id() == from_id), target)
+            {
+                // Only a Client<->Homeserver pair is a valid Connection;
+                // normalize direction so `from` is always the client
+                // regardless of which end the drag started from.
+                let pair = match (source, target) {
+                    (Node::Client(c), Node::Homeserver(h)) => Some((c.id.clone(), h.id.clone())),
+                    (Node::Homeserver(h), Node::Client(c)) => Some((c.id.clone(), h.id.clone())),
+                    _ => None,
+                };
+
+                if let Some((client_id, homeserver_id)) = pair {
+                    let already_exists = props.edges.iter().any(|e| {
+                        e.edge_type == EdgeType::Connection
+                            && e.from == client_id
+                            && e.to == homeserver_id
+                    });
+                    if !already_exists {
+                        on_connect.call((client_id, homeserver_id));
+                    }
+                }
+            }
+            // Self-drop, drop on empty canvas, or a same-type pair: cancel
+            // silently, same as any other invalid drop target.
+        }
+
         dragging.set(None);
         panning.set(None);
     };
@@ -253,18 +488,74 @@ pub fn NetworkVisualization(props: NetworkVisualizationProps) -> Element {
         }
     };
 
+    // Keyboard navigation: Tab/Shift-Tab walk a stable (sorted-by-id) node
+    // order, mirroring focus into `on_select` the way a mouse click would.
+    // Arrow keys nudge the focused node via `apply_focus_op`, `+`/`-` zoom
+    // within the same clamps the scroll-wheel and zoom buttons use, and
+    // `0` resets pan/zoom like the Reset button.
+    let on_key_down = move |evt: KeyboardEvent| {
+        let mut sorted_ids: Vec<String> = props.nodes.iter().map(|n| n.id().to_string()).collect();
+        sorted_ids.sort();
+        if sorted_ids.is_empty() {
+            return;
+        }
+
+        match evt.key() {
+            Key::Tab => {
+                evt.prevent_default();
+                let current_idx = focused_id().and_then(|id| sorted_ids.iter().position(|i| *i == id));
+                let next_idx = if evt.modifiers().shift() {
+                    current_idx.map(|i| (i + sorted_ids.len() - 1) % sorted_ids.len()).unwrap_or(sorted_ids.len() - 1)
+                } else {
+                    current_idx.map(|i| (i + 1) % sorted_ids.len()).unwrap_or(0)
+                };
+                let next_id = sorted_ids[next_idx].clone();
+                focused_id.set(Some(next_id.clone()));
+                props.on_select.call(next_id);
+            }
+            Key::ArrowUp | Key::ArrowDown | Key::ArrowLeft | Key::ArrowRight => {
+                evt.prevent_default();
+                if let Some(id) = focused_id() {
+                    let (dx, dy) = match evt.key() {
+                        Key::ArrowUp => (0.0, -FOCUS_NUDGE),
+                        Key::ArrowDown => (0.0, FOCUS_NUDGE),
+                        Key::ArrowLeft => (-FOCUS_NUDGE, 0.0),
+                        Key::ArrowRight => (FOCUS_NUDGE, 0.0),
+                        _ => unreachable!(),
+                    };
+                    if let Some((nx, ny)) = apply_focus_op(&props.nodes, &id, FocusOp::Nudge { dx, dy }) {
+                        props.on_node_move.call((id, nx, ny));
+                    }
+                }
+            }
+            Key::Character(c) if c == "+" || c == "=" => {
+                zoom.set((zoom() * 1.2).min(5.0));
+            }
+            Key::Character(c) if c == "-" => {
+                zoom.set((zoom() / 1.2).max(0.1));
+            }
+            Key::Character(c) if c == "0" => {
+                zoom.set(1.0);
+                pan_offset.set((0.0, 0.0));
+            }
+            _ => {}
+        }
+    };
+
     let (pan_x, pan_y) = pan_offset();
     let current_zoom = zoom();
     let transform = format!("translate({} {}) scale({})", pan_x, pan_y, current_zoom);
 
     rsx! {
         div {
-            class: "flex-1 bg-black relative overflow-hidden select-none",
+            class: "flex-1 bg-black relative overflow-hidden select-none focus:outline-none",
             style: "user-select: none; -webkit-user-select: none; -moz-user-select: none; -ms-user-select: none; cursor: default; will-change: transform;",
+            tabindex: "0",
             onmousemove: on_mouse_move,
             onmouseup: on_mouse_up,
             onmousedown: on_canvas_mouse_down,
             onwheel: on_wheel,
+            onkeydown: on_key_down,
 
             if props.nodes.is_empty() {
                 // Empty state
@@ -351,6 +642,14 @@ pub fn NetworkVisualization(props: NetworkVisualizationProps) -> Element {
                             if let (Some(from), Some(to)) = (from_node, to_node) {
                                 let (x1, y1) = from.position();
                                 let (x2, y2) = to.position();
+                                let is_dht_peer = matches!(edge.edge_type, EdgeType::DhtPeer);
+                                let stroke = if is_dht_peer { "#60a5fa" } else { "#c7ff00" };
+                                let stroke_dasharray = if is_dht_peer { "4 4" } else { "none" };
+                                let opacity = if is_dht_peer { "0.35" } else { "0.6" };
+                                let bandwidth_bps = if is_dht_peer { None } else { edge.bandwidth_bps };
+                                // Thickness grows with the smoothed rate so the
+                                // hottest links during a scenario stand out.
+                                let stroke_width = 3.0 + (bandwidth_bps.unwrap_or(0.0) / 1024.0).min(9.0);
 
                                 rsx! {
                                     line {
@@ -359,9 +658,19 @@ pub fn NetworkVisualization(props: NetworkVisualizationProps) -> Element {
                                         y1: "{y1}",
                                         x2: "{x2}",
                                         y2: "{y2}",
-                                        stroke: "#c7ff00",
-                                        stroke_width: "3",
-                                        opacity: "0.6"
+                                        stroke: stroke,
+                                        stroke_width: "{stroke_width}",
+                                        stroke_dasharray: stroke_dasharray,
+                                        opacity: opacity
+                                    }
+                                    if let Some(bps) = bandwidth_bps.filter(|b| *b > 0.0) {
+                                        text {
+                                            x: "{(x1 + x2) / 2.0}",
+                                            y: "{(y1 + y2) / 2.0 - 6.0}",
+                                            text_anchor: "middle",
+                                            class: "text-[10px] fill-zinc-300 font-mono pointer-events-none select-none",
+                                            "{format_rate(bps)}"
+                                        }
                                     }
                                 }
                             } else {
@@ -370,14 +679,36 @@ pub fn NetworkVisualization(props: NetworkVisualizationProps) -> Element {
                         }
                     }
 
+                    // While dragging out a new edge, show a dashed preview
+                    // line from the source node to the current cursor.
+                    if let Some(DragMode::CreateEdge { from_id, cursor }) = dragging() {
+                        if let Some(source) = props.nodes.iter().find(|n| n.id() == from_id) {
+                            let (x1, y1) = source.position();
+                            rsx! {
+                                line {
+                                    x1: "{x1}",
+                                    y1: "{y1}",
+                                    x2: "{cursor.0}",
+                                    y2: "{cursor.1}",
+                                    stroke: "#c7ff00",
+                                    stroke_width: "2",
+                                    stroke_dasharray: "4 4",
+                                    pointer_events: "none",
+                                }
+                            }
+                        }
+                    }
+
                     // Draw nodes
                     for node in props.nodes.iter() {
                         {
                             let node_id_str = node.id();
                             let is_selected = props.selected_id.as_ref().map(|s| s.as_str()) == Some(node_id_str);
+                            let is_focused = focused_id().as_deref() == Some(node_id_str);
                             let node_id = node_id_str.to_string();
                             let node_id_for_drag = node_id.clone();
                             let node_id_for_select = node_id.clone();
+                            let node_id_for_ring = node_id.clone();
                             let (x, y) = node.position();
 
                             let (fill_color, stroke_color) = match node.status() {
@@ -388,6 +719,8 @@ pub fn NetworkVisualization(props: NetworkVisualizationProps) -> Element {
                             };
 
                             let stroke_width = if is_selected { "3" } else { "2" };
+                            let is_highlighted = props.highlighted_ids.iter().any(|id| id == node_id_str);
+                            let group = props.groups.get(node_id_str).cloned();
 
                             rsx! {
                                 g {
@@ -395,6 +728,79 @@ pub fn NetworkVisualization(props: NetworkVisualizationProps) -> Element {
                                     cursor: "pointer",
                                     onclick: move |_| props.on_select.call(node_id.clone()),
 
+                                    // Group ring, drawn behind the node and any search highlight
+                                    if let Some(group) = &group {
+                                        circle {
+                                            cx: "{x}",
+                                            cy: "{y}",
+                                            r: "34",
+                                            fill: "{group.color}",
+                                            fill_opacity: "0.12",
+                                            stroke: "{group.color}",
+                                            stroke_width: "2",
+                                        }
+                                        text {
+                                            x: "{x}",
+                                            y: "{y - 42.0}",
+                                            text_anchor: "middle",
+                                            fill: "{group.color}",
+                                            font_size: "10",
+                                            "{group.label}"
+                                        }
+                                    }
+
+                                    // Search-match ring, drawn behind the node
+                                    if is_highlighted {
+                                        circle {
+                                            cx: "{x}",
+                                            cy: "{y}",
+                                            r: "38",
+                                            fill: "none",
+                                            stroke: "#c7ff00",
+                                            stroke_width: "2",
+                                            stroke_dasharray: "4 3",
+                                        }
+                                    }
+
+                                    // Keyboard-focus ring, distinct from both the
+                                    // selection stroke (node-colored, always on) and
+                                    // the search-match ring, so Tab-navigation is
+                                    // visible even on an already-selected node.
+                                    if is_focused {
+                                        circle {
+                                            cx: "{x}",
+                                            cy: "{y}",
+                                            r: "41",
+                                            fill: "none",
+                                            stroke: "#60a5fa",
+                                            stroke_width: "2",
+                                            stroke_dasharray: "2 3",
+                                        }
+                                    }
+
+                                    // Outer ring: drag from here (or hold Shift while
+                                    // pressing the node itself) to drag out a new edge
+                                    // instead of repositioning the node.
+                                    circle {
+                                        cx: "{x}",
+                                        cy: "{y}",
+                                        r: "36",
+                                        fill: "none",
+                                        stroke: stroke_color,
+                                        stroke_width: "6",
+                                        stroke_opacity: "0.25",
+                                        pointer_events: "stroke",
+                                        cursor: "crosshair",
+                                        onmousedown: move |evt: MouseEvent| {
+                                            evt.stop_propagation();
+                                            let (mx_svg, my_svg) = mouse_svg!(viewport_g, evt);
+                                            dragging.set(Some(DragMode::CreateEdge {
+                                                from_id: node_id_for_ring.clone(),
+                                                cursor: (mx_svg, my_svg),
+                                            }));
+                                        },
+                                    }
+
                                     // Node circle
                                     circle {
                                         cx: "{x}",
@@ -410,11 +816,22 @@ pub fn NetworkVisualization(props: NetworkVisualizationProps) -> Element {
                                             // Compute everything in SVG units
                                             let (mx_svg, my_svg) = mouse_svg!(viewport_g, evt);
 
-                                            // Offset in SVG units
-                                            let offset_x = x - mx_svg;
-                                            let offset_y = y - my_svg;
+                                            if evt.modifiers().shift() {
+                                                dragging.set(Some(DragMode::CreateEdge {
+                                                    from_id: node_id_for_drag.clone(),
+                                                    cursor: (mx_svg, my_svg),
+                                                }));
+                                            } else {
+                                                // Offset in SVG units
+                                                let offset_x = x - mx_svg;
+                                                let offset_y = y - my_svg;
 
-                                            dragging.set(Some((node_id_for_drag.clone(), offset_x, offset_y)));
+                                                dragging.set(Some(DragMode::MoveNode {
+                                                    id: node_id_for_drag.clone(),
+                                                    ox: offset_x,
+                                                    oy: offset_y,
+                                                }));
+                                            }
                                         },
                                     }
 
@@ -522,12 +939,87 @@ pub fn NetworkVisualization(props: NetworkVisualizationProps) -> Element {
                 }
             }
 
+            // Hover tooltip, anchored to this frame's cursor position rather
+            // than CSS `:hover` so it tracks node centers exactly while
+            // panning/zooming/dragging.
+            if let Some(node) = hovered().and_then(|id| props.nodes.iter().find(|n| n.id() == id).cloned()) {
+                {
+                    let (px, py) = hover_pos();
+                    rsx! {
+                        div {
+                            class: "absolute pointer-events-none bg-zinc-900/95 border border-zinc-800 rounded-md px-3 py-2 text-xs text-zinc-300 shadow-lg z-10",
+                            style: "left: {px + 16.0}px; top: {py + 16.0}px; max-width: 220px;",
+                            div { class: "font-medium text-zinc-100 mb-1", "{node.name()}" }
+
+                            match &node {
+                                Node::Homeserver(h) => rsx! {
+                                    div {
+                                        class: if matches!(h.connectivity_status, ConnectivityStatus::Testing) {
+                                            "flex items-center gap-1.5 mb-1 animate-pulse"
+                                        } else {
+                                            "flex items-center gap-1.5 mb-1"
+                                        },
+                                        match &h.connectivity_status {
+                                            ConnectivityStatus::DirectlyReachable { latency_ms } => format!("Directly reachable ({latency_ms} ms)"),
+                                            ConnectivityStatus::BehindNat { latency_ms } => format!("Behind NAT ({latency_ms} ms)"),
+                                            ConnectivityStatus::Testing => "Testing...".to_string(),
+                                            ConnectivityStatus::Unreachable { reason } => format!("Unreachable: {reason}"),
+                                            ConnectivityStatus::Unknown => "Unknown".to_string(),
+                                        }
+                                    }
+                                    if let Some(stats) = &h.storage_stats {
+                                        div { "{stats.total_keys} keys · {format_bytes(stats.total_size_bytes)}" }
+                                    }
+                                    div {
+                                        class: "flex gap-2 mt-1.5 pointer-events-auto",
+                                        if h.status != NodeStatus::Running {
+                                            button {
+                                                class: "text-green-400 hover:text-green-300",
+                                                onclick: {
+                                                    let id = h.id.clone();
+                                                    move |evt: MouseEvent| {
+                                                        evt.stop_propagation();
+                                                        props.on_start.call(id.clone());
+                                                    }
+                                                },
+                                                "▶ Start"
+                                            }
+                                        }
+                                        if h.status != NodeStatus::Stopped {
+                                            button {
+                                                class: "text-red-400 hover:text-red-300",
+                                                onclick: {
+                                                    let id = h.id.clone();
+                                                    move |evt: MouseEvent| {
+                                                        evt.stop_propagation();
+                                                        props.on_stop.call(id.clone());
+                                                    }
+                                                },
+                                                "■ Stop"
+                                            }
+                                        }
+                                    }
+                                },
+                                Node::Client(c) => rsx! {
+                                    match &c.connected_homeserver {
+                                        Some(homeserver_id) => rsx! { div { "Connected to {homeserver_id}" } },
+                                        None => rsx! { div { class: "italic text-zinc-500", "Not connected" } },
+                                    }
+                                },
+                            }
+                        }
+                    }
+                }
+            }
+
             // Instructions overlay
             div {
                 class: "absolute top-4 left-4 bg-zinc-900/90 border border-zinc-800 rounded-md px-3 py-2 text-xs text-zinc-400",
                 div { "Scroll to zoom" }
                 div { "Middle-click + drag to pan" }
                 div { "Drag nodes to move" }
+                div { "Drag a node's outer ring (or Shift+drag it) onto another node to connect" }
+                div { "Tab/Shift-Tab to focus a node, arrows to nudge it, +/-/0 to zoom" }
             }
         }
     }
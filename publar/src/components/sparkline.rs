@@ -0,0 +1,57 @@
+use dioxus::prelude::*;
+
+/// Small inline SVG polyline chart, scaled to its own window's max value.
+/// Used for the storage panel's read/write bandwidth sparklines.
+#[derive(Props, Clone, PartialEq)]
+pub struct SparklineProps {
+    /// Most-recent-last sample values (e.g. bytes/sec).
+    pub values: Vec<f64>,
+    pub width: u32,
+    pub height: u32,
+    pub stroke: String,
+}
+
+#[component]
+pub fn Sparkline(props: SparklineProps) -> Element {
+    if props.values.len() < 2 {
+        return rsx! {
+            svg { width: "{props.width}", height: "{props.height}" }
+        };
+    }
+
+    let width = props.width as f64;
+    let height = props.height as f64;
+    let max = props.values.iter().cloned().fold(0.0_f64, f64::max);
+    let step = width / (props.values.len() - 1) as f64;
+
+    // An all-zero window would divide by zero scaling to the max; draw a
+    // flat line at the baseline instead.
+    let points: Vec<String> = props
+        .values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let x = i as f64 * step;
+            let y = if max > 0.0 {
+                height - (value / max * height)
+            } else {
+                height
+            };
+            format!("{x:.1},{y:.1}")
+        })
+        .collect();
+
+    rsx! {
+        svg {
+            width: "{props.width}",
+            height: "{props.height}",
+            view_box: "0 0 {width} {height}",
+            polyline {
+                points: "{points.join(\" \")}",
+                fill: "none",
+                stroke: "{props.stroke}",
+                stroke_width: "1.5",
+            }
+        }
+    }
+}
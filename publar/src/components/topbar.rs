@@ -13,13 +13,28 @@ pub struct TopbarProps {
     pub on_add_client: EventHandler<()>,
     pub on_scenario_select: EventHandler<usize>,
     pub on_play_scenario: EventHandler<()>,
+    pub is_recording: bool,
+    pub on_start_recording: EventHandler<()>,
+    pub on_stop_recording: EventHandler<String>,
     pub on_reset: EventHandler<()>,
-    pub on_import_scenario: EventHandler<()>,
+    pub on_import_scenario: EventHandler<String>,
     pub on_export_scenario: EventHandler<()>,
+    pub on_export_network: EventHandler<()>,
+    pub on_import_network: EventHandler<String>,
+    pub auto_topology_enabled: bool,
+    pub on_toggle_auto_topology: EventHandler<()>,
 }
 
 #[component]
 pub fn Topbar(props: TopbarProps) -> Element {
+    // Name typed in while recording, submitted with the stop action so the
+    // saved scenario doesn't have to be renamed after the fact.
+    let mut recording_name = use_signal(String::new);
+    // Path typed in for Import, submitted on click.
+    let mut import_path = use_signal(String::new);
+    // Path typed in for loading a saved network snapshot.
+    let mut network_import_path = use_signal(String::new);
+
     rsx! {
         div {
             class: "h-12 bg-black border-b border-zinc-800 flex items-center px-4",
@@ -123,10 +138,24 @@ pub fn Topbar(props: TopbarProps) -> Element {
                         }
                     }
 
+                    // Import path: a scenario JSON file written by Export
+                    // (or hand-edited / shared by another user).
+                    input {
+                        class: "h-8 px-2 rounded-md bg-zinc-900 text-zinc-300 text-xs border border-zinc-800 w-36",
+                        value: "{import_path}",
+                        oninput: move |evt| import_path.set(evt.value()),
+                        placeholder: "/path/to/scenario.json",
+                    }
+
                     // Import button
                     button {
                         class: "h-8 w-8 flex items-center justify-center rounded-md bg-zinc-900 hover:bg-zinc-800 text-zinc-300 border border-zinc-800 transition-all",
-                        onclick: move |_| props.on_import_scenario.call(()),
+                        onclick: move |_| {
+                            if !import_path().trim().is_empty() {
+                                props.on_import_scenario.call(import_path());
+                                import_path.set(String::new());
+                            }
+                        },
                         title: "Import Scenario",
                         // Import/download icon
                         svg {
@@ -162,6 +191,34 @@ pub fn Topbar(props: TopbarProps) -> Element {
                             }
                         }
                     }
+
+                    // Record toggle: captures add/connect/write/read calls
+                    // into a new scenario while active, named on stop.
+                    if props.is_recording {
+                        input {
+                            class: "h-8 px-2 rounded-md bg-zinc-900 text-zinc-300 text-xs border border-zinc-800 w-32",
+                            value: "{recording_name}",
+                            oninput: move |evt| recording_name.set(evt.value()),
+                            placeholder: "Scenario name",
+                        }
+                        button {
+                            class: "h-8 px-3 rounded-md text-white text-xs font-medium transition-all flex items-center gap-1.5",
+                            style: "background-color: #ff0000;",
+                            onclick: move |_| {
+                                props.on_stop_recording.call(recording_name());
+                                recording_name.set(String::new());
+                            },
+                            title: "Stop recording and save as a scenario",
+                            "● Stop & Save"
+                        }
+                    } else {
+                        button {
+                            class: "h-8 px-3 rounded-md bg-zinc-900 hover:bg-zinc-800 text-zinc-300 text-xs font-medium transition-all border border-zinc-800 hover:border-zinc-700 flex items-center gap-1.5",
+                            onclick: move |_| props.on_start_recording.call(()),
+                            title: "Record interactive actions into a new scenario",
+                            "● Record"
+                        }
+                    }
                 }
             }
 
@@ -169,6 +226,72 @@ pub fn Topbar(props: TopbarProps) -> Element {
             div {
                 class: "flex items-center gap-2 flex-1 justify-end",
 
+                // Auto-topology toggle: self-organizes clients toward a
+                // target peer count instead of requiring manual connects.
+                if props.is_running {
+                    button {
+                        class: if props.auto_topology_enabled {
+                            "h-8 px-3 rounded-md text-black text-xs font-medium transition-all"
+                        } else {
+                            "h-8 px-3 rounded-md bg-zinc-900 hover:bg-zinc-800 text-xs font-medium transition-all border border-zinc-800 hover:border-zinc-700"
+                        },
+                        style: if props.auto_topology_enabled { "background-color: #c7ff00;" } else { "" },
+                        onclick: move |_| props.on_toggle_auto_topology.call(()),
+                        title: "Auto-Topology: keep clients near their target peer count",
+                        "Auto-Topology"
+                    }
+                }
+
+                // Network snapshot: save/load node positions, status, and
+                // edges as a versioned JSON file, separate from scenarios.
+                if props.is_running {
+                    input {
+                        class: "h-8 px-2 rounded-md bg-zinc-900 text-zinc-300 text-xs border border-zinc-800 w-36",
+                        value: "{network_import_path}",
+                        oninput: move |evt| network_import_path.set(evt.value()),
+                        placeholder: "/path/to/network.json",
+                    }
+                    button {
+                        class: "h-8 w-8 flex items-center justify-center rounded-md bg-zinc-900 hover:bg-zinc-800 text-zinc-300 border border-zinc-800 transition-all",
+                        onclick: move |_| {
+                            if !network_import_path().trim().is_empty() {
+                                props.on_import_network.call(network_import_path());
+                                network_import_path.set(String::new());
+                            }
+                        },
+                        title: "Load Network",
+                        svg {
+                            class: "w-3.5 h-3.5",
+                            fill: "none",
+                            stroke: "currentColor",
+                            view_box: "0 0 24 24",
+                            path {
+                                stroke_linecap: "round",
+                                stroke_linejoin: "round",
+                                stroke_width: "2",
+                                d: "M7 16a4 4 0 01-.88-7.903A5 5 0 1115.9 6L16 6a5 5 0 011 9.9M9 19l3 3m0 0l3-3m-3 3V10"
+                            }
+                        }
+                    }
+                    button {
+                        class: "h-8 w-8 flex items-center justify-center rounded-md bg-zinc-900 hover:bg-zinc-800 text-zinc-300 border border-zinc-800 transition-all",
+                        onclick: move |_| props.on_export_network.call(()),
+                        title: "Save Network",
+                        svg {
+                            class: "w-3.5 h-3.5",
+                            fill: "none",
+                            stroke: "currentColor",
+                            view_box: "0 0 24 24",
+                            path {
+                                stroke_linecap: "round",
+                                stroke_linejoin: "round",
+                                stroke_width: "2",
+                                d: "M7 16a4 4 0 01-.88-7.903A5 5 0 1115.9 6L16 6a5 5 0 011 9.9M15 13l-3-3m0 0l-3 3m3-3v12"
+                            }
+                        }
+                    }
+                }
+
                 // Reset button (outline style)
                 if props.is_running {
                     button {
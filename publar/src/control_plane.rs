@@ -0,0 +1,128 @@
+/// Background control-plane subsystem that drives one homeserver's
+/// lifecycle and live status independently of the UI's own read/write
+/// paths: a long-lived task per homeserver applies `Start`/`Stop` commands
+/// (triggered from the visualization's node hover tooltip) and periodically reports back `ConnectivityStatus` and
+/// `StorageStats`, all over a `ControlTransport` so the same protocol can
+/// run in-process for this desktop build or over a socket for a remote
+/// homeserver.
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::sync::mpsc;
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::network_visualization::{ConnectivityStatus, NodeStatus, StorageStats};
+
+/// Commands a control channel carries to a homeserver's control-plane task.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ControlCommand {
+    Start,
+    Stop,
+}
+
+/// Events a control channel carries back: the outcome of a command, or an
+/// unsolicited status/stats update from the periodic poll.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ControlEvent {
+    Status(NodeStatus),
+    Connectivity(ConnectivityStatus),
+    Storage(StorageStats),
+}
+
+/// A duplex, transport-agnostic link to one homeserver's control-plane
+/// task: commands queue up for the far end, and events queue up for us.
+/// Both directions are non-blocking so the UI's reactive poll can drain
+/// whatever has arrived without ever waiting on I/O.
+pub trait ControlTransport: Send {
+    fn send_command(&mut self, command: ControlCommand) -> io::Result<()>;
+    fn try_recv_event(&mut self) -> Option<ControlEvent>;
+}
+
+/// In-process transport: this desktop build's homeservers run in the same
+/// process as the UI, so commands and events just cross two channels
+/// instead of a real wire.
+pub struct InProcessTransport {
+    commands: mpsc::Sender<ControlCommand>,
+    events: mpsc::Receiver<ControlEvent>,
+}
+
+impl InProcessTransport {
+    /// Build a connected pair: the transport handed to the UI side, plus
+    /// the raw channel halves the per-homeserver control-plane task drives.
+    pub fn pair() -> (Self, mpsc::Receiver<ControlCommand>, mpsc::Sender<ControlEvent>) {
+        let (command_tx, command_rx) = mpsc::channel();
+        let (event_tx, event_rx) = mpsc::channel();
+        (Self { commands: command_tx, events: event_rx }, command_rx, event_tx)
+    }
+}
+
+impl ControlTransport for InProcessTransport {
+    fn send_command(&mut self, command: ControlCommand) -> io::Result<()> {
+        self.commands
+            .send(command)
+            .map_err(|e| io::Error::new(io::ErrorKind::BrokenPipe, e.to_string()))
+    }
+
+    fn try_recv_event(&mut self) -> Option<ControlEvent> {
+        self.events.try_recv().ok()
+    }
+}
+
+/// Socket transport for a remote homeserver: the same `ControlCommand`/
+/// `ControlEvent` messages cross a `TcpStream`, each framed as a 4-byte
+/// big-endian length prefix followed by its JSON payload. A background
+/// thread owns the blocking read loop so `try_recv_event` never blocks the
+/// caller, the same way the diff view's fetch thread keeps blocking I/O
+/// off the UI thread.
+pub struct SocketTransport {
+    stream: TcpStream,
+    events: mpsc::Receiver<ControlEvent>,
+}
+
+impl SocketTransport {
+    pub fn connect(addr: &str) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        let mut reader_stream = stream.try_clone()?;
+        let (event_tx, event_rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            while let Ok(event) = read_frame::<ControlEvent>(&mut reader_stream) {
+                if event_tx.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self { stream, events: event_rx })
+    }
+}
+
+impl ControlTransport for SocketTransport {
+    fn send_command(&mut self, command: ControlCommand) -> io::Result<()> {
+        write_frame(&mut self.stream, &command)
+    }
+
+    fn try_recv_event(&mut self) -> Option<ControlEvent> {
+        self.events.try_recv().ok()
+    }
+}
+
+/// Write `value` as a 4-byte big-endian length prefix followed by its JSON
+/// encoding.
+fn write_frame<T: Serialize>(w: &mut impl Write, value: &T) -> io::Result<()> {
+    let payload =
+        serde_json::to_vec(value).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+    w.write_all(&(payload.len() as u32).to_be_bytes())?;
+    w.write_all(&payload)
+}
+
+/// Read one length-prefixed JSON frame back out.
+fn read_frame<T: for<'de> Deserialize<'de>>(r: &mut impl Read) -> io::Result<T> {
+    let mut len_buf = [0u8; 4];
+    r.read_exact(&mut len_buf)?;
+    let len = u32::from_be_bytes(len_buf) as usize;
+    let mut payload = vec![0u8; len];
+    r.read_exact(&mut payload)?;
+    serde_json::from_slice(&payload).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
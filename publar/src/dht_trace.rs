@@ -0,0 +1,192 @@
+/// Kademlia iterative-lookup tracer for the Mainline/pkarr DHT.
+///
+/// `build_pubky` / `build_pubky_from_env` (in `pubky-cli`) configure pkarr
+/// bootstrap nodes and relays but the resolver itself hides the iterative
+/// lookup. This module re-implements the standard Kademlia walk against any
+/// set of known node ids and emits one `LookupHop` per queried node, so a
+/// caller can feed the hops into `ForceLayout`/`network_visualization` and
+/// watch the resolution happen live.
+use crate::components::network_visualization::{Homeserver, ConnectivityStatus, Node, NodeStatus, BandwidthHistory};
+use crate::force_layout::calculate_initial_position;
+
+/// Number of closest nodes kept in the shortlist.
+const DEFAULT_K: usize = 8;
+/// Number of un-queried nodes probed in parallel per round.
+const DEFAULT_ALPHA: usize = 3;
+/// Hard bound on rounds in case convergence never triggers.
+const DEFAULT_MAX_STEPS: usize = 20;
+
+/// A node as seen by the tracer: an opaque id plus whatever neighbors it
+/// would hand back when queried (the simulated/real DHT response).
+pub trait LookupSource {
+    fn neighbors_of(&self, node_id: &str) -> Vec<String>;
+    /// Whether this node holds the record being resolved.
+    fn has_record(&self, node_id: &str, target: &str) -> bool;
+}
+
+/// One queried node in the lookup path: its id, XOR distance to the
+/// target key, and whether it answered with the record.
+#[derive(Clone, Debug)]
+pub struct LookupHop {
+    pub node_id: String,
+    pub parent_id: Option<String>,
+    pub xor_distance: u64,
+    pub answered: bool,
+}
+
+/// XOR distance between two ids, truncated to 64 bits. Ids are hashed to a
+/// fixed-width key first so arbitrary strings (z32 pubkeys, bootstrap host
+/// names, ...) can be compared.
+fn xor_distance(a: &str, b: &str) -> u64 {
+    key_of(a) ^ key_of(b)
+}
+
+fn key_of(id: &str) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    id.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Run the standard iterative Kademlia lookup for `target`, starting from
+/// `bootstrap`, and invoke `on_hop` once per queried node in the order it
+/// was queried.
+///
+/// Maintains a shortlist of the `k` closest known ids by XOR distance,
+/// repeatedly queries the `alpha` closest un-queried entries, merges their
+/// neighbor sets into the shortlist, and stops once a round fails to
+/// surface anything closer than the current best (or after `max_steps`).
+pub fn trace_lookup(
+    source: &dyn LookupSource,
+    target: &str,
+    bootstrap: &[String],
+    mut on_hop: impl FnMut(LookupHop),
+) -> Vec<LookupHop> {
+    trace_lookup_with_params(
+        source,
+        target,
+        bootstrap,
+        DEFAULT_K,
+        DEFAULT_ALPHA,
+        DEFAULT_MAX_STEPS,
+        &mut on_hop,
+    )
+}
+
+fn trace_lookup_with_params(
+    source: &dyn LookupSource,
+    target: &str,
+    bootstrap: &[String],
+    k: usize,
+    alpha: usize,
+    max_steps: usize,
+    on_hop: &mut impl FnMut(LookupHop),
+) -> Vec<LookupHop> {
+    let mut shortlist: Vec<String> = bootstrap.to_vec();
+    let mut parent_of: std::collections::HashMap<String, Option<String>> =
+        bootstrap.iter().map(|id| (id.clone(), None)).collect();
+    let mut queried: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut hops = Vec::new();
+    let mut best_distance = shortlist.iter().map(|id| xor_distance(id, target)).min();
+
+    for _ in 0..max_steps {
+        shortlist.sort_by_key(|id| xor_distance(id, target));
+        shortlist.truncate(k);
+
+        let to_query: Vec<String> = shortlist
+            .iter()
+            .filter(|id| !queried.contains(*id))
+            .take(alpha)
+            .cloned()
+            .collect();
+
+        if to_query.is_empty() {
+            break;
+        }
+
+        let mut closest_this_round = best_distance;
+
+        for node_id in &to_query {
+            queried.insert(node_id.clone());
+            let distance = xor_distance(node_id, target);
+            let answered = source.has_record(node_id, target);
+
+            let hop = LookupHop {
+                node_id: node_id.clone(),
+                parent_id: parent_of.get(node_id).cloned().flatten(),
+                xor_distance: distance,
+                answered,
+            };
+            on_hop(hop.clone());
+            hops.push(hop);
+
+            if answered {
+                return hops;
+            }
+
+            for neighbor in source.neighbors_of(node_id) {
+                if !parent_of.contains_key(&neighbor) {
+                    parent_of.insert(neighbor.clone(), Some(node_id.clone()));
+                    shortlist.push(neighbor.clone());
+                }
+                let neighbor_distance = xor_distance(&neighbor, target);
+                closest_this_round = Some(closest_this_round.map_or(neighbor_distance, |d| d.min(neighbor_distance)));
+            }
+        }
+
+        match (closest_this_round, best_distance) {
+            (Some(closest), Some(best)) if closest >= best => break,
+            _ => best_distance = closest_this_round,
+        }
+    }
+
+    hops
+}
+
+/// Turn recorded lookup hops into graph nodes/edges, placing each hop near
+/// the node that referred it via `calculate_initial_position`. DHT peers
+/// are represented as `Node::Homeserver` entries (they are network peers
+/// answering resolution queries, not full homeservers, but reuse the same
+/// shape rather than growing the `Node` enum for a display-only distinction).
+pub fn hops_to_graph(hops: &[LookupHop], existing_nodes: &[Node]) -> (Vec<Node>, Vec<(String, String)>) {
+    let mut nodes = Vec::with_capacity(hops.len());
+    let mut edges = Vec::new();
+    let mut placed: Vec<Node> = existing_nodes.to_vec();
+
+    for hop in hops {
+        let connected_to = hop.parent_id.as_deref();
+        let (x, y) = calculate_initial_position(&placed, connected_to);
+
+        let node = Node::Homeserver(Homeserver {
+            id: hop.node_id.clone(),
+            name: format!("dht:{}", short_id(&hop.node_id)),
+            port: 0,
+            http_url: None,
+            status: if hop.answered {
+                NodeStatus::Running
+            } else {
+                NodeStatus::Starting
+            },
+            public_key: Some(hop.node_id.clone()),
+            connectivity_status: ConnectivityStatus::Unknown,
+            storage_stats: None,
+            last_seen_secs_ago: None,
+            bandwidth_history: BandwidthHistory::new(),
+            x,
+            y,
+        });
+
+        if let Some(parent_id) = &hop.parent_id {
+            edges.push((parent_id.clone(), hop.node_id.clone()));
+        }
+
+        placed.push(node.clone());
+        nodes.push(node);
+    }
+
+    (nodes, edges)
+}
+
+fn short_id(id: &str) -> String {
+    id.chars().take(8).collect()
+}
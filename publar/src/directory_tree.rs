@@ -0,0 +1,77 @@
+//! Turn a flat list of a homeserver's tracked paths into a nested
+//! folder/leaf tree for the client panel's content browser, the way a
+//! filesystem tree view groups paths by `/`-separated segment.
+
+use crate::testnet::DirectoryEntry;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LeafInfo {
+    pub size_bytes: usize,
+    pub modified_secs_ago: u64,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct TreeNode {
+    pub name: String,
+    /// Full path from the root, e.g. `/pub/publar/notes.txt`.
+    pub path: String,
+    pub children: Vec<TreeNode>,
+    /// Set when a key was written at exactly this path. A node can be both
+    /// a leaf and a folder at once, if some other key extends this path
+    /// with a further `/segment`.
+    pub leaf: Option<LeafInfo>,
+}
+
+impl TreeNode {
+    fn folder(name: String, path: String) -> Self {
+        Self {
+            name,
+            path,
+            children: Vec::new(),
+            leaf: None,
+        }
+    }
+}
+
+/// Build a forest of `TreeNode`s from `entries`, nesting each path's
+/// segments (split on `/`) below `prefix`. A path landing exactly on
+/// `prefix` itself (the trailing-slash edge case) has no segment left to
+/// name it, so it's dropped rather than shown as an unnamed root.
+pub fn build_tree(entries: &[DirectoryEntry], prefix: &str) -> Vec<TreeNode> {
+    let mut roots: Vec<TreeNode> = Vec::new();
+
+    for entry in entries {
+        let rest = entry.path.strip_prefix(prefix).unwrap_or(&entry.path);
+        let segments: Vec<&str> = rest.split('/').filter(|s| !s.is_empty()).collect();
+        if segments.is_empty() {
+            continue;
+        }
+
+        let mut siblings = &mut roots;
+        let mut path_so_far = prefix.trim_end_matches('/').to_string();
+
+        for (i, segment) in segments.iter().enumerate() {
+            path_so_far.push('/');
+            path_so_far.push_str(segment);
+
+            let idx = match siblings.iter().position(|n| n.name == *segment) {
+                Some(idx) => idx,
+                None => {
+                    siblings.push(TreeNode::folder(segment.to_string(), path_so_far.clone()));
+                    siblings.len() - 1
+                }
+            };
+
+            if i == segments.len() - 1 {
+                siblings[idx].leaf = Some(LeafInfo {
+                    size_bytes: entry.size_bytes,
+                    modified_secs_ago: entry.modified_secs_ago,
+                });
+            }
+
+            siblings = &mut siblings[idx].children;
+        }
+    }
+
+    roots
+}
@@ -3,11 +3,34 @@
 
 use crate::components::network_visualization::Node;
 
-const REPULSION_STRENGTH: f64 = 5000.0; // Repulsion between nodes
-const ATTRACTION_STRENGTH: f64 = 0.05; // Attraction along edges
 const DAMPING: f64 = 0.85; // Velocity damping (0-1)
 const MIN_DISTANCE: f64 = 50.0; // Minimum distance between nodes
-const IDEAL_EDGE_LENGTH: f64 = 150.0; // Target distance for connected nodes
+
+// Canvas bounds nodes are clamped to, and the area `ideal_edge_length` uses
+// to size `k` for the current node count.
+const CANVAS_MIN_X: f64 = 100.0;
+const CANVAS_MAX_X: f64 = 1100.0;
+const CANVAS_MIN_Y: f64 = 100.0;
+const CANVAS_MAX_Y: f64 = 700.0;
+const CANVAS_AREA: f64 = (CANVAS_MAX_X - CANVAS_MIN_X) * (CANVAS_MAX_Y - CANVAS_MIN_Y);
+
+/// Fruchterman-Reingold's ideal edge length `k = C * sqrt(area / n)`: the
+/// spacing at which repulsion (`k^2/d`) and attraction (`d^2/k`) balance for
+/// `n` nodes spread evenly over the canvas.
+fn ideal_edge_length(n: usize) -> f64 {
+    const C: f64 = 1.0;
+    C * (CANVAS_AREA / n.max(1) as f64).sqrt()
+}
+
+/// Starting per-tick displacement cap ("temperature"), proportional to the
+/// canvas so a fresh layout can move nodes across it in a few ticks.
+const INITIAL_TEMPERATURE: f64 = (CANVAS_MAX_X - CANVAS_MIN_X) / 10.0;
+/// Temperature multiplier applied once per `tick`, so displacement shrinks
+/// as the layout settles instead of oscillating forever.
+const COOLING_FACTOR: f64 = 0.98;
+const MIN_TEMPERATURE: f64 = 0.5;
+/// Below this summed displacement, a tick is considered to have converged.
+const CONVERGENCE_THRESHOLD: f64 = 0.5;
 
 #[derive(Clone, Debug)]
 pub struct ForceNode {
@@ -21,6 +44,184 @@ pub struct ForceNode {
 pub struct ForceLayout {
     pub nodes: Vec<ForceNode>,
     pub edges: Vec<(String, String)>, // (from_id, to_id)
+    /// When true, `tick` approximates repulsion with a Barnes-Hut quadtree
+    /// instead of the exact O(n^2) all-pairs loop.
+    pub use_barnes_hut: bool,
+    /// Accuracy threshold for the Barnes-Hut approximation: a cell is
+    /// treated as a single aggregate body when `side / distance < theta`.
+    pub theta: f64,
+    /// Ideal edge length for the current node count, recomputed whenever
+    /// the simulation is (re)built from a node set.
+    k: f64,
+    /// Per-tick displacement cap, cooled by `COOLING_FACTOR` on every
+    /// `integrate` so motion shrinks instead of oscillating indefinitely.
+    pub temperature: f64,
+    /// Summed per-node displacement from the last `tick`, used to detect
+    /// convergence.
+    last_movement: f64,
+}
+
+/// A node in the Barnes-Hut quadtree: either an empty region, a single
+/// leaf body, or an internal cell summarizing its four children as one
+/// aggregate mass at their center of mass.
+enum QuadTree {
+    Empty {
+        bounds: Bounds,
+    },
+    Leaf {
+        bounds: Bounds,
+        id: usize,
+        x: f64,
+        y: f64,
+    },
+    Internal {
+        bounds: Bounds,
+        mass: usize,
+        com_x: f64,
+        com_y: f64,
+        children: Box<[QuadTree; 4]>,
+    },
+}
+
+#[derive(Clone, Copy)]
+struct Bounds {
+    min_x: f64,
+    min_y: f64,
+    max_x: f64,
+    max_y: f64,
+}
+
+impl Bounds {
+    fn side(&self) -> f64 {
+        (self.max_x - self.min_x).max(self.max_y - self.min_y)
+    }
+
+    fn quadrant(&self, x: f64, y: f64) -> usize {
+        let mid_x = (self.min_x + self.max_x) / 2.0;
+        let mid_y = (self.min_y + self.max_y) / 2.0;
+        match (x >= mid_x, y >= mid_y) {
+            (false, false) => 0,
+            (true, false) => 1,
+            (false, true) => 2,
+            (true, true) => 3,
+        }
+    }
+
+    fn split(&self) -> [Bounds; 4] {
+        let mid_x = (self.min_x + self.max_x) / 2.0;
+        let mid_y = (self.min_y + self.max_y) / 2.0;
+        [
+            Bounds { min_x: self.min_x, min_y: self.min_y, max_x: mid_x, max_y: mid_y },
+            Bounds { min_x: mid_x, min_y: self.min_y, max_x: self.max_x, max_y: mid_y },
+            Bounds { min_x: self.min_x, min_y: mid_y, max_x: mid_x, max_y: self.max_y },
+            Bounds { min_x: mid_x, min_y: mid_y, max_x: self.max_x, max_y: self.max_y },
+        ]
+    }
+}
+
+impl QuadTree {
+    fn new(bounds: Bounds) -> Self {
+        QuadTree::Empty { bounds }
+    }
+
+    fn bounds(&self) -> Bounds {
+        match self {
+            QuadTree::Empty { bounds }
+            | QuadTree::Leaf { bounds, .. }
+            | QuadTree::Internal { bounds, .. } => *bounds,
+        }
+    }
+
+    fn insert(&mut self, id: usize, x: f64, y: f64) {
+        match self {
+            QuadTree::Empty { bounds } => {
+                *self = QuadTree::Leaf { bounds: *bounds, id, x, y };
+            }
+            QuadTree::Leaf {
+                bounds,
+                id: leaf_id,
+                x: leaf_x,
+                y: leaf_y,
+            } => {
+                let bounds = *bounds;
+                let (leaf_id, leaf_x, leaf_y) = (*leaf_id, *leaf_x, *leaf_y);
+                let child_bounds = bounds.split();
+                let mut children = Box::new([
+                    QuadTree::new(child_bounds[0]),
+                    QuadTree::new(child_bounds[1]),
+                    QuadTree::new(child_bounds[2]),
+                    QuadTree::new(child_bounds[3]),
+                ]);
+                children[bounds.quadrant(leaf_x, leaf_y)].insert(leaf_id, leaf_x, leaf_y);
+                children[bounds.quadrant(x, y)].insert(id, x, y);
+                *self = QuadTree::Internal {
+                    bounds,
+                    mass: 2,
+                    com_x: (leaf_x + x) / 2.0,
+                    com_y: (leaf_y + y) / 2.0,
+                    children,
+                };
+            }
+            QuadTree::Internal {
+                bounds,
+                mass,
+                com_x,
+                com_y,
+                children,
+            } => {
+                *com_x = (*com_x * *mass as f64 + x) / (*mass + 1) as f64;
+                *com_y = (*com_y * *mass as f64 + y) / (*mass + 1) as f64;
+                *mass += 1;
+                let quadrant = bounds.quadrant(x, y);
+                children[quadrant].insert(id, x, y);
+            }
+        }
+    }
+
+    /// Accumulate the Barnes-Hut repulsion force this cell exerts on the
+    /// node at `(x, y)` (which is excluded from self-interaction via `id`).
+    fn accumulate_force(&self, id: usize, x: f64, y: f64, theta: f64, k: f64, fx: &mut f64, fy: &mut f64) {
+        match self {
+            QuadTree::Empty { .. } => {}
+            QuadTree::Leaf { id: leaf_id, x: leaf_x, y: leaf_y, .. } => {
+                if *leaf_id == id {
+                    return;
+                }
+                apply_repulsion(x, y, *leaf_x, *leaf_y, 1.0, k, fx, fy);
+            }
+            QuadTree::Internal { bounds, mass, com_x, com_y, children } => {
+                let dx = com_x - x;
+                let dy = com_y - y;
+                let distance = (dx * dx + dy * dy).sqrt().max(MIN_DISTANCE);
+                let s = bounds.side();
+                if s / distance < theta {
+                    apply_repulsion(x, y, *com_x, *com_y, *mass as f64, k, fx, fy);
+                } else {
+                    for child in children.iter() {
+                        child.accumulate_force(id, x, y, theta, k, fx, fy);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Kinetic energy of a single node (vx^2 + vy^2), used by
+/// `ForceLayout::run_until_stable` to detect convergence.
+fn kinetic_energy(node: &ForceNode) -> f64 {
+    node.vx * node.vx + node.vy * node.vy
+}
+
+/// Fruchterman-Reingold repulsion `f_rep = k^2/d` exerted by a body of the
+/// given mass located at `(other_x, other_y)` on the node at `(x, y)`,
+/// added into `(fx, fy)`.
+fn apply_repulsion(x: f64, y: f64, other_x: f64, other_y: f64, mass: f64, k: f64, fx: &mut f64, fy: &mut f64) {
+    let dx = x - other_x;
+    let dy = y - other_y;
+    let distance = (dx * dx + dy * dy).sqrt().max(MIN_DISTANCE);
+    let force = mass * (k * k) / distance;
+    *fx += (dx / distance) * force;
+    *fy += (dy / distance) * force;
 }
 
 impl ForceLayout {
@@ -29,12 +230,17 @@ impl ForceLayout {
         Self {
             nodes: Vec::new(),
             edges: Vec::new(),
+            use_barnes_hut: false,
+            theta: 0.5,
+            k: ideal_edge_length(0),
+            temperature: INITIAL_TEMPERATURE,
+            last_movement: 0.0,
         }
     }
 
     /// Initialize from existing nodes
     pub fn from_nodes(nodes: &[Node], edges: &[(String, String)]) -> Self {
-        let force_nodes = nodes
+        let force_nodes: Vec<ForceNode> = nodes
             .iter()
             .map(|node| {
                 let (x, y) = node.position();
@@ -47,24 +253,73 @@ impl ForceLayout {
                 }
             })
             .collect();
+        let k = ideal_edge_length(force_nodes.len());
 
         Self {
             nodes: force_nodes,
             edges: edges.to_vec(),
+            use_barnes_hut: false,
+            theta: 0.5,
+            k,
+            temperature: INITIAL_TEMPERATURE,
+            last_movement: 0.0,
         }
     }
 
-    /// Run one iteration of the force simulation
+    /// Override the starting temperature (e.g. to resume a cooled-down
+    /// simulation across ticks instead of restarting at full heat).
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    /// Total node displacement applied by the last `tick`/`tick_barnes_hut`/
+    /// `tick_parallel` call. Below `CONVERGENCE_THRESHOLD`, the layout has
+    /// settled and further ticks can be skipped until the topology changes.
+    pub fn total_movement(&self) -> f64 {
+        self.last_movement
+    }
+
+    /// Whether the last tick's total movement settled below
+    /// `CONVERGENCE_THRESHOLD` — the layout has reached equilibrium and
+    /// further ticks can be skipped until the topology changes.
+    pub fn has_converged(&self) -> bool {
+        self.last_movement < CONVERGENCE_THRESHOLD
+    }
+
+    /// Run one iteration of the force simulation, dispatching to the
+    /// Barnes-Hut approximation when `use_barnes_hut` is set.
     pub fn tick(&mut self) {
-        // Calculate repulsion forces (all nodes repel each other)
+        if self.use_barnes_hut {
+            self.apply_repulsion_barnes_hut();
+        } else {
+            self.apply_repulsion_exact();
+        }
+
+        self.apply_attraction();
+        self.integrate();
+    }
+
+    /// Run one iteration using the Barnes-Hut approximation regardless of
+    /// the `use_barnes_hut` flag. Large graphs stay interactive since this
+    /// drops repulsion from O(n^2) to O(n log n).
+    pub fn tick_barnes_hut(&mut self) {
+        self.apply_repulsion_barnes_hut();
+        self.apply_attraction();
+        self.integrate();
+    }
+
+    /// Exact O(n^2) all-pairs repulsion.
+    fn apply_repulsion_exact(&mut self) {
+        let k = self.k;
         for i in 0..self.nodes.len() {
             for j in (i + 1)..self.nodes.len() {
                 let dx = self.nodes[j].x - self.nodes[i].x;
                 let dy = self.nodes[j].y - self.nodes[i].y;
                 let distance = (dx * dx + dy * dy).sqrt().max(MIN_DISTANCE);
 
-                // Repulsion force: F = k^2 / distance
-                let force = REPULSION_STRENGTH / (distance * distance);
+                // Fruchterman-Reingold repulsion: f_rep(d) = k^2 / d
+                let force = (k * k) / distance;
                 let fx = (dx / distance) * force;
                 let fy = (dy / distance) * force;
 
@@ -74,9 +329,145 @@ impl ForceLayout {
                 self.nodes[j].vy += fy;
             }
         }
+    }
+
+    /// Approximate repulsion via a quadtree over the node bounding box:
+    /// cells whose `side / distance` ratio is below `theta` are treated as
+    /// a single aggregate body at their center of mass.
+    fn apply_repulsion_barnes_hut(&mut self) {
+        if self.nodes.len() < 2 {
+            return;
+        }
+
+        let (mut min_x, mut min_y, mut max_x, mut max_y) =
+            (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+        for node in &self.nodes {
+            min_x = min_x.min(node.x);
+            min_y = min_y.min(node.y);
+            max_x = max_x.max(node.x);
+            max_y = max_y.max(node.y);
+        }
+        // Pad so no node lands exactly on the outer boundary, and guarantee
+        // a non-degenerate (non-zero-area) bounding box even when every
+        // node shares one position.
+        let pad = 1.0;
+        let bounds = Bounds {
+            min_x: min_x - pad,
+            min_y: min_y - pad,
+            max_x: max_x + pad,
+            max_y: max_y + pad,
+        };
+
+        let mut tree = QuadTree::new(bounds);
+        for (idx, node) in self.nodes.iter().enumerate() {
+            tree.insert(idx, node.x, node.y);
+        }
+
+        let theta = self.theta;
+        let k = self.k;
+        let positions: Vec<(f64, f64)> = self.nodes.iter().map(|n| (n.x, n.y)).collect();
+        for (idx, (x, y)) in positions.into_iter().enumerate() {
+            let (mut fx, mut fy) = (0.0, 0.0);
+            tree.accumulate_force(idx, x, y, theta, k, &mut fx, &mut fy);
+            // accumulate_force (via apply_repulsion) computes dx = x - other_x,
+            // so (fx, fy) already points away from the other body -- add it
+            // directly. apply_repulsion_exact instead computes dx = x_j - x_i
+            // (pointing toward the other node) and subtracts for node i, the
+            // opposite sign convention; it isn't a template for this one.
+            self.nodes[idx].vx += fx;
+            self.nodes[idx].vy += fy;
+        }
+    }
+
+    /// Same as `apply_repulsion_exact`, but splits the O(n^2) accumulation
+    /// across rayon's thread pool: each worker folds its share of pairs into
+    /// a thread-local force buffer, which are then reduced by summation.
+    /// Used by the headless snapshot solver, where exported graphs can be
+    /// large and nothing needs to run on the UI thread.
+    fn apply_repulsion_exact_parallel(&mut self) {
+        use rayon::prelude::*;
 
-        // Calculate attraction forces (connected nodes attract each other)
-        // Uses spring force: pulls nodes together if too far, pushes apart if too close
+        let k = self.k;
+        let positions: Vec<(f64, f64)> = self.nodes.iter().map(|n| (n.x, n.y)).collect();
+        let n = positions.len();
+
+        let forces = (0..n)
+            .into_par_iter()
+            .fold(
+                || vec![(0.0f64, 0.0f64); n],
+                |mut local: Vec<(f64, f64)>, i| {
+                    for j in (i + 1)..n {
+                        let dx = positions[j].0 - positions[i].0;
+                        let dy = positions[j].1 - positions[i].1;
+                        let distance = (dx * dx + dy * dy).sqrt().max(MIN_DISTANCE);
+
+                        let force = (k * k) / distance;
+                        let fx = (dx / distance) * force;
+                        let fy = (dy / distance) * force;
+
+                        local[i].0 -= fx;
+                        local[i].1 -= fy;
+                        local[j].0 += fx;
+                        local[j].1 += fy;
+                    }
+                    local
+                },
+            )
+            .reduce(
+                || vec![(0.0, 0.0); n],
+                |mut a, b| {
+                    for (acc, contrib) in a.iter_mut().zip(b.iter()) {
+                        acc.0 += contrib.0;
+                        acc.1 += contrib.1;
+                    }
+                    a
+                },
+            );
+
+        for (node, (fx, fy)) in self.nodes.iter_mut().zip(forces.into_iter()) {
+            node.vx += fx;
+            node.vy += fy;
+        }
+    }
+
+    /// Run one iteration with rayon-parallel repulsion, otherwise identical
+    /// to `tick`. Barnes-Hut tree construction stays single-threaded since
+    /// it is already O(n log n) and cheap relative to building a concurrent
+    /// tree would be.
+    fn tick_parallel(&mut self) {
+        if self.use_barnes_hut {
+            self.apply_repulsion_barnes_hut();
+        } else {
+            self.apply_repulsion_exact_parallel();
+        }
+
+        self.apply_attraction();
+        self.integrate();
+    }
+
+    /// Run the simulation headlessly until it settles or `max_iters` is
+    /// reached, whichever comes first. Settled means the total kinetic
+    /// energy across all nodes (sum of `vx^2 + vy^2`) has dropped below
+    /// `epsilon`. Repulsion is computed in parallel via rayon, since this is
+    /// meant for offline/server-side rendering of graphs too large to
+    /// relax interactively. Returns the number of iterations actually run.
+    pub fn run_until_stable(&mut self, max_iters: usize, epsilon: f64) -> usize {
+        for i in 0..max_iters {
+            self.tick_parallel();
+
+            let energy: f64 = self.nodes.iter().map(kinetic_energy).sum();
+            if energy < epsilon {
+                return i + 1;
+            }
+        }
+        max_iters
+    }
+
+    /// Fruchterman-Reingold attraction `f_attr(d) = d^2/k` pulling each
+    /// edge's endpoints together, growing (rather than capping out like a
+    /// spring) the further apart they are.
+    fn apply_attraction(&mut self) {
+        let k = self.k;
         for (from_id, to_id) in &self.edges {
             if let (Some(from_idx), Some(to_idx)) = (
                 self.nodes.iter().position(|n| &n.id == from_id),
@@ -86,10 +477,7 @@ impl ForceLayout {
                 let dy = self.nodes[to_idx].y - self.nodes[from_idx].y;
                 let distance = (dx * dx + dy * dy).sqrt().max(1.0); // Avoid division by zero
 
-                // Spring force: F = (distance - ideal_length) * k
-                // This creates attraction if too far, repulsion if too close
-                let displacement = distance - IDEAL_EDGE_LENGTH;
-                let force = displacement * ATTRACTION_STRENGTH;
+                let force = (distance * distance) / k;
                 let fx = (dx / distance) * force;
                 let fy = (dy / distance) * force;
 
@@ -99,18 +487,33 @@ impl ForceLayout {
                 self.nodes[to_idx].vy -= fy;
             }
         }
+    }
+
+    /// Apply velocity with damping, capped to `temperature` so no node
+    /// jumps further than the current (cooling) step size allows, then
+    /// clamp positions to the canvas and cool `temperature` for next tick.
+    fn integrate(&mut self) {
+        let temperature = self.temperature;
+        let mut total_movement = 0.0;
 
-        // Apply velocity with damping and update positions
         for node in &mut self.nodes {
             node.vx *= DAMPING;
             node.vy *= DAMPING;
-            node.x += node.vx;
-            node.y += node.vy;
 
-            // Keep nodes within reasonable bounds
-            node.x = node.x.max(100.0).min(1100.0);
-            node.y = node.y.max(100.0).min(700.0);
+            let speed = (node.vx * node.vx + node.vy * node.vy).sqrt();
+            let (dx, dy) = if speed > temperature && speed > 0.0 {
+                (node.vx / speed * temperature, node.vy / speed * temperature)
+            } else {
+                (node.vx, node.vy)
+            };
+
+            node.x = (node.x + dx).max(CANVAS_MIN_X).min(CANVAS_MAX_X);
+            node.y = (node.y + dy).max(CANVAS_MIN_Y).min(CANVAS_MAX_Y);
+            total_movement += (dx * dx + dy * dy).sqrt();
         }
+
+        self.last_movement = total_movement;
+        self.temperature = (self.temperature * COOLING_FACTOR).max(MIN_TEMPERATURE);
     }
 
     /// Run multiple iterations to stabilize the layout
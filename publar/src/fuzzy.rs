@@ -0,0 +1,37 @@
+//! Subsequence fuzzy matching for the event log's filter box: a query
+//! matches a candidate if every query character appears in it in order
+//! (not necessarily contiguous), the way a TUI fuzzy picker filters a list.
+
+/// Score how well `query` fuzzy-matches `candidate`, or `None` if some
+/// query character isn't found in order. Contiguous runs and earlier
+/// matches score higher than scattered, late ones, so tighter and more
+/// specific matches rank first.
+pub fn score(candidate: &str, query: &str) -> Option<i32> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let candidate_chars: Vec<char> = candidate.chars().collect();
+    let mut query_chars = query.chars().map(|c| c.to_ascii_lowercase());
+    let mut next_query_char = query_chars.next();
+
+    let mut total = 0i32;
+    let mut run_length = 0i32;
+    let mut prev_match_index: Option<usize> = None;
+
+    for (i, &c) in candidate_chars.iter().enumerate() {
+        let Some(qc) = next_query_char else { break };
+        if c.to_ascii_lowercase() == qc {
+            let contiguous = i.checked_sub(1).is_some_and(|prev| prev_match_index == Some(prev));
+            run_length = if contiguous { run_length + 1 } else { 1 };
+
+            let position_bonus = (candidate_chars.len() - i) as i32;
+            total += run_length * 2 + position_bonus;
+
+            prev_match_index = Some(i);
+            next_query_char = query_chars.next();
+        }
+    }
+
+    if next_query_char.is_some() { None } else { Some(total) }
+}
@@ -0,0 +1,107 @@
+//! Parses a raw HTTP/1.1 message into a structured `HttpExchange` for the
+//! client panel's "Inspect" view, and builds a request/response pair for a
+//! storage write or read this app just performed. The pubky SDK doesn't
+//! hand this caller the raw bytes it put on the wire, so the raw text fed
+//! to the parser is reconstructed from what the client already knows it
+//! sent (verb, path, headers, body) and what the call's outcome tells us
+//! about the response, then run through the same parser a genuine wire
+//! capture would use.
+
+use std::collections::BTreeMap;
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct HttpExchange {
+    pub verb: String,
+    pub path: String,
+    pub headers: BTreeMap<String, String>,
+    pub body: Option<String>,
+    pub status: Option<u16>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct HttpExchangeCapture {
+    pub request: HttpExchange,
+    pub response: HttpExchange,
+}
+
+/// Parse a raw HTTP/1.1 request or response. The first line is the
+/// request line (`VERB path HTTP/1.1`) or status line (`HTTP/1.1 200 OK`);
+/// header lines accumulate until the first blank line, and everything
+/// after that is the body. A header line with no `:` separator is skipped
+/// rather than failing the parse, and a message with no blank-line
+/// boundary (or nothing after it) yields `body: None`.
+pub fn parse_http_message(raw: &str) -> HttpExchange {
+    let normalized = raw.replace("\r\n", "\n");
+    let lines: Vec<&str> = normalized.split('\n').collect();
+
+    let start_line_tokens: Vec<&str> = lines.first().copied().unwrap_or("").split_whitespace().collect();
+    let (verb, path, status) = if start_line_tokens.first().is_some_and(|t| t.starts_with("HTTP/")) {
+        let status = start_line_tokens.get(1).and_then(|s| s.parse::<u16>().ok());
+        (String::new(), String::new(), status)
+    } else {
+        (
+            start_line_tokens.first().copied().unwrap_or("").to_string(),
+            start_line_tokens.get(1).copied().unwrap_or("").to_string(),
+            None,
+        )
+    };
+
+    let mut headers = BTreeMap::new();
+    let mut blank_line_idx = None;
+    for (i, line) in lines.iter().enumerate().skip(1) {
+        if line.is_empty() {
+            blank_line_idx = Some(i);
+            break;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            headers.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+
+    let body = blank_line_idx
+        .map(|idx| lines[idx + 1..].join("\n"))
+        .filter(|b| !b.is_empty());
+
+    HttpExchange { verb, path, headers, body, status }
+}
+
+fn host_of(homeserver_http_url: &str) -> &str {
+    homeserver_http_url
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+}
+
+/// Capture the request/response pair for a `PUT` storage write.
+pub fn capture_write(homeserver_http_url: &str, path: &str, content: &str, result: &Result<(), String>) -> HttpExchangeCapture {
+    let request_raw = format!(
+        "PUT {path} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\n\r\n{}",
+        host_of(homeserver_http_url),
+        content.len(),
+        content,
+    );
+
+    let response_raw = match result {
+        Ok(()) => "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n".to_string(),
+        Err(e) => format!("HTTP/1.1 502 Bad Gateway\r\n\r\n{e}"),
+    };
+
+    HttpExchangeCapture {
+        request: parse_http_message(&request_raw),
+        response: parse_http_message(&response_raw),
+    }
+}
+
+/// Capture the request/response pair for a `GET` storage read.
+pub fn capture_read(homeserver_http_url: &str, path: &str, result: &Result<String, String>) -> HttpExchangeCapture {
+    let request_raw = format!("GET {path} HTTP/1.1\r\nHost: {}\r\n\r\n", host_of(homeserver_http_url));
+
+    let response_raw = match result {
+        Ok(body) => format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}", body.len(), body),
+        Err(e) => format!("HTTP/1.1 404 Not Found\r\n\r\n{e}"),
+    };
+
+    HttpExchangeCapture {
+        request: parse_http_message(&request_raw),
+        response: parse_http_message(&response_raw),
+    }
+}
@@ -3,16 +3,205 @@ mod testnet;
 mod api;
 mod scenario;
 mod force_layout;
+mod dht_trace;
+mod snapshot;
+mod task_manager;
+mod routing;
+mod throughput;
+mod network_snapshot;
+mod ui_prefs;
+mod search_index;
+mod fuzzy;
+mod session_export;
+mod directory_tree;
+mod alerts;
+mod http_exchange;
+mod control_plane;
 
 use dioxus::prelude::*;
 use components::{Topbar, NetworkVisualization, ContextSidebar, EventLogEntry, EventType};
-use components::network_visualization::{Node, Homeserver, Client, Edge, NodeStatus, ConnectivityStatus, StorageStats, EdgeType};
+use components::network_visualization::{Node, Homeserver, Client, Edge, NodeStatus, ConnectivityStatus, StorageStats, EdgeType, BandwidthHistory, NodeGroup};
 use testnet::TestnetManager;
 use std::sync::{Arc, Mutex};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use pubky::Keypair;
 use chrono::Local;
 use force_layout::calculate_initial_position;
+use task_manager::{TaskCategory, TaskManager, TaskOutcome, TaskState};
+use routing::{NodeId, PeerSource, RoutingTable};
+use throughput::ThroughputTracker;
+use tokio_util::sync::CancellationToken;
+use control_plane::{ControlCommand, ControlEvent, ControlTransport, InProcessTransport};
+
+/// Push an entry to the event log, recording a task's lifecycle
+/// transition so the UI reflects true in-flight operations.
+fn log_task_state(
+    mut log: Signal<Vec<EventLogEntry>>,
+    mut counter: Signal<usize>,
+    name: &str,
+    state: TaskState,
+) {
+    let (message, event_type) = match state {
+        TaskState::Running => (format!("▶ {}", name), EventType::Info),
+        TaskState::Completed => (format!("✓ {} done", name), EventType::Success),
+        TaskState::Failed => (format!("✗ {} failed", name), EventType::Error),
+        TaskState::Cancelled => (format!("⊘ {} cancelled", name), EventType::Error),
+    };
+
+    let id = counter();
+    counter.set(id + 1);
+    let timestamp = Local::now().format("%H:%M:%S%.3f").to_string();
+    log.write().push(EventLogEntry { id, timestamp, message, event_type });
+}
+
+/// Register one homeserver's control-plane task: it owns the far end of a
+/// fresh `InProcessTransport` pair (the near end is stashed in
+/// `control_channels` for `start_node`/`stop_node` to send commands
+/// through), applies `Start`/`Stop` as they arrive by transitioning
+/// `NodeStatus` (a `Start` goes to `Starting`, then to `Running` or `Error`
+/// by a connectivity probe, where `add_homeserver`'s node-creation task
+/// goes straight from `Starting` to `Running`), and
+/// otherwise polls connectivity/storage roughly once a second, writing
+/// results into `nodes` and echoing every transition as a `ControlEvent`
+/// for anything reading this homeserver's transport directly.
+fn spawn_control_plane(
+    homeserver_id: String,
+    http_url: String,
+    manager: Arc<Mutex<TestnetManager>>,
+    tasks: TaskManager,
+    mut nodes: Signal<Vec<Node>>,
+    control_channels: Signal<Arc<Mutex<HashMap<String, Box<dyn ControlTransport>>>>>,
+) {
+    let (transport, mut commands, events) = InProcessTransport::pair();
+    if let Ok(mut channels) = control_channels().lock() {
+        channels.insert(homeserver_id.clone(), Box::new(transport));
+    }
+
+    let task_name = format!("control-plane {homeserver_id}");
+    tasks.spawn(task_name, TaskCategory::Connectivity, move |token| async move {
+        loop {
+            if token.is_cancelled() {
+                return TaskOutcome::Completed;
+            }
+
+            while let Ok(command) = commands.try_recv() {
+                let status = match command {
+                    ControlCommand::Stop => NodeStatus::Stopped,
+                    ControlCommand::Start => {
+                        set_homeserver_status(nodes, &homeserver_id, NodeStatus::Starting);
+                        let _ = events.send(ControlEvent::Status(NodeStatus::Starting));
+
+                        tokio::time::sleep(tokio::time::Duration::from_millis(400)).await;
+                        let reachable = if let Ok(mgr) = manager.lock() {
+                            mgr.probe_connectivity(&http_url).await.reachable
+                        } else {
+                            false
+                        };
+                        if reachable {
+                            NodeStatus::Running
+                        } else {
+                            NodeStatus::Error
+                        }
+                    }
+                };
+                set_homeserver_status(nodes, &homeserver_id, status.clone());
+                let _ = events.send(ControlEvent::Status(status));
+            }
+
+            tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+            if token.is_cancelled() {
+                return TaskOutcome::Completed;
+            }
+
+            let is_running = nodes
+                .read()
+                .iter()
+                .any(|n| matches!(n, Node::Homeserver(h) if h.id == homeserver_id && h.status == NodeStatus::Running));
+            if !is_running {
+                continue;
+            }
+
+            let probe = if let Ok(mgr) = manager.lock() {
+                Some(mgr.probe_connectivity(&http_url).await)
+            } else {
+                None
+            };
+            if let Some(probe) = probe {
+                let connectivity = if probe.reachable {
+                    ConnectivityStatus::DirectlyReachable { latency_ms: probe.latency_ms }
+                } else {
+                    ConnectivityStatus::Unreachable {
+                        reason: probe.error.unwrap_or_else(|| "unreachable".to_string()),
+                    }
+                };
+                let mut nodes_write = nodes.write();
+                for node in nodes_write.iter_mut() {
+                    if let Node::Homeserver(h) = node {
+                        if h.id == homeserver_id {
+                            h.connectivity_status = connectivity.clone();
+                        }
+                    }
+                }
+                drop(nodes_write);
+                let _ = events.send(ControlEvent::Connectivity(connectivity));
+            }
+
+            let stats = if let Ok(mgr) = manager.lock() { mgr.storage_stats(&homeserver_id) } else { None };
+            if let Some(stats) = stats {
+                let mut nodes_write = nodes.write();
+                for node in nodes_write.iter_mut() {
+                    if let Node::Homeserver(h) = node {
+                        if h.id == homeserver_id {
+                            h.storage_stats = Some(stats.clone());
+                        }
+                    }
+                }
+                drop(nodes_write);
+                let _ = events.send(ControlEvent::Storage(stats));
+            }
+        }
+    });
+}
+
+/// Set a homeserver's `NodeStatus` by id, if it's still present.
+fn set_homeserver_status(mut nodes: Signal<Vec<Node>>, homeserver_id: &str, status: NodeStatus) {
+    let mut nodes_write = nodes.write();
+    for node in nodes_write.iter_mut() {
+        if let Node::Homeserver(h) = node {
+            if h.id == homeserver_id {
+                h.status = status;
+                break;
+            }
+        }
+    }
+}
+
+/// Feeds the Kademlia routing-table refresh from the simulated network's
+/// own node list: every other known node is a potential neighbor, and a
+/// node answers PING iff it's currently `Running`. This testnet has no
+/// real peer-to-peer gossip, so the full node list doubles as each node's
+/// simulated view of the network (the same mocking `test_connectivity`
+/// already relies on for its liveness result).
+struct GraphPeerSource {
+    nodes: Vec<Node>,
+}
+
+impl PeerSource for GraphPeerSource {
+    fn neighbors_of(&self, peer: NodeId) -> Vec<(NodeId, String)> {
+        self.nodes
+            .iter()
+            .map(|n| (NodeId::from_public_key(n.id()), n.id().to_string()))
+            .filter(|(id, _)| *id != peer)
+            .collect()
+    }
+
+    fn ping(&self, peer: NodeId) -> bool {
+        self.nodes.iter().any(|n| {
+            NodeId::from_public_key(n.id()) == peer && matches!(n.status(), NodeStatus::Running)
+        })
+    }
+}
+
 fn main() {
     // Initialize tracing
     tracing_subscriber::fmt::init();
@@ -41,6 +230,11 @@ fn App() -> Element {
     let mut is_creating_homeserver = use_signal(|| false);
     let mut is_creating_client = use_signal(|| false);
 
+    // Auto-topology: when enabled, a periodic tick keeps every client near
+    // `AUTO_TOPOLOGY_TARGET_PEERS` connections instead of requiring a manual
+    // `connect_client` per link.
+    let mut auto_topology_enabled = use_signal(|| false);
+
     // Store client keypairs (can't be cloned/stored in Node struct)
     let client_keypairs: Signal<Arc<Mutex<HashMap<String, Keypair>>>> =
         use_signal(|| Arc::new(Mutex::new(HashMap::new())));
@@ -53,22 +247,140 @@ fn App() -> Element {
     let testnet_manager: Signal<Arc<Mutex<TestnetManager>>> =
         use_signal(|| Arc::new(Mutex::new(TestnetManager::new())));
 
+    // Control-plane transport for each homeserver's long-lived background
+    // task (see `control_plane.rs`), keyed by node id. `Start`/`Stop`
+    // handlers send commands through here. The task writes its results
+    // straight into `nodes`; the `ControlEvent`s it sends back on this
+    // transport are not drained anywhere in this file.
+    let control_channels: Signal<Arc<Mutex<HashMap<String, Box<dyn ControlTransport>>>>> =
+        use_signal(|| Arc::new(Mutex::new(HashMap::new())));
+
+    // Registry of in-flight background tasks (node creation, writes/reads,
+    // scenario playback, ...). Lets `toggle_network` cancel everything
+    // outstanding instead of leaving it to race a torn-down testnet.
+    let task_manager = use_signal(TaskManager::new);
+
     // Store homeserver URLs for API
     let homeserver_urls: Signal<Arc<Mutex<Vec<String>>>> =
         use_signal(|| Arc::new(Mutex::new(Vec::new())));
 
+    // Per-edge byte counters fed by the write/read paths, sampled by an
+    // independent periodic effect into each `Edge`'s `bandwidth_bps`.
+    let throughput: Signal<Arc<Mutex<ThroughputTracker>>> =
+        use_signal(|| Arc::new(Mutex::new(ThroughputTracker::new())));
+
     // Scenario state
-    let scenarios = use_signal(|| scenario::Scenario::built_in_scenarios());
+    let mut scenarios = use_signal(|| scenario::Scenario::built_in_scenarios());
     let mut selected_scenario_idx = use_signal(|| Option::<usize>::None);
     let mut is_playing_scenario = use_signal(|| false);
 
+    // Scenario recording: while active, the interactive handlers below
+    // (add_homeserver, add_client, connect_client, write_data, read_data)
+    // append the same `Action` variants `on_play_scenario` executes, so a
+    // demonstrated session can be saved and replayed like any authored
+    // scenario.
+    let mut is_recording = use_signal(|| false);
+    let mut recording_started_at = use_signal(|| Option::<std::time::Instant>::None);
+    let mut recorded_ops: Signal<Vec<scenario::Operation>> = use_signal(Vec::new);
+
     // Event log state
     let mut event_log = use_signal(|| Vec::<EventLogEntry>::new());
     let event_counter = use_signal(|| 0_usize);
 
+    // Search over the event log and node graph, shared by a single query
+    // box in ContextSidebar.
+    let mut search_query = use_signal(String::new);
+
+    // Which event severities are currently visible; all three by default.
+    let mut event_type_filter: Signal<std::collections::HashSet<EventType>> =
+        use_signal(|| EventType::ALL.into_iter().collect());
+
+    // Node index is cheap enough to rebuild whenever the node list changes
+    // rather than tracked incrementally like the event log.
+    let node_search_index = use_memo(move || {
+        let mut index = search_index::InvertedIndex::new();
+        for node in nodes().iter() {
+            let kind = match node {
+                Node::Homeserver(_) => "homeserver",
+                Node::Client(_) => "client",
+            };
+            let status = match node.status() {
+                NodeStatus::Starting => "starting",
+                NodeStatus::Running => "running",
+                NodeStatus::Stopped => "stopped",
+                NodeStatus::Error => "error",
+            };
+            let connected = match node {
+                Node::Client(c) => c.connected_homeserver.as_deref().unwrap_or(""),
+                Node::Homeserver(_) => "",
+            };
+            let text = format!("{} {} {} {} {}", node.id(), node.name(), kind, status, connected);
+            index.index(node.id().to_string(), &text);
+        }
+        index
+    });
+
+    // Event log entries matching the active severity chips and the current
+    // query (subsequence fuzzy match over `message`), ranked by descending
+    // score. `sort_by` is stable, so equal-scoring entries (including the
+    // no-query case, where every visible entry scores 0) keep the log's
+    // original timestamp order.
+    let filtered_event_log = use_memo(move || {
+        let query = search_query();
+        let query = query.trim();
+        let visible_types = event_type_filter();
+
+        let mut scored: Vec<(EventLogEntry, i32)> = event_log()
+            .into_iter()
+            .filter(|e| visible_types.contains(&e.event_type))
+            .filter_map(|e| {
+                if query.is_empty() {
+                    Some((e, 0))
+                } else {
+                    fuzzy::score(&e.message, query).map(|score| (e, score))
+                }
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.cmp(&a.1));
+        scored.into_iter().map(|(entry, _)| entry).collect::<Vec<_>>()
+    });
+    let matched_node_ids = use_memo(move || {
+        let query = search_query();
+        if query.trim().is_empty() {
+            Vec::new()
+        } else {
+            node_search_index().search(&query).into_iter().map(|(id, _)| id).collect()
+        }
+    });
+
     // Loading states for write/read operations
     let is_writing = use_signal(|| false);
     let is_reading = use_signal(|| false);
+    // Per-path loading state for the "Read All" batch action, keyed by
+    // path rather than client id since a batch only ever targets one
+    // client's directory browser at a time.
+    let reading_paths: Signal<HashSet<String>> = use_signal(HashSet::new);
+
+    // Last directory listing requested from the client panel's content
+    // browser, plus the prefix it was scoped to (needed again to build the
+    // nested tree the same way `on_list_directory` populated it).
+    let directory_entries: Signal<Vec<testnet::DirectoryEntry>> = use_signal(Vec::new);
+    let directory_prefix = use_signal(|| String::from("/pub/"));
+
+    // Anomaly alert engine (flapping, storage growth, stuck ops, homeserver
+    // errors), re-evaluated on the same cadence as bandwidth sampling below.
+    let alert_engine: Signal<Arc<Mutex<alerts::AlertEngine>>> =
+        use_signal(|| Arc::new(Mutex::new(alerts::AlertEngine::new())));
+    let alerts: Signal<Vec<alerts::Alert>> = use_signal(Vec::new);
+
+    // Most recent HTTP request/response pair captured from a client
+    // write/read, for the context sidebar's "Inspect" panel.
+    let last_http_exchange: Signal<Option<http_exchange::HttpExchangeCapture>> = use_signal(|| None);
+
+    // Group membership by node id, for the node-detail panel's Group
+    // control and the canvas's tinted group rings.
+    let node_groups: Signal<HashMap<String, NodeGroup>> = use_signal(HashMap::new);
 
     // Notification state
     let mut notification_message = use_signal(|| Option::<String>::None);
@@ -92,6 +404,23 @@ fn App() -> Element {
     let mut resize_start_y = use_signal(|| 0.0);
     let mut resize_start_width = use_signal(|| 0);
     let mut resize_start_height = use_signal(|| 0);
+    let mut sidebar_hidden = use_signal(|| false);
+
+    // Restore persisted panel sizes/collapse state from local storage once
+    // on mount (falling back to the defaults above when a key is absent).
+    use_effect(move || {
+        spawn(async move {
+            if let Some(width) = ui_prefs::load_number(ui_prefs::SIDEBAR_WIDTH_KEY).await {
+                sidebar_width.set(width as i32);
+            }
+            if let Some(height) = ui_prefs::load_number(ui_prefs::EVENTLOG_HEIGHT_KEY).await {
+                event_log_height.set(height as i32);
+            }
+            if let Some(hidden) = ui_prefs::load_bool(ui_prefs::SIDEBAR_HIDDEN_KEY).await {
+                sidebar_hidden.set(hidden);
+            }
+        });
+    });
 
     // Computed: Get selected node
     let selected_node = use_memo(move || {
@@ -102,32 +431,543 @@ fn App() -> Element {
             .cloned()
     });
 
-    // Force-directed layout simulation (runs periodically)
+    // Force-directed layout simulation (runs periodically). `layout_temperature`
+    // carries the cooled "temperature" across ticks so displacement keeps
+    // shrinking instead of resetting to full heat every 50ms; it's reheated
+    // whenever the node/edge count changes so a newly added node still gets
+    // a proper relaxation pass. Once a tick reports convergence, this
+    // effect stops writing positions; it still re-runs whenever another
+    // effect writes `nodes` (storage and bandwidth updates), but a settled
+    // layout moves nothing until the topology changes again.
+    let mut layout_temperature: Signal<Option<f64>> = use_signal(|| None);
+    let mut layout_topology: Signal<(usize, usize)> = use_signal(|| (0, 0));
     use_effect(move || {
-        if nodes.read().len() > 1 {
-            spawn(async move {
+        let node_count = nodes.read().len();
+        let edge_count = edges.read().len();
+
+        if node_count > 1 {
+            let topology = (node_count, edge_count);
+            if topology != layout_topology() {
+                layout_topology.set(topology);
+                layout_temperature.set(None);
+            }
+
+            let manager = task_manager();
+            let mut all_nodes = nodes;
+            let all_edges = edges;
+            let mut temperature = layout_temperature;
+
+            manager.spawn("layout tick", TaskCategory::Layout, move |token| async move {
                 tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+                if token.is_cancelled() {
+                    return TaskOutcome::Completed;
+                }
 
                 // Build edge list from current edges
-                let edge_list: Vec<(String, String)> = edges
+                let edge_list: Vec<(String, String)> = all_edges
                     .read()
                     .iter()
                     .map(|e| (e.from.clone(), e.to.clone()))
                     .collect();
 
-                // Create layout simulation
-                let mut layout = force_layout::ForceLayout::from_nodes(&nodes.read(), &edge_list);
+                // Create layout simulation, resuming from the last tick's
+                // cooled temperature if we have one
+                let mut layout = force_layout::ForceLayout::from_nodes(&all_nodes.read(), &edge_list);
+                if let Some(t) = temperature() {
+                    layout = layout.with_temperature(t);
+                }
 
                 // Run simulation step
                 layout.tick();
 
+                if token.is_cancelled() {
+                    return TaskOutcome::Completed;
+                }
+
+                if layout.has_converged() {
+                    // Settled: leave positions as they are and don't
+                    // reschedule, until the topology changes again.
+                    return TaskOutcome::Completed;
+                }
+                temperature.set(Some(layout.temperature));
+
                 // Update node positions
                 let positions = layout.get_positions();
                 for (node_id, new_x, new_y) in positions {
-                    if let Some(node) = nodes.write().iter_mut().find(|n| n.id() == node_id) {
+                    if let Some(node) = all_nodes.write().iter_mut().find(|n| n.id() == node_id) {
                         node.set_position(new_x, new_y);
                     }
                 }
+
+                TaskOutcome::Completed
+            });
+        }
+    });
+
+    // Kademlia routing table refresh (runs periodically, mirrors the
+    // layout tick above). Each known node gets its own `RoutingTable`,
+    // refreshed against a `GraphPeerSource` built from the current node
+    // list — the simulated network's mesh view doubles as every node's
+    // simulated FIND_NODE/PING responder. `dht_edges` holds the selected
+    // node's current k-closest peers as derived `DhtPeer` edges.
+    let mut routing_tables: Signal<Arc<Mutex<HashMap<String, RoutingTable>>>> =
+        use_signal(|| Arc::new(Mutex::new(HashMap::new())));
+    let mut dht_edges: Signal<Vec<Edge>> = use_signal(Vec::new);
+
+    use_effect(move || {
+        if nodes.read().len() > 1 {
+            let manager = task_manager();
+            let all_nodes = nodes;
+            let tables = routing_tables();
+            let mut derived_edges = dht_edges;
+            let selected = selected_node_id;
+
+            manager.spawn("routing refresh", TaskCategory::Routing, move |token| async move {
+                tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+                if token.is_cancelled() {
+                    return TaskOutcome::Completed;
+                }
+
+                let snapshot = all_nodes.read().clone();
+                let source = GraphPeerSource { nodes: snapshot.clone() };
+
+                if let Ok(mut tables) = tables.lock() {
+                    for node in &snapshot {
+                        let id = NodeId::from_public_key(node.id());
+                        let table = tables
+                            .entry(node.id().to_string())
+                            .or_insert_with(|| RoutingTable::new(id));
+                        routing::refresh(table, &source, id);
+                    }
+
+                    if let Some(selected_id) = selected() {
+                        if let Some(table) = tables.get(&selected_id) {
+                            let target = table.self_id;
+                            derived_edges.set(
+                                table
+                                    .closest(target, routing::K)
+                                    .into_iter()
+                                    .map(|peer| Edge {
+                                        from: selected_id.clone(),
+                                        to: peer.label,
+                                        edge_type: EdgeType::DhtPeer,
+                                        bandwidth_bps: None,
+                                    })
+                                    .collect(),
+                            );
+                        }
+                    } else {
+                        derived_edges.set(Vec::new());
+                    }
+                }
+
+                TaskOutcome::Completed
+            });
+        }
+    });
+
+    // Live storage stats (runs periodically, mirrors the layout tick above).
+    // `write_data` already reflects a write onto its own homeserver node
+    // immediately; this sweep keeps every homeserver's sidebar numbers in
+    // sync with `TestnetManager`'s ledger even if that direct update was
+    // ever missed (e.g. a write from the scenario player while unselected).
+    use_effect(move || {
+        if nodes.read().iter().any(|n| matches!(n, Node::Homeserver(_))) {
+            let manager = task_manager();
+            let testnet = testnet_manager();
+            let mut all_nodes = nodes;
+
+            manager.spawn("storage stats", TaskCategory::Storage, move |token| async move {
+                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                if token.is_cancelled() {
+                    return TaskOutcome::Completed;
+                }
+
+                if let Ok(mgr) = testnet.lock() {
+                    let mut nodes_write = all_nodes.write();
+                    for node in nodes_write.iter_mut() {
+                        if let Node::Homeserver(h) = node {
+                            if let Some(stats) = mgr.storage_stats(&h.id) {
+                                h.storage_stats = Some(stats);
+                            }
+                        }
+                    }
+                }
+
+                TaskOutcome::Completed
+            });
+        }
+    });
+
+    // Background health poll (runs periodically, mirrors the storage
+    // stats sweep above): automatically probes every running homeserver's
+    // `http_url` instead of requiring a manual "Test Connectivity" click,
+    // classifying latency the same way that handler does and recording a
+    // last-seen timestamp so the sidebar can show a liveness age. Only
+    // logs a transition (went up/down), not every tick, so the event log
+    // isn't flooded.
+    use_effect(move || {
+        if nodes.read().iter().any(|n| matches!(n, Node::Homeserver(_))) {
+            let manager = task_manager();
+            let testnet = testnet_manager();
+            let mut all_nodes = nodes;
+            let mut log = event_log;
+            let mut counter = event_counter;
+
+            manager.spawn("health poll", TaskCategory::Connectivity, move |token| async move {
+                tokio::time::sleep(tokio::time::Duration::from_millis(3000)).await;
+                if token.is_cancelled() {
+                    return TaskOutcome::Completed;
+                }
+
+                let targets: Vec<(String, String)> = all_nodes
+                    .read()
+                    .iter()
+                    .filter_map(|n| match n {
+                        Node::Homeserver(h) if h.status == NodeStatus::Running => {
+                            h.http_url.clone().map(|url| (h.id.clone(), url))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+
+                for (homeserver_id, url) in targets {
+                    if token.is_cancelled() {
+                        break;
+                    }
+
+                    let probe = if let Ok(mgr) = testnet.lock() {
+                        mgr.probe_connectivity(&url).await
+                    } else {
+                        break;
+                    };
+
+                    let status = if probe.reachable {
+                        if let Ok(mut mgr) = testnet.lock() {
+                            mgr.record_seen(&homeserver_id);
+                        }
+                        if probe.latency_ms <= NAT_LATENCY_THRESHOLD_MS {
+                            ConnectivityStatus::DirectlyReachable { latency_ms: probe.latency_ms }
+                        } else {
+                            ConnectivityStatus::BehindNat { latency_ms: probe.latency_ms }
+                        }
+                    } else {
+                        ConnectivityStatus::Unreachable {
+                            reason: probe.error.clone().unwrap_or_else(|| "unreachable".to_string()),
+                        }
+                    };
+                    let last_seen = testnet.lock().ok().and_then(|mgr| mgr.last_seen_secs_ago(&homeserver_id));
+
+                    let went_down_to_up = {
+                        let mut nodes_write = all_nodes.write();
+                        nodes_write.iter_mut().find_map(|n| {
+                            let Node::Homeserver(h) = n else { return None };
+                            if h.id != homeserver_id {
+                                return None;
+                            }
+                            let was_reachable = matches!(
+                                h.connectivity_status,
+                                ConnectivityStatus::DirectlyReachable { .. } | ConnectivityStatus::BehindNat { .. }
+                            );
+                            h.connectivity_status = status.clone();
+                            h.last_seen_secs_ago = last_seen;
+                            Some(was_reachable != probe.reachable)
+                        })
+                    };
+
+                    if went_down_to_up == Some(true) {
+                        let id = counter();
+                        counter.set(id + 1);
+                        let timestamp = Local::now().format("%H:%M:%S%.3f").to_string();
+                        let (message, event_type) = if probe.reachable {
+                            (format!("✓ {} is up", homeserver_id), EventType::Success)
+                        } else {
+                            (
+                                format!(
+                                    "✗ {} went down: {}",
+                                    homeserver_id,
+                                    probe.error.clone().unwrap_or_else(|| "unreachable".to_string())
+                                ),
+                                EventType::Error,
+                            )
+                        };
+                        log.write().push(EventLogEntry { id, timestamp, message, event_type });
+                    }
+                }
+
+                TaskOutcome::Completed
+            });
+        }
+    });
+
+    // Edge bandwidth sampling (runs periodically, mirrors the effects
+    // above): independent of any write/read operation, so an edge that
+    // stops carrying traffic decays back to 0 on its own once its samples
+    // age out of `ThroughputTracker`'s window rather than needing a reset.
+    use_effect(move || {
+        if edges.read().iter().any(|e| matches!(e.edge_type, EdgeType::Connection)) {
+            let manager = task_manager();
+            let meter = throughput();
+            let mut all_edges = edges;
+
+            manager.spawn("edge throughput", TaskCategory::Storage, move |token| async move {
+                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                if token.is_cancelled() {
+                    return TaskOutcome::Completed;
+                }
+
+                if let Ok(mut meter) = meter.lock() {
+                    let mut edges_write = all_edges.write();
+                    for edge in edges_write.iter_mut() {
+                        if matches!(edge.edge_type, EdgeType::Connection) {
+                            let edge_key = format!("{}-{}", edge.from, edge.to);
+                            edge.bandwidth_bps = Some(meter.rate_bps(&edge_key));
+                        }
+                    }
+                }
+
+                TaskOutcome::Completed
+            });
+        }
+    });
+
+    // Homeserver bandwidth history sampling (same cadence as edge bandwidth
+    // above): sums each homeserver's incoming edges' cumulative up/down
+    // counters and pushes one `BandwidthHistory` sample, so the sidebar's
+    // sparklines show read/write rate over time rather than a running total.
+    use_effect(move || {
+        if nodes.read().iter().any(|n| matches!(n, Node::Homeserver(_))) {
+            let manager = task_manager();
+            let meter = throughput();
+            let all_edges = edges;
+            let mut all_nodes = nodes;
+
+            manager.spawn("homeserver bandwidth", TaskCategory::Storage, move |token| async move {
+                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                if token.is_cancelled() {
+                    return TaskOutcome::Completed;
+                }
+
+                if let Ok(meter) = meter.lock() {
+                    let edge_snapshot: Vec<(String, String)> = all_edges
+                        .read()
+                        .iter()
+                        .filter(|e| matches!(e.edge_type, EdgeType::Connection))
+                        .map(|e| (e.from.clone(), e.to.clone()))
+                        .collect();
+
+                    let mut nodes_write = all_nodes.write();
+                    for node in nodes_write.iter_mut() {
+                        if let Node::Homeserver(homeserver) = node {
+                            let (mut bytes_written, mut bytes_read) = (0u64, 0u64);
+                            for (from, to) in &edge_snapshot {
+                                if to == &homeserver.id {
+                                    let edge_key = format!("{from}-{to}");
+                                    if let Some(counter) = meter.counter(&edge_key) {
+                                        bytes_written += counter.total_up;
+                                        bytes_read += counter.total_down;
+                                    }
+                                }
+                            }
+                            homeserver.bandwidth_history.push(bytes_read, bytes_written);
+                        }
+                    }
+                }
+
+                TaskOutcome::Completed
+            });
+        }
+    });
+
+    // Anomaly alert evaluation (same 500ms cadence as the bandwidth
+    // sampling above): re-runs every alert rule against the current node
+    // snapshot and republishes the deduplicated list for the sidebar banner.
+    use_effect(move || {
+        if !nodes.read().is_empty() {
+            let manager = task_manager();
+            let engine = alert_engine();
+            let all_nodes = nodes;
+            let mut alerts_signal = alerts;
+
+            manager.spawn("alert evaluation", TaskCategory::Storage, move |token| async move {
+                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                if token.is_cancelled() {
+                    return TaskOutcome::Completed;
+                }
+
+                if let Ok(mut engine) = engine.lock() {
+                    alerts_signal.set(engine.evaluate(&all_nodes()));
+                }
+
+                TaskOutcome::Completed
+            });
+        }
+    });
+
+    // Target per-client connectivity degree for auto-topology. This
+    // testnet's `Client` models a single active homeserver connection, so
+    // "maintain N peers" collapses to "stay connected to one homeserver" —
+    // the knob is kept configurable and named after the real P2P host
+    // setting (typical default ~10) it stands in for.
+    const AUTO_TOPOLOGY_TARGET_PEERS: usize = 1;
+
+    // Auto-topology (opt-in, runs periodically like the effects above):
+    // connects any under-target client to its DHT-closest reachable
+    // homeserver, and prunes edges into homeservers that have gone Error so
+    // their clients become eligible to reconnect elsewhere.
+    use_effect(move || {
+        if auto_topology_enabled() && nodes.read().len() > 1 {
+            let manager = task_manager();
+            let testnet = testnet_manager();
+            let keypairs = client_keypairs();
+            let sessions = client_sessions();
+            let mut all_nodes = nodes;
+            let mut all_edges = edges;
+            let mut log = event_log;
+            let mut counter = event_counter;
+
+            manager.spawn("auto-topology tick", TaskCategory::Connectivity, move |token| async move {
+                tokio::time::sleep(tokio::time::Duration::from_millis(750)).await;
+                if token.is_cancelled() {
+                    return TaskOutcome::Completed;
+                }
+
+                let mut log_event = |message: String, event_type: EventType| {
+                    let id = counter();
+                    counter.set(id + 1);
+                    let timestamp = Local::now().format("%H:%M:%S%.3f").to_string();
+                    log.write().push(EventLogEntry { id, timestamp, message, event_type });
+                };
+
+                // Prune edges into homeservers that have gone Error, and
+                // free up the clients on the other end to reconnect.
+                let error_homeservers: std::collections::HashSet<String> = all_nodes
+                    .read()
+                    .iter()
+                    .filter(|n| matches!(n, Node::Homeserver(h) if h.status == NodeStatus::Error))
+                    .map(|n| n.id().to_string())
+                    .collect();
+
+                if !error_homeservers.is_empty() {
+                    let mut pruned: Vec<(String, String)> = Vec::new();
+                    all_edges.write().retain(|e| {
+                        let prune = matches!(e.edge_type, EdgeType::Connection)
+                            && error_homeservers.contains(&e.to);
+                        if prune {
+                            pruned.push((e.from.clone(), e.to.clone()));
+                        }
+                        !prune
+                    });
+
+                    if !pruned.is_empty() {
+                        let mut nodes_write = all_nodes.write();
+                        for (client_id, _) in &pruned {
+                            if let Some(Node::Client(c)) = nodes_write.iter_mut().find(|n| n.id() == client_id) {
+                                c.connected_homeserver = None;
+                            }
+                        }
+                        drop(nodes_write);
+
+                        for (client_id, homeserver_id) in pruned {
+                            log_event(
+                                format!("⊘ Auto-topology pruned {} → {} (homeserver errored)", client_id, homeserver_id),
+                                EventType::Info,
+                            );
+                        }
+                    }
+                }
+
+                if token.is_cancelled() {
+                    return TaskOutcome::Completed;
+                }
+
+                // Candidate reachable homeservers: Running status, with a
+                // known public key to compute DHT distance against.
+                let homeservers: Vec<(String, String)> = all_nodes
+                    .read()
+                    .iter()
+                    .filter_map(|n| match n {
+                        Node::Homeserver(h) if h.status == NodeStatus::Running => {
+                            h.public_key.as_ref().map(|pk| (h.id.clone(), pk.clone()))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+
+                let under_target: Vec<(String, String)> = all_nodes
+                    .read()
+                    .iter()
+                    .filter_map(|n| match n {
+                        Node::Client(c)
+                            if c.status == NodeStatus::Running
+                                && c.connected_homeserver.is_none()
+                                && AUTO_TOPOLOGY_TARGET_PEERS > 0 =>
+                        {
+                            Some((c.id.clone(), c.public_key.clone()))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+
+                for (client_id, client_pubkey) in under_target {
+                    if token.is_cancelled() {
+                        return TaskOutcome::Completed;
+                    }
+
+                    let client_node_id = NodeId::from_public_key(&client_pubkey);
+                    let Some((homeserver_id, homeserver_pubkey)) = homeservers
+                        .iter()
+                        .min_by_key(|(_, pk)| client_node_id.distance(&NodeId::from_public_key(pk)))
+                        .cloned()
+                    else {
+                        break; // no reachable homeservers to connect to yet
+                    };
+
+                    let keypair = {
+                        if let Ok(kp_map) = keypairs.lock() {
+                            kp_map.get(&client_id).cloned()
+                        } else {
+                            None
+                        }
+                    };
+                    let Some(keypair) = keypair else { continue };
+
+                    if let Ok(mgr) = manager.lock() {
+                        match mgr.connect_client(&keypair, &homeserver_pubkey).await {
+                            Ok(session) => {
+                                if let Ok(mut sess_map) = sessions.lock() {
+                                    sess_map.insert(client_id.clone(), session);
+                                }
+
+                                let mut nodes_write = all_nodes.write();
+                                if let Some(Node::Client(c)) = nodes_write.iter_mut().find(|n| n.id() == client_id) {
+                                    c.connected_homeserver = Some(homeserver_id.clone());
+                                }
+                                drop(nodes_write);
+
+                                all_edges.write().push(Edge {
+                                    from: client_id.clone(),
+                                    to: homeserver_id.clone(),
+                                    edge_type: EdgeType::Connection,
+                                    bandwidth_bps: None,
+                                });
+
+                                log_event(
+                                    format!("⚡ Auto-topology connected {} → {} (DHT-closest)", client_id, homeserver_id),
+                                    EventType::Success,
+                                );
+                            }
+                            Err(e) => {
+                                log_event(
+                                    format!("✗ Auto-topology failed to connect {}: {}", client_id, e),
+                                    EventType::Error,
+                                );
+                            }
+                        }
+                    }
+                }
+
+                TaskOutcome::Completed
             });
         }
     });
@@ -136,29 +976,55 @@ fn App() -> Element {
     let toggle_network = move |_| {
         let running = is_network_running();
         let manager = testnet_manager();
+        let tasks = task_manager();
+        let log = event_log;
+        let counter = event_counter;
 
         if running {
-            // Stop network
-            is_network_running.set(false);
-            nodes.set(Vec::new());
-            edges.set(Vec::new());
-            selected_node_id.set(None);
-
-            // Clear homeserver URLs
-            if let Ok(mut urls) = homeserver_urls().lock() {
-                urls.clear();
-            }
-
-            // Stop the testnet
-            spawn(async move {
+            // Gracefully drain the API server before cancelling anything
+            // else or clearing state: `mgr.stop()` signals the server's
+            // shutdown token and awaits its drain, so the listener is
+            // fully released rather than hard-aborted mid-request. Only
+            // once that's done do we hard-cancel any other outstanding
+            // tasks (node creation, writes/reads, scenario playback) and
+            // tear down the UI state.
+            let mut running_flag = is_network_running;
+            let mut all_nodes = nodes;
+            let mut all_edges = edges;
+            let mut sel = selected_node_id;
+            let mut derived_edges = dht_edges;
+            let urls_handle = homeserver_urls();
+            let tables_handle = routing_tables();
+            let stop_tasks = tasks.clone();
+
+            tasks.spawn("stop testnet", TaskCategory::Network, move |_token| async move {
                 if let Ok(mut mgr) = manager.lock() {
                     mgr.stop().await;
                 }
+
+                stop_tasks.shutdown();
+                log_task_state(log, counter, "network", TaskState::Cancelled);
+
+                running_flag.set(false);
+                all_nodes.set(Vec::new());
+                all_edges.set(Vec::new());
+                sel.set(None);
+
+                if let Ok(mut urls) = urls_handle.lock() {
+                    urls.clear();
+                }
+
+                if let Ok(mut tables) = tables_handle.lock() {
+                    tables.clear();
+                }
+                derived_edges.set(Vec::new());
+
+                TaskOutcome::Completed
             });
         } else {
             // Start network (initialize DHT and relays)
             let urls_for_api = homeserver_urls();
-            spawn(async move {
+            tasks.spawn("start testnet", TaskCategory::Network, move |token| async move {
                 if let Ok(mut mgr) = manager.lock() {
                     match mgr.start().await {
                         Ok(_) => {
@@ -166,20 +1032,51 @@ fn App() -> Element {
                         }
                         Err(e) => {
                             eprintln!("Failed to start testnet: {}", e);
+                            return TaskOutcome::Failed(e.to_string());
                         }
                     }
                 }
 
-                // Start API server
+                if token.is_cancelled() {
+                    return TaskOutcome::Completed;
+                }
+
+                // Start API server with its own graceful-shutdown trigger,
+                // stored alongside the `TestnetManager` so `stop()` can
+                // signal and await it directly instead of relying on this
+                // task's hard abort handle.
+                let (events_tx, _events_rx) = tokio::sync::broadcast::channel(64);
+                let auth_token = Keypair::random().public_key().to_z32();
                 let api_state = api::ApiState {
                     homeserver_urls: urls_for_api,
+                    wiki_pages: Arc::new(Mutex::new(HashMap::new())),
+                    outbox: Arc::new(Mutex::new(HashMap::new())),
+                    public_base_url: "http://127.0.0.1:3030".to_string(),
+                    auth_token: auth_token.clone(),
+                    events: events_tx.clone(),
                 };
+                println!("/ws/events auth token: {}", auth_token);
+                let shutdown_token = CancellationToken::new();
+                let (drain_tx, drain_rx) = tokio::sync::oneshot::channel();
+                if let Ok(mut mgr) = manager.lock() {
+                    mgr.set_api_shutdown(shutdown_token.clone(), drain_rx);
+                    mgr.set_event_sender(events_tx);
+                }
 
-                tokio::spawn(async move {
-                    if let Err(e) = api::start_api_server(api_state, 3030).await {
-                        eprintln!("API server error: {}", e);
-                    }
-                });
+                let result = api::start_api_server(
+                    api_state,
+                    3030,
+                    shutdown_token.cancelled_owned(),
+                )
+                .await;
+                let _ = drain_tx.send(());
+
+                if let Err(e) = result {
+                    eprintln!("API server error: {}", e);
+                    return TaskOutcome::Failed(e.to_string());
+                }
+
+                TaskOutcome::Completed
             });
             is_network_running.set(true);
         }
@@ -204,6 +1101,16 @@ fn App() -> Element {
         let id = format!("homeserver-{}", homeserver_count + 1);
         let name = format!("Homeserver {}", homeserver_count + 1);
 
+        if is_recording() {
+            let at_seconds = recording_started_at()
+                .map(|t| t.elapsed().as_secs_f64())
+                .unwrap_or(0.0);
+            recorded_ops.write().push(scenario::Operation {
+                at_seconds,
+                action: scenario::Action::CreateHomeserver { id: id.clone() },
+            });
+        }
+
         // Calculate initial position using force-directed principles
         let (x, y) = calculate_initial_position(&nodes.read(), None);
 
@@ -217,19 +1124,27 @@ fn App() -> Element {
             public_key: None,
             connectivity_status: ConnectivityStatus::Unknown,
             storage_stats: None,
+            last_seen_secs_ago: None,
+            bandwidth_history: BandwidthHistory::new(),
             x,
             y,
         }));
 
         // Create the homeserver
         let manager = testnet_manager();
+        let tasks = task_manager();
         let id_clone = id.clone();
-        let mut all_nodes = nodes.clone();
-        let mut creating_flag = is_creating_homeserver.clone();
+        let mut all_nodes = nodes;
+        let mut creating_flag = is_creating_homeserver;
         let urls = homeserver_urls();
+        let log = event_log;
+        let counter = event_counter;
+        let task_name = format!("create {}", id_clone);
 
-        spawn(async move {
-            if let Ok(mut mgr) = manager.lock() {
+        tasks.spawn(task_name.clone(), TaskCategory::NodeCreate, move |token| async move {
+            log_task_state(log, counter, &task_name, TaskState::Running);
+
+            let outcome = if let Ok(mut mgr) = manager.lock() {
                 match mgr.create_homeserver().await {
                     Ok(info) => {
                         // Add URL to shared state for API
@@ -237,39 +1152,70 @@ fn App() -> Element {
                             urls_list.push(info.http_url.clone());
                         }
 
-                        // Update the homeserver with actual info
-                        let mut nodes_write = all_nodes.write();
-                        for node in nodes_write.iter_mut() {
-                            if let Node::Homeserver(h) = node {
-                                if h.id == id_clone {
-                                    h.port = info.port;
-                                    h.http_url = Some(info.http_url.clone());
-                                    h.public_key = Some(info.public_key);
-                                    h.status = NodeStatus::Running;
-                                    break;
+                        if token.is_cancelled() {
+                            TaskOutcome::Completed
+                        } else {
+                            // Update the homeserver with actual info
+                            let mut nodes_write = all_nodes.write();
+                            for node in nodes_write.iter_mut() {
+                                if let Node::Homeserver(h) = node {
+                                    if h.id == id_clone {
+                                        h.port = info.port;
+                                        h.http_url = Some(info.http_url.clone());
+                                        h.public_key = Some(info.public_key);
+                                        h.status = NodeStatus::Running;
+                                        break;
+                                    }
                                 }
                             }
+                            drop(nodes_write);
+
+                            spawn_control_plane(
+                                id_clone.clone(),
+                                info.http_url.clone(),
+                                manager.clone(),
+                                tasks.clone(),
+                                all_nodes,
+                                control_channels,
+                            );
+
+                            println!("Homeserver created: {} on port {}", info.http_url, info.port);
+                            TaskOutcome::Completed
                         }
-                        println!("Homeserver created: {} on port {}", info.http_url, info.port);
                     }
                     Err(e) => {
                         eprintln!("Failed to create homeserver: {}", e);
-                        // Update status to Error
-                        let mut nodes_write = all_nodes.write();
-                        for node in nodes_write.iter_mut() {
-                            if let Node::Homeserver(h) = node {
-                                if h.id == id_clone {
-                                    h.status = NodeStatus::Error;
-                                    break;
+                        if !token.is_cancelled() {
+                            // Update status to Error
+                            let mut nodes_write = all_nodes.write();
+                            for node in nodes_write.iter_mut() {
+                                if let Node::Homeserver(h) = node {
+                                    if h.id == id_clone {
+                                        h.status = NodeStatus::Error;
+                                        break;
+                                    }
                                 }
                             }
                         }
+                        TaskOutcome::Failed(e.to_string())
                     }
                 }
-            }
+            } else {
+                TaskOutcome::Failed("testnet manager lock poisoned".to_string())
+            };
 
             // Clear the creating flag
             creating_flag.set(false);
+            log_task_state(
+                log,
+                counter,
+                &task_name,
+                match &outcome {
+                    TaskOutcome::Completed => TaskState::Completed,
+                    TaskOutcome::Failed(_) => TaskState::Failed,
+                },
+            );
+            outcome
         });
     };
 
@@ -292,6 +1238,16 @@ fn App() -> Element {
         let id = format!("client-{}", client_count + 1);
         let name = format!("Client {}", client_count + 1);
 
+        if is_recording() {
+            let at_seconds = recording_started_at()
+                .map(|t| t.elapsed().as_secs_f64())
+                .unwrap_or(0.0);
+            recorded_ops.write().push(scenario::Operation {
+                at_seconds,
+                action: scenario::Action::CreateClient { id: id.clone() },
+            });
+        }
+
         // Calculate initial position using force-directed principles
         let (x, y) = calculate_initial_position(&nodes.read(), None);
 
@@ -308,13 +1264,19 @@ fn App() -> Element {
 
         // Create the client
         let manager = testnet_manager();
+        let tasks = task_manager();
         let id_clone = id.clone();
-        let mut all_nodes = nodes.clone();
+        let mut all_nodes = nodes;
         let keypairs = client_keypairs();
-        let mut creating_flag = is_creating_client.clone();
+        let mut creating_flag = is_creating_client;
+        let log = event_log;
+        let counter = event_counter;
+        let task_name = format!("create {}", id_clone);
 
-        spawn(async move {
-            if let Ok(mut mgr) = manager.lock() {
+        tasks.spawn(task_name.clone(), TaskCategory::NodeCreate, move |token| async move {
+            log_task_state(log, counter, &task_name, TaskState::Running);
+
+            let outcome = if let Ok(mut mgr) = manager.lock() {
                 match mgr.create_client().await {
                     Ok(info) => {
                         // Store the keypair
@@ -322,37 +1284,57 @@ fn App() -> Element {
                             kp_map.insert(id_clone.clone(), info.keypair);
                         }
 
-                        // Update the client with actual info
-                        let mut nodes_write = all_nodes.write();
-                        for node in nodes_write.iter_mut() {
-                            if let Node::Client(c) = node {
-                                if c.id == id_clone {
-                                    c.public_key = info.public_key;
-                                    c.status = NodeStatus::Running;
-                                    break;
+                        if token.is_cancelled() {
+                            TaskOutcome::Completed
+                        } else {
+                            // Update the client with actual info
+                            let mut nodes_write = all_nodes.write();
+                            for node in nodes_write.iter_mut() {
+                                if let Node::Client(c) = node {
+                                    if c.id == id_clone {
+                                        c.public_key = info.public_key;
+                                        c.status = NodeStatus::Running;
+                                        break;
+                                    }
                                 }
                             }
+                            println!("Client created");
+                            TaskOutcome::Completed
                         }
-                        println!("Client created");
                     }
                     Err(e) => {
                         eprintln!("Failed to create client: {}", e);
-                        // Update status to Error
-                        let mut nodes_write = all_nodes.write();
-                        for node in nodes_write.iter_mut() {
-                            if let Node::Client(c) = node {
-                                if c.id == id_clone {
-                                    c.status = NodeStatus::Error;
-                                    break;
+                        if !token.is_cancelled() {
+                            // Update status to Error
+                            let mut nodes_write = all_nodes.write();
+                            for node in nodes_write.iter_mut() {
+                                if let Node::Client(c) = node {
+                                    if c.id == id_clone {
+                                        c.status = NodeStatus::Error;
+                                        break;
+                                    }
                                 }
                             }
                         }
+                        TaskOutcome::Failed(e.to_string())
                     }
                 }
-            }
+            } else {
+                TaskOutcome::Failed("testnet manager lock poisoned".to_string())
+            };
 
             // Clear the creating flag
             creating_flag.set(false);
+            log_task_state(
+                log,
+                counter,
+                &task_name,
+                match &outcome {
+                    TaskOutcome::Completed => TaskState::Completed,
+                    TaskOutcome::Failed(_) => TaskState::Failed,
+                },
+            );
+            outcome
         });
     };
 
@@ -372,14 +1354,25 @@ fn App() -> Element {
         }
     };
 
-    // Handler: Stop node (not supported - nodes managed by testnet)
-    let stop_node = move |_id: String| {
-        println!("Stop node not yet implemented");
+    // Handler: Stop node. Sends a `ControlCommand::Stop` through that
+    // homeserver's control-plane transport (see `control_plane.rs`); the
+    // task on the other end actually flips `NodeStatus`. A no-op for
+    // clients or a homeserver whose control plane hasn't registered yet.
+    let stop_node = move |id: String| {
+        if let Ok(mut channels) = control_channels().lock() {
+            if let Some(transport) = channels.get_mut(&id) {
+                let _ = transport.send_command(ControlCommand::Stop);
+            }
+        }
     };
 
-    // Handler: Start node (not supported - nodes managed by testnet)
-    let start_node = move |_id: String| {
-        println!("Start node not yet implemented");
+    // Handler: Start node. Same as `stop_node`, but with `ControlCommand::Start`.
+    let start_node = move |id: String| {
+        if let Ok(mut channels) = control_channels().lock() {
+            if let Some(transport) = channels.get_mut(&id) {
+                let _ = transport.send_command(ControlCommand::Start);
+            }
+        }
     };
 
     // Handler: Remove node (removes from UI only)
@@ -396,55 +1389,167 @@ fn App() -> Element {
         }
     };
 
+    // A direct local connection in this testnet answers in a handful of
+    // milliseconds; anything slower than this is classified as having gone
+    // through a relay or hole-punched path rather than a direct socket.
+    const NAT_LATENCY_THRESHOLD_MS: u64 = 50;
+
     // Handler: Test connectivity (for homeservers only)
-    let test_connectivity = move |id: String| {
-        let mut all_nodes = nodes.clone();
-        let id_clone = id.clone();
+    let test_connectivity = move |homeserver_id: String| {
+        let manager = testnet_manager();
+        let mut all_nodes = nodes;
+        let mut log = event_log;
+        let mut counter = event_counter;
+        let tasks = task_manager();
+        let task_name = format!("connectivity {}", homeserver_id);
+
+        let http_url = all_nodes.read().iter().find_map(|n| match n {
+            Node::Homeserver(h) if h.id == homeserver_id => h.http_url.clone(),
+            _ => None,
+        });
 
         // Set status to Testing
-        let mut nodes_write = all_nodes.write();
-        for node in nodes_write.iter_mut() {
-            if let Node::Homeserver(h) = node {
-                if h.id == id {
-                    h.connectivity_status = ConnectivityStatus::Testing;
-                    h.storage_stats = Some(StorageStats {
-                        total_keys: 42, // Mock data for now
-                        total_size_bytes: 1024 * 256, // 256 KB mock
-                    });
-                    break;
+        {
+            let mut nodes_write = all_nodes.write();
+            for node in nodes_write.iter_mut() {
+                if let Node::Homeserver(h) = node {
+                    if h.id == homeserver_id {
+                        h.connectivity_status = ConnectivityStatus::Testing;
+                        break;
+                    }
                 }
             }
         }
-        drop(nodes_write);
 
-        // Simulate connectivity test
-        spawn(async move {
-            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        tasks.spawn(task_name, TaskCategory::Connectivity, move |token| async move {
+            let id = counter();
+            counter.set(id + 1);
+            let timestamp = Local::now().format("%H:%M:%S%.3f").to_string();
+            log.write().push(EventLogEntry {
+                id,
+                timestamp,
+                message: format!("Probing connectivity for {}", homeserver_id),
+                event_type: EventType::Info,
+            });
+
+            let Some(url) = http_url else {
+                let id = counter();
+                counter.set(id + 1);
+                let timestamp = Local::now().format("%H:%M:%S%.3f").to_string();
+                log.write().push(EventLogEntry {
+                    id,
+                    timestamp,
+                    message: "✗ Connectivity probe failed: homeserver has no http_url".to_string(),
+                    event_type: EventType::Error,
+                });
+                return TaskOutcome::Failed("no http_url".to_string());
+            };
+
+            if token.is_cancelled() {
+                return TaskOutcome::Completed;
+            }
+
+            let probe = if let Ok(mgr) = manager.lock() {
+                mgr.probe_connectivity(&url).await
+            } else {
+                return TaskOutcome::Failed("testnet manager lock poisoned".to_string());
+            };
+
+            if token.is_cancelled() {
+                return TaskOutcome::Completed;
+            }
+
+            let status = if probe.reachable {
+                if probe.latency_ms <= NAT_LATENCY_THRESHOLD_MS {
+                    ConnectivityStatus::DirectlyReachable { latency_ms: probe.latency_ms }
+                } else {
+                    ConnectivityStatus::BehindNat { latency_ms: probe.latency_ms }
+                }
+            } else {
+                ConnectivityStatus::Unreachable {
+                    reason: probe.error.unwrap_or_else(|| "unreachable".to_string()),
+                }
+            };
 
-            // Update to Connected status (mock success)
             let mut nodes_write = all_nodes.write();
             for node in nodes_write.iter_mut() {
                 if let Node::Homeserver(h) = node {
-                    if h.id == id_clone {
-                        h.connectivity_status = ConnectivityStatus::Connected;
+                    if h.id == homeserver_id {
+                        h.connectivity_status = status.clone();
                         break;
                     }
                 }
             }
+            drop(nodes_write);
+
+            let (message, event_type) = match &status {
+                ConnectivityStatus::DirectlyReachable { latency_ms } => {
+                    (format!("✓ {} directly reachable in {} ms", homeserver_id, latency_ms), EventType::Success)
+                }
+                ConnectivityStatus::BehindNat { latency_ms } => {
+                    (format!("⚠ {} reachable via relay/NAT in {} ms", homeserver_id, latency_ms), EventType::Info)
+                }
+                ConnectivityStatus::Unreachable { reason } => {
+                    (format!("✗ {} unreachable: {}", homeserver_id, reason), EventType::Error)
+                }
+                _ => (format!("Connectivity probe for {} finished", homeserver_id), EventType::Info),
+            };
+            let log_id = counter();
+            counter.set(log_id + 1);
+            let timestamp = Local::now().format("%H:%M:%S%.3f").to_string();
+            log.write().push(EventLogEntry { id: log_id, timestamp, message, event_type });
+
+            TaskOutcome::Completed
         });
     };
 
     // Handler: Write data to homeserver
     let write_data = move |(client_id, path, content): (String, String, String)| {
+        if is_recording() {
+            let at_seconds = recording_started_at()
+                .map(|t| t.elapsed().as_secs_f64())
+                .unwrap_or(0.0);
+            recorded_ops.write().push(scenario::Operation {
+                at_seconds,
+                action: scenario::Action::WriteData {
+                    client_id: client_id.clone(),
+                    path: path.clone(),
+                    content: content.clone(),
+                },
+            });
+        }
+
         let manager = testnet_manager();
         let sessions = client_sessions();
         let mut writing_flag = is_writing;
         let mut log = event_log;
         let mut counter = event_counter;
+        let tasks = task_manager();
+        let task_name = format!("write {}:{}", client_id, path);
+        let throughput = throughput();
+        let engine = alert_engine();
+
+        // Which homeserver node this write lands on, so the live storage
+        // stats the manager tracks can be reflected back onto it.
+        let homeserver_id = nodes.read().iter().find_map(|n| match n {
+            Node::Client(c) if c.id == client_id => c.connected_homeserver.clone(),
+            _ => None,
+        });
+        let homeserver_http_url = homeserver_id.as_ref().and_then(|hs_id| {
+            nodes.read().iter().find_map(|n| match n {
+                Node::Homeserver(h) if &h.id == hs_id => h.http_url.clone(),
+                _ => None,
+            })
+        });
+        let mut all_nodes = nodes;
+        let mut http_exchange_signal = last_http_exchange;
 
-        spawn(async move {
+        tasks.spawn(task_name, TaskCategory::Storage, move |token| async move {
             // Set loading state
             writing_flag.set(true);
+            if let Ok(mut eng) = engine.lock() {
+                eng.mark_op_started(&client_id);
+            }
 
             // Log the start of the operation
             let id = counter();
@@ -466,12 +1571,46 @@ fn App() -> Element {
                 }
             };
 
-            if let Some(session) = session {
-                if let Ok(mgr) = manager.lock() {
-                    match mgr.write_to_homeserver(&session, &path, content.as_bytes()).await {
+            let outcome = if token.is_cancelled() {
+                TaskOutcome::Completed
+            } else if let Some(session) = session {
+                if let Ok(mut mgr) = manager.lock() {
+                    let node_id = homeserver_id.as_deref().unwrap_or("unknown");
+                    let write_result = mgr.write_to_homeserver(node_id, &session, &path, content.as_bytes()).await;
+                    if let Some(url) = &homeserver_http_url {
+                        let captured = write_result.as_ref().map(|_| ()).map_err(|e| e.to_string());
+                        http_exchange_signal.set(Some(http_exchange::capture_write(url, &path, &content, &captured)));
+                    }
+                    match write_result {
                         Ok(_) => {
                             println!("Successfully wrote to path: {}", path);
 
+                            // Meter the write onto its client→homeserver
+                            // edge so the periodic bandwidth sample effect
+                            // can smooth it into a rate.
+                            if let Some(hs_id) = &homeserver_id {
+                                let edge_key = format!("{}-{}", client_id, hs_id);
+                                if let Ok(mut meter) = throughput.lock() {
+                                    meter.record_up(&edge_key, content.len() as u64);
+                                }
+                            }
+
+                            // Reflect the manager's real key count/byte total
+                            // for this homeserver back onto its node.
+                            if let Some(hs_id) = &homeserver_id {
+                                if let Some(stats) = mgr.storage_stats(hs_id) {
+                                    let mut nodes_write = all_nodes.write();
+                                    for node in nodes_write.iter_mut() {
+                                        if let Node::Homeserver(h) = node {
+                                            if &h.id == hs_id {
+                                                h.storage_stats = Some(stats);
+                                                break;
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+
                             // Log success
                             let id = counter();
                             counter.set(id + 1);
@@ -482,6 +1621,7 @@ fn App() -> Element {
                                 message: format!("✓ Wrote data: {} → {}", client_id, path),
                                 event_type: EventType::Success,
                             });
+                            TaskOutcome::Completed
                         }
                         Err(e) => {
                             eprintln!("Failed to write data: {}", e);
@@ -493,11 +1633,14 @@ fn App() -> Element {
                             log.write().push(EventLogEntry {
                                 id,
                                 timestamp,
-                                message: format!("✗ Write failed: {}", e),
+                                message: format!("✗ Write failed: {} → {}: {}", client_id, path, e),
                                 event_type: EventType::Error,
                             });
+                            TaskOutcome::Failed(e.to_string())
                         }
                     }
+                } else {
+                    TaskOutcome::Failed("testnet manager lock poisoned".to_string())
                 }
             } else {
                 eprintln!("Client session not found - client must be connected first");
@@ -512,24 +1655,63 @@ fn App() -> Element {
                     message: "✗ Client not connected".to_string(),
                     event_type: EventType::Error,
                 });
-            }
+                TaskOutcome::Failed("client not connected".to_string())
+            };
 
             // Clear loading state
             writing_flag.set(false);
+            if let Ok(mut eng) = engine.lock() {
+                eng.mark_op_finished(&client_id);
+            }
+            outcome
         });
     };
 
     // Handler: Read data from homeserver
     let read_data = move |(client_id, path): (String, String)| {
+        if is_recording() {
+            let at_seconds = recording_started_at()
+                .map(|t| t.elapsed().as_secs_f64())
+                .unwrap_or(0.0);
+            recorded_ops.write().push(scenario::Operation {
+                at_seconds,
+                action: scenario::Action::ReadData {
+                    client_id: client_id.clone(),
+                    path: path.clone(),
+                },
+            });
+        }
+
         let manager = testnet_manager();
         let sessions = client_sessions();
         let mut reading_flag = is_reading;
         let mut log = event_log;
         let mut counter = event_counter;
+        let tasks = task_manager();
+        let task_name = format!("read {}:{}", client_id, path);
+        let throughput = throughput();
+        let engine = alert_engine();
+
+        // Which homeserver node this read comes from, so the read can be
+        // metered onto the right client→homeserver edge.
+        let homeserver_id = nodes.read().iter().find_map(|n| match n {
+            Node::Client(c) if c.id == client_id => c.connected_homeserver.clone(),
+            _ => None,
+        });
+        let homeserver_http_url = homeserver_id.as_ref().and_then(|hs_id| {
+            nodes.read().iter().find_map(|n| match n {
+                Node::Homeserver(h) if &h.id == hs_id => h.http_url.clone(),
+                _ => None,
+            })
+        });
+        let mut http_exchange_signal = last_http_exchange;
 
-        spawn(async move {
+        tasks.spawn(task_name, TaskCategory::Storage, move |token| async move {
             // Set loading state
             reading_flag.set(true);
+            if let Ok(mut eng) = engine.lock() {
+                eng.mark_op_started(&client_id);
+            }
 
             // Log the start of the operation
             let id = counter();
@@ -551,14 +1733,32 @@ fn App() -> Element {
                 }
             };
 
-            if let Some(session) = session {
+            let outcome = if token.is_cancelled() {
+                TaskOutcome::Completed
+            } else if let Some(session) = session {
                 if let Ok(mgr) = manager.lock() {
-                    match mgr.read_from_homeserver(&session, &path).await {
+                    let read_result = mgr.read_from_homeserver(&session, &path).await;
+                    if let Some(url) = &homeserver_http_url {
+                        let captured = read_result
+                            .as_ref()
+                            .map(|data| String::from_utf8_lossy(data).to_string())
+                            .map_err(|e| e.to_string());
+                        http_exchange_signal.set(Some(http_exchange::capture_read(url, &path, &captured)));
+                    }
+                    match read_result {
                         Ok(data) => {
                             let content = String::from_utf8_lossy(&data);
                             println!("Successfully read {} bytes from path: {}", data.len(), path);
                             println!("Content: {}", content);
 
+                            // Meter the read onto its client→homeserver edge.
+                            if let Some(hs_id) = &homeserver_id {
+                                let edge_key = format!("{}-{}", client_id, hs_id);
+                                if let Ok(mut meter) = throughput.lock() {
+                                    meter.record_down(&edge_key, data.len() as u64);
+                                }
+                            }
+
                             // Log success
                             let id = counter();
                             counter.set(id + 1);
@@ -580,6 +1780,7 @@ fn App() -> Element {
                                 message: format!("Content: {}", content),
                                 event_type: EventType::Info,
                             });
+                            TaskOutcome::Completed
                         }
                         Err(e) => {
                             eprintln!("Failed to read data: {}", e);
@@ -591,43 +1792,209 @@ fn App() -> Element {
                             log.write().push(EventLogEntry {
                                 id,
                                 timestamp,
-                                message: format!("✗ Read failed: {}", e),
+                                message: format!("✗ Read failed: {} ← {}: {}", client_id, path, e),
                                 event_type: EventType::Error,
                             });
+                            TaskOutcome::Failed(e.to_string())
+                        }
+                    }
+                } else {
+                    TaskOutcome::Failed("testnet manager lock poisoned".to_string())
+                }
+            } else {
+                eprintln!("Client session not found - client must be connected first");
+
+                // Log error
+                let id = counter();
+                counter.set(id + 1);
+                let timestamp = Local::now().format("%H:%M:%S%.3f").to_string();
+                log.write().push(EventLogEntry {
+                    id,
+                    timestamp,
+                    message: "✗ Client not connected".to_string(),
+                    event_type: EventType::Error,
+                });
+                TaskOutcome::Failed("client not connected".to_string())
+            };
+
+            // Clear loading state
+            reading_flag.set(false);
+            if let Ok(mut eng) = engine.lock() {
+                eng.mark_op_finished(&client_id);
+            }
+            outcome
+        });
+    };
+
+    // Handler: Read every listed path for a client concurrently (one
+    // independent task per path, same as every other async op in this
+    // app), tracking each path's own loading state in `reading_paths` and
+    // logging a single summary once the whole batch has settled.
+    let read_all = move |(client_id, paths): (String, Vec<String>)| {
+        let tally: Arc<Mutex<(usize, usize)>> = Arc::new(Mutex::new((0, 0)));
+        let total = paths.len();
+
+        let manager = testnet_manager();
+        let sessions = client_sessions();
+        let mut log = event_log;
+        let mut counter = event_counter;
+        let tasks = task_manager();
+        let engine = alert_engine();
+
+        for path in paths {
+            let client_id = client_id.clone();
+            let manager = manager.clone();
+            let sessions = sessions.clone();
+            let mut reading_flag = reading_paths;
+            let engine = engine.clone();
+            let tally = tally.clone();
+            let task_name = format!("read all {}:{}", client_id, path);
+
+            reading_flag.write().insert(path.clone());
+
+            tasks.spawn(task_name, TaskCategory::Storage, move |token| async move {
+                if let Ok(mut eng) = engine.lock() {
+                    eng.mark_op_started(&client_id);
+                }
+
+                let session = {
+                    if let Ok(sess_map) = sessions.lock() {
+                        sess_map.get(&client_id).cloned()
+                    } else {
+                        None
+                    }
+                };
+
+                let ok = if token.is_cancelled() {
+                    true
+                } else if let Some(session) = session {
+                    if let Ok(mgr) = manager.lock() {
+                        mgr.read_from_homeserver(&session, &path).await.is_ok()
+                    } else {
+                        false
+                    }
+                } else {
+                    false
+                };
+
+                reading_flag.write().remove(&path);
+                if let Ok(mut eng) = engine.lock() {
+                    eng.mark_op_finished(&client_id);
+                }
+
+                let (done, ok_count, err_count) = {
+                    if let Ok(mut t) = tally.lock() {
+                        if ok {
+                            t.0 += 1;
+                        } else {
+                            t.1 += 1;
                         }
+                        (t.0 + t.1 == total, t.0, t.1)
+                    } else {
+                        (false, 0, 0)
                     }
+                };
+
+                if done {
+                    let id = counter();
+                    counter.set(id + 1);
+                    let timestamp = Local::now().format("%H:%M:%S%.3f").to_string();
+                    log.write().push(EventLogEntry {
+                        id,
+                        timestamp,
+                        message: format!("Read {total} paths: {ok_count} ok, {err_count} error"),
+                        event_type: EventType::Info,
+                    });
                 }
-            } else {
-                eprintln!("Client session not found - client must be connected first");
 
-                // Log error
-                let id = counter();
-                counter.set(id + 1);
-                let timestamp = Local::now().format("%H:%M:%S%.3f").to_string();
-                log.write().push(EventLogEntry {
-                    id,
-                    timestamp,
-                    message: "✗ Client not connected".to_string(),
-                    event_type: EventType::Error,
-                });
-            }
+                if ok {
+                    TaskOutcome::Completed
+                } else {
+                    TaskOutcome::Failed("read failed".to_string())
+                }
+            });
+        }
+    };
 
-            // Clear loading state
-            reading_flag.set(false);
+    // Handler: Dismiss an alert. Suppresses it on the engine so it doesn't
+    // immediately reappear while its condition is still active, and updates
+    // the visible list right away rather than waiting for the next tick.
+    let acknowledge_alert = move |alert_id: usize| {
+        if let Ok(mut engine) = alert_engine().lock() {
+            engine.acknowledge(alert_id);
+        }
+        let mut alerts_signal = alerts;
+        alerts_signal.set(alerts_signal().into_iter().filter(|a| a.id != alert_id).collect());
+    };
+
+    // Handler: Empty the event log.
+    let clear_log = move |_| {
+        let mut log = event_log;
+        log.set(Vec::new());
+    };
+
+    // Handler: Assign a node to a (possibly brand new) group.
+    let assign_group = move |(node_id, group_id, label, color): (String, String, String, String)| {
+        let mut groups = node_groups;
+        groups.write().insert(node_id, NodeGroup { id: group_id, label, color });
+    };
+
+    // Handler: Remove a node from its group.
+    let clear_group = move |node_id: String| {
+        let mut groups = node_groups;
+        groups.write().remove(&node_id);
+    };
+
+    // Handler: List a homeserver's tracked keys under a prefix for the
+    // client panel's content browser. Purely a lookup against the
+    // manager's write ledger (no network round trip), so unlike
+    // write/read this runs synchronously instead of via a spawned task.
+    let list_directory = move |(client_id, prefix): (String, String)| {
+        let homeserver_id = nodes.read().iter().find_map(|n| match n {
+            Node::Client(c) if c.id == client_id => c.connected_homeserver.clone(),
+            _ => None,
         });
+
+        let mut entries = directory_entries;
+        let mut prefix_signal = directory_prefix;
+        prefix_signal.set(prefix.clone());
+
+        match homeserver_id {
+            Some(hs_id) => {
+                if let Ok(mgr) = testnet_manager().lock() {
+                    entries.set(mgr.list_directory(&hs_id, &prefix));
+                }
+            }
+            None => entries.set(Vec::new()),
+        }
     };
 
     // Handler: Connect client to homeserver
     let connect_client = move |(client_id, homeserver_id): (String, String)| {
+        if is_recording() {
+            let at_seconds = recording_started_at()
+                .map(|t| t.elapsed().as_secs_f64())
+                .unwrap_or(0.0);
+            recorded_ops.write().push(scenario::Operation {
+                at_seconds,
+                action: scenario::Action::ConnectClient {
+                    client_id: client_id.clone(),
+                    homeserver_id: homeserver_id.clone(),
+                },
+            });
+        }
+
         let manager = testnet_manager();
         let keypairs = client_keypairs();
         let sessions = client_sessions();
-        let mut all_nodes = nodes.clone();
-        let mut all_edges = edges.clone();
+        let mut all_nodes = nodes;
+        let mut all_edges = edges;
         let client_id_clone = client_id.clone();
         let homeserver_id_clone = homeserver_id.clone();
+        let tasks = task_manager();
+        let task_name = format!("connect {} -> {}", client_id, homeserver_id);
 
-        spawn(async move {
+        tasks.spawn(task_name, TaskCategory::Connectivity, move |token| async move {
             // Get the client's keypair
             let keypair = {
                 if let Ok(kp_map) = keypairs.lock() {
@@ -637,6 +2004,10 @@ fn App() -> Element {
                 }
             };
 
+            if token.is_cancelled() {
+                return TaskOutcome::Completed;
+            }
+
             if let Some(keypair) = keypair {
                 // Get the homeserver's public key
                 let homeserver_pubkey = {
@@ -660,6 +2031,10 @@ fn App() -> Element {
                     if let Ok(mgr) = manager.lock() {
                         match mgr.connect_client(&keypair, &pubkey).await {
                             Ok(session) => {
+                                if token.is_cancelled() {
+                                    return TaskOutcome::Completed;
+                                }
+
                                 println!("Client {} connected to homeserver {}", client_id_clone, homeserver_id_clone);
 
                                 // Store the session for reuse
@@ -684,18 +2059,25 @@ fn App() -> Element {
                                     from: client_id_clone.clone(),
                                     to: homeserver_id_clone.clone(),
                                     edge_type: EdgeType::Connection,
+                                    bandwidth_bps: None,
                                 });
+                                TaskOutcome::Completed
                             }
                             Err(e) => {
                                 eprintln!("Failed to connect client to homeserver: {}", e);
+                                TaskOutcome::Failed(e.to_string())
                             }
                         }
+                    } else {
+                        TaskOutcome::Failed("testnet manager lock poisoned".to_string())
                     }
                 } else {
                     eprintln!("Homeserver {} not found or has no public key", homeserver_id);
+                    TaskOutcome::Failed("homeserver not found".to_string())
                 }
             } else {
                 eprintln!("Client keypair not found for {}", client_id);
+                TaskOutcome::Failed("client keypair not found".to_string())
             }
         });
     };
@@ -705,6 +2087,223 @@ fn App() -> Element {
         selected_scenario_idx.set(Some(idx));
     };
 
+    // Handler: Start recording interactive actions
+    let on_start_recording = move |_| {
+        recorded_ops.set(Vec::new());
+        recording_started_at.set(Some(std::time::Instant::now()));
+        is_recording.set(true);
+    };
+
+    // Handler: Stop recording and save the captured operations as a new
+    // scenario, ready to select and replay through `on_play_scenario`.
+    let on_stop_recording = move |name: String| {
+        is_recording.set(false);
+        recording_started_at.set(None);
+
+        let operations = recorded_ops();
+        if operations.is_empty() {
+            return;
+        }
+
+        let name = if name.trim().is_empty() {
+            format!("Recorded {}", Local::now().format("%H:%M:%S"))
+        } else {
+            name
+        };
+
+        scenarios.write().push(scenario::Scenario {
+            name,
+            description: "Recorded from an interactive session".to_string(),
+            operations,
+            stop_on_failure: false,
+        });
+        recorded_ops.set(Vec::new());
+    };
+
+    // Handler: Export the selected scenario as a human-editable JSON file
+    // in the scenarios directory, so it can be hand-edited and shared.
+    let on_export_scenario = move |_| {
+        let Some(idx) = selected_scenario_idx() else {
+            notification_message.set(Some("Select a scenario to export first".to_string()));
+            return;
+        };
+        let scenario = scenarios()[idx].clone();
+        let path = scenario::Scenario::scenarios_dir().join(scenario.suggested_filename());
+
+        match scenario.to_file(&path) {
+            Ok(()) => {
+                notification_message.set(Some(format!("Exported to {}", path.display())));
+            }
+            Err(e) => {
+                notification_message.set(Some(format!("Export failed: {}", e)));
+            }
+        }
+    };
+
+    // Handler: Import a scenario JSON file by path, validating its
+    // operations before it's added to the scenario list.
+    let on_import_scenario = move |path: String| {
+        match scenario::Scenario::from_file(&path) {
+            Ok(imported) => {
+                let name = imported.name.clone();
+                scenarios.write().push(imported);
+                notification_message.set(Some(format!("Imported scenario '{}'", name)));
+            }
+            Err(e) => {
+                let id = event_counter();
+                event_counter.set(id + 1);
+                let timestamp = Local::now().format("%H:%M:%S%.3f").to_string();
+                event_log.write().push(EventLogEntry {
+                    id,
+                    timestamp,
+                    message: format!("✗ Failed to import scenario from {}: {}", path, e),
+                    event_type: EventType::Error,
+                });
+            }
+        }
+    };
+
+    // Handler: Save the current graph (node positions/status and edges) to
+    // a versioned JSON file, so a laid-out network can be restored without
+    // rebuilding it by hand. Unlike `on_export_scenario`, this captures a
+    // point-in-time snapshot of the graph rather than a timed action script.
+    let on_export_network = move |_| {
+        let snapshot = network_snapshot::NetworkSnapshot::capture(&nodes(), &edges());
+        let path = network_snapshot::NetworkSnapshot::default_path();
+
+        match snapshot.to_file(&path) {
+            Ok(()) => {
+                notification_message.set(Some(format!("Network saved to {}", path.display())));
+            }
+            Err(e) => {
+                notification_message.set(Some(format!("Save failed: {}", e)));
+            }
+        }
+    };
+
+    // Handler: Export node metadata/storage stats/connectivity and the full
+    // event log to a timestamped JSON or CSV capture, for bug reports.
+    let on_export_session = move |format: session_export::ExportFormat| {
+        let path = session_export::default_path(format);
+        match session_export::write_to_file(format, &nodes(), &event_log(), &path) {
+            Ok(()) => {
+                notification_message.set(Some(format!("Session exported to {}", path.display())));
+            }
+            Err(e) => {
+                notification_message.set(Some(format!("Export failed: {}", e)));
+            }
+        }
+    };
+
+    // Handler: Load a network snapshot JSON file by path, replacing the
+    // current `nodes`/`edges`/`selected_node_id` signals so
+    // `NetworkVisualization` re-renders the restored graph. Restored
+    // connections are visual only — clients come back without a live
+    // pubky session, so writes/reads need a fresh Connect first.
+    let on_import_network = move |path: String| {
+        match network_snapshot::NetworkSnapshot::from_file(&path) {
+            Ok(snapshot) => {
+                let (new_nodes, new_edges) = snapshot.into_nodes_and_edges();
+                nodes.set(new_nodes);
+                edges.set(new_edges);
+                selected_node_id.set(None);
+                notification_message.set(Some("Network loaded".to_string()));
+            }
+            Err(e) => {
+                let id = event_counter();
+                event_counter.set(id + 1);
+                let timestamp = Local::now().format("%H:%M:%S%.3f").to_string();
+                event_log.write().push(EventLogEntry {
+                    id,
+                    timestamp,
+                    message: format!("✗ Failed to load network from {}: {}", path, e),
+                    event_type: EventType::Error,
+                });
+            }
+        }
+    };
+
+    // Handler: Export the current graph — node positions/status/edges,
+    // group assignments, and the event log — as a single JSON capture a
+    // teammate can load back with "Import Graph". Unlike `on_export_network`
+    // (nodes and edges only, and loaded back by replacing the graph), this one
+    // bundles richer state for a merge-style import.
+    let on_export_graph = move |_| {
+        let snapshot = network_snapshot::NetworkSnapshot::capture_full(&nodes(), &edges(), &node_groups(), &event_log());
+        let path = network_snapshot::NetworkSnapshot::graph_export_path();
+
+        match snapshot.to_file(&path) {
+            Ok(()) => {
+                notification_message.set(Some(format!("Graph exported to {}", path.display())));
+            }
+            Err(e) => {
+                notification_message.set(Some(format!("Export failed: {}", e)));
+            }
+        }
+    };
+
+    // Handler: Import a previously exported graph and merge it into the
+    // current one, rather than replacing it. Node ids already present get
+    // a fresh `-copyN` suffix and their position nudged, so importing a
+    // graph that overlaps what's already open doesn't stack duplicates
+    // directly on top of their originals.
+    let on_import_graph = move |path: String| {
+        match network_snapshot::NetworkSnapshot::from_file(&path) {
+            Ok(snapshot) => {
+                let existing_ids: HashSet<String> = nodes().iter().map(|n| n.id().to_string()).collect();
+                let (new_nodes, new_edges, new_groups, new_events) = snapshot.into_parts(&existing_ids);
+                let imported = new_nodes.len();
+
+                nodes.write().extend(new_nodes);
+                edges.write().extend(new_edges);
+                node_groups.write().extend(new_groups);
+
+                let mut log = event_log;
+                let mut counter = event_counter;
+                for entry in new_events {
+                    let id = counter();
+                    counter.set(id + 1);
+                    log.write().push(EventLogEntry { id, ..entry });
+                }
+
+                notification_message.set(Some(format!("Imported {imported} node(s) from graph")));
+            }
+            Err(e) => {
+                let id = event_counter();
+                event_counter.set(id + 1);
+                let timestamp = Local::now().format("%H:%M:%S%.3f").to_string();
+                event_log.write().push(EventLogEntry {
+                    id,
+                    timestamp,
+                    message: format!("✗ Failed to import graph from {}: {}", path, e),
+                    event_type: EventType::Error,
+                });
+            }
+        }
+    };
+
+    // Handler: Actively re-probe a node for the Diagnostics section's
+    // "Refresh" button. Homeservers reuse the existing connectivity test;
+    // clients don't have an equivalent active probe yet, so refresh just
+    // logs a fresh marker that Diagnostics' event-log-derived stats will
+    // pick up.
+    let on_refresh_diagnostics = move |node_id: String| {
+        let is_homeserver = nodes().iter().any(|n| matches!(n, Node::Homeserver(h) if h.id == node_id));
+        if is_homeserver {
+            test_connectivity(node_id);
+        } else {
+            let id = event_counter();
+            event_counter.set(id + 1);
+            let timestamp = Local::now().format("%H:%M:%S%.3f").to_string();
+            event_log.write().push(EventLogEntry {
+                id,
+                timestamp,
+                message: format!("Diagnostics refreshed for {}", node_id),
+                event_type: EventType::Info,
+            });
+        }
+    };
+
     // Handler: Play scenario
     let on_play_scenario = move |_| {
         if let Some(idx) = selected_scenario_idx() {
@@ -740,6 +2339,7 @@ fn App() -> Element {
             let keypairs = client_keypairs.clone();
             let sessions = client_sessions.clone();
             let urls = homeserver_urls.clone();
+            let throughput = throughput.clone();
             let mut playing_flag = is_playing_scenario.clone();
             let mut log = event_log.clone();
             let mut counter = event_counter.clone();
@@ -749,6 +2349,13 @@ fn App() -> Element {
                 use scenario::Action;
 
                 let start_time = Instant::now();
+                let stop_on_failure = scenario.stop_on_failure;
+                let mut passed: u32 = 0;
+                let mut failed: u32 = 0;
+                let mut first_failure: Option<String> = None;
+                // Per-operation wall-clock time, so scenarios double as
+                // latency tests and not just pass/fail checks.
+                let mut op_latencies: Vec<(String, f64)> = Vec::new();
 
                 // Helper to log events
                 let mut log_event = |message: String, event_type: EventType| {
@@ -772,6 +2379,8 @@ fn App() -> Element {
                     }
 
                     println!("[@{:.1}s] Executing: {:?}", op.at_seconds, op.action);
+                    let op_label = op.action.label();
+                    let op_start = Instant::now();
 
                     match op.action {
                         Action::CreateHomeserver { id } => {
@@ -796,6 +2405,8 @@ fn App() -> Element {
                                             public_key: Some(info.public_key),
                                             connectivity_status: ConnectivityStatus::Unknown,
                                             storage_stats: None,
+                                            last_seen_secs_ago: None,
+                                            bandwidth_history: BandwidthHistory::new(),
                                             x,
                                             y,
                                         }));
@@ -891,6 +2502,7 @@ fn App() -> Element {
                                                     from: client_id.clone(),
                                                     to: homeserver_id.clone(),
                                                     edge_type: EdgeType::Connection,
+                                                    bandwidth_bps: None,
                                                 });
                                                 println!("  ✓ Connected {} to {}", client_id, homeserver_id);
                                                 log_event(format!("Connected {} → {}", client_id, homeserver_id), EventType::Success);
@@ -914,10 +2526,34 @@ fn App() -> Element {
                             };
 
                             if let Some(sess) = session {
-                                if let Ok(mgr) = manager.lock() {
-                                    match mgr.write_to_homeserver(&sess, &path, content.as_bytes()).await {
+                                let homeserver_id = all_nodes.read().iter().find_map(|n| match n {
+                                    Node::Client(c) if c.id == client_id => c.connected_homeserver.clone(),
+                                    _ => None,
+                                });
+                                if let Ok(mut mgr) = manager.lock() {
+                                    let node_id = homeserver_id.as_deref().unwrap_or("unknown");
+                                    match mgr.write_to_homeserver(node_id, &sess, &path, content.as_bytes()).await {
                                         Ok(_) => {
                                             println!("  ✓ Wrote to {}: {}", client_id, path);
+                                            if let Some(hs_id) = &homeserver_id {
+                                                let edge_key = format!("{}-{}", client_id, hs_id);
+                                                if let Ok(mut meter) = throughput.read().lock() {
+                                                    meter.record_up(&edge_key, content.len() as u64);
+                                                }
+                                            }
+                                            if let Some(hs_id) = &homeserver_id {
+                                                if let Some(stats) = mgr.storage_stats(hs_id) {
+                                                    let mut nodes_write = all_nodes.write();
+                                                    for node in nodes_write.iter_mut() {
+                                                        if let Node::Homeserver(h) = node {
+                                                            if &h.id == hs_id {
+                                                                h.storage_stats = Some(stats);
+                                                                break;
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                            }
                                             log_event(format!("Wrote data: {} → {}", client_id, path), EventType::Success);
                                         }
                                         Err(e) => {
@@ -936,6 +2572,10 @@ fn App() -> Element {
                                     None
                                 }
                             };
+                            let homeserver_id = all_nodes.read().iter().find_map(|n| match n {
+                                Node::Client(c) if c.id == client_id => c.connected_homeserver.clone(),
+                                _ => None,
+                            });
 
                             if let Some(sess) = session {
                                 if let Ok(mgr) = manager.lock() {
@@ -943,6 +2583,12 @@ fn App() -> Element {
                                         Ok(data) => {
                                             let content = String::from_utf8_lossy(&data);
                                             println!("  ✓ Read from {}: {} = {}", client_id, path, content);
+                                            if let Some(hs_id) = &homeserver_id {
+                                                let edge_key = format!("{}-{}", client_id, hs_id);
+                                                if let Ok(mut meter) = throughput.read().lock() {
+                                                    meter.record_down(&edge_key, data.len() as u64);
+                                                }
+                                            }
                                             log_event(format!("Read data: {} ← {}", client_id, path), EventType::Success);
                                         }
                                         Err(e) => {
@@ -1000,15 +2646,280 @@ fn App() -> Element {
                                 log_event(format!("Homeserver {} not found", homeserver_id), EventType::Error);
                             }
                         }
+                        Action::AssertData { client_id, path, expected_content } => {
+                            let session = {
+                                if let Ok(sess_map) = sessions.read().lock() {
+                                    sess_map.get(&client_id).cloned()
+                                } else {
+                                    None
+                                }
+                            };
+
+                            let assertion = format!("{} has '{}' at {}", client_id, expected_content, path);
+                            let result = if let Some(sess) = session {
+                                if let Ok(mgr) = manager.lock() {
+                                    match mgr.read_from_homeserver(&sess, &path).await {
+                                        Ok(data) => {
+                                            let actual = String::from_utf8_lossy(&data).to_string();
+                                            if actual == expected_content {
+                                                Ok(())
+                                            } else {
+                                                Err(format!("expected '{}', got '{}'", expected_content, actual))
+                                            }
+                                        }
+                                        Err(e) => Err(format!("read failed: {}", e)),
+                                    }
+                                } else {
+                                    Err("testnet manager lock poisoned".to_string())
+                                }
+                            } else {
+                                Err(format!("{} has no active session", client_id))
+                            };
+
+                            match result {
+                                Ok(()) => {
+                                    passed += 1;
+                                    println!("  ✓ Assert passed: {}", assertion);
+                                    log_event(format!("Assert passed: {}", assertion), EventType::Success);
+                                }
+                                Err(reason) => {
+                                    failed += 1;
+                                    let message = format!("Assert failed: {} ({})", assertion, reason);
+                                    if first_failure.is_none() {
+                                        first_failure = Some(message.clone());
+                                    }
+                                    eprintln!("  ✗ {}", message);
+                                    log_event(message, EventType::Error);
+                                }
+                            }
+                        }
+                        Action::AssertConnected { client_id, homeserver_id } => {
+                            let connected = all_nodes.read().iter().any(|n| match n {
+                                Node::Client(c) => c.id == client_id && c.connected_homeserver.as_deref() == Some(homeserver_id.as_str()),
+                                _ => false,
+                            });
+
+                            let assertion = format!("{} connected to {}", client_id, homeserver_id);
+                            if connected {
+                                passed += 1;
+                                println!("  ✓ Assert passed: {}", assertion);
+                                log_event(format!("Assert passed: {}", assertion), EventType::Success);
+                            } else {
+                                failed += 1;
+                                let message = format!("Assert failed: {}", assertion);
+                                if first_failure.is_none() {
+                                    first_failure = Some(message.clone());
+                                }
+                                eprintln!("  ✗ {}", message);
+                                log_event(message, EventType::Error);
+                            }
+                        }
+                        Action::Disconnect { client_id } => {
+                            let session = {
+                                if let Ok(mut sess_map) = sessions.read().lock() {
+                                    sess_map.remove(&client_id)
+                                } else {
+                                    None
+                                }
+                            };
+
+                            if let Some(sess) = session {
+                                if let Ok(mgr) = manager.lock() {
+                                    match mgr.disconnect_client(sess).await {
+                                        Ok(()) => {
+                                            println!("  ✓ Disconnected {}", client_id);
+                                            log_event(format!("Disconnected {}", client_id), EventType::Success);
+                                        }
+                                        Err(e) => {
+                                            eprintln!("  ✗ Disconnect failed: {}", e);
+                                            log_event(format!("Disconnect failed: {}", e), EventType::Error);
+                                        }
+                                    }
+                                }
+                            }
+
+                            for node in all_nodes.write().iter_mut() {
+                                if let Node::Client(c) = node {
+                                    if c.id == client_id {
+                                        c.connected_homeserver = None;
+                                        break;
+                                    }
+                                }
+                            }
+                            all_edges.write().retain(|e| !(e.from == client_id && matches!(e.edge_type, EdgeType::Connection)));
+                        }
+                        Action::DeleteData { client_id, path } => {
+                            let session = {
+                                if let Ok(sess_map) = sessions.read().lock() {
+                                    sess_map.get(&client_id).cloned()
+                                } else {
+                                    None
+                                }
+                            };
+                            let homeserver_id = all_nodes.read().iter().find_map(|n| match n {
+                                Node::Client(c) if c.id == client_id => c.connected_homeserver.clone(),
+                                _ => None,
+                            });
+
+                            if let Some(sess) = session {
+                                if let Ok(mut mgr) = manager.lock() {
+                                    let node_id = homeserver_id.as_deref().unwrap_or("unknown");
+                                    match mgr.delete_from_homeserver(node_id, &sess, &path).await {
+                                        Ok(()) => {
+                                            println!("  ✓ Deleted {}: {}", client_id, path);
+                                            log_event(format!("Deleted data: {} ← {}", client_id, path), EventType::Success);
+                                        }
+                                        Err(e) => {
+                                            eprintln!("  ✗ Delete failed: {}", e);
+                                            log_event(format!("Delete failed: {}", e), EventType::Error);
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        Action::Repeat { times, every_seconds, action } => {
+                            for rep in 0..times {
+                                match action.as_ref() {
+                                    Action::WriteData { client_id, path, content } => {
+                                        let session = {
+                                            if let Ok(sess_map) = sessions.read().lock() {
+                                                sess_map.get(client_id).cloned()
+                                            } else {
+                                                None
+                                            }
+                                        };
+                                        let homeserver_id = all_nodes.read().iter().find_map(|n| match n {
+                                            Node::Client(c) if &c.id == client_id => c.connected_homeserver.clone(),
+                                            _ => None,
+                                        });
+
+                                        if let Some(sess) = session {
+                                            if let Ok(mut mgr) = manager.lock() {
+                                                let node_id = homeserver_id.as_deref().unwrap_or("unknown");
+                                                match mgr.write_to_homeserver(node_id, &sess, path, content.as_bytes()).await {
+                                                    Ok(_) => {
+                                                        if let Some(hs_id) = &homeserver_id {
+                                                            let edge_key = format!("{}-{}", client_id, hs_id);
+                                                            if let Ok(mut meter) = throughput.read().lock() {
+                                                                meter.record_up(&edge_key, content.len() as u64);
+                                                            }
+                                                        }
+                                                        log_event(
+                                                            format!("[repeat {}/{}] Wrote data: {} → {}", rep + 1, times, client_id, path),
+                                                            EventType::Success,
+                                                        );
+                                                    }
+                                                    Err(e) => {
+                                                        log_event(
+                                                            format!("[repeat {}/{}] Write failed: {}", rep + 1, times, e),
+                                                            EventType::Error,
+                                                        );
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                    Action::ReadData { client_id, path } => {
+                                        let session = {
+                                            if let Ok(sess_map) = sessions.read().lock() {
+                                                sess_map.get(client_id).cloned()
+                                            } else {
+                                                None
+                                            }
+                                        };
+
+                                        if let Some(sess) = session {
+                                            if let Ok(mgr) = manager.lock() {
+                                                match mgr.read_from_homeserver(&sess, path).await {
+                                                    Ok(data) => {
+                                                        if let Some(hs_id) = all_nodes.read().iter().find_map(|n| match n {
+                                                            Node::Client(c) if &c.id == client_id => c.connected_homeserver.clone(),
+                                                            _ => None,
+                                                        }) {
+                                                            let edge_key = format!("{}-{}", client_id, hs_id);
+                                                            if let Ok(mut meter) = throughput.read().lock() {
+                                                                meter.record_down(&edge_key, data.len() as u64);
+                                                            }
+                                                        }
+                                                        log_event(
+                                                            format!("[repeat {}/{}] Read data: {} ← {}", rep + 1, times, client_id, path),
+                                                            EventType::Success,
+                                                        );
+                                                    }
+                                                    Err(e) => {
+                                                        log_event(
+                                                            format!("[repeat {}/{}] Read failed: {}", rep + 1, times, e),
+                                                            EventType::Error,
+                                                        );
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                    other => {
+                                        log_event(
+                                            format!("Repeat: unsupported inner action {:?}", other),
+                                            EventType::Error,
+                                        );
+                                        break;
+                                    }
+                                }
+
+                                if rep + 1 < times {
+                                    tokio::time::sleep(std::time::Duration::from_secs_f64(every_seconds)).await;
+                                }
+                            }
+                        }
+                    }
+
+                    let op_latency_ms = op_start.elapsed().as_secs_f64() * 1000.0;
+                    op_latencies.push((op_label, op_latency_ms));
+
+                    if stop_on_failure && failed > 0 {
+                        println!("  ⏹ Stopping scenario early: stop_on_failure is set and an assertion failed");
+                        break;
                     }
                 }
 
                 playing_flag.set(false);
-                println!("✓ Scenario '{}' complete!", scenario.name);
+
+                let avg_latency_ms = if op_latencies.is_empty() {
+                    0.0
+                } else {
+                    op_latencies.iter().map(|(_, ms)| ms).sum::<f64>() / op_latencies.len() as f64
+                };
+                let summary = format!(
+                    "Scenario {}: {} passed, {} failed ({} ops, avg {:.1}ms/op)",
+                    scenario.name,
+                    passed,
+                    failed,
+                    op_latencies.len(),
+                    avg_latency_ms
+                );
+                log_event(
+                    summary.clone(),
+                    if failed == 0 { EventType::Success } else { EventType::Error },
+                );
+
+                println!("Per-operation latency:");
+                for (label, ms) in &op_latencies {
+                    println!("  {label}: {ms:.1}ms");
+                }
+
+                if let Some(reason) = &first_failure {
+                    println!("✓ Scenario '{}' complete! {} (first failure: {})", scenario.name, summary, reason);
+                } else {
+                    println!("✓ Scenario '{}' complete! {}", scenario.name, summary);
+                }
             });
         }
     };
 
+    // Handler: Toggle auto-topology mode
+    let on_toggle_auto_topology = move |_| {
+        auto_topology_enabled.set(!auto_topology_enabled());
+    };
+
     // Handler: Reset visualization (clear nodes/clients but keep network running)
     let on_reset = move |_| {
         println!("Clearing visualization...");
@@ -1031,6 +2942,12 @@ fn App() -> Element {
             sess_map.clear();
         }
 
+        // Clear routing tables and derived DHT edges
+        if let Ok(mut tables) = routing_tables().lock() {
+            tables.clear();
+        }
+        dht_edges.set(Vec::new());
+
         // Clear event log
         event_log.set(Vec::new());
 
@@ -1095,6 +3012,15 @@ fn App() -> Element {
 
     // Global mouse up handler to stop resizing
     let on_global_mouse_up = move |_evt: MouseEvent| {
+        // Persist the size a completed resize settled on, so it survives
+        // a reload instead of resetting to the defaults.
+        if is_resizing_sidebar() {
+            ui_prefs::save_number(ui_prefs::SIDEBAR_WIDTH_KEY, sidebar_width() as f64);
+        }
+        if is_resizing_eventlog() {
+            ui_prefs::save_number(ui_prefs::EVENTLOG_HEIGHT_KEY, event_log_height() as f64);
+        }
+
         is_resizing_sidebar.set(false);
         is_resizing_eventlog.set(false);
         // Reset start positions for next resize
@@ -1125,8 +3051,41 @@ fn App() -> Element {
 
         div {
             class: "h-screen flex flex-col bg-black",
+            tabindex: "0",
+            autofocus: true,
             onmousemove: on_global_mouse_move,
             onmouseup: on_global_mouse_up,
+            // Arrow keys cycle node selection through `nodes()` in order,
+            // matching the prev/next keyboard pattern of documentation/flow
+            // UIs. Modifier keys are left alone so they don't interfere
+            // with text inputs (copy/paste, browser shortcuts, etc).
+            onkeydown: move |evt: KeyboardEvent| {
+                let mods = evt.modifiers();
+                if mods.shift() || mods.ctrl() || mods.alt() || mods.meta() {
+                    return;
+                }
+                let current_nodes = nodes();
+                if current_nodes.is_empty() {
+                    return;
+                }
+                let current_idx = selected_node_id()
+                    .as_ref()
+                    .and_then(|id| current_nodes.iter().position(|n| n.id() == id));
+                let next_idx = match evt.key() {
+                    Key::ArrowLeft | Key::ArrowUp => Some(match current_idx {
+                        Some(0) | None => current_nodes.len() - 1,
+                        Some(i) => i - 1,
+                    }),
+                    Key::ArrowRight | Key::ArrowDown => Some(match current_idx {
+                        Some(i) if i + 1 < current_nodes.len() => i + 1,
+                        _ => 0,
+                    }),
+                    _ => None,
+                };
+                if let Some(idx) = next_idx {
+                    select_node(current_nodes[idx].id().to_string());
+                }
+            },
 
             // Topbar
             Topbar {
@@ -1141,13 +3100,16 @@ fn App() -> Element {
                 on_add_client: add_client,
                 on_scenario_select: on_scenario_select,
                 on_play_scenario: on_play_scenario,
+                is_recording: is_recording(),
+                on_start_recording: on_start_recording,
+                on_stop_recording: on_stop_recording,
                 on_reset: on_reset,
-                on_import_scenario: move |_| {
-                    notification_message.set(Some("Not implemented".to_string()));
-                },
-                on_export_scenario: move |_| {
-                    notification_message.set(Some("Not implemented".to_string()));
-                },
+                on_import_scenario: on_import_scenario,
+                on_export_scenario: on_export_scenario,
+                on_export_network: on_export_network,
+                on_import_network: on_import_network,
+                auto_topology_enabled: auto_topology_enabled(),
+                on_toggle_auto_topology: on_toggle_auto_topology,
             }
 
             // Main content area
@@ -1157,31 +3119,92 @@ fn App() -> Element {
                 // Network visualization (left)
                 NetworkVisualization {
                     nodes: nodes(),
-                    edges: edges(),
+                    edges: edges().into_iter().chain(dht_edges()).collect::<Vec<_>>(),
                     selected_id: selected_node_id(),
                     on_select: select_node,
                     on_node_move: move_node,
+                    on_connect: connect_client,
+                    on_start: start_node,
+                    on_stop: stop_node,
                     is_loading_scenario: is_playing_scenario() && nodes().is_empty(),
+                    highlighted_ids: matched_node_ids(),
+                    groups: node_groups(),
                 }
 
-                // Context sidebar (right)
-                ContextSidebar {
-                    selected_node: selected_node(),
-                    all_nodes: nodes(),
-                    event_log: event_log(),
-                    is_writing: is_writing(),
-                    is_reading: is_reading(),
-                    sidebar_width: sidebar_width(),
-                    event_log_height: event_log_height(),
-                    on_stop_node: stop_node,
-                    on_start_node: start_node,
-                    on_remove_node: remove_node,
-                    on_test_connectivity: test_connectivity,
-                    on_connect_client: connect_client,
-                    on_write_data: write_data,
-                    on_read_data: read_data,
-                    on_resize_sidebar: on_resize_sidebar,
-                    on_resize_eventlog: on_resize_eventlog,
+                // Context sidebar (right), or a thin re-open handle when
+                // collapsed so NetworkVisualization can take the full width.
+                if sidebar_hidden() {
+                    div {
+                        class: "w-6 bg-black border-l border-zinc-800 flex items-start justify-center pt-2 cursor-pointer hover:bg-zinc-900 transition-colors",
+                        onclick: move |_| {
+                            sidebar_hidden.set(false);
+                            ui_prefs::save_bool(ui_prefs::SIDEBAR_HIDDEN_KEY, false);
+                        },
+                        title: "Show sidebar",
+                        span { class: "text-zinc-500 text-xs", "«" }
+                    }
+                } else {
+                    ContextSidebar {
+                        selected_node: selected_node(),
+                        all_nodes: nodes(),
+                        routing_summary: selected_node_id().and_then(|id| {
+                            routing_tables()
+                                .lock()
+                                .ok()
+                                .and_then(|tables| tables.get(&id).map(|table| table.summary()))
+                        }),
+                        event_log: filtered_event_log(),
+                        event_log_total: event_log().len(),
+                        search_query: search_query(),
+                        on_search_change: move |q| search_query.set(q),
+                        event_type_filter: event_type_filter(),
+                        on_toggle_event_type: move |event_type| {
+                            let mut types = event_type_filter();
+                            if !types.remove(&event_type) {
+                                types.insert(event_type);
+                            }
+                            event_type_filter.set(types);
+                        },
+                        is_writing: is_writing(),
+                        is_reading: is_reading(),
+                        sidebar_width: sidebar_width(),
+                        event_log_height: event_log_height(),
+                        on_stop_node: stop_node,
+                        on_start_node: start_node,
+                        on_remove_node: remove_node,
+                        on_test_connectivity: test_connectivity,
+                        on_connect_client: connect_client,
+                        on_write_data: write_data,
+                        on_read_data: read_data,
+                        directory_tree: directory_tree::build_tree(&directory_entries(), &directory_prefix()),
+                        on_list_directory: list_directory,
+                        reading_paths: reading_paths(),
+                        on_read_all: read_all,
+                        alerts: alerts(),
+                        on_acknowledge_alert: acknowledge_alert,
+                        http_exchange: last_http_exchange(),
+                        on_clear_log: clear_log,
+                        all_groups: {
+                            let mut seen = std::collections::HashSet::new();
+                            node_groups()
+                                .into_values()
+                                .filter(|g| seen.insert(g.id.clone()))
+                                .collect::<Vec<_>>()
+                        },
+                        current_group: selected_node_id().and_then(|id| node_groups().get(&id).cloned()),
+                        on_assign_group: assign_group,
+                        on_clear_group: clear_group,
+                        on_export_graph: on_export_graph,
+                        on_import_graph: on_import_graph,
+                        on_refresh_diagnostics: on_refresh_diagnostics,
+                        on_resize_sidebar: on_resize_sidebar,
+                        on_resize_eventlog: on_resize_eventlog,
+                        on_collapse: move |_| {
+                            sidebar_hidden.set(true);
+                            ui_prefs::save_bool(ui_prefs::SIDEBAR_HIDDEN_KEY, true);
+                        },
+                        on_export: on_export_session,
+                    }
                 }
             }
 
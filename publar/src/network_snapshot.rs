@@ -0,0 +1,464 @@
+//! Portable JSON save/load of the visible network topology (node
+//! positions, status, and edges). Distinct from [`crate::scenario`], which
+//! captures a timed script of actions to replay rather than a point-in-time
+//! snapshot of the graph.
+
+use crate::components::context_sidebar::{EventLogEntry, EventType};
+use crate::components::network_visualization::{
+    BandwidthHistory, Client, ConnectivityStatus, Edge, EdgeType, Homeserver, Node, NodeGroup, NodeStatus,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Bumped whenever `NodeSnapshot`/`EdgeSnapshot`'s shape changes in a way
+/// older readers can't tolerate.
+const SCHEMA_VERSION: u32 = 1;
+
+/// How far (in both x and y) a node whose id collided with an existing one
+/// is nudged on import, so a pasted copy doesn't land exactly on top of its
+/// original.
+const POSITION_OFFSET: f64 = 40.0;
+
+#[derive(Serialize, Deserialize)]
+pub struct NetworkSnapshot {
+    pub schema_version: u32,
+    pub nodes: Vec<NodeSnapshot>,
+    pub edges: Vec<EdgeSnapshot>,
+    /// Group assignments keyed by node id. Absent from files written before
+    /// this field existed, so it defaults to empty on read.
+    #[serde(default)]
+    pub groups: Vec<GroupAssignmentSnapshot>,
+    /// The captured event log. Also defaults to empty for older files.
+    #[serde(default)]
+    pub events: Vec<EventSnapshot>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GroupAssignmentSnapshot {
+    pub node_id: String,
+    pub group_id: String,
+    pub label: String,
+    pub color: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum EventTypeSnapshot {
+    Success,
+    Error,
+    Info,
+}
+
+impl From<EventType> for EventTypeSnapshot {
+    fn from(event_type: EventType) -> Self {
+        match event_type {
+            EventType::Success => EventTypeSnapshot::Success,
+            EventType::Error => EventTypeSnapshot::Error,
+            EventType::Info => EventTypeSnapshot::Info,
+        }
+    }
+}
+
+impl From<EventTypeSnapshot> for EventType {
+    fn from(event_type: EventTypeSnapshot) -> Self {
+        match event_type {
+            EventTypeSnapshot::Success => EventType::Success,
+            EventTypeSnapshot::Error => EventType::Error,
+            EventTypeSnapshot::Info => EventType::Info,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EventSnapshot {
+    pub id: usize,
+    pub timestamp: String,
+    pub event_type: EventTypeSnapshot,
+    pub message: String,
+}
+
+impl From<EventSnapshot> for EventLogEntry {
+    fn from(snapshot: EventSnapshot) -> Self {
+        Self {
+            id: snapshot.id,
+            timestamp: snapshot.timestamp,
+            message: snapshot.message,
+            event_type: snapshot.event_type.into(),
+        }
+    }
+}
+
+/// Give `id` a fresh, unused suffix if it collides with one already in
+/// `taken` (an existing node or an earlier node in the same import), and
+/// record the (possibly no-op) mapping in `remap` so edges/groups
+/// referencing the original id can follow it. Returns the id actually used
+/// and whether it had to change.
+fn dedupe_id(id: String, taken: &mut HashSet<String>, remap: &mut HashMap<String, String>) -> (String, bool) {
+    if taken.insert(id.clone()) {
+        remap.insert(id.clone(), id.clone());
+        return (id, false);
+    }
+
+    let mut n = 2;
+    let new_id = loop {
+        let candidate = format!("{id}-copy{n}");
+        if taken.insert(candidate.clone()) {
+            break candidate;
+        }
+        n += 1;
+    };
+    remap.insert(id, new_id.clone());
+    (new_id, true)
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NodeSnapshot {
+    Homeserver {
+        id: String,
+        name: String,
+        port: u16,
+        http_url: Option<String>,
+        status: NodeStatusSnapshot,
+        public_key: Option<String>,
+        x: f64,
+        y: f64,
+    },
+    Client {
+        id: String,
+        name: String,
+        public_key: String,
+        status: NodeStatusSnapshot,
+        connected_homeserver: Option<String>,
+        x: f64,
+        y: f64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeStatusSnapshot {
+    Starting,
+    Running,
+    Stopped,
+    Error,
+}
+
+impl From<&NodeStatus> for NodeStatusSnapshot {
+    fn from(status: &NodeStatus) -> Self {
+        match status {
+            NodeStatus::Starting => NodeStatusSnapshot::Starting,
+            NodeStatus::Running => NodeStatusSnapshot::Running,
+            NodeStatus::Stopped => NodeStatusSnapshot::Stopped,
+            NodeStatus::Error => NodeStatusSnapshot::Error,
+        }
+    }
+}
+
+impl From<NodeStatusSnapshot> for NodeStatus {
+    fn from(status: NodeStatusSnapshot) -> Self {
+        match status {
+            NodeStatusSnapshot::Starting => NodeStatus::Starting,
+            NodeStatusSnapshot::Running => NodeStatus::Running,
+            NodeStatusSnapshot::Stopped => NodeStatus::Stopped,
+            NodeStatusSnapshot::Error => NodeStatus::Error,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EdgeSnapshot {
+    pub from: String,
+    pub to: String,
+    pub edge_type: EdgeTypeSnapshot,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeTypeSnapshot {
+    Connection,
+    DhtPeer,
+}
+
+impl From<&EdgeType> for EdgeTypeSnapshot {
+    fn from(edge_type: &EdgeType) -> Self {
+        match edge_type {
+            EdgeType::Connection => EdgeTypeSnapshot::Connection,
+            EdgeType::DhtPeer => EdgeTypeSnapshot::DhtPeer,
+        }
+    }
+}
+
+impl From<EdgeTypeSnapshot> for EdgeType {
+    fn from(edge_type: EdgeTypeSnapshot) -> Self {
+        match edge_type {
+            EdgeTypeSnapshot::Connection => EdgeType::Connection,
+            EdgeTypeSnapshot::DhtPeer => EdgeType::DhtPeer,
+        }
+    }
+}
+
+impl NetworkSnapshot {
+    /// Capture the current graph: node positions/status and edges.
+    /// Connections are visual only — re-establishing a live pubky session
+    /// on import is out of scope, the same way a scenario replay needs a
+    /// fresh `ConnectClient` action rather than a restored socket.
+    pub fn capture(nodes: &[Node], edges: &[Edge]) -> Self {
+        let nodes = nodes
+            .iter()
+            .map(|node| match node {
+                Node::Homeserver(h) => NodeSnapshot::Homeserver {
+                    id: h.id.clone(),
+                    name: h.name.clone(),
+                    port: h.port,
+                    http_url: h.http_url.clone(),
+                    status: (&h.status).into(),
+                    public_key: h.public_key.clone(),
+                    x: h.x,
+                    y: h.y,
+                },
+                Node::Client(c) => NodeSnapshot::Client {
+                    id: c.id.clone(),
+                    name: c.name.clone(),
+                    public_key: c.public_key.clone(),
+                    status: (&c.status).into(),
+                    connected_homeserver: c.connected_homeserver.clone(),
+                    x: c.x,
+                    y: c.y,
+                },
+            })
+            .collect();
+
+        let edges = edges
+            .iter()
+            .map(|e| EdgeSnapshot {
+                from: e.from.clone(),
+                to: e.to.clone(),
+                edge_type: (&e.edge_type).into(),
+            })
+            .collect();
+
+        Self {
+            schema_version: SCHEMA_VERSION,
+            nodes,
+            edges,
+            groups: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Like [`Self::capture`], but also bundles group assignments and the
+    /// event log, for the context sidebar's "Export Graph" action — a
+    /// richer, mergeable capture rather than the topbar's nodes-and-edges
+    /// save.
+    pub fn capture_full(nodes: &[Node], edges: &[Edge], groups: &HashMap<String, NodeGroup>, events: &[EventLogEntry]) -> Self {
+        let mut snapshot = Self::capture(nodes, edges);
+
+        snapshot.groups = groups
+            .iter()
+            .map(|(node_id, g)| GroupAssignmentSnapshot {
+                node_id: node_id.clone(),
+                group_id: g.id.clone(),
+                label: g.label.clone(),
+                color: g.color.clone(),
+            })
+            .collect();
+
+        snapshot.events = events
+            .iter()
+            .map(|e| EventSnapshot {
+                id: e.id,
+                timestamp: e.timestamp.clone(),
+                event_type: e.event_type.into(),
+                message: e.message.clone(),
+            })
+            .collect();
+
+        snapshot
+    }
+
+    /// Rebuild `Node`/`Edge` values to feed back into the reactive
+    /// `nodes`/`edges` signals. Homeservers come back with
+    /// `connectivity_status: Unknown`, no storage stats, and an empty
+    /// bandwidth history — those are re-learned from the next health
+    /// poll/write rather than persisted.
+    pub fn into_nodes_and_edges(self) -> (Vec<Node>, Vec<Edge>) {
+        let (nodes, edges, _, _) = self.into_parts(&HashSet::new());
+        (nodes, edges)
+    }
+
+    /// Like [`Self::into_nodes_and_edges`], but also returns group
+    /// assignments and events, and de-duplicates against `existing_ids`:
+    /// any imported node id already in use is given a fresh `-copyN`
+    /// suffix and its position nudged by [`POSITION_OFFSET`], so merging
+    /// an export into a graph that already has some of the same nodes
+    /// doesn't stack a pasted copy directly on top of its original. Edges
+    /// and group assignments follow the same id through the rename.
+    pub fn into_parts(
+        self,
+        existing_ids: &HashSet<String>,
+    ) -> (Vec<Node>, Vec<Edge>, HashMap<String, NodeGroup>, Vec<EventLogEntry>) {
+        let mut taken = existing_ids.clone();
+        let mut remap: HashMap<String, String> = HashMap::new();
+        let mut offset_ids: HashSet<String> = HashSet::new();
+        let mut assigned_ids: Vec<String> = Vec::with_capacity(self.nodes.len());
+
+        for snapshot in &self.nodes {
+            let id = match snapshot {
+                NodeSnapshot::Homeserver { id, .. } => id,
+                NodeSnapshot::Client { id, .. } => id,
+            };
+            let (new_id, collided) = dedupe_id(id.clone(), &mut taken, &mut remap);
+            if collided {
+                offset_ids.insert(new_id.clone());
+            }
+            assigned_ids.push(new_id);
+        }
+
+        let nodes = self
+            .nodes
+            .into_iter()
+            .zip(assigned_ids)
+            .map(|(snapshot, id)| match snapshot {
+                NodeSnapshot::Homeserver {
+                    id: _,
+                    name,
+                    port,
+                    http_url,
+                    status,
+                    public_key,
+                    x,
+                    y,
+                } => {
+                    let offset = if offset_ids.contains(&id) { POSITION_OFFSET } else { 0.0 };
+                    Node::Homeserver(Homeserver {
+                        id,
+                        name,
+                        port,
+                        http_url,
+                        status: status.into(),
+                        public_key,
+                        connectivity_status: ConnectivityStatus::Unknown,
+                        storage_stats: None,
+                        last_seen_secs_ago: None,
+                        bandwidth_history: BandwidthHistory::new(),
+                        x: x + offset,
+                        y: y + offset,
+                    })
+                }
+                NodeSnapshot::Client {
+                    id: _,
+                    name,
+                    public_key,
+                    status,
+                    connected_homeserver,
+                    x,
+                    y,
+                } => {
+                    let offset = if offset_ids.contains(&id) { POSITION_OFFSET } else { 0.0 };
+                    Node::Client(Client {
+                        id,
+                        name,
+                        public_key,
+                        status: status.into(),
+                        connected_homeserver: connected_homeserver.and_then(|hs| remap.get(&hs).cloned()),
+                        x: x + offset,
+                        y: y + offset,
+                    })
+                }
+            })
+            .collect();
+
+        let edges = self
+            .edges
+            .into_iter()
+            .filter_map(|e| {
+                Some(Edge {
+                    from: remap.get(&e.from).cloned()?,
+                    to: remap.get(&e.to).cloned()?,
+                    edge_type: e.edge_type.into(),
+                    bandwidth_bps: None,
+                })
+            })
+            .collect();
+
+        let groups = self
+            .groups
+            .into_iter()
+            .filter_map(|g| {
+                let node_id = remap.get(&g.node_id).cloned()?;
+                Some((
+                    node_id,
+                    NodeGroup {
+                        id: g.group_id,
+                        label: g.label,
+                        color: g.color,
+                    },
+                ))
+            })
+            .collect();
+
+        let events = self.events.into_iter().map(EventLogEntry::from).collect();
+
+        (nodes, edges, groups, events)
+    }
+
+    /// Serialize to pretty-printed JSON for a human-editable save file.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Parse a save file back into a snapshot. An unrecognized `kind`/
+    /// `edge_type` tag (from an older or newer schema version) surfaces as
+    /// serde's normal "unknown variant" error rather than a panic, and a
+    /// mismatched `schema_version` is rejected explicitly with a readable
+    /// message.
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let snapshot: Self = serde_json::from_str(json)?;
+        if snapshot.schema_version != SCHEMA_VERSION {
+            return Err(format!(
+                "unsupported schema version {} (this build reads version {})",
+                snapshot.schema_version, SCHEMA_VERSION
+            )
+            .into());
+        }
+        Ok(snapshot)
+    }
+
+    /// Save to a JSON file.
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn std::error::Error>> {
+        let json = self.to_json()?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Load from a JSON file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let json = fs::read_to_string(path)?;
+        Self::from_json(&json)
+    }
+
+    /// Default save location (`~/.publar/network-snapshot.json`), mirroring
+    /// `Scenario::scenarios_dir()`'s convention for where this app keeps its
+    /// on-disk state.
+    pub fn default_path() -> PathBuf {
+        let home = std::env::var("HOME")
+            .or_else(|_| std::env::var("USERPROFILE"))
+            .unwrap_or_else(|_| ".".to_string());
+
+        PathBuf::from(home).join(".publar").join("network-snapshot.json")
+    }
+
+    /// Default save location for a mergeable "Export Graph" capture,
+    /// distinct from `default_path()`'s nodes-and-edges save so the two
+    /// actions don't clobber each other's file.
+    pub fn graph_export_path() -> PathBuf {
+        let home = std::env::var("HOME")
+            .or_else(|_| std::env::var("USERPROFILE"))
+            .unwrap_or_else(|_| ".".to_string());
+
+        PathBuf::from(home).join(".publar").join("graph-export.json")
+    }
+}
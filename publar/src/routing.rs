@@ -0,0 +1,273 @@
+/// Kademlia k-bucket routing table over 256-bit node ids.
+///
+/// `dht_trace` traces a single iterative FIND_NODE walk; this module
+/// models the steady-state routing table each node keeps between lookups:
+/// the set of peers it actually knows, bucketed by XOR distance, refreshed
+/// by periodic FIND_NODE-style lookups and PING/PONG liveness checks. The
+/// visualization uses it to derive "who talks to whom" edges instead of
+/// relying solely on hand-placed client/homeserver connections.
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Peers kept per bucket before the least-recently-seen one is evicted.
+pub const K: usize = 8;
+/// Un-queried peers probed in parallel per FIND_NODE round.
+pub const ALPHA: usize = 3;
+/// Bit width of a node id (matches a Mainline/pkarr z32 public key's
+/// underlying 32-byte Ed25519 key).
+pub const ID_BITS: usize = 256;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct NodeId(pub [u8; 32]);
+
+impl NodeId {
+    /// Derive an id from an opaque string (a z32 public key, or any other
+    /// peer identifier the visualization assigns). Hashed to 256 bits via
+    /// SHA-256 so ids spread evenly across the id space regardless of the
+    /// input's own distribution.
+    pub fn from_public_key(key: &str) -> Self {
+        use sha2::{Digest, Sha256};
+        let mut hasher = Sha256::new();
+        hasher.update(key.as_bytes());
+        let digest = hasher.finalize();
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&digest);
+        NodeId(bytes)
+    }
+
+    /// XOR distance to `other`, as the standard Kademlia metric.
+    pub fn distance(&self, other: &NodeId) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for i in 0..32 {
+            out[i] = self.0[i] ^ other.0[i];
+        }
+        out
+    }
+
+    /// Index of the bucket `other` belongs in relative to `self`: the
+    /// position of the most-significant set bit in the XOR distance,
+    /// counted from the most significant bit of the id (bucket 0 is the
+    /// peer that differs in the very first bit; bucket `ID_BITS - 1` is the
+    /// peer that differs only in the last bit). Returns `None` for `self`.
+    pub fn bucket_index(&self, other: &NodeId) -> Option<usize> {
+        let distance = self.distance(other);
+        for (byte_idx, byte) in distance.iter().enumerate() {
+            if *byte != 0 {
+                let bit_in_byte = byte.leading_zeros() as usize;
+                return Some(byte_idx * 8 + bit_in_byte);
+            }
+        }
+        None
+    }
+}
+
+/// A known peer's liveness, tracked via PING/PONG.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Liveness {
+    Unknown,
+    Alive,
+    Stale,
+}
+
+/// A single bucket peer, flattened for display — `Clone + PartialEq` so it
+/// can cross into a Dioxus `Props` struct.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PeerSummary {
+    pub label: String,
+    pub alive: bool,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct BucketSummary {
+    pub index: usize,
+    pub peers: Vec<PeerSummary>,
+}
+
+/// A `Clone + PartialEq` snapshot of a `RoutingTable`, suitable for a
+/// Dioxus component prop (the table itself holds no such bound, since its
+/// `NodeId`s/bytes aren't meant to be diffed for re-render purposes).
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct RoutingSummary {
+    pub buckets: Vec<BucketSummary>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Peer {
+    pub id: NodeId,
+    pub label: String,
+    pub liveness: Liveness,
+    /// Unix millis of the last time this peer answered a PING or was
+    /// otherwise seen, used for least-recently-seen eviction.
+    pub last_seen_millis: u128,
+}
+
+/// One Kademlia bucket: up to `K` peers sharing the same distance prefix
+/// length, ordered oldest-seen first so the front is the eviction
+/// candidate.
+#[derive(Clone, Debug, Default)]
+pub struct Bucket {
+    pub peers: Vec<Peer>,
+}
+
+impl Bucket {
+    /// Insert or refresh `peer`. If the bucket is full and `peer` is new,
+    /// the least-recently-seen entry is evicted to make room — matching
+    /// the standard Kademlia bucket-replacement policy (a full bucket never
+    /// grows past `K`, and refreshed peers move to the back).
+    fn insert(&mut self, peer: Peer) {
+        if let Some(existing) = self.peers.iter_mut().find(|p| p.id == peer.id) {
+            existing.liveness = peer.liveness;
+            existing.last_seen_millis = peer.last_seen_millis;
+            let updated = self.peers.remove(
+                self.peers.iter().position(|p| p.id == peer.id).unwrap(),
+            );
+            self.peers.push(updated);
+            return;
+        }
+
+        if self.peers.len() >= K {
+            self.peers.remove(0);
+        }
+        self.peers.push(peer);
+    }
+}
+
+/// A node's view of the DHT: its own id plus one bucket per possible
+/// shared-prefix length.
+pub struct RoutingTable {
+    pub self_id: NodeId,
+    buckets: Vec<Bucket>,
+}
+
+impl RoutingTable {
+    pub fn new(self_id: NodeId) -> Self {
+        Self {
+            self_id,
+            buckets: (0..ID_BITS).map(|_| Bucket::default()).collect(),
+        }
+    }
+
+    /// Record that `peer` answered a PING (or was otherwise observed),
+    /// placing it in the bucket matching its distance from `self_id`.
+    pub fn observe(&mut self, peer_id: NodeId, label: impl Into<String>, liveness: Liveness) {
+        let Some(bucket_idx) = self.self_id.bucket_index(&peer_id) else {
+            return; // a peer can't share our own id
+        };
+        self.buckets[bucket_idx].insert(Peer {
+            id: peer_id,
+            label: label.into(),
+            liveness,
+            last_seen_millis: now_millis(),
+        });
+    }
+
+    pub fn bucket(&self, index: usize) -> &Bucket {
+        &self.buckets[index]
+    }
+
+    /// Non-empty buckets, furthest-from-empty first is not guaranteed;
+    /// returned in bucket-index order for a stable, readable sidebar view.
+    pub fn non_empty_buckets(&self) -> Vec<(usize, &Bucket)> {
+        self.buckets
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| !b.peers.is_empty())
+            .collect()
+    }
+
+    pub fn all_peers(&self) -> Vec<&Peer> {
+        self.buckets.iter().flat_map(|b| &b.peers).collect()
+    }
+
+    /// A display-friendly, diffable snapshot for `ContextSidebar`.
+    pub fn summary(&self) -> RoutingSummary {
+        RoutingSummary {
+            buckets: self
+                .non_empty_buckets()
+                .into_iter()
+                .map(|(index, bucket)| BucketSummary {
+                    index,
+                    peers: bucket
+                        .peers
+                        .iter()
+                        .map(|p| PeerSummary {
+                            label: p.label.clone(),
+                            alive: p.liveness == Liveness::Alive,
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// The `count` known peers closest to `target` by XOR distance —
+    /// `RoutingTable::closest`, used both to answer a simulated FIND_NODE
+    /// and to pick which edges to highlight in the visualization.
+    pub fn closest(&self, target: NodeId, count: usize) -> Vec<Peer> {
+        let mut peers: Vec<Peer> = self.all_peers().into_iter().cloned().collect();
+        peers.sort_by(|a, b| a.id.distance(&target).cmp(&b.id.distance(&target)));
+        peers.truncate(count);
+        peers
+    }
+}
+
+/// A source of neighbor information for a FIND_NODE round, analogous to
+/// `dht_trace::LookupSource` but keyed on `NodeId` and able to report
+/// liveness for a PING.
+pub trait PeerSource {
+    fn neighbors_of(&self, peer: NodeId) -> Vec<(NodeId, String)>;
+    fn ping(&self, peer: NodeId) -> bool;
+}
+
+/// Run one periodic FIND_NODE refresh against `target` (typically the
+/// table's own id, to discover peers near ourselves): query the `ALPHA`
+/// known peers closest to `target` that haven't answered yet this round,
+/// merge their neighbor lists into the table, and PING each newly
+/// discovered peer to record its liveness. Mirrors `dht_trace::trace_lookup`
+/// but updates a persistent `RoutingTable` instead of emitting a one-shot
+/// hop list.
+pub fn refresh(table: &mut RoutingTable, source: &dyn PeerSource, target: NodeId) {
+    let mut known: Vec<NodeId> = table.all_peers().into_iter().map(|p| p.id).collect();
+    let mut queried = std::collections::HashSet::new();
+
+    for _ in 0..K {
+        known.sort_by(|a, b| a.distance(&target).cmp(&b.distance(&target)));
+        let round: Vec<NodeId> = known
+            .iter()
+            .filter(|id| !queried.contains(*id))
+            .take(ALPHA)
+            .cloned()
+            .collect();
+
+        if round.is_empty() {
+            break;
+        }
+
+        for peer_id in round {
+            queried.insert(peer_id);
+            let alive = source.ping(peer_id);
+            table.observe(
+                peer_id,
+                format!("{:02x}{:02x}..", peer_id.0[0], peer_id.0[1]),
+                if alive { Liveness::Alive } else { Liveness::Stale },
+            );
+
+            for (neighbor_id, label) in source.neighbors_of(peer_id) {
+                if neighbor_id == table.self_id {
+                    continue;
+                }
+                if !known.contains(&neighbor_id) {
+                    known.push(neighbor_id);
+                }
+                let alive = source.ping(neighbor_id);
+                table.observe(neighbor_id, label, if alive { Liveness::Alive } else { Liveness::Stale });
+            }
+        }
+    }
+}
+
+fn now_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
@@ -7,6 +7,11 @@ pub struct Scenario {
     pub name: String,
     pub description: String,
     pub operations: Vec<Operation>,
+    /// When true, the executor stops at the first failing `Action::AssertData`
+    /// / `Action::AssertConnected` instead of running the remaining
+    /// operations, so a scenario can double as a fail-fast CI check.
+    #[serde(default)]
+    pub stop_on_failure: bool,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -45,6 +50,59 @@ pub enum Action {
         /// Maximum time to wait in seconds
         timeout_seconds: f64,
     },
+    /// Read `path` back and assert it equals `expected_content`, turning the
+    /// scenario into a test case rather than just a demo script.
+    AssertData {
+        client_id: String,
+        path: String,
+        expected_content: String,
+    },
+    /// Assert that `client_id` is currently connected to `homeserver_id`.
+    AssertConnected {
+        client_id: String,
+        homeserver_id: String,
+    },
+    /// Sign the client's session out and drop its connection edge.
+    Disconnect {
+        client_id: String,
+    },
+    /// Delete `path` from the homeserver `client_id` is connected to.
+    DeleteData {
+        client_id: String,
+        path: String,
+    },
+    /// Fire the boxed `action` `times` times, `every_seconds` apart, to
+    /// generate load. Only `WriteData`/`ReadData` are supported as the
+    /// inner action — repeating a one-shot action like `CreateClient`
+    /// isn't meaningful, and nesting `Repeat` inside itself is rejected by
+    /// `validate()`.
+    Repeat {
+        times: u32,
+        every_seconds: f64,
+        action: Box<Action>,
+    },
+}
+
+impl Action {
+    /// Short human-readable label for per-operation latency reporting,
+    /// e.g. `"write_data(client1)"`.
+    pub fn label(&self) -> String {
+        match self {
+            Action::CreateHomeserver { id } => format!("create_homeserver({id})"),
+            Action::CreateClient { id } => format!("create_client({id})"),
+            Action::ConnectClient { client_id, .. } => format!("connect_client({client_id})"),
+            Action::WriteData { client_id, .. } => format!("write_data({client_id})"),
+            Action::ReadData { client_id, .. } => format!("read_data({client_id})"),
+            Action::WaitForHomeserver { homeserver_id, .. } => {
+                format!("wait_for_homeserver({homeserver_id})")
+            }
+            Action::AssertData { client_id, .. } => format!("assert_data({client_id})"),
+            Action::AssertConnected { client_id, .. } => format!("assert_connected({client_id})"),
+            Action::Disconnect { client_id } => format!("disconnect({client_id})"),
+            Action::DeleteData { client_id, .. } => format!("delete_data({client_id})"),
+            Action::Repeat { times, action, .. } => format!("repeat({}x {})", times, action.label()),
+        }
+    }
 }
 
 impl Scenario {
@@ -54,7 +112,6 @@ impl Scenario {
     }
 
     /// Save scenario to JSON file
-    #[allow(dead_code)]
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string_pretty(self)
     }
@@ -68,17 +125,107 @@ impl Scenario {
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
         let json = fs::read_to_string(path)?;
         let scenario = Self::from_json(&json)?;
+        scenario.validate()?;
         Ok(scenario)
     }
 
     /// Save scenario to a JSON file
-    #[allow(dead_code)]
     pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn std::error::Error>> {
         let json = self.to_json()?;
         fs::write(path, json)?;
         Ok(())
     }
 
+    /// Check that an imported scenario is actually playable: non-empty, in
+    /// chronological order, and every id referenced by a later operation
+    /// (`ConnectClient`/`WriteData`/`ReadData`) was created by an earlier
+    /// `CreateHomeserver`/`CreateClient`. Serde already rejects malformed
+    /// JSON/unknown `Action` variants before this runs.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.operations.is_empty() {
+            return Err("scenario has no operations".to_string());
+        }
+
+        let mut known_ids: std::collections::HashSet<&str> = std::collections::HashSet::new();
+        let mut last_at_seconds = 0.0;
+
+        for (idx, op) in self.operations.iter().enumerate() {
+            if op.at_seconds < last_at_seconds {
+                return Err(format!(
+                    "operation {idx} has at_seconds {} before the preceding operation's {}",
+                    op.at_seconds, last_at_seconds
+                ));
+            }
+            last_at_seconds = op.at_seconds;
+
+            match &op.action {
+                Action::CreateHomeserver { id } | Action::CreateClient { id } => {
+                    known_ids.insert(id);
+                }
+                Action::ConnectClient { client_id, homeserver_id } => {
+                    if !known_ids.contains(client_id.as_str()) {
+                        return Err(format!("operation {idx} connects unknown client '{client_id}'"));
+                    }
+                    if !known_ids.contains(homeserver_id.as_str()) {
+                        return Err(format!("operation {idx} connects unknown homeserver '{homeserver_id}'"));
+                    }
+                }
+                Action::WriteData { client_id, .. }
+                | Action::ReadData { client_id, .. }
+                | Action::AssertData { client_id, .. }
+                | Action::Disconnect { client_id }
+                | Action::DeleteData { client_id, .. } => {
+                    if !known_ids.contains(client_id.as_str()) {
+                        return Err(format!("operation {idx} references unknown client '{client_id}'"));
+                    }
+                }
+                Action::WaitForHomeserver { homeserver_id, .. } => {
+                    if !known_ids.contains(homeserver_id.as_str()) {
+                        return Err(format!("operation {idx} waits on unknown homeserver '{homeserver_id}'"));
+                    }
+                }
+                Action::AssertConnected { client_id, homeserver_id } => {
+                    if !known_ids.contains(client_id.as_str()) {
+                        return Err(format!("operation {idx} references unknown client '{client_id}'"));
+                    }
+                    if !known_ids.contains(homeserver_id.as_str()) {
+                        return Err(format!("operation {idx} references unknown homeserver '{homeserver_id}'"));
+                    }
+                }
+                Action::Repeat { action, .. } => match action.as_ref() {
+                    Action::WriteData { client_id, .. } | Action::ReadData { client_id, .. } => {
+                        if !known_ids.contains(client_id.as_str()) {
+                            return Err(format!(
+                                "operation {idx} repeats an action referencing unknown client '{client_id}'"
+                            ));
+                        }
+                    }
+                    _ => {
+                        return Err(format!(
+                            "operation {idx} repeats an unsupported action (only write_data/read_data can be repeated)"
+                        ));
+                    }
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Filesystem-safe file name for exporting this scenario, e.g.
+    /// `"My Scenario!"` -> `"my_scenario.json"`.
+    pub fn suggested_filename(&self) -> String {
+        let slug: String = self
+            .name
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        let slug = slug.trim_matches('_');
+        let slug = if slug.is_empty() { "scenario" } else { slug };
+        format!("{slug}.json")
+    }
+
     /// Get the scenarios directory path (~/.publar/scenarios)
     pub fn scenarios_dir() -> PathBuf {
         let home = std::env::var("HOME")
@@ -0,0 +1,64 @@
+//! A small in-memory inverted index: term -> (id -> term frequency),
+//! used by the node search in `main.rs` (the event log search scores
+//! messages with `fuzzy::score` instead). Deliberately simple — linear
+//! prefix scans over the term set rather than a trie — since the node list
+//! is small enough that this stays instant.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Split text into lowercase alphanumeric terms, e.g. `"node-3 WRITE"` ->
+/// `["node", "3", "write"]`.
+fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+pub struct InvertedIndex<Id: Clone + Eq + Hash> {
+    /// term -> id -> number of times the term occurs in that id's text.
+    postings: HashMap<String, HashMap<Id, u32>>,
+}
+
+impl<Id: Clone + Eq + Hash> InvertedIndex<Id> {
+    pub fn new() -> Self {
+        Self { postings: HashMap::new() }
+    }
+
+    /// Tokenize `text` and add its terms to the index under `id`. Calling it
+    /// again for the same `id` adds to that id's existing term counts; terms
+    /// from earlier text are not removed.
+    pub fn index(&mut self, id: Id, text: &str) {
+        for term in tokenize(text) {
+            *self.postings.entry(term).or_default().entry(id.clone()).or_insert(0) += 1;
+        }
+    }
+
+    /// Score every id matching any term in `query` (by term frequency,
+    /// summed across terms) and return them ranked highest first. A query
+    /// term matches both indexed terms equal to it and indexed terms it is
+    /// a prefix of, so "nod" matches an indexed "node-3" while still typing.
+    pub fn search(&self, query: &str) -> Vec<(Id, u32)> {
+        let terms = tokenize(query);
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let mut scores: HashMap<Id, u32> = HashMap::new();
+        for term in &terms {
+            for (indexed_term, postings) in &self.postings {
+                if indexed_term.starts_with(term.as_str()) {
+                    for (id, freq) in postings {
+                        *scores.entry(id.clone()).or_insert(0) += freq;
+                    }
+                }
+            }
+        }
+
+        let mut ranked: Vec<(Id, u32)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1));
+        ranked
+    }
+}
@@ -0,0 +1,205 @@
+//! Export the current simulation state (node metadata, storage stats,
+//! connectivity, and the event log) to a downloadable JSON capture, or just
+//! the event log to CSV —
+//! a reproducible record of a run for bug reports, analogous to a packet
+//! sniffer's capture export. Distinct from [`crate::network_snapshot`],
+//! which persists just enough of the graph (positions, edges) to restore it,
+//! not a full human-readable report of what happened.
+
+use crate::components::context_sidebar::EventLogEntry;
+use crate::components::network_visualization::{ConnectivityStatus, Node, NodeStatus};
+use chrono::Local;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Bumped whenever the exported JSON envelope's shape changes in a way
+/// older readers can't tolerate.
+const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+#[derive(Serialize)]
+struct ExportedStorageStats {
+    total_keys: usize,
+    total_size_bytes: usize,
+    available_bytes: u64,
+    capacity_bytes: u64,
+}
+
+#[derive(Serialize)]
+struct ExportedNode {
+    id: String,
+    name: String,
+    kind: &'static str,
+    status: &'static str,
+    public_key: Option<String>,
+    connectivity_status: Option<String>,
+    connected_homeserver: Option<String>,
+    storage_stats: Option<ExportedStorageStats>,
+}
+
+#[derive(Serialize)]
+struct ExportedEvent {
+    id: usize,
+    timestamp: String,
+    event_type: &'static str,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct ExportEnvelope {
+    schema: u32,
+    exported_at: String,
+    nodes: Vec<ExportedNode>,
+    events: Vec<ExportedEvent>,
+}
+
+fn node_status_label(status: &NodeStatus) -> &'static str {
+    match status {
+        NodeStatus::Starting => "starting",
+        NodeStatus::Running => "running",
+        NodeStatus::Stopped => "stopped",
+        NodeStatus::Error => "error",
+    }
+}
+
+fn connectivity_label(status: &ConnectivityStatus) -> String {
+    match status {
+        ConnectivityStatus::Unknown => "unknown".to_string(),
+        ConnectivityStatus::Testing => "testing".to_string(),
+        ConnectivityStatus::DirectlyReachable { latency_ms } => {
+            format!("directly_reachable ({latency_ms}ms)")
+        }
+        ConnectivityStatus::BehindNat { latency_ms } => format!("behind_nat ({latency_ms}ms)"),
+        ConnectivityStatus::Unreachable { reason } => format!("unreachable ({reason})"),
+    }
+}
+
+fn event_type_label(event_type: &crate::components::context_sidebar::EventType) -> &'static str {
+    use crate::components::context_sidebar::EventType;
+    match event_type {
+        EventType::Success => "success",
+        EventType::Error => "error",
+        EventType::Info => "info",
+    }
+}
+
+fn export_node(node: &Node) -> ExportedNode {
+    match node {
+        Node::Homeserver(h) => ExportedNode {
+            id: h.id.clone(),
+            name: h.name.clone(),
+            kind: "homeserver",
+            status: node_status_label(&h.status),
+            public_key: h.public_key.clone(),
+            connectivity_status: Some(connectivity_label(&h.connectivity_status)),
+            connected_homeserver: None,
+            storage_stats: h.storage_stats.as_ref().map(|stats| ExportedStorageStats {
+                total_keys: stats.total_keys,
+                total_size_bytes: stats.total_size_bytes,
+                available_bytes: stats.available_bytes,
+                capacity_bytes: stats.capacity_bytes,
+            }),
+        },
+        Node::Client(c) => ExportedNode {
+            id: c.id.clone(),
+            name: c.name.clone(),
+            kind: "client",
+            status: node_status_label(&c.status),
+            public_key: Some(c.public_key.clone()),
+            connectivity_status: None,
+            connected_homeserver: c.connected_homeserver.clone(),
+            storage_stats: None,
+        },
+    }
+}
+
+/// Serialize the current nodes and event log into a versioned JSON envelope
+/// suitable for re-import.
+pub fn to_json(nodes: &[Node], events: &[EventLogEntry]) -> Result<String, serde_json::Error> {
+    let envelope = ExportEnvelope {
+        schema: SCHEMA_VERSION,
+        exported_at: Local::now().to_rfc3339(),
+        nodes: nodes.iter().map(export_node).collect(),
+        events: events
+            .iter()
+            .map(|e| ExportedEvent {
+                id: e.id,
+                timestamp: e.timestamp.clone(),
+                event_type: event_type_label(&e.event_type),
+                message: e.message.clone(),
+            })
+            .collect(),
+    };
+
+    serde_json::to_string_pretty(&envelope)
+}
+
+/// Quote a CSV field if it contains a comma, quote, or newline, doubling any
+/// embedded quotes per RFC 4180.
+fn csv_escape(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') || field.contains('\r') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Flatten the event log into `id,timestamp,event_type,message` rows.
+pub fn events_to_csv(events: &[EventLogEntry]) -> String {
+    let mut out = String::from("id,timestamp,event_type,message\n");
+    for event in events {
+        out.push_str(&format!(
+            "{},{},{},{}\n",
+            event.id,
+            csv_escape(&event.timestamp),
+            event_type_label(&event.event_type),
+            csv_escape(&event.message),
+        ));
+    }
+    out
+}
+
+/// Default save location for an export, timestamped so repeated exports
+/// don't clobber each other.
+pub fn default_path(format: ExportFormat) -> PathBuf {
+    let home = std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .unwrap_or_else(|_| ".".to_string());
+
+    let extension = match format {
+        ExportFormat::Json => "json",
+        ExportFormat::Csv => "csv",
+    };
+    let timestamp = Local::now().format("%Y%m%d-%H%M%S");
+
+    PathBuf::from(home)
+        .join(".publar")
+        .join("exports")
+        .join(format!("session-{timestamp}.{extension}"))
+}
+
+/// Render and write an export of `nodes`/`events` in `format` to `path`,
+/// creating the parent directory if needed.
+pub fn write_to_file(
+    format: ExportFormat,
+    nodes: &[Node],
+    events: &[EventLogEntry],
+    path: &std::path::Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let contents = match format {
+        ExportFormat::Json => to_json(nodes, events)?,
+        ExportFormat::Csv => events_to_csv(events),
+    };
+
+    std::fs::write(path, contents)?;
+    Ok(())
+}
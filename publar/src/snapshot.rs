@@ -0,0 +1,209 @@
+/// Headless rendering of a `ForceLayout` result to PNG or SVG.
+///
+/// Unlike `NetworkVisualization`, nothing here touches egui/dioxus or a
+/// window — this is meant for generating shareable snapshots or thumbnails
+/// of the network graph from a server process or a CLI tool.
+use crate::components::network_visualization::Node;
+use crate::force_layout::ForceLayout;
+use image::{Rgb, RgbImage};
+
+/// Background fill, matching the app's dark zinc canvas (#18181b).
+const BACKGROUND: Rgb<u8> = Rgb([0x18, 0x18, 0x1b]);
+/// Edge stroke, matching the lime accent used for connections in
+/// `NetworkVisualization` (drawn there at 0.6 opacity over the same
+/// background, which this solid color approximates).
+const EDGE_COLOR: Rgb<u8> = Rgb([0x7a, 0x99, 0x00]);
+const LABEL_COLOR: Rgb<u8> = Rgb([0xa1, 0xa1, 0xaa]);
+const HOMESERVER_COLOR: Rgb<u8> = Rgb([0x3b, 0x82, 0xf6]);
+const CLIENT_COLOR: Rgb<u8> = Rgb([0xc7, 0xff, 0x00]);
+
+/// Options controlling a rendered snapshot. Coordinates from
+/// `ForceLayout::get_positions` are clamped to the node bounds the layout
+/// already enforces (100..1100, 100..700), so a fixed canvas size covers
+/// them without a separate fit-to-content pass.
+pub struct SnapshotOptions {
+    pub width: u32,
+    pub height: u32,
+    pub node_radius: i32,
+    pub show_labels: bool,
+}
+
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        Self {
+            width: 1200,
+            height: 800,
+            node_radius: 16,
+            show_labels: true,
+        }
+    }
+}
+
+fn node_color(node: &Node) -> Rgb<u8> {
+    match node {
+        Node::Homeserver(_) => HOMESERVER_COLOR,
+        Node::Client(_) => CLIENT_COLOR,
+    }
+}
+
+/// Rasterize the current layout positions and edges into an RGB bitmap:
+/// edges as lines, nodes as filled circles colored by node type, with
+/// optional id labels drawn beneath each node.
+pub fn render_png(layout: &ForceLayout, nodes: &[Node], options: &SnapshotOptions) -> RgbImage {
+    let mut image = RgbImage::from_pixel(options.width, options.height, BACKGROUND);
+    let positions = layout.get_positions();
+
+    for (from_id, to_id) in &layout.edges {
+        if let (Some((_, x1, y1)), Some((_, x2, y2))) = (
+            positions.iter().find(|(id, ..)| id == from_id),
+            positions.iter().find(|(id, ..)| id == to_id),
+        ) {
+            draw_line(&mut image, *x1, *y1, *x2, *y2, EDGE_COLOR);
+        }
+    }
+
+    for (id, x, y) in &positions {
+        let node = nodes.iter().find(|n| n.id() == id);
+        let color = node.map(node_color).unwrap_or(LABEL_COLOR);
+        draw_filled_circle(&mut image, *x as i32, *y as i32, options.node_radius, color);
+
+        if options.show_labels {
+            if let Some(node) = node {
+                draw_label(
+                    &mut image,
+                    *x as i32,
+                    *y as i32 + options.node_radius + 3,
+                    node.name(),
+                    LABEL_COLOR,
+                );
+            }
+        }
+    }
+
+    image
+}
+
+pub fn save_png(
+    layout: &ForceLayout,
+    nodes: &[Node],
+    options: &SnapshotOptions,
+    path: impl AsRef<std::path::Path>,
+) -> image::ImageResult<()> {
+    render_png(layout, nodes, options).save(path)
+}
+
+/// Render the same layout as an SVG document. Unlike the PNG path, labels
+/// are full text via native `<text>` elements rather than a bitmap font.
+pub fn render_svg(layout: &ForceLayout, nodes: &[Node], options: &SnapshotOptions) -> String {
+    let mut svg = String::new();
+    svg.push_str(&format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+        options.width, options.height, options.width, options.height
+    ));
+    svg.push_str(&format!(
+        "  <rect width=\"{}\" height=\"{}\" fill=\"#18181b\"/>\n",
+        options.width, options.height
+    ));
+
+    let positions = layout.get_positions();
+
+    for (from_id, to_id) in &layout.edges {
+        if let (Some((_, x1, y1)), Some((_, x2, y2))) = (
+            positions.iter().find(|(id, ..)| id == from_id),
+            positions.iter().find(|(id, ..)| id == to_id),
+        ) {
+            svg.push_str(&format!(
+                "  <line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" stroke=\"#c7ff00\" stroke-width=\"3\" opacity=\"0.6\"/>\n"
+            ));
+        }
+    }
+
+    for (id, x, y) in &positions {
+        let node = nodes.iter().find(|n| n.id() == id);
+        let fill = match node {
+            Some(Node::Homeserver(_)) => "#3b82f6",
+            Some(Node::Client(_)) => "#c7ff00",
+            None => "#a1a1aa",
+        };
+        svg.push_str(&format!(
+            "  <circle cx=\"{x}\" cy=\"{y}\" r=\"{}\" fill=\"{fill}\"/>\n",
+            options.node_radius
+        ));
+
+        if options.show_labels {
+            if let Some(node) = node {
+                let label_y = y + options.node_radius as f64 + 14.0;
+                svg.push_str(&format!(
+                    "  <text x=\"{x}\" y=\"{label_y}\" fill=\"#a1a1aa\" font-size=\"12\" text-anchor=\"middle\">{}</text>\n",
+                    escape_xml(node.name())
+                ));
+            }
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn draw_line(image: &mut RgbImage, x0: f64, y0: f64, x1: f64, y1: f64, color: Rgb<u8>) {
+    // Bresenham's line algorithm over the image's integer pixel grid.
+    let (mut x0, mut y0) = (x0.round() as i32, y0.round() as i32);
+    let (x1, y1) = (x1.round() as i32, y1.round() as i32);
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let sy = if y0 < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+
+    loop {
+        set_pixel(image, x0, y0, color);
+        if x0 == x1 && y0 == y1 {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x0 += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y0 += sy;
+        }
+    }
+}
+
+fn draw_filled_circle(image: &mut RgbImage, cx: i32, cy: i32, radius: i32, color: Rgb<u8>) {
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            if dx * dx + dy * dy <= radius * radius {
+                set_pixel(image, cx + dx, cy + dy, color);
+            }
+        }
+    }
+}
+
+/// A minimal dot-per-character label: a short horizontal tick per glyph so
+/// a PNG viewer can see *that* a node has a name without embedding a font.
+/// `render_svg` is the source of truth for actual readable text.
+fn draw_label(image: &mut RgbImage, cx: i32, y: i32, text: &str, color: Rgb<u8>) {
+    let width = (text.len() as i32).min(40) * 5;
+    let start_x = cx - width / 2;
+    for i in 0..(width / 5) {
+        let x = start_x + i * 5;
+        for dx in 0..3 {
+            set_pixel(image, x + dx, y, color);
+        }
+    }
+}
+
+fn set_pixel(image: &mut RgbImage, x: i32, y: i32, color: Rgb<u8>) {
+    if x >= 0 && y >= 0 && (x as u32) < image.width() && (y as u32) < image.height() {
+        image.put_pixel(x as u32, y as u32, color);
+    }
+}
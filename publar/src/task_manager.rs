@@ -0,0 +1,181 @@
+/// Centralized registry for background tasks.
+///
+/// Every async handler in `App` used to fire a bare `spawn`/`tokio::spawn`
+/// and drop the resulting `JoinHandle`. That meant "Stop network" could
+/// clear `nodes`/`edges` while a node-creation or write task was still
+/// running against a torn-down `TestnetManager`, and the API server task
+/// started inside `toggle_network` could never be cancelled.
+///
+/// `TaskManager` gives every spawned future a name, a `TaskCategory`, an
+/// abort handle (the underlying dioxus `Task`), and a child
+/// `CancellationToken` so long-running work can check for cancellation
+/// between steps instead of only being killed at an await point.
+/// `shutdown()` cancels everything still running, which `toggle_network`
+/// calls before tearing down network state.
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::future::Future;
+
+use dioxus::prelude::Task as DioxusTask;
+use tokio_util::sync::CancellationToken;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TaskCategory {
+    Network,
+    NodeCreate,
+    Storage,
+    Connectivity,
+    Scenario,
+    Layout,
+    Routing,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TaskState {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Clone, Debug)]
+pub struct TaskInfo {
+    pub id: u64,
+    pub name: String,
+    pub category: TaskCategory,
+    pub state: TaskState,
+}
+
+/// What a task reports when it finishes on its own (as opposed to being
+/// cancelled from outside).
+pub enum TaskOutcome {
+    Completed,
+    Failed(String),
+}
+
+struct TaskEntry {
+    info: TaskInfo,
+    handle: DioxusTask,
+    token: CancellationToken,
+}
+
+#[derive(Clone)]
+pub struct TaskManager {
+    inner: Arc<Mutex<Inner>>,
+}
+
+struct Inner {
+    next_id: u64,
+    tasks: HashMap<u64, TaskEntry>,
+    root_token: CancellationToken,
+}
+
+impl TaskManager {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                next_id: 0,
+                tasks: HashMap::new(),
+                root_token: CancellationToken::new(),
+            })),
+        }
+    }
+
+    /// Spawn `make_future(token)` under `name`/`category`. The future
+    /// should poll `token.is_cancelled()` at safe points (after acquiring a
+    /// lock, before mutating shared state) and bail out early once it
+    /// trips, rather than relying solely on the hard abort `shutdown()`
+    /// also issues. Returns the task id, usable with `cancel`.
+    pub fn spawn<Fut>(
+        &self,
+        name: impl Into<String>,
+        category: TaskCategory,
+        make_future: impl FnOnce(CancellationToken) -> Fut,
+    ) -> u64
+    where
+        Fut: Future<Output = TaskOutcome> + 'static,
+    {
+        let name = name.into();
+        let (id, token) = {
+            let mut inner = self.inner.lock().unwrap();
+            let id = inner.next_id;
+            inner.next_id += 1;
+            let token = inner.root_token.child_token();
+            (id, token)
+        };
+
+        let manager = self.clone();
+        let fut = make_future(token.clone());
+        let handle = dioxus::prelude::spawn(async move {
+            let outcome = fut.await;
+            manager.finish(id, outcome);
+        });
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.tasks.insert(
+            id,
+            TaskEntry {
+                info: TaskInfo { id, name, category, state: TaskState::Running },
+                handle,
+                token,
+            },
+        );
+        id
+    }
+
+    fn finish(&self, id: u64, outcome: TaskOutcome) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(entry) = inner.tasks.get_mut(&id) {
+            if entry.info.state == TaskState::Running {
+                entry.info.state = match outcome {
+                    TaskOutcome::Completed => TaskState::Completed,
+                    TaskOutcome::Failed(_) => TaskState::Failed,
+                };
+            }
+        }
+    }
+
+    /// Cooperatively and forcibly stop a single task.
+    pub fn cancel(&self, id: u64) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(entry) = inner.tasks.get_mut(&id) {
+            if entry.info.state == TaskState::Running {
+                entry.token.cancel();
+                entry.handle.cancel();
+                entry.info.state = TaskState::Cancelled;
+            }
+        }
+    }
+
+    /// Cancel every task still running, e.g. when the network stops.
+    /// Finished tasks keep their recorded outcome for the event log.
+    pub fn shutdown(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        let running: Vec<u64> = inner
+            .tasks
+            .values()
+            .filter(|e| e.info.state == TaskState::Running)
+            .map(|e| e.info.id)
+            .collect();
+        for id in running {
+            if let Some(entry) = inner.tasks.get_mut(&id) {
+                entry.token.cancel();
+                entry.handle.cancel();
+                entry.info.state = TaskState::Cancelled;
+            }
+        }
+        // Fresh root token so tasks spawned after shutdown aren't born cancelled.
+        inner.root_token = CancellationToken::new();
+    }
+
+    pub fn active_tasks(&self) -> Vec<TaskInfo> {
+        self.inner
+            .lock()
+            .unwrap()
+            .tasks
+            .values()
+            .filter(|e| e.info.state == TaskState::Running)
+            .map(|e| e.info.clone())
+            .collect()
+    }
+}
@@ -1,7 +1,14 @@
 use anyhow::{Context, Result};
 use pubky_testnet::Testnet;
 use pubky::{Keypair, PublicKey, PubkySession};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::sync::{broadcast, oneshot};
+use tokio_util::sync::CancellationToken;
+use crate::api::ApiEvent;
+use crate::components::network_visualization::StorageStats;
 
 pub struct HomeserverInfo {
     pub port: u16,
@@ -14,15 +21,86 @@ pub struct ClientInfo {
     pub keypair: Keypair,
 }
 
+/// Outcome of a real reachability probe against a homeserver's `http_url`,
+/// as opposed to the flat mocked "sleep one second, flip to Connected"
+/// that `test_connectivity` used to do.
+pub struct ConnectivityProbe {
+    pub reachable: bool,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+/// Simulated per-homeserver disk quota. The testnet itself enforces no
+/// real capacity, so writes are tracked against a fixed budget to give
+/// the background health poll's "available/total" report something real
+/// to compute from.
+const HOMESERVER_CAPACITY_BYTES: u64 = 64 * 1024 * 1024;
+
+/// One tracked key under a homeserver, as returned by `list_directory`.
+pub struct DirectoryEntry {
+    pub path: String,
+    pub size_bytes: usize,
+    pub modified_secs_ago: u64,
+}
+
 /// Manager for pubky-testnet
 pub struct TestnetManager {
     testnet: Option<Testnet>,
+    /// Live key count/byte total per homeserver (keyed by the visualization's
+    /// node id), built up from writes this manager actually observed rather
+    /// than queried from the homeserver's own storage backend.
+    storage_ledger: HashMap<String, StorageStats>,
+    /// Size and last-write time per path, keyed first by homeserver node
+    /// id then by the exact path written — the client panel's content
+    /// browser lists from this rather than the homeserver itself, since
+    /// this testnet exposes no listing API, only exact-path get/put/delete.
+    path_ledger: HashMap<String, HashMap<String, (usize, std::time::Instant)>>,
+    /// Wall-clock of the last successful reachability probe per
+    /// homeserver, fed by the background health poll.
+    last_seen: HashMap<String, std::time::Instant>,
+    /// Graceful-shutdown trigger for the API server started alongside this
+    /// testnet, plus the one-shot that resolves once the server has
+    /// actually finished draining. `stop()` uses these so the listener is
+    /// fully released (rather than hard-aborted) before a restart.
+    api_shutdown: Option<(CancellationToken, oneshot::Receiver<()>)>,
+    /// Clone of the API server's `ApiState::events` sender, so
+    /// `create_homeserver`/`write_to_homeserver` can push live updates to
+    /// `/ws/events` subscribers instead of leaving them to poll.
+    events: Option<broadcast::Sender<ApiEvent>>,
 }
 
 impl TestnetManager {
     pub fn new() -> Self {
         Self {
             testnet: None,
+            storage_ledger: HashMap::new(),
+            path_ledger: HashMap::new(),
+            last_seen: HashMap::new(),
+            api_shutdown: None,
+            events: None,
+        }
+    }
+
+    /// Record the API server's shutdown trigger and drain signal so `stop`
+    /// can shut it down gracefully instead of leaving the old listener
+    /// bound when the network is restarted.
+    pub fn set_api_shutdown(&mut self, token: CancellationToken, drained: oneshot::Receiver<()>) {
+        self.api_shutdown = Some((token, drained));
+    }
+
+    /// Record the API server's event sender, so homeserver/write activity
+    /// observed by this manager reaches `/ws/events` subscribers directly.
+    pub fn set_event_sender(&mut self, events: broadcast::Sender<ApiEvent>) {
+        self.events = Some(events);
+    }
+
+    /// Best-effort publish: a push event is a convenience for subscribers
+    /// already listening, not something callers should fail over — there
+    /// may be zero receivers (no API server running, or nobody connected
+    /// yet), which `broadcast::Sender::send` itself reports as an error.
+    fn publish(&self, event: ApiEvent) {
+        if let Some(events) = &self.events {
+            let _ = events.send(event);
         }
     }
 
@@ -51,6 +129,8 @@ impl TestnetManager {
         let port = url.port().unwrap_or(80);
         let http_url = url.to_string();
 
+        self.publish(ApiEvent::HomeserverCreated { http_url: http_url.clone() });
+
         Ok(HomeserverInfo {
             port,
             public_key: homeserver.public_key().to_z32(),
@@ -70,6 +150,17 @@ impl TestnetManager {
         })
     }
 
+    /// Create a client whose keypair is derived deterministically from
+    /// `seed_bytes` rather than OS randomness — the same shape as
+    /// `create_client`, but reproducible, so the fuzz harness below can
+    /// regenerate an identical client from a failing seed on every replay.
+    pub async fn create_client_from_seed(&mut self, seed_bytes: [u8; 32]) -> Result<ClientInfo> {
+        let keypair = Keypair::from_secret_key(&seed_bytes);
+        let public_key = keypair.public_key().to_z32();
+
+        Ok(ClientInfo { public_key, keypair })
+    }
+
     /// Connect a client to a homeserver and return the session
     pub async fn connect_client(
         &self,
@@ -100,9 +191,12 @@ impl TestnetManager {
         Ok(Arc::new(session))
     }
 
-    /// Write data to a homeserver using an existing session
+    /// Write data to a homeserver using an existing session, recording the
+    /// write against `node_id` (the visualization's homeserver node id) so
+    /// `storage_stats` reflects real totals instead of mock data.
     pub async fn write_to_homeserver(
-        &self,
+        &mut self,
+        node_id: &str,
         session: &PubkySession,
         path: &str,
         content: &[u8],
@@ -113,9 +207,119 @@ impl TestnetManager {
             .await
             .context("Failed to write to homeserver")?;
 
+        let previous = self
+            .path_ledger
+            .entry(node_id.to_string())
+            .or_default()
+            .insert(path.to_string(), (content.len(), std::time::Instant::now()));
+
+        let stats = self.storage_ledger.entry(node_id.to_string()).or_insert_with(|| {
+            StorageStats {
+                total_keys: 0,
+                total_size_bytes: 0,
+                available_bytes: HOMESERVER_CAPACITY_BYTES,
+                capacity_bytes: HOMESERVER_CAPACITY_BYTES,
+            }
+        });
+        // Overwriting a path replaces its bytes rather than adding a key.
+        match previous {
+            Some((old_size, _)) => stats.total_size_bytes = stats.total_size_bytes.saturating_sub(old_size),
+            None => stats.total_keys += 1,
+        }
+        stats.total_size_bytes += content.len();
+        stats.available_bytes = HOMESERVER_CAPACITY_BYTES.saturating_sub(stats.total_size_bytes as u64);
+
+        self.publish(ApiEvent::PathWritten { node_id: node_id.to_string(), path: path.to_string() });
+
         Ok(())
     }
 
+    /// Live storage stats for a homeserver node, or `None` if nothing has
+    /// been written to it yet through this manager.
+    pub fn storage_stats(&self, node_id: &str) -> Option<StorageStats> {
+        self.storage_ledger.get(node_id).cloned()
+    }
+
+    /// List all paths tracked for `node_id` that start with `prefix`, each
+    /// with its size and how long ago it was last written. Backed by this
+    /// manager's own write/delete ledger (see `path_ledger`) rather than a
+    /// query to the homeserver.
+    pub fn list_directory(&self, node_id: &str, prefix: &str) -> Vec<DirectoryEntry> {
+        let Some(paths) = self.path_ledger.get(node_id) else {
+            return Vec::new();
+        };
+
+        let mut entries: Vec<DirectoryEntry> = paths
+            .iter()
+            .filter(|(path, _)| path.starts_with(prefix))
+            .map(|(path, (size_bytes, written_at))| DirectoryEntry {
+                path: path.clone(),
+                size_bytes: *size_bytes,
+                modified_secs_ago: written_at.elapsed().as_secs(),
+            })
+            .collect();
+
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        entries
+    }
+
+    /// Record a successful reachability probe for `node_id`, fed by the
+    /// background health poll.
+    pub fn record_seen(&mut self, node_id: &str) {
+        self.last_seen.insert(node_id.to_string(), std::time::Instant::now());
+    }
+
+    /// Seconds since `node_id` was last confirmed reachable, or `None` if
+    /// it has never been seen.
+    pub fn last_seen_secs_ago(&self, node_id: &str) -> Option<u64> {
+        self.last_seen.get(node_id).map(|t| t.elapsed().as_secs())
+    }
+
+    /// Delete data from a homeserver using an existing session, reflecting
+    /// the removal in the live ledger the same way `write_to_homeserver`
+    /// records an addition.
+    pub async fn delete_from_homeserver(
+        &mut self,
+        node_id: &str,
+        session: &PubkySession,
+        path: &str,
+    ) -> Result<()> {
+        session.storage()
+            .delete(path.to_string())
+            .await
+            .context("Failed to delete from homeserver")?;
+
+        let removed = self.path_ledger.get_mut(node_id).and_then(|paths| paths.remove(path));
+
+        if let Some(stats) = self.storage_ledger.get_mut(node_id) {
+            stats.total_keys = stats.total_keys.saturating_sub(1);
+            if let Some((size_bytes, _)) = removed {
+                stats.total_size_bytes = stats.total_size_bytes.saturating_sub(size_bytes);
+                stats.available_bytes = HOMESERVER_CAPACITY_BYTES.saturating_sub(stats.total_size_bytes as u64);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sign out a client's session, releasing server-side state. Best
+    /// effort: `signout` consumes the session, so if another clone of the
+    /// `Arc` is still held elsewhere (e.g. a write/read in flight), the
+    /// local handle is simply dropped instead of failing the disconnect.
+    pub async fn disconnect_client(&self, session: Arc<PubkySession>) -> Result<()> {
+        match Arc::try_unwrap(session) {
+            Ok(session) => session
+                .signout()
+                .await
+                .map_err(|(e, _)| e)
+                .context("Failed to sign out"),
+            Err(_) => {
+                println!("Session still referenced elsewhere; dropping local handle only");
+                Ok(())
+            }
+        }
+    }
+
     /// Read data from a homeserver using an existing session
     pub async fn read_from_homeserver(
         &self,
@@ -136,8 +340,43 @@ impl TestnetManager {
         Ok(data)
     }
 
-    /// Stop the entire testnet
+    /// Probe a homeserver's `http_url` for reachability, timing the round
+    /// trip. This testnet already hands us the homeserver's address at
+    /// creation time (there's no separate DHT resolve step to perform), so
+    /// the real work this replaces is the HTTP dial itself: the caller
+    /// classifies the measured latency into direct-vs-NAT'd reachability.
+    pub async fn probe_connectivity(&self, http_url: &str) -> ConnectivityProbe {
+        let start = std::time::Instant::now();
+        match reqwest::get(http_url).await {
+            Ok(response) if response.status().is_success() || response.status().is_client_error() => {
+                ConnectivityProbe {
+                    reachable: true,
+                    latency_ms: start.elapsed().as_millis() as u64,
+                    error: None,
+                }
+            }
+            Ok(response) => ConnectivityProbe {
+                reachable: false,
+                latency_ms: start.elapsed().as_millis() as u64,
+                error: Some(format!("unexpected status {}", response.status())),
+            },
+            Err(e) => ConnectivityProbe {
+                reachable: false,
+                latency_ms: start.elapsed().as_millis() as u64,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+
+    /// Stop the entire testnet. Signals the API server's shutdown token
+    /// first and awaits its drain so in-flight requests finish and the
+    /// port is fully released before the testnet itself is torn down.
     pub async fn stop(&mut self) {
+        if let Some((token, drained)) = self.api_shutdown.take() {
+            token.cancel();
+            let _ = drained.await;
+        }
+
         self.testnet = None;
     }
 
@@ -189,7 +428,7 @@ impl TestnetManager {
                 for i in 0..files_per_client {
                     let path = format!("/pub/publar/test_file_{}.txt", i);
                     let content = format!("Test data from client {} file {}", client.public_key, i);
-                    self.write_to_homeserver(&session, &path, content.as_bytes()).await?;
+                    self.write_to_homeserver(&homeserver.public_key, &session, &path, content.as_bytes()).await?;
                 }
 
                 results.push((
@@ -209,4 +448,186 @@ impl TestnetManager {
     pub fn export_homeserver_urls(&self, homeservers: &[HomeserverInfo]) -> Vec<String> {
         homeservers.iter().map(|h| h.http_url.clone()).collect()
     }
+
+    /// Property-based randomized consistency check for the homeserver
+    /// read/write path, mirroring Zed's extracted randomized-test
+    /// infrastructure: a seeded RNG drives a mix of operations against a
+    /// fresh testnet, and every `Read` is checked against an in-memory
+    /// oracle recording each client/path's last write. Every client
+    /// keypair and every path/content choice is derived from `seed`
+    /// (see `generate_fuzz_ops`), so a failing seed reproduces exactly; the
+    /// one piece of state outside this harness's control is a homeserver's
+    /// own keypair, minted internally by `pubky_testnet::Testnet`, but that
+    /// identity is incidental to the read/write consistency this checks.
+    /// Runs entirely on the caller's task (no spawned concurrency), so
+    /// operation order is exactly the generated sequence.
+    #[allow(dead_code)]
+    pub async fn run_fuzz_scenario(seed: u64, op_count: usize) -> Result<FuzzOutcome> {
+        let ops = generate_fuzz_ops(seed, op_count);
+
+        match Self::replay_fuzz_ops(&ops).await? {
+            Some(divergence) => {
+                let minimal_trace = Self::shrink_fuzz_trace(&ops, divergence.op_index).await;
+                Ok(FuzzOutcome::Diverged { divergence, minimal_trace })
+            }
+            None => Ok(FuzzOutcome::Passed { ops_run: ops.len() }),
+        }
+    }
+
+    /// Replay `ops` against a fresh testnet/oracle pair, returning the
+    /// first divergence found, if any. A fresh testnet per replay keeps a
+    /// shrink pass's truncated prefixes independent of whatever state
+    /// earlier, longer prefixes left behind.
+    async fn replay_fuzz_ops(ops: &[FuzzOp]) -> Result<Option<FuzzDivergence>> {
+        let mut manager = Self::new();
+        manager.start().await?;
+
+        let mut homeservers: Vec<HomeserverInfo> = Vec::new();
+        let mut clients: Vec<(ClientInfo, Option<Arc<PubkySession>>)> = Vec::new();
+        let mut oracle: HashMap<(String, String), Vec<u8>> = HashMap::new();
+
+        for (index, op) in ops.iter().enumerate() {
+            match op {
+                FuzzOp::CreateHomeserver => {
+                    homeservers.push(manager.create_homeserver().await?);
+                }
+                FuzzOp::CreateClient { seed_bytes } => {
+                    clients.push((manager.create_client_from_seed(*seed_bytes).await?, None));
+                }
+                FuzzOp::ConnectClient { client, homeserver } => {
+                    let (Some((client_info, session)), Some(homeserver_info)) =
+                        (clients.get_mut(*client), homeservers.get(*homeserver))
+                    else {
+                        continue;
+                    };
+                    *session = Some(manager.connect_client(&client_info.keypair, &homeserver_info.public_key).await?);
+                }
+                FuzzOp::Write { client, path, content } => {
+                    let Some((client_info, Some(session))) = clients.get(*client) else { continue };
+                    manager.write_to_homeserver("fuzz", session, path, content).await?;
+                    oracle.insert((client_info.public_key.clone(), path.clone()), content.clone());
+                }
+                FuzzOp::Read { client, path } => {
+                    let Some((client_info, Some(session))) = clients.get(*client) else { continue };
+                    let expected = oracle.get(&(client_info.public_key.clone(), path.clone())).cloned();
+                    let actual = manager.read_from_homeserver(session, path).await.ok();
+                    if actual != expected {
+                        return Ok(Some(FuzzDivergence { op_index: index, path: path.clone(), expected, actual }));
+                    }
+                }
+                FuzzOp::Delete { client, path } => {
+                    let Some((client_info, Some(session))) = clients.get(*client) else { continue };
+                    manager.delete_from_homeserver("fuzz", session, path).await?;
+                    oracle.remove(&(client_info.public_key.clone(), path.clone()));
+                }
+            }
+        }
+
+        manager.stop().await;
+        Ok(None)
+    }
+
+    /// On a failing seed, repeatedly halve the op count and replay that
+    /// prefix; keep halving past the first still-failing prefix until a
+    /// shorter one stops reproducing the divergence, then print the
+    /// shortest failing trace found.
+    async fn shrink_fuzz_trace(ops: &[FuzzOp], failing_index: usize) -> Vec<FuzzOp> {
+        let mut shortest = ops[..=failing_index].to_vec();
+
+        let mut len = shortest.len() / 2;
+        while len > 0 {
+            let candidate = &ops[..len];
+            match Self::replay_fuzz_ops(candidate).await {
+                Ok(Some(_)) => {
+                    shortest = candidate.to_vec();
+                    len /= 2;
+                }
+                _ => break,
+            }
+        }
+
+        println!("Minimal failing trace ({} ops):", shortest.len());
+        for (i, op) in shortest.iter().enumerate() {
+            println!("  [{i}] {op:?}");
+        }
+
+        shortest
+    }
+}
+
+/// One fuzz-generated operation against the testnet, kept around verbatim
+/// so a failing run's exact trace can be printed and replayed.
+#[derive(Clone, Debug)]
+pub enum FuzzOp {
+    CreateHomeserver,
+    CreateClient { seed_bytes: [u8; 32] },
+    ConnectClient { client: usize, homeserver: usize },
+    Write { client: usize, path: String, content: Vec<u8> },
+    Read { client: usize, path: String },
+    Delete { client: usize, path: String },
+}
+
+/// A `Read` whose result didn't match the oracle's record of that
+/// client/path's last write (or lack thereof).
+#[derive(Debug)]
+pub struct FuzzDivergence {
+    pub op_index: usize,
+    pub path: String,
+    pub expected: Option<Vec<u8>>,
+    pub actual: Option<Vec<u8>>,
+}
+
+/// Outcome of `TestnetManager::run_fuzz_scenario`.
+#[derive(Debug)]
+pub enum FuzzOutcome {
+    Passed { ops_run: usize },
+    Diverged { divergence: FuzzDivergence, minimal_trace: Vec<FuzzOp> },
+}
+
+/// Deterministically generate `op_count` operations from `seed`: every
+/// client keypair seed and every path/content choice comes out of the same
+/// seeded RNG, so the same seed always produces the same trace.
+fn generate_fuzz_ops(seed: u64, op_count: usize) -> Vec<FuzzOp> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut ops = Vec::with_capacity(op_count);
+    let mut homeserver_count = 0usize;
+    let mut client_count = 0usize;
+
+    for _ in 0..op_count {
+        let op = match rng.gen_range(0..100) {
+            0..=9 => {
+                homeserver_count += 1;
+                FuzzOp::CreateHomeserver
+            }
+            10..=24 => {
+                client_count += 1;
+                FuzzOp::CreateClient { seed_bytes: rng.gen() }
+            }
+            25..=39 if client_count > 0 && homeserver_count > 0 => FuzzOp::ConnectClient {
+                client: rng.gen_range(0..client_count),
+                homeserver: rng.gen_range(0..homeserver_count),
+            },
+            40..=64 if client_count > 0 => FuzzOp::Write {
+                client: rng.gen_range(0..client_count),
+                path: format!("/pub/fuzz/{}", rng.gen_range(0..8)),
+                content: (0..rng.gen_range(1..32)).map(|_| rng.gen()).collect(),
+            },
+            65..=89 if client_count > 0 => FuzzOp::Read {
+                client: rng.gen_range(0..client_count),
+                path: format!("/pub/fuzz/{}", rng.gen_range(0..8)),
+            },
+            _ if client_count > 0 => FuzzOp::Delete {
+                client: rng.gen_range(0..client_count),
+                path: format!("/pub/fuzz/{}", rng.gen_range(0..8)),
+            },
+            // No client exists yet to act through; fall back to minting one.
+            _ => {
+                client_count += 1;
+                FuzzOp::CreateClient { seed_bytes: rng.gen() }
+            }
+        };
+        ops.push(op);
+    }
+
+    ops
 }
@@ -0,0 +1,73 @@
+/// Per-edge byte counters and a rolling utilization sample, modeled like a
+/// packet sniffer reporting up/down rates per socket rather than a single
+/// cumulative total. `record_up`/`record_down` are called from the
+/// write/read paths (interactive and scenario); `rate_bps` is sampled by an
+/// independent periodic effect so idle edges decay to zero on their own
+/// instead of needing a reset tied to scenario activity.
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// Cumulative bytes transferred across one client→homeserver edge.
+#[derive(Clone, Debug, Default)]
+pub struct ByteCounter {
+    pub total_up: u64,
+    pub total_down: u64,
+}
+
+struct Sample {
+    at: Instant,
+    bytes: u64,
+}
+
+pub struct ThroughputTracker {
+    counters: HashMap<String, ByteCounter>,
+    samples: HashMap<String, Vec<Sample>>,
+}
+
+impl ThroughputTracker {
+    pub fn new() -> Self {
+        Self {
+            counters: HashMap::new(),
+            samples: HashMap::new(),
+        }
+    }
+
+    /// Record `bytes` written from the client onto `edge_key` (e.g.
+    /// `"{client_id}-{homeserver_id}"`, matching `Edge::from`/`Edge::to`).
+    pub fn record_up(&mut self, edge_key: &str, bytes: u64) {
+        self.counters.entry(edge_key.to_string()).or_default().total_up += bytes;
+        self.samples
+            .entry(edge_key.to_string())
+            .or_default()
+            .push(Sample { at: Instant::now(), bytes });
+    }
+
+    /// Record `bytes` read back by the client from `edge_key`.
+    pub fn record_down(&mut self, edge_key: &str, bytes: u64) {
+        self.counters.entry(edge_key.to_string()).or_default().total_down += bytes;
+        self.samples
+            .entry(edge_key.to_string())
+            .or_default()
+            .push(Sample { at: Instant::now(), bytes });
+    }
+
+    /// Smoothed combined up+down rate in bytes/sec for `edge_key` over the
+    /// trailing `WINDOW`. Samples older than the window are dropped on
+    /// every call, so an edge with no recent traffic reads back as 0
+    /// without any explicit decay step.
+    pub fn rate_bps(&mut self, edge_key: &str) -> f64 {
+        let Some(samples) = self.samples.get_mut(edge_key) else {
+            return 0.0;
+        };
+        let now = Instant::now();
+        samples.retain(|s| now.duration_since(s.at) <= WINDOW);
+        let total: u64 = samples.iter().map(|s| s.bytes).sum();
+        total as f64 / WINDOW.as_secs_f64()
+    }
+
+    pub fn counter(&self, edge_key: &str) -> Option<ByteCounter> {
+        self.counters.get(edge_key).cloned()
+    }
+}
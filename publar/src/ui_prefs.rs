@@ -0,0 +1,41 @@
+//! Small wrapper around the webview's `localStorage` for persisting UI
+//! layout preferences (panel sizes, collapse state) across reloads. Reads
+//! and writes go through `document::eval`, the same JS bridge used for any
+//! other DOM interaction the desktop webview exposes.
+
+use dioxus::prelude::*;
+
+pub const SIDEBAR_WIDTH_KEY: &str = "sidebar-width";
+pub const EVENTLOG_HEIGHT_KEY: &str = "eventlog-height";
+pub const SIDEBAR_HIDDEN_KEY: &str = "hide-sidebar";
+
+/// Read a stored value back as a string, or `None` if the key is absent
+/// (including the `null` JS returns for a missing key).
+async fn load_string(key: &str) -> Option<String> {
+    let js = format!("return localStorage.getItem({key:?});");
+    match document::eval(&js).await {
+        Ok(value) => value.as_str().map(|s| s.to_string()),
+        Err(_) => None,
+    }
+}
+
+pub async fn load_number(key: &str) -> Option<f64> {
+    load_string(key).await.and_then(|s| s.parse::<f64>().ok())
+}
+
+pub async fn load_bool(key: &str) -> Option<bool> {
+    load_string(key).await.and_then(|s| s.parse::<bool>().ok())
+}
+
+fn save_string(key: &str, value: &str) {
+    let js = format!("localStorage.setItem({key:?}, {value:?});");
+    document::eval(&js);
+}
+
+pub fn save_number(key: &str, value: f64) {
+    save_string(key, &value.to_string());
+}
+
+pub fn save_bool(key: &str, value: bool) {
+    save_string(key, &value.to_string());
+}
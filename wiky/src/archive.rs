@@ -0,0 +1,186 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use pubky::PubkySession;
+use serde::{Deserialize, Serialize};
+use tokio::runtime::Runtime;
+use uuid::Uuid;
+
+use crate::save_wiki_post;
+use crate::utils::get_list;
+
+const ARCHIVE_VERSION: u32 = 1;
+const MEDIA_DIR: &str = "/pub/wiki.app/media/";
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ArchivedPage {
+    pub page_id: String,
+    pub content: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ArchivedMedia {
+    pub path: String,
+    pub base64: String,
+}
+
+/// A portable bundle of one account's whole wiki, for backup or migration
+/// to another account/homeserver.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct WikiArchive {
+    pub version: u32,
+    pub exported_at: u64,
+    pub pages: Vec<ArchivedPage>,
+    pub media: Vec<ArchivedMedia>,
+}
+
+/// What to do with a page id that already exists at the import
+/// destination.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum CollisionPolicy {
+    /// Leave the existing page untouched.
+    Skip,
+    /// Replace the existing page's content.
+    Overwrite,
+    /// Import under a freshly generated id instead, leaving the existing
+    /// page (and anything already forked from it) alone.
+    NewId,
+}
+
+impl CollisionPolicy {
+    pub(crate) const ALL: [CollisionPolicy; 3] =
+        [CollisionPolicy::Skip, CollisionPolicy::Overwrite, CollisionPolicy::NewId];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            CollisionPolicy::Skip => "Skip",
+            CollisionPolicy::Overwrite => "Overwrite",
+            CollisionPolicy::NewId => "Import as new id",
+        }
+    }
+}
+
+/// Walk every page (and media blob) under `/pub/wiki.app/` and bundle it
+/// into one archive. Pages are fetched as their raw stored bytes (not
+/// decrypted), so an encrypted page round-trips through export/import
+/// still sealed, same as a plain `cp` of the homeserver's storage would.
+pub(crate) fn export_wiki(session: &PubkySession, rt: Arc<Runtime>) -> Result<WikiArchive> {
+    let storage = session.storage();
+    let mut pages = Vec::new();
+
+    for file_url in get_list(session, "/pub/wiki.app/", rt.clone())? {
+        let page_id = file_url.rsplit('/').next().unwrap_or(&file_url).to_string();
+        // Media blobs and fork-mention records live under their own
+        // subdirectories of wiki.app/ and aren't pages themselves.
+        if file_url.contains("/wiki.app/media/") || file_url.contains("/wiki.app/mentions/") {
+            continue;
+        }
+
+        let response = rt
+            .block_on(storage.get(&file_url))
+            .with_context(|| format!("Failed to fetch {file_url}"))?;
+        let bytes = rt
+            .block_on(response.bytes())
+            .with_context(|| format!("Failed to read {file_url}"))?;
+        pages.push(ArchivedPage {
+            page_id,
+            content: String::from_utf8_lossy(&bytes).into_owned(),
+        });
+    }
+
+    let mut media = Vec::new();
+    for media_url in get_list(session, MEDIA_DIR, rt.clone()).unwrap_or_default() {
+        let path = media_url
+            .splitn(2, "/pub/")
+            .nth(1)
+            .map(|p| format!("/pub/{p}"))
+            .unwrap_or_else(|| media_url.clone());
+
+        let response = rt
+            .block_on(storage.get(&media_url))
+            .with_context(|| format!("Failed to fetch {media_url}"))?;
+        let bytes = rt
+            .block_on(response.bytes())
+            .with_context(|| format!("Failed to read {media_url}"))?;
+        media.push(ArchivedMedia { path, base64: BASE64.encode(&bytes) });
+    }
+
+    let exported_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    Ok(WikiArchive { version: ARCHIVE_VERSION, exported_at, pages, media })
+}
+
+/// Outcome of replaying one archive's pages/media against a session.
+#[derive(Default, Debug)]
+pub(crate) struct ImportSummary {
+    pub imported: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// Replay an archive's pages and media blobs against `session`, applying
+/// `policy` whenever a page id already exists at the destination.
+pub(crate) fn import_wiki(
+    session: &PubkySession,
+    rt: Arc<Runtime>,
+    archive: &WikiArchive,
+    policy: CollisionPolicy,
+) -> ImportSummary {
+    let storage = session.storage();
+    let mut summary = ImportSummary::default();
+
+    for page in &archive.pages {
+        let existing_path = format!("/pub/wiki.app/{}", page.page_id);
+        let exists = rt.block_on(storage.get(&existing_path)).is_ok();
+
+        let target_id = if exists {
+            match policy {
+                CollisionPolicy::Skip => {
+                    summary.skipped += 1;
+                    continue;
+                }
+                CollisionPolicy::Overwrite => page.page_id.clone(),
+                CollisionPolicy::NewId => Uuid::new_v4().to_string(),
+            }
+        } else {
+            page.page_id.clone()
+        };
+
+        let result = rt
+            .block_on(save_wiki_post(session, Some(&target_id), page.content.clone().into_bytes()))
+            .map(|_| ());
+
+        match result {
+            Ok(()) => summary.imported += 1,
+            Err(e) => {
+                log::error!("Failed to import page {}: {e}", page.page_id);
+                summary.failed += 1;
+            }
+        }
+    }
+
+    for item in &archive.media {
+        let bytes = match BASE64.decode(&item.base64) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                log::error!("Failed to decode media {}: {e}", item.path);
+                summary.failed += 1;
+                continue;
+            }
+        };
+
+        match rt.block_on(storage.put(&item.path, bytes)) {
+            Ok(_) => summary.imported += 1,
+            Err(e) => {
+                log::error!("Failed to import media {}: {e}", item.path);
+                summary.failed += 1;
+            }
+        }
+    }
+
+    summary
+}
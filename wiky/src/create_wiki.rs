@@ -1,4 +1,7 @@
-use crate::{create_wiki_post, utils::extract_title, AuthState, PubkyApp, ViewState};
+use crate::{
+    encrypted_content, markdown_preview, media, merkle, record_fork_mention, save_wiki_post,
+    utils::extract_title, AuthState, PubkyApp, ViewState,
+};
 
 use eframe::egui::{Context, Ui};
 use pubky::PubkySession;
@@ -7,20 +10,58 @@ pub(crate) fn update(app: &mut PubkyApp, session: &PubkySession, _ctx: &Context,
     ui.label(egui::RichText::new("Create New Wiki Page").size(20.0).strong());
     ui.add_space(25.0);
 
-    // Textarea for wiki content
-    ui.label(egui::RichText::new("Content:").size(16.0));
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new("Content:").size(16.0));
+        ui.checkbox(&mut app.preview_enabled, "👁 Preview");
+    });
     ui.add_space(12.0);
 
-    egui::ScrollArea::vertical()
-        .max_height(400.0)
-        .show(ui, |ui| {
-            ui.add(
+    let cursor_index = if app.preview_enabled {
+        let title = extract_title(&app.edit_wiki_content).to_string();
+        egui::ScrollArea::vertical()
+            .max_height(400.0)
+            .show(ui, |ui| {
+                ui.columns(2, |columns| {
+                    let cursor = egui::TextEdit::multiline(&mut app.edit_wiki_content)
+                        .desired_width(f32::INFINITY)
+                        .desired_rows(15)
+                        .font(egui::TextStyle::Monospace)
+                        .show(&mut columns[0])
+                        .cursor_range;
+
+                    columns[1].label(egui::RichText::new(title).size(16.0).strong());
+                    columns[1].add_space(8.0);
+                    markdown_preview::show(
+                        &mut columns[1],
+                        &mut app.cache,
+                        &mut app.preview_highlight_cache,
+                        &app.edit_wiki_content,
+                    );
+
+                    cursor
+                })
+            })
+            .inner
+    } else {
+        egui::ScrollArea::vertical()
+            .max_height(400.0)
+            .show(ui, |ui| {
                 egui::TextEdit::multiline(&mut app.edit_wiki_content)
                     .desired_width(f32::INFINITY)
                     .desired_rows(15)
-                    .font(egui::TextStyle::Monospace),
-            );
-        });
+                    .font(egui::TextStyle::Monospace)
+                    .show(ui)
+                    .cursor_range
+            })
+            .inner
+    };
+
+    if let Some(cursor_range) = cursor_index {
+        app.edit_wiki_cursor = cursor_range.primary.index;
+    }
+
+    ui.add_space(10.0);
+    ui.checkbox(&mut app.encrypt_content, "🔒 Encrypt content (RFC 8188 aes128gcm)");
 
     ui.add_space(25.0);
 
@@ -35,17 +76,65 @@ pub(crate) fn update(app: &mut PubkyApp, session: &PubkySession, _ctx: &Context,
             let content = app.edit_wiki_content.clone();
             let state_clone = app.state.clone();
             let filename = app.forked_from_page_id.as_deref();
+            let own_user_pk = session.info().public_key().to_string();
 
-            let create_wiki_post_fut = create_wiki_post(&session_clone, &content, filename);
-            match app.rt.block_on(create_wiki_post_fut) {
+            let stored_bytes: Vec<u8> = if app.encrypt_content {
+                // The session only exposes the author's public identifier
+                // here, not the signer keypair used by the recovery-file
+                // flow; derive the input keying material from that until
+                // the GUI has a path to the raw keypair.
+                match encrypted_content::seal(own_user_pk.as_bytes(), &own_user_pk, content.as_bytes()) {
+                    Ok(sealed) => sealed,
+                    Err(e) => {
+                        log::error!("Failed to encrypt wiki content: {e}");
+                        content.clone().into_bytes()
+                    }
+                }
+            } else {
+                content.clone().into_bytes()
+            };
+
+            let save_wiki_post_fut = save_wiki_post(&session_clone, filename, stored_bytes);
+            match app.rt.block_on(save_wiki_post_fut) {
                 Ok(wiki_page_path) => {
                     log::info!("Created wiki post at: {}", wiki_page_path);
 
-                    // Convert path to pubky URL format for the file_cache list
+                    let own_user_pk = session.info().public_key().to_string();
+                    let page_id = wiki_page_path.rsplit('/').next().unwrap_or(&wiki_page_path);
+
+                    if let Some(forked_id) = app.forked_from_page_id.clone() {
+                        let source_user = app.selected_wiki_user_id.clone();
+                        if source_user != own_user_pk {
+                            let mention_fut =
+                                record_fork_mention(&session_clone, &source_user, &forked_id, page_id);
+                            if let Err(e) = app.rt.block_on(mention_fut) {
+                                log::error!("Failed to record fork mention: {e}");
+                            }
+                        }
+                    }
+
+                    let parent_leaf = app
+                        .forked_from_page_id
+                        .as_deref()
+                        .and_then(|forked_id| app.page_revisions.get(forked_id).copied());
+                    let revision = app.merkle.append(content.as_bytes(), &own_user_pk, parent_leaf);
+                    app.page_revisions.insert(page_id.to_string(), revision.leaf_index);
+                    log::info!(
+                        "Committed revision {} for {} (root {})",
+                        revision.leaf_index,
+                        page_id,
+                        merkle::to_hex(&revision.root)
+                    );
+
+                    // Patch the file cache and search index in place rather
+                    // than triggering a full `needs_refresh`, so creating a
+                    // page in a large wiki doesn't re-fetch and re-index
+                    // every other page.
                     if let Ok(mut state) = state_clone.lock() {
                         if let AuthState::Authenticated {
                             ref session,
                             ref mut file_cache,
+                            ref mut search_index,
                             ..
                         } = *state
                         {
@@ -53,6 +142,7 @@ pub(crate) fn update(app: &mut PubkyApp, session: &PubkySession, _ctx: &Context,
                             let file_url = format!("pubky://{own_user_pk}{wiki_page_path}");
                             let file_title = extract_title(&content);
                             file_cache.insert(file_url, file_title.into());
+                            search_index.upsert(&own_user_pk, page_id, &content);
                         }
                     }
                 }
@@ -64,6 +154,28 @@ pub(crate) fn update(app: &mut PubkyApp, session: &PubkySession, _ctx: &Context,
             app.view_state = ViewState::WikiList;
         }
 
+        ui.add_space(10.0);
+        let attach_button = ui.add_sized(
+            [140.0, 35.0],
+            egui::Button::new(egui::RichText::new("🖼 Attach Image").size(15.0))
+        );
+        if attach_button.clicked() {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("image", &["png", "jpg", "jpeg", "gif", "bmp", "webp"])
+                .pick_file()
+            {
+                match media::upload_image_attachment(session, &app.rt, &app.thumbnail_tx, &path) {
+                    Ok(markdown_link) => {
+                        let insert_at =
+                            media::char_index_to_byte_index(&app.edit_wiki_content, app.edit_wiki_cursor);
+                        app.edit_wiki_content.insert_str(insert_at, &markdown_link);
+                        app.edit_wiki_cursor += markdown_link.chars().count();
+                    }
+                    Err(e) => log::error!("Failed to attach image: {e}"),
+                }
+            }
+        }
+
         ui.add_space(10.0);
         let cancel_button = ui.add_sized(
             [120.0, 35.0],
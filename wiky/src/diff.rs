@@ -0,0 +1,311 @@
+use std::collections::HashSet;
+
+/// One line's classification in a two-way diff between `ours` (the page
+/// currently being viewed) and `theirs` (a selected fork).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DiffLineKind {
+    Equal,
+    /// Present in `theirs` but not `ours`.
+    Insert,
+    /// Present in `ours` but not `theirs`.
+    Delete,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct DiffLine {
+    pub kind: DiffLineKind,
+    pub text: String,
+}
+
+/// A maximal run of consecutive non-`Equal` diff lines: one conflicting
+/// region a user can accept or reject as a unit.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Hunk {
+    /// Indices into the `Vec<DiffLine>` this hunk spans, `start..end`.
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Longest-common-subsequence line diff: a dynamic-programming LCS table,
+/// backtracked into insert/delete/equal runs. `str::lines()` already
+/// normalizes away a trailing-newline mismatch, so identical content that
+/// differs only by a final `\n` diffs as all `Equal` lines (no hunks).
+pub(crate) fn diff_lines(ours: &str, theirs: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = ours.lines().collect();
+    let b: Vec<&str> = theirs.lines().collect();
+    let (n, m) = (a.len(), b.len());
+
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            result.push(DiffLine { kind: DiffLineKind::Equal, text: a[i].to_string() });
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            result.push(DiffLine { kind: DiffLineKind::Delete, text: a[i].to_string() });
+            i += 1;
+        } else {
+            result.push(DiffLine { kind: DiffLineKind::Insert, text: b[j].to_string() });
+            j += 1;
+        }
+    }
+    while i < n {
+        result.push(DiffLine { kind: DiffLineKind::Delete, text: a[i].to_string() });
+        i += 1;
+    }
+    while j < m {
+        result.push(DiffLine { kind: DiffLineKind::Insert, text: b[j].to_string() });
+        j += 1;
+    }
+
+    result
+}
+
+/// Group a diff's consecutive non-`Equal` runs into hunks. Identical
+/// inputs produce no hunks; one side empty produces a single all-insert
+/// or all-delete hunk spanning the whole diff.
+pub(crate) fn hunks(diff: &[DiffLine]) -> Vec<Hunk> {
+    let mut result = Vec::new();
+    let mut start: Option<usize> = None;
+
+    for (idx, line) in diff.iter().enumerate() {
+        match (line.kind == DiffLineKind::Equal, start) {
+            (false, None) => start = Some(idx),
+            (true, Some(s)) => {
+                result.push(Hunk { start: s, end: idx });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        result.push(Hunk { start: s, end: diff.len() });
+    }
+
+    result
+}
+
+/// Build the merged text: `theirs`' version for every hunk index in
+/// `accepted`, `ours` everywhere else.
+pub(crate) fn merge(diff: &[DiffLine], hunks: &[Hunk], accepted: &HashSet<usize>) -> String {
+    let mut take_theirs = vec![false; diff.len()];
+    for (hunk_idx, hunk) in hunks.iter().enumerate() {
+        if accepted.contains(&hunk_idx) {
+            for i in hunk.start..hunk.end {
+                take_theirs[i] = true;
+            }
+        }
+    }
+
+    diff.iter()
+        .enumerate()
+        .filter_map(|(idx, line)| match line.kind {
+            DiffLineKind::Equal => Some(line.text.as_str()),
+            DiffLineKind::Insert if take_theirs[idx] => Some(line.text.as_str()),
+            DiffLineKind::Delete if !take_theirs[idx] => Some(line.text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// One ancestor-line range replaced by a side's edit, in the form
+/// `three_way_merge` aligns against the shared base: `anc_start..anc_end`
+/// is empty (`anc_start == anc_end`) for a pure insertion at that position.
+struct Edit {
+    anc_start: usize,
+    anc_end: usize,
+    lines: Vec<String>,
+}
+
+/// Re-express `diff_lines(ancestor, other)` as a list of ancestor-line-range
+/// replacements instead of a flat insert/delete/equal stream, so
+/// `three_way_merge` can line edits from both sides of a fork up against
+/// the same base.
+fn edits_against_ancestor(ancestor: &str, other: &str) -> Vec<Edit> {
+    let mut edits = Vec::new();
+    let mut anc_idx = 0;
+    let mut current: Option<Edit> = None;
+
+    for line in diff_lines(ancestor, other) {
+        match line.kind {
+            DiffLineKind::Equal => {
+                if let Some(edit) = current.take() {
+                    edits.push(edit);
+                }
+                anc_idx += 1;
+            }
+            DiffLineKind::Delete => {
+                let edit = current.get_or_insert_with(|| Edit { anc_start: anc_idx, anc_end: anc_idx, lines: Vec::new() });
+                anc_idx += 1;
+                edit.anc_end = anc_idx;
+            }
+            DiffLineKind::Insert => {
+                current
+                    .get_or_insert_with(|| Edit { anc_start: anc_idx, anc_end: anc_idx, lines: Vec::new() })
+                    .lines
+                    .push(line.text);
+            }
+        }
+    }
+    if let Some(edit) = current.take() {
+        edits.push(edit);
+    }
+    edits
+}
+
+/// Reconstruct one side's view of `anc_lines[group_start..group_end]` by
+/// splicing that side's edits (already known to fall within the range) over
+/// the ancestor text, falling back to the ancestor's own lines wherever
+/// that side left a gap untouched.
+fn side_view(anc_lines: &[&str], group_start: usize, group_end: usize, edits: &[&Edit]) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut cursor = group_start;
+    for edit in edits {
+        if cursor < edit.anc_start {
+            out.extend(anc_lines[cursor..edit.anc_start].iter().map(|s| s.to_string()));
+        }
+        out.extend(edit.lines.iter().cloned());
+        cursor = edit.anc_end.max(cursor);
+    }
+    if cursor < group_end {
+        out.extend(anc_lines[cursor..group_end].iter().map(|s| s.to_string()));
+    }
+    out
+}
+
+#[derive(Clone, Copy)]
+enum Side {
+    Ours,
+    Theirs,
+}
+
+/// How one segment of a three-way merge's linear view relates to the
+/// common ancestor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum MergeSegmentKind {
+    /// Untouched by either side.
+    Unchanged,
+    /// Changed only on our side; the fork kept the ancestor's text here.
+    OursOnly,
+    /// Changed only on the fork's side; we kept the ancestor's text here.
+    TheirsOnly,
+    /// Changed on both sides, to different text — needs a pick.
+    Conflict,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct MergeSegment {
+    pub kind: MergeSegmentKind,
+    pub ancestor_lines: Vec<String>,
+    pub ours_lines: Vec<String>,
+    pub theirs_lines: Vec<String>,
+}
+
+/// Three-way (diff3-style) merge of `ours` and `theirs` against their
+/// common `ancestor`: diff each side against the ancestor independently,
+/// then walk both edit lists in ancestor-line order, merging any
+/// overlapping edited ranges into a single `Conflict` segment. Two edits
+/// that happen to produce identical text are treated as `OursOnly` rather
+/// than a conflict — both sides already agree, there is nothing to pick.
+pub(crate) fn three_way_merge(ancestor: &str, ours: &str, theirs: &str) -> Vec<MergeSegment> {
+    let anc_lines: Vec<&str> = ancestor.lines().collect();
+    let ours_edits = edits_against_ancestor(ancestor, ours);
+    let theirs_edits = edits_against_ancestor(ancestor, theirs);
+
+    let mut tagged: Vec<(Side, &Edit)> = Vec::with_capacity(ours_edits.len() + theirs_edits.len());
+    tagged.extend(ours_edits.iter().map(|e| (Side::Ours, e)));
+    tagged.extend(theirs_edits.iter().map(|e| (Side::Theirs, e)));
+    tagged.sort_by_key(|(_, e)| e.anc_start);
+
+    let mut segments = Vec::new();
+    let mut anc_idx = 0;
+    let mut i = 0;
+
+    while i < tagged.len() {
+        let group_start = tagged[i].1.anc_start;
+        if anc_idx < group_start {
+            segments.push(unchanged_segment(&anc_lines[anc_idx..group_start]));
+        }
+
+        let mut group_end = tagged[i].1.anc_end;
+        let mut j = i + 1;
+        // Edits starting at the same ancestor line (e.g. both sides
+        // inserting there) share a group even when their ranges are empty.
+        while j < tagged.len() && (tagged[j].1.anc_start < group_end || tagged[j].1.anc_start == group_start) {
+            group_end = group_end.max(tagged[j].1.anc_end);
+            j += 1;
+        }
+
+        let ours_in_group: Vec<&Edit> = tagged[i..j].iter().filter(|(side, _)| matches!(side, Side::Ours)).map(|(_, e)| *e).collect();
+        let theirs_in_group: Vec<&Edit> = tagged[i..j].iter().filter(|(side, _)| matches!(side, Side::Theirs)).map(|(_, e)| *e).collect();
+
+        let ancestor_lines: Vec<String> = anc_lines[group_start..group_end].iter().map(|s| s.to_string()).collect();
+        let ours_lines = side_view(&anc_lines, group_start, group_end, &ours_in_group);
+        let theirs_lines = side_view(&anc_lines, group_start, group_end, &theirs_in_group);
+
+        let kind = match (ours_in_group.is_empty(), theirs_in_group.is_empty()) {
+            (false, true) => MergeSegmentKind::OursOnly,
+            (true, false) => MergeSegmentKind::TheirsOnly,
+            (false, false) if ours_lines == theirs_lines => MergeSegmentKind::OursOnly,
+            (false, false) => MergeSegmentKind::Conflict,
+            (true, true) => unreachable!("every group has at least one edit"),
+        };
+
+        segments.push(MergeSegment { kind, ancestor_lines, ours_lines, theirs_lines });
+        anc_idx = group_end;
+        i = j;
+    }
+
+    if anc_idx < anc_lines.len() {
+        segments.push(unchanged_segment(&anc_lines[anc_idx..]));
+    }
+
+    segments
+}
+
+fn unchanged_segment(lines: &[&str]) -> MergeSegment {
+    let owned: Vec<String> = lines.iter().map(|s| s.to_string()).collect();
+    MergeSegment {
+        kind: MergeSegmentKind::Unchanged,
+        ancestor_lines: owned.clone(),
+        ours_lines: owned.clone(),
+        theirs_lines: owned,
+    }
+}
+
+/// Build the merged text from a three-way merge's segments: for each
+/// `Conflict` segment whose index is in `accepted`, take `theirs`; `ours`
+/// for every other conflict; each side's own text for `OursOnly`/
+/// `TheirsOnly`; the shared text for `Unchanged`.
+pub(crate) fn merge3(segments: &[MergeSegment], accepted: &HashSet<usize>) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for (idx, segment) in segments.iter().enumerate() {
+        let chosen = match segment.kind {
+            MergeSegmentKind::Unchanged => &segment.ancestor_lines,
+            MergeSegmentKind::OursOnly => &segment.ours_lines,
+            MergeSegmentKind::TheirsOnly => &segment.theirs_lines,
+            MergeSegmentKind::Conflict => {
+                if accepted.contains(&idx) {
+                    &segment.theirs_lines
+                } else {
+                    &segment.ours_lines
+                }
+            }
+        };
+        lines.extend(chosen.iter().map(|s| s.as_str()));
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,171 @@
+use crate::{diff, DiffState, PubkyApp, ViewState};
+
+use eframe::egui::{Color32, Context, RichText, ScrollArea, Ui};
+use pubky::PubkySession;
+
+/// Guided merge between a root page and a selected fork: a two-way
+/// line-level diff with per-hunk accept/reject controls, or — when this
+/// session recorded the fork's common ancestor — a three-way merge that
+/// auto-applies non-conflicting changes and only asks for a pick on hunks
+/// both sides touched.
+pub(crate) fn update(app: &mut PubkyApp, session: &PubkySession, _ctx: &Context, ui: &mut Ui) {
+    ui.label(RichText::new("Diff / Merge").size(20.0).strong());
+    ui.add_space(10.0);
+    ui.label(
+        RichText::new(format!(
+            "{}/{}  vs fork  {}/{}",
+            app.selected_wiki_user_id,
+            app.selected_wiki_page_id,
+            app.diff_target_user,
+            app.diff_target_page
+        ))
+        .monospace(),
+    );
+    ui.add_space(20.0);
+
+    let state = app.diff_state.lock().unwrap().clone();
+
+    match state {
+        DiffState::Idle => {
+            ui.label("Nothing to diff yet.");
+        }
+        DiffState::Loading => {
+            ui.spinner();
+            ui.label("Fetching both pages...");
+        }
+        DiffState::Error(ref message) => {
+            ui.colored_label(Color32::RED, message);
+        }
+        DiffState::Ready { diff, hunks, accepted } => {
+            if hunks.is_empty() {
+                ui.label(
+                    RichText::new("No differences — the fork matches this page.")
+                        .italics()
+                        .color(Color32::GRAY),
+                );
+            } else {
+                let mut new_accepted = accepted.clone();
+
+                ScrollArea::vertical().max_height(350.0).show(ui, |ui| {
+                    for (hunk_idx, hunk) in hunks.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Hunk {}", hunk_idx + 1));
+                            if accepted.contains(&hunk_idx) {
+                                if ui.button("↺ Keep mine").clicked() {
+                                    new_accepted.remove(&hunk_idx);
+                                }
+                            } else if ui.button("✓ Accept fork's version").clicked() {
+                                new_accepted.insert(hunk_idx);
+                            }
+                        });
+
+                        for line in &diff[hunk.start..hunk.end] {
+                            let (prefix, color) = match line.kind {
+                                diff::DiffLineKind::Delete => ("- ", Color32::from_rgb(200, 80, 80)),
+                                diff::DiffLineKind::Insert => ("+ ", Color32::from_rgb(80, 180, 90)),
+                                diff::DiffLineKind::Equal => ("  ", Color32::GRAY),
+                            };
+                            ui.label(RichText::new(format!("{prefix}{}", line.text)).monospace().color(color));
+                        }
+                        ui.separator();
+                    }
+                });
+
+                if new_accepted != accepted {
+                    *app.diff_state.lock().unwrap() =
+                        DiffState::Ready { diff: diff.clone(), hunks: hunks.clone(), accepted: new_accepted };
+                }
+
+                ui.add_space(15.0);
+                if ui.button("📝 Load Merge into Editor").clicked() {
+                    let merged = diff::merge(&diff, &hunks, &accepted);
+                    app.edit_wiki_content = merged;
+
+                    let own_pk = session.info().public_key().to_string();
+                    if app.selected_wiki_user_id == own_pk {
+                        app.view_state = ViewState::EditWiki;
+                    } else {
+                        app.forked_from_page_id = Some(app.selected_wiki_page_id.clone());
+                        app.view_state = ViewState::CreateWiki;
+                    }
+                }
+            }
+        }
+        DiffState::ReadyMerge3 { segments, accepted } => {
+            ui.label(
+                RichText::new("Three-way merge against the common ancestor recorded when this fork was made.")
+                    .italics()
+                    .color(Color32::GRAY),
+            );
+            ui.add_space(10.0);
+
+            let mut new_accepted = accepted.clone();
+
+            ScrollArea::vertical().max_height(350.0).show(ui, |ui| {
+                for (seg_idx, segment) in segments.iter().enumerate() {
+                    match segment.kind {
+                        diff::MergeSegmentKind::Unchanged => {
+                            for line in &segment.ancestor_lines {
+                                ui.label(RichText::new(format!("  {line}")).monospace().color(Color32::GRAY));
+                            }
+                        }
+                        diff::MergeSegmentKind::OursOnly => {
+                            for line in &segment.ours_lines {
+                                ui.label(RichText::new(format!("= {line}")).monospace().color(Color32::from_rgb(80, 180, 90)));
+                            }
+                        }
+                        diff::MergeSegmentKind::TheirsOnly => {
+                            for line in &segment.theirs_lines {
+                                ui.label(RichText::new(format!("= {line}")).monospace().color(Color32::from_rgb(80, 180, 90)));
+                            }
+                        }
+                        diff::MergeSegmentKind::Conflict => {
+                            ui.horizontal(|ui| {
+                                ui.colored_label(Color32::from_rgb(220, 160, 40), format!("⚠ Conflict {}", seg_idx + 1));
+                                if accepted.contains(&seg_idx) {
+                                    if ui.button("↺ Keep mine").clicked() {
+                                        new_accepted.remove(&seg_idx);
+                                    }
+                                } else if ui.button("✓ Accept fork's version").clicked() {
+                                    new_accepted.insert(seg_idx);
+                                }
+                            });
+                            for line in &segment.ours_lines {
+                                ui.label(RichText::new(format!("- {line}")).monospace().color(Color32::from_rgb(200, 80, 80)));
+                            }
+                            for line in &segment.theirs_lines {
+                                ui.label(RichText::new(format!("+ {line}")).monospace().color(Color32::from_rgb(80, 180, 90)));
+                            }
+                        }
+                    }
+                    if segment.kind != diff::MergeSegmentKind::Unchanged {
+                        ui.separator();
+                    }
+                }
+            });
+
+            if new_accepted != accepted {
+                *app.diff_state.lock().unwrap() = DiffState::ReadyMerge3 { segments: segments.clone(), accepted: new_accepted };
+            }
+
+            ui.add_space(15.0);
+            if ui.button("📝 Load Merge into Editor").clicked() {
+                let merged = diff::merge3(&segments, &accepted);
+                app.edit_wiki_content = merged;
+
+                let own_pk = session.info().public_key().to_string();
+                if app.selected_wiki_user_id == own_pk {
+                    app.view_state = ViewState::EditWiki;
+                } else {
+                    app.forked_from_page_id = Some(app.selected_wiki_page_id.clone());
+                    app.view_state = ViewState::CreateWiki;
+                }
+            }
+        }
+    }
+
+    ui.add_space(20.0);
+    if ui.button("← Back").clicked() {
+        app.view_state = ViewState::ViewWiki;
+    }
+}
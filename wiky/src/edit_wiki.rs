@@ -1,59 +1,146 @@
-use crate::{delete_wiki_post, update_wiki_post, AuthState, PubkyApp, ViewState};
+use crate::{
+    append_wiki_op, delete_wiki_post, encrypted_content, fetch_remote_wiki_ops, i18n::tr, media,
+    merkle, save_wiki_post, utils::extract_title, AuthState, PubkyApp, ViewState,
+};
 
 use eframe::egui::{Context, Ui};
-use pubky::PubkySession;
+use pubky::{PubkySession, PublicStorage};
 
-pub(crate) fn update(app: &mut PubkyApp, session: &PubkySession, _ctx: &Context, ui: &mut Ui) {
-    ui.label(egui::RichText::new("Edit Wiki Page").size(20.0).strong());
+pub(crate) fn update(
+    app: &mut PubkyApp,
+    session: &PubkySession,
+    pub_storage: &PublicStorage,
+    _ctx: &Context,
+    ui: &mut Ui,
+) {
+    ui.label(egui::RichText::new(tr!(app, "edit-wiki-title")).size(20.0).strong());
     ui.add_space(25.0);
 
     // Textarea for wiki content
-    ui.label(egui::RichText::new("Content:").size(16.0));
+    ui.label(egui::RichText::new(tr!(app, "content-label")).size(16.0));
     ui.add_space(12.0);
 
-    egui::ScrollArea::vertical()
+    let text_edit_output = egui::ScrollArea::vertical()
         .max_height(400.0)
         .show(ui, |ui| {
-            ui.add(
-                egui::TextEdit::multiline(&mut app.edit_wiki_content)
-                    .desired_width(f32::INFINITY)
-                    .desired_rows(15)
-                    .font(egui::TextStyle::Monospace),
-            );
-        });
+            egui::TextEdit::multiline(&mut app.edit_wiki_content)
+                .desired_width(f32::INFINITY)
+                .desired_rows(15)
+                .font(egui::TextStyle::Monospace)
+                .show(ui)
+        })
+        .inner;
+
+    if let Some(cursor_range) = text_edit_output.cursor_range {
+        app.edit_wiki_cursor = cursor_range.primary.index;
+    }
+
+    // Fold whatever the edit above changed into the local CRDT document as
+    // insert/delete ops (a no-op when nothing changed this frame), so a
+    // concurrent editor's merged ops never clobber this session's edits.
+    app.edit_doc.sync_from_text(&app.edit_wiki_content);
+
+    ui.add_space(10.0);
+    ui.checkbox(&mut app.encrypt_content, tr!(app, "encrypt-content"));
 
     ui.add_space(25.0);
 
     ui.horizontal(|ui| {
         let update_button = ui.add_sized(
             [120.0, 35.0],
-            egui::Button::new(egui::RichText::new("✓ Update").size(15.0))
+            egui::Button::new(egui::RichText::new(tr!(app, "update")).size(15.0))
         );
         if update_button.clicked() {
             let session_clone = session.clone();
             let content = app.edit_wiki_content.clone();
             let page_id = app.selected_wiki_page_id.clone();
+            let own_user_pk = session.info().public_key().to_string();
+
+            // Log this session's CRDT ops alongside the whole-document
+            // write, so other sites editing the same page can replay them
+            // instead of only ever seeing this snapshot overwrite theirs.
+            for op in app.edit_doc.take_outbox() {
+                if let Err(e) = app.rt.block_on(append_wiki_op(&session_clone, &page_id, &op)) {
+                    log::error!("Failed to append wiki op: {e}");
+                }
+            }
+
+            let stored_bytes: Vec<u8> = if app.encrypt_content {
+                match encrypted_content::seal(own_user_pk.as_bytes(), &own_user_pk, content.as_bytes()) {
+                    Ok(sealed) => sealed,
+                    Err(e) => {
+                        log::error!("Failed to encrypt wiki content: {e}");
+                        content.clone().into_bytes()
+                    }
+                }
+            } else {
+                content.clone().into_bytes()
+            };
 
-            let update_wiki_post_fut = update_wiki_post(&session_clone, &page_id, &content);
-            match app.rt.block_on(update_wiki_post_fut) {
+            let save_wiki_post_fut = save_wiki_post(&session_clone, Some(&page_id), stored_bytes);
+            match app.rt.block_on(save_wiki_post_fut) {
                 Ok(_) => {
                     log::info!("Updated wiki post: {}", page_id);
+
+                    let own_user_pk = session.info().public_key().to_string();
+                    let parent_leaf = app.page_revisions.get(&page_id).copied();
+                    let revision = app.merkle.append(content.as_bytes(), &own_user_pk, parent_leaf);
+                    app.page_revisions.insert(page_id.clone(), revision.leaf_index);
+                    log::info!(
+                        "Committed revision {} for {} (root {})",
+                        revision.leaf_index,
+                        page_id,
+                        merkle::to_hex(&revision.root)
+                    );
+
                     // Update the selected content to reflect changes
-                    app.selected_wiki_content = content;
+                    app.selected_wiki_content = content.clone();
+
+                    // Patch the file cache and search index for this one
+                    // page in place, rather than setting `needs_refresh`,
+                    // so editing a page in a large wiki doesn't re-fetch and
+                    // re-index every other page.
+                    if let Ok(mut state) = app.state.lock() {
+                        if let AuthState::Authenticated { ref mut file_cache, ref mut search_index, .. } = *state {
+                            let file_url = format!("pubky://{own_user_pk}/pub/wiki.app/{page_id}");
+                            file_cache.insert(file_url, extract_title(&content).into());
+                            search_index.upsert(&own_user_pk, &page_id, &content);
+                        }
+                    }
                 }
                 Err(e) => log::error!("Failed to update wiki post: {e}"),
             }
 
             app.edit_wiki_content.clear();
             app.view_state = ViewState::WikiList;
-            app.needs_refresh = true;
+        }
+
+        ui.add_space(10.0);
+        let sync_button = ui.add_sized(
+            [100.0, 35.0],
+            egui::Button::new(egui::RichText::new(tr!(app, "sync")).size(15.0))
+        );
+        if sync_button.clicked() {
+            let page_id = app.selected_wiki_page_id.clone();
+            let owner_pk = app.selected_wiki_user_id.clone();
+            let own_site = app.site_id;
+
+            for op in app.edit_doc.take_outbox() {
+                if let Err(e) = app.rt.block_on(append_wiki_op(session, &page_id, &op)) {
+                    log::error!("Failed to append wiki op: {e}");
+                }
+            }
+
+            let remote_ops = fetch_remote_wiki_ops(pub_storage, &app.rt, &owner_pk, &page_id, own_site);
+            app.edit_doc.apply_all(remote_ops);
+            app.edit_wiki_content = app.edit_doc.visible_text();
         }
 
         ui.add_space(10.0);
         // Delete button for editing existing page
         let delete_button = ui.add_sized(
             [120.0, 35.0],
-            egui::Button::new(egui::RichText::new("🗑 Delete").size(15.0).color(egui::Color32::from_rgb(200, 80, 80)))
+            egui::Button::new(egui::RichText::new(tr!(app, "delete")).size(15.0).color(egui::Color32::from_rgb(200, 80, 80)))
         );
         if delete_button.clicked() {
             let session_clone = session.clone();
@@ -90,10 +177,32 @@ pub(crate) fn update(app: &mut PubkyApp, session: &PubkySession, _ctx: &Context,
             app.needs_refresh = true;
         }
 
+        ui.add_space(10.0);
+        let attach_button = ui.add_sized(
+            [140.0, 35.0],
+            egui::Button::new(egui::RichText::new(tr!(app, "attach-image")).size(15.0))
+        );
+        if attach_button.clicked() {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("image", &["png", "jpg", "jpeg", "gif", "bmp", "webp"])
+                .pick_file()
+            {
+                match media::upload_image_attachment(session, &app.rt, &app.thumbnail_tx, &path) {
+                    Ok(markdown_link) => {
+                        let insert_at =
+                            media::char_index_to_byte_index(&app.edit_wiki_content, app.edit_wiki_cursor);
+                        app.edit_wiki_content.insert_str(insert_at, &markdown_link);
+                        app.edit_wiki_cursor += markdown_link.chars().count();
+                    }
+                    Err(e) => log::error!("Failed to attach image: {e}"),
+                }
+            }
+        }
+
         ui.add_space(10.0);
         let cancel_button = ui.add_sized(
             [120.0, 35.0],
-            egui::Button::new(egui::RichText::new("Cancel").size(15.0))
+            egui::Button::new(egui::RichText::new(tr!(app, "cancel")).size(15.0))
         );
         if cancel_button.clicked() {
             app.edit_wiki_content.clear();
@@ -0,0 +1,156 @@
+//! RFC 8188 ("Encrypted Content-Encoding for HTTP") aes128gcm framing for
+//! wiki page bodies.
+//!
+//! Pages are stored as plaintext at `pubky://.../pub/wiki.app/...`. This gives
+//! `edit_wiki_content` an opt-in path to seal the body before
+//! `save_wiki_post` writes it, and the inverse path for the reader, so
+//! shared/collaborative pages can carry confidential content without
+//! changing the storage transport.
+
+use aes_gcm::aead::{generic_array::GenericArray, Aead, KeyInit, Payload};
+use aes_gcm::Aes128Gcm;
+use anyhow::{anyhow, Result};
+use hkdf::Hkdf;
+use rand::RngCore;
+use sha2::Sha256;
+
+const SALT_LEN: usize = 16;
+const TAG_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+/// Default record size: plenty for a single wiki page body in one record,
+/// while still exercising the multi-record path for longer pages.
+const DEFAULT_RECORD_SIZE: u32 = 4096;
+
+/// Seal `plaintext` per RFC 8188 aes128gcm, deriving the content-encryption
+/// key and nonce from `ikm` (input keying material, e.g. derived from the
+/// author's keypair) and a fresh random salt. `keyid` is stored in the
+/// header so an authorized reader knows which key to use to decrypt.
+pub fn seal(ikm: &[u8], keyid: &str, plaintext: &[u8]) -> Result<Vec<u8>> {
+    seal_with_record_size(ikm, keyid, plaintext, DEFAULT_RECORD_SIZE)
+}
+
+fn seal_with_record_size(ikm: &[u8], keyid: &str, plaintext: &[u8], record_size: u32) -> Result<Vec<u8>> {
+    if keyid.len() > u8::MAX as usize {
+        return Err(anyhow!("keyid too long for RFC 8188 header"));
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+
+    let (cek, nonce_base) = derive_key_and_nonce_base(ikm, &salt, keyid.as_bytes())?;
+    let cipher = Aes128Gcm::new(GenericArray::from_slice(&cek));
+
+    let mut out = Vec::new();
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&record_size.to_be_bytes());
+    out.push(keyid.len() as u8);
+    out.extend_from_slice(keyid.as_bytes());
+
+    // Plaintext is padded with a single 0x01 (more records follow) or 0x02
+    // (last record) delimiter byte before the AEAD tag, per RFC 8188 section 2.
+    let plaintext_record_len = record_size as usize - TAG_LEN - 1;
+    let chunks: Vec<&[u8]> = if plaintext.is_empty() {
+        vec![&[]]
+    } else {
+        plaintext.chunks(plaintext_record_len).collect()
+    };
+
+    for (index, chunk) in chunks.iter().enumerate() {
+        let is_last = index + 1 == chunks.len();
+        let mut record = chunk.to_vec();
+        record.push(if is_last { 0x02 } else { 0x01 });
+
+        let nonce = record_nonce(&nonce_base, index as u64);
+        let sealed = cipher
+            .encrypt(GenericArray::from_slice(&nonce), Payload { msg: &record, aad: &[] })
+            .map_err(|_| anyhow!("AES-128-GCM seal failed"))?;
+        out.extend_from_slice(&sealed);
+    }
+
+    Ok(out)
+}
+
+/// Inverse of [`seal`]: parse the RFC 8188 header, re-derive the CEK/nonce
+/// from `ikm`, and authenticate + decrypt every record back into plaintext.
+pub fn open(ikm: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+    if ciphertext.len() < SALT_LEN + 4 + 1 {
+        return Err(anyhow!("ciphertext shorter than RFC 8188 header"));
+    }
+
+    let salt = &ciphertext[0..SALT_LEN];
+    let record_size = u32::from_be_bytes(ciphertext[SALT_LEN..SALT_LEN + 4].try_into().unwrap());
+    let idlen = ciphertext[SALT_LEN + 4] as usize;
+    let header_len = SALT_LEN + 4 + 1 + idlen;
+    if ciphertext.len() < header_len {
+        return Err(anyhow!("ciphertext truncated before end of keyid"));
+    }
+    let keyid = &ciphertext[SALT_LEN + 4 + 1..header_len];
+
+    let (cek, nonce_base) = derive_key_and_nonce_base(ikm, salt, keyid)?;
+    let cipher = Aes128Gcm::new(GenericArray::from_slice(&cek));
+
+    let mut plaintext = Vec::new();
+    let body = &ciphertext[header_len..];
+    let mut offset = 0usize;
+    let mut index = 0u64;
+
+    while offset < body.len() {
+        let record_len = (record_size as usize).min(body.len() - offset);
+        let record = &body[offset..offset + record_len];
+        offset += record_len;
+
+        let nonce = record_nonce(&nonce_base, index);
+        let opened = cipher
+            .decrypt(GenericArray::from_slice(&nonce), Payload { msg: record, aad: &[] })
+            .map_err(|_| anyhow!("AES-128-GCM authentication failed for record {index}"))?;
+
+        let delimiter_pos = opened
+            .iter()
+            .rposition(|&b| b != 0)
+            .ok_or_else(|| anyhow!("record {index} missing delimiter"))?;
+        let delimiter = opened[delimiter_pos];
+        let is_last = match delimiter {
+            0x01 => false,
+            0x02 => true,
+            _ => return Err(anyhow!("record {index} has invalid delimiter {delimiter:#x}")),
+        };
+        plaintext.extend_from_slice(&opened[..delimiter_pos]);
+
+        if is_last && offset != body.len() {
+            return Err(anyhow!("last-record delimiter seen before end of ciphertext"));
+        }
+        index += 1;
+    }
+
+    Ok(plaintext)
+}
+
+/// HKDF-SHA256 derivation of the content-encryption key and the nonce base
+/// (the per-message nonce is this base XORed with the big-endian record
+/// sequence number), per RFC 8188 section 2.1-2.2.
+fn derive_key_and_nonce_base(ikm: &[u8], salt: &[u8], keyid: &[u8]) -> Result<([u8; 16], [u8; NONCE_LEN])> {
+    let hk = Hkdf::<Sha256>::new(Some(salt), ikm);
+
+    let mut cek_info = b"Content-Encoding: aes128gcm\0".to_vec();
+    cek_info.extend_from_slice(keyid);
+    let mut cek = [0u8; 16];
+    hk.expand(&cek_info, &mut cek)
+        .map_err(|_| anyhow!("HKDF expand failed for content-encryption key"))?;
+
+    let mut nonce_info = b"Content-Encoding: nonce\0".to_vec();
+    nonce_info.extend_from_slice(keyid);
+    let mut nonce_base = [0u8; NONCE_LEN];
+    hk.expand(&nonce_info, &mut nonce_base)
+        .map_err(|_| anyhow!("HKDF expand failed for nonce base"))?;
+
+    Ok((cek, nonce_base))
+}
+
+fn record_nonce(nonce_base: &[u8; NONCE_LEN], sequence: u64) -> [u8; NONCE_LEN] {
+    let mut nonce = *nonce_base;
+    let seq_bytes = sequence.to_be_bytes();
+    for i in 0..8 {
+        nonce[NONCE_LEN - 8 + i] ^= seq_bytes[i];
+    }
+    nonce
+}
@@ -6,14 +6,27 @@ use std::{
 use anyhow::{anyhow, Result};
 use eframe::egui;
 use egui_commonmark::*;
-use pubky::{Capabilities, Pubky, PubkyAuthFlow, PubkySession, PublicStorage};
+use pubky::{Capabilities, Pubky, PubkyAuthFlow, PubkyResource, PubkySession, PublicStorage};
+use serde::{Deserialize, Serialize};
 use tokio::runtime::Runtime;
 use uuid::Uuid;
 
+use crate::merkle::MerkleAccumulator;
 use crate::utils::{extract_title, generate_qr_image, get_list};
 
+mod archive;
 mod create_wiki;
+mod diff;
+mod diff_view;
 mod edit_wiki;
+mod encrypted_content;
+mod i18n;
+mod markdown_preview;
+mod media;
+mod merkle;
+mod rga;
+mod search;
+mod session_store;
 mod utils;
 mod view_wiki;
 
@@ -68,6 +81,9 @@ fn load_logo_image() -> Option<egui::ColorImage> {
 #[derive(Clone)]
 pub(crate) enum AuthState {
     Initializing,
+    /// Attempting to resume a session saved by a previous run before
+    /// falling back to a fresh QR login.
+    Restoring,
     ShowingQR {
         auth_url: String,
     },
@@ -76,6 +92,29 @@ pub(crate) enum AuthState {
         pub_storage: PublicStorage,
         /// Map file URL to file title
         file_cache: HashMap<String, String>,
+        /// Inverted index over this account's (and, best-effort, followed
+        /// authors') page bodies, feeding the search box above the list.
+        search_index: search::SearchIndex,
+    },
+    Error(String),
+}
+
+/// Progress of a background fetch-and-diff kicked off by
+/// `navigate_to_diff_view`.
+#[derive(Clone)]
+pub(crate) enum DiffState {
+    Idle,
+    Loading,
+    Ready {
+        diff: Vec<diff::DiffLine>,
+        hunks: Vec<diff::Hunk>,
+        accepted: std::collections::HashSet<usize>,
+    },
+    /// Three-way merge against a common ancestor, available only when this
+    /// session itself forked the page (see `PubkyApp::fork_ancestors`).
+    ReadyMerge3 {
+        segments: Vec<diff::MergeSegment>,
+        accepted: std::collections::HashSet<usize>,
     },
     Error(String),
 }
@@ -86,6 +125,7 @@ pub(crate) enum ViewState {
     CreateWiki,
     ViewWiki,
     EditWiki,
+    DiffMerge,
 }
 
 pub(crate) struct PubkyApp {
@@ -96,21 +136,75 @@ pub(crate) struct PubkyApp {
     pub(crate) view_state: ViewState,
     /// Content for the Edit Wiki view
     pub(crate) edit_wiki_content: String,
+    /// Character index of the last known cursor position in the content
+    /// editor, so "Attach Image" can insert its markdown link there.
+    pub(crate) edit_wiki_cursor: usize,
+    /// Feeds thumbnail encode jobs to the background worker spawned by
+    /// `media::spawn_thumbnail_worker`.
+    pub(crate) thumbnail_tx: std::sync::mpsc::Sender<media::ThumbnailJob>,
+    /// Query typed into the WikiList search box
+    pub(crate) wiki_search_query: String,
+    /// How to handle page id collisions on the next "Import Wiki"
+    pub(crate) import_collision_policy: archive::CollisionPolicy,
+    /// Result message from the last export/import, shown under the buttons
+    pub(crate) archive_status: Option<String>,
     pub(crate) selected_wiki_fork_urls: Vec<String>,
+    /// Forks of the selected page by people we follow, discovered via their
+    /// mention records rather than our own forks
+    pub(crate) selected_wiki_inbound_forks: Vec<ForkMention>,
     pub(crate) selected_wiki_page_id: String,
     pub(crate) selected_wiki_content: String,
     pub(crate) selected_wiki_user_id: String,
+    /// Fork currently being compared to the selected page in the
+    /// DiffMerge view
+    pub(crate) diff_target_user: String,
+    pub(crate) diff_target_page: String,
+    pub(crate) diff_state: Arc<Mutex<DiffState>>,
     pub(crate) needs_refresh: bool,
     cache: CommonMarkCache,
+    /// Whether the Create/Edit views show a split-pane Markdown preview
+    /// alongside the editor.
+    pub(crate) preview_enabled: bool,
+    preview_highlight_cache: markdown_preview::HighlightCache,
     rt: Arc<Runtime>,
     pub(crate) show_copy_tooltip: bool,
     /// Page ID from which content is being forked (when forking)
     pub(crate) forked_from_page_id: Option<String>,
+    /// Append-only Merkle history of every revision this app has committed
+    pub(crate) merkle: MerkleAccumulator,
+    /// Most recent Merkle leaf index committed for each page id
+    pub(crate) page_revisions: HashMap<String, usize>,
+    /// Whether to seal new/updated page content with RFC 8188 aes128gcm
+    pub(crate) encrypt_content: bool,
+    /// This process's site id for the RGA sequence CRDT backing
+    /// collaborative editing, picked once at startup.
+    pub(crate) site_id: u64,
+    /// Live CRDT state for the page in the Edit Wiki view; reset from the
+    /// stored page content whenever a new page is opened for editing.
+    pub(crate) edit_doc: rga::Doc,
+    /// Resolves UI strings for the currently selected language.
+    pub(crate) localizer: i18n::Localizer,
+    /// Snapshot of a page's content captured the moment this session forked
+    /// it, keyed by page id (forks keep their root's page id, see
+    /// `discover_fork_urls`). Forks live in each author's own storage with
+    /// no shared revision history, so this in-memory snapshot is the only
+    /// place a genuine common ancestor is available for three-way merge —
+    /// forks made outside this session fall back to a two-way diff.
+    pub(crate) fork_ancestors: HashMap<String, String>,
+    /// Sending half of `content_fetch_rx`, cloned into each `execute`d fetch
+    /// so it can hand its result back without blocking the render thread.
+    pub(crate) content_fetch_tx: std::sync::mpsc::Sender<(String, String)>,
+    /// Receives `(page_id, content)` from in-flight `ViewWiki` fetches,
+    /// drained once per frame in `update()`.
+    content_fetch_rx: std::sync::mpsc::Receiver<(String, String)>,
+    /// Page id of the fetch currently in flight, if any, so a response for a
+    /// page the user has since navigated away from is discarded.
+    pub(crate) pending_content_fetch: Option<String>,
 }
 
 impl PubkyApp {
     fn new(rt: Runtime) -> Self {
-        let state = Arc::new(Mutex::new(AuthState::Initializing));
+        let state = Arc::new(Mutex::new(AuthState::Restoring));
 
         // Start the auth flow in a background task
         let state_clone = state.clone();
@@ -118,40 +212,35 @@ impl PubkyApp {
         let rt_arc = Arc::new(rt);
         let rt_arc_clone = rt_arc.clone();
         std::thread::spawn(move || {
-            let initialize_auth_fut = initialize_auth();
-            match rt_arc_clone.block_on(initialize_auth_fut) {
-                Ok((pubky, flow, auth_url)) => {
-                    *state_clone.lock().unwrap() = AuthState::ShowingQR {
-                        auth_url: auth_url.clone(),
-                    };
-
-                    // Poll for authentication
-                    let await_approval_fut = flow.await_approval();
-                    match rt_arc_clone.block_on(await_approval_fut) {
-                        Ok(session) => {
-                            Self::fetch_files_and_update(
-                                &session,
-                                &pubky.public_storage(),
-                                rt_arc_clone,
-                                state_clone,
-                            );
-                        }
-                        Err(e) => {
-                            *state_clone.lock().unwrap() =
-                                AuthState::Error(format!("Authentication failed: {e}"));
-                        }
-                    }
-                }
-                Err(e) => {
-                    *state_clone.lock().unwrap() =
-                        AuthState::Error(format!("Failed to initialize: {e}"));
-                }
+            let restore_fut = async {
+                let pubky = Pubky::new()?;
+                let session = session_store::load_session(&pubky).await?;
+                anyhow::Ok((pubky, session))
+            };
+
+            if let Ok((pubky, Some(session))) = rt_arc_clone.block_on(restore_fut) {
+                log::info!("Restored saved session for {}", session.info().public_key());
+                Self::fetch_files_and_update(
+                    &session,
+                    &pubky.public_storage(),
+                    rt_arc_clone,
+                    state_clone,
+                );
+                return;
             }
+
+            // Nothing saved, or the homeserver no longer accepts it — fall
+            // back to a fresh QR login.
+            Self::spawn_qr_auth_flow(rt_arc_clone, state_clone);
         });
 
         // Load logo image
         let logo_image = load_logo_image();
 
+        let thumbnail_tx = media::spawn_thumbnail_worker(rt_arc.clone());
+        let site_id = rand::random::<u64>();
+        let (content_fetch_tx, content_fetch_rx) = std::sync::mpsc::channel();
+
         Self {
             state,
             qr_texture: None,
@@ -159,15 +248,84 @@ impl PubkyApp {
             logo_image,
             view_state: ViewState::WikiList,
             edit_wiki_content: String::new(),
+            edit_wiki_cursor: 0,
+            thumbnail_tx,
+            wiki_search_query: String::new(),
+            import_collision_policy: archive::CollisionPolicy::Skip,
+            archive_status: None,
             selected_wiki_page_id: String::new(),
             selected_wiki_content: String::new(),
             selected_wiki_user_id: String::new(),
             selected_wiki_fork_urls: vec![],
+            selected_wiki_inbound_forks: vec![],
+            diff_target_user: String::new(),
+            diff_target_page: String::new(),
+            diff_state: Arc::new(Mutex::new(DiffState::Idle)),
             needs_refresh: false,
             cache: CommonMarkCache::default(),
+            preview_enabled: false,
+            preview_highlight_cache: markdown_preview::HighlightCache::default(),
             rt: rt_arc,
             show_copy_tooltip: false,
             forked_from_page_id: None,
+            merkle: MerkleAccumulator::new(),
+            page_revisions: HashMap::new(),
+            encrypt_content: false,
+            site_id,
+            edit_doc: rga::Doc::new(site_id),
+            localizer: i18n::Localizer::new("en-US"),
+            fork_ancestors: HashMap::new(),
+            content_fetch_tx,
+            content_fetch_rx,
+            pending_content_fetch: None,
+        }
+    }
+
+    /// Spawns `future` on the shared runtime instead of blocking the calling
+    /// thread or opening a new OS thread per call. Any result it produces
+    /// should travel back to the UI over a channel polled once per frame,
+    /// e.g. `content_fetch_tx`/`content_fetch_rx`.
+    pub(crate) fn execute<F>(&self, future: F)
+    where
+        F: std::future::Future<Output = ()> + Send + 'static,
+    {
+        self.rt.spawn(future);
+    }
+
+    /// Runs the QR-based `PubkyAuthFlow` to completion, driving `state_clone`
+    /// through `Initializing` -> `ShowingQR` -> `Authenticated`/`Error`.
+    /// Must be called on a background thread — it blocks on network I/O.
+    /// Shared by first launch (after a restore miss) and `Logout`.
+    fn spawn_qr_auth_flow(rt_arc_clone: Arc<Runtime>, state_clone: Arc<Mutex<AuthState>>) {
+        *state_clone.lock().unwrap() = AuthState::Initializing;
+
+        let initialize_auth_fut = initialize_auth();
+        match rt_arc_clone.block_on(initialize_auth_fut) {
+            Ok((pubky, flow, auth_url)) => {
+                *state_clone.lock().unwrap() = AuthState::ShowingQR {
+                    auth_url: auth_url.clone(),
+                };
+
+                // Poll for authentication
+                let await_approval_fut = flow.await_approval();
+                match rt_arc_clone.block_on(await_approval_fut) {
+                    Ok(session) => {
+                        Self::fetch_files_and_update(
+                            &session,
+                            &pubky.public_storage(),
+                            rt_arc_clone,
+                            state_clone,
+                        );
+                    }
+                    Err(e) => {
+                        *state_clone.lock().unwrap() =
+                            AuthState::Error(format!("Authentication failed: {e}"));
+                    }
+                }
+            }
+            Err(e) => {
+                *state_clone.lock().unwrap() = AuthState::Error(format!("Failed to initialize: {e}"));
+            }
         }
     }
 
@@ -179,6 +337,9 @@ impl PubkyApp {
         state_clone: Arc<Mutex<AuthState>>,
     ) {
         let mut file_cache = HashMap::new();
+        let mut search_docs: Vec<(String, String, String)> = Vec::new();
+
+        let own_user_pk = session.info().public_key().to_string();
 
         match get_list(session, "/pub/wiki.app/", rt_arc_clone.clone()) {
             Ok(file_urls) => {
@@ -187,12 +348,16 @@ impl PubkyApp {
                     let get_path_fut = pub_storage.get(file_url);
                     match rt_arc_clone.block_on(get_path_fut) {
                         Ok(response) => {
-                            let response_text_fut = response.text();
-                            match rt_arc_clone.block_on(response_text_fut) {
-                                Ok(content) => {
+                            let response_bytes_fut = response.bytes();
+                            match rt_arc_clone.block_on(response_bytes_fut) {
+                                Ok(bytes) => {
+                                    let content = decode_wiki_body(own_user_pk.as_bytes(), &bytes);
                                     let file_title = extract_title(&content);
 
                                     file_cache.insert(file_url.into(), file_title.into());
+
+                                    let page_id = file_url.rsplit('/').next().unwrap_or(file_url);
+                                    search_docs.push((own_user_pk.clone(), page_id.to_string(), content));
                                 }
                                 Err(e) => log::error!("Error reading content: {e}"),
                             }
@@ -204,10 +369,72 @@ impl PubkyApp {
             Err(e) => log::error!("Failed to list files: {e}"),
         }
 
+        // Widen the index to followed authors' pages too, so search can
+        // surface forkable pages across the network rather than just this
+        // account's own. Best-effort: a follow whose wiki.app directory
+        // can't be listed or read just contributes nothing.
+        match get_list(session, "/pub/pubky.app/follows/", rt_arc_clone.clone()) {
+            Ok(follow_paths) => {
+                for follow_path in &follow_paths {
+                    let follow_pk = follow_path.rsplit('/').next().unwrap_or(follow_path).to_string();
+                    let wiki_url = format!("pubky://{follow_pk}/pub/wiki.app/");
+                    let resource: PubkyResource = match wiki_url.parse() {
+                        Ok(resource) => resource,
+                        Err(e) => {
+                            log::error!("Invalid wiki.app URL {wiki_url}: {e}");
+                            continue;
+                        }
+                    };
+
+                    let builder = match pub_storage.list(resource) {
+                        Ok(builder) => builder.shallow(true),
+                        Err(e) => {
+                            log::error!("Failed to list wiki.app for {follow_pk}: {e}");
+                            continue;
+                        }
+                    };
+
+                    let entries = match rt_arc_clone.block_on(builder.send()) {
+                        Ok(entries) => entries,
+                        Err(e) => {
+                            log::error!("Failed to list wiki.app for {follow_pk}: {e}");
+                            continue;
+                        }
+                    };
+
+                    for entry in entries {
+                        let page_url = entry.to_pubky_url();
+                        let page_id = page_url.rsplit('/').next().unwrap_or(&page_url).to_string();
+
+                        // Skip the mentions subdirectory entry itself; only
+                        // actual pages belong in the search index.
+                        if page_id == "mentions" {
+                            continue;
+                        }
+
+                        match rt_arc_clone.block_on(pub_storage.get(&page_url)) {
+                            Ok(response) => match rt_arc_clone.block_on(response.bytes()) {
+                                Ok(bytes) => {
+                                    let content = decode_wiki_body(follow_pk.as_bytes(), &bytes);
+                                    search_docs.push((follow_pk.clone(), page_id, content));
+                                }
+                                Err(e) => log::error!("Error reading {follow_pk}'s {page_id}: {e}"),
+                            },
+                            Err(e) => log::error!("Error fetching {follow_pk}'s {page_id}: {e}"),
+                        }
+                    }
+                }
+            }
+            Err(e) => log::error!("Failed to list follows for search index: {e}"),
+        }
+
+        let search_index = search::SearchIndex::build(search_docs);
+
         *state_clone.lock().unwrap() = AuthState::Authenticated {
             session: session.clone(),
             pub_storage: pub_storage.clone(),
             file_cache,
+            search_index,
         };
     }
 
@@ -221,16 +448,74 @@ impl PubkyApp {
         self.selected_wiki_user_id = user_pk.to_string();
         self.selected_wiki_page_id = page_id.to_string();
         self.selected_wiki_fork_urls = self.discover_fork_urls(session, pub_storage, page_id);
+        self.selected_wiki_inbound_forks = self.discover_inbound_forks(session, pub_storage, page_id);
         self.selected_wiki_content.clear();
+        self.pending_content_fetch = None;
 
         self.view_state = ViewState::ViewWiki;
     }
 
     fn navigate_to_edit_selected_wiki_page(&mut self) {
         self.edit_wiki_content = self.selected_wiki_content.clone();
+        self.edit_doc.reset(self.site_id, &self.selected_wiki_content);
         self.view_state = ViewState::EditWiki;
     }
 
+    /// Switch to the DiffMerge view and kick off a background fetch of the
+    /// currently selected page and `fork_page` (owned by `fork_user`), so
+    /// the two bodies can be diffed without blocking the UI thread. When
+    /// this session has a recorded ancestor snapshot for the page (i.e. it
+    /// forked it itself, see `fork_ancestors`), this runs a three-way merge
+    /// against that ancestor instead of a plain two-way diff.
+    fn navigate_to_diff_view(
+        &mut self,
+        fork_user: &str,
+        fork_page: &str,
+        pub_storage: &PublicStorage,
+    ) {
+        self.diff_target_user = fork_user.to_string();
+        self.diff_target_page = fork_page.to_string();
+        self.view_state = ViewState::DiffMerge;
+        *self.diff_state.lock().unwrap() = DiffState::Loading;
+
+        let root_user = self.selected_wiki_user_id.clone();
+        let root_page = self.selected_wiki_page_id.clone();
+        let ancestor = self.fork_ancestors.get(&root_page).cloned();
+        let fork_user = fork_user.to_string();
+        let fork_page = fork_page.to_string();
+        let pub_storage = pub_storage.clone();
+        let rt = self.rt.clone();
+        let diff_state = self.diff_state.clone();
+
+        std::thread::spawn(move || {
+            let fetch_body = |user_pk: &str, page_id: &str| -> Result<String> {
+                let url = format!("pubky://{user_pk}/pub/wiki.app/{page_id}");
+                let response = rt.block_on(pub_storage.get(&url))?;
+                let bytes = rt.block_on(response.bytes())?;
+                Ok(decode_wiki_body(user_pk.as_bytes(), &bytes))
+            };
+
+            let result = fetch_body(&root_user, &root_page).and_then(|root_content| {
+                fetch_body(&fork_user, &fork_page).map(|fork_content| (root_content, fork_content))
+            });
+
+            *diff_state.lock().unwrap() = match result {
+                Ok((root_content, fork_content)) => match ancestor {
+                    Some(ancestor) => {
+                        let segments = diff::three_way_merge(&ancestor, &root_content, &fork_content);
+                        DiffState::ReadyMerge3 { segments, accepted: std::collections::HashSet::new() }
+                    }
+                    None => {
+                        let diff = diff::diff_lines(&root_content, &fork_content);
+                        let hunks = diff::hunks(&diff);
+                        DiffState::Ready { diff, hunks, accepted: std::collections::HashSet::new() }
+                    }
+                },
+                Err(e) => DiffState::Error(format!("Failed to load diff: {e}")),
+            };
+        });
+    }
+
     fn get_my_follows(&self, session: &PubkySession) -> Vec<String> {
         get_list(session, "/pub/pubky.app/follows/", self.rt.clone())
             .inspect_err(|e| log::error!("Failed to get follows: {e}"))
@@ -268,6 +553,73 @@ impl PubkyApp {
         }
         result
     }
+
+    /// Forks live under *their own* author's storage (Pubky only lets you
+    /// write to your own space), so the only way to learn "who forked my
+    /// page" is to poll each follow's `/pub/wiki.app/mentions/` directory
+    /// for a record pointing back at `page_id`.
+    fn discover_inbound_forks(
+        &self,
+        session: &PubkySession,
+        pub_storage: &PublicStorage,
+        page_id: &str,
+    ) -> Vec<ForkMention> {
+        let follows = self.get_my_follows(session);
+        let own_pk = session.info().public_key().to_string();
+
+        let mut inbound = vec![];
+        for follow_pk in follows {
+            let mentions_url = format!("pubky://{follow_pk}/pub/wiki.app/mentions/");
+            let resource: PubkyResource = match mentions_url.parse() {
+                Ok(resource) => resource,
+                Err(e) => {
+                    log::error!("Invalid mentions URL {mentions_url}: {e}");
+                    continue;
+                }
+            };
+
+            let builder = match pub_storage.list(resource) {
+                Ok(builder) => builder.shallow(true),
+                Err(e) => {
+                    log::error!("Failed to list mentions for {follow_pk}: {e}");
+                    continue;
+                }
+            };
+
+            let entries = match self.rt.block_on(builder.send()) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    log::error!("Failed to list mentions for {follow_pk}: {e}");
+                    continue;
+                }
+            };
+
+            for entry in entries {
+                let mention_url = entry.to_pubky_url();
+                let mention = match self.rt.block_on(pub_storage.get(&mention_url)) {
+                    Ok(response) => match self.rt.block_on(response.bytes()) {
+                        Ok(bytes) => serde_json::from_slice::<ForkMention>(&bytes).ok(),
+                        Err(e) => {
+                            log::error!("Failed to read mention {mention_url}: {e}");
+                            None
+                        }
+                    },
+                    Err(e) => {
+                        log::error!("Failed to fetch mention {mention_url}: {e}");
+                        None
+                    }
+                };
+
+                if let Some(mention) = mention {
+                    if mention.target_user == own_pk && mention.target_page == page_id {
+                        inbound.push(mention);
+                    }
+                }
+            }
+        }
+
+        inbound
+    }
 }
 
 impl eframe::App for PubkyApp {
@@ -294,11 +646,34 @@ impl eframe::App for PubkyApp {
                 }
 
                 ui.heading(egui::RichText::new(APP_NAME).size(24.0).strong());
-                ui.add_space(30.0);
+                ui.add_space(10.0);
+
+                egui::ComboBox::from_label("🌐")
+                    .selected_text(
+                        i18n::AVAILABLE_LANGUAGES
+                            .iter()
+                            .find(|(code, _)| *code == self.localizer.language())
+                            .map(|(_, name)| *name)
+                            .unwrap_or(self.localizer.language()),
+                    )
+                    .show_ui(ui, |ui| {
+                        for (code, name) in i18n::AVAILABLE_LANGUAGES {
+                            if ui.selectable_label(self.localizer.language() == code, name).clicked() {
+                                self.localizer.set_language(code);
+                            }
+                        }
+                    });
+                ui.add_space(20.0);
 
                 let state = self.state.lock().unwrap().clone();
 
                 match state {
+                    AuthState::Restoring => {
+                        ui.add_space(20.0);
+                        ui.spinner();
+                        ui.add_space(10.0);
+                        ui.label(egui::RichText::new("Restoring session...").size(16.0));
+                    }
                     AuthState::Initializing => {
                         ui.add_space(20.0);
                         ui.spinner();
@@ -335,7 +710,19 @@ impl eframe::App for PubkyApp {
                         session,
                         ref pub_storage,
                         ref file_cache,
+                        ref search_index,
                     } => {
+                        // Drain any content fetched in the background by
+                        // `execute` since the last frame, discarding a
+                        // response for a page we've since navigated away
+                        // from.
+                        while let Ok((page_id, content)) = self.content_fetch_rx.try_recv() {
+                            if self.pending_content_fetch.as_deref() == Some(page_id.as_str()) {
+                                self.selected_wiki_content = content;
+                                self.pending_content_fetch = None;
+                            }
+                        }
+
                         // Check if we need to refresh the files cache
                         if self.needs_refresh {
                             let state_clone = self.state.clone();
@@ -355,22 +742,158 @@ impl eframe::App for PubkyApp {
                         // Show different views based on view_state
                         match self.view_state {
                             ViewState::WikiList => {
-                                ui.add_space(10.0);
-                                let create_button = ui.add_sized(
-                                    [200.0, 40.0],
-                                    egui::Button::new(egui::RichText::new("âœ¨ Create New Wiki Page").size(16.0))
-                                );
-                                if create_button.clicked() {
-                                    self.view_state = ViewState::CreateWiki;
+                                ui.horizontal(|ui| {
+                                    ui.add_space(10.0);
+                                    let create_button = ui.add_sized(
+                                        [200.0, 40.0],
+                                        egui::Button::new(egui::RichText::new("âœ¨ Create New Wiki Page").size(16.0))
+                                    );
+                                    if create_button.clicked() {
+                                        self.view_state = ViewState::CreateWiki;
+                                    }
+
+                                    ui.add_space(10.0);
+                                    if ui.button("🚪 Logout").clicked() {
+                                        self.qr_texture = None;
+                                        if let Err(e) = session_store::clear_session() {
+                                            log::error!("Failed to clear saved session: {e}");
+                                        }
+
+                                        let state_clone = self.state.clone();
+                                        let rt_clone = self.rt.clone();
+                                        std::thread::spawn(move || {
+                                            Self::spawn_qr_auth_flow(rt_clone, state_clone);
+                                        });
+                                    }
+                                });
+                                ui.add_space(15.0);
+
+                                ui.horizontal(|ui| {
+                                    if ui.button("📦 Export Wiki").clicked() {
+                                        match archive::export_wiki(&session, self.rt.clone()) {
+                                            Ok(bundle) => match serde_json::to_string_pretty(&bundle) {
+                                                Ok(json) => {
+                                                    if let Some(path) = rfd::FileDialog::new()
+                                                        .set_file_name("wiki-export.json")
+                                                        .add_filter("json", &["json"])
+                                                        .save_file()
+                                                    {
+                                                        match std::fs::write(&path, json) {
+                                                            Ok(()) => {
+                                                                self.archive_status = Some(format!(
+                                                                    "Exported {} page(s), {} media file(s) to {}",
+                                                                    bundle.pages.len(),
+                                                                    bundle.media.len(),
+                                                                    path.display()
+                                                                ));
+                                                            }
+                                                            Err(e) => {
+                                                                self.archive_status = Some(format!("Failed to write export: {e}"));
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                                Err(e) => {
+                                                    self.archive_status = Some(format!("Failed to serialize export: {e}"));
+                                                }
+                                            },
+                                            Err(e) => {
+                                                self.archive_status = Some(format!("Export failed: {e}"));
+                                            }
+                                        }
+                                    }
+
+                                    ui.add_space(10.0);
+
+                                    egui::ComboBox::from_label("on collision")
+                                        .selected_text(self.import_collision_policy.label())
+                                        .show_ui(ui, |ui| {
+                                            for policy in archive::CollisionPolicy::ALL {
+                                                ui.selectable_value(
+                                                    &mut self.import_collision_policy,
+                                                    policy,
+                                                    policy.label(),
+                                                );
+                                            }
+                                        });
+
+                                    if ui.button("📥 Import Wiki").clicked() {
+                                        if let Some(path) = rfd::FileDialog::new()
+                                            .add_filter("json", &["json"])
+                                            .pick_file()
+                                        {
+                                            match std::fs::read_to_string(&path)
+                                                .map_err(anyhow::Error::from)
+                                                .and_then(|json| Ok(serde_json::from_str::<archive::WikiArchive>(&json)?))
+                                            {
+                                                Ok(bundle) => {
+                                                    let summary = archive::import_wiki(
+                                                        &session,
+                                                        self.rt.clone(),
+                                                        &bundle,
+                                                        self.import_collision_policy,
+                                                    );
+                                                    self.archive_status = Some(format!(
+                                                        "Imported {}, skipped {}, failed {}",
+                                                        summary.imported, summary.skipped, summary.failed
+                                                    ));
+                                                    self.needs_refresh = true;
+                                                }
+                                                Err(e) => {
+                                                    self.archive_status = Some(format!("Failed to read archive: {e}"));
+                                                }
+                                            }
+                                        }
+                                    }
+                                });
+
+                                if let Some(status) = &self.archive_status {
+                                    ui.add_space(5.0);
+                                    ui.label(egui::RichText::new(status).italics().color(egui::Color32::GRAY));
                                 }
-                                ui.add_space(30.0);
+
+                                ui.add_space(25.0);
 
                                 ui.label(egui::RichText::new("My Wiki Posts").size(18.0).strong());
                                 ui.add_space(15.0);
 
-                                // List all wiki posts as buttons
+                                ui.horizontal(|ui| {
+                                    ui.label("🔎");
+                                    ui.add(
+                                        egui::TextEdit::singleline(&mut self.wiki_search_query)
+                                            .hint_text("Search pages by content...")
+                                            .desired_width(300.0),
+                                    );
+                                });
+                                ui.add_space(10.0);
+
+                                // List all wiki posts as buttons, or search results when the
+                                // search box has a query in it.
                                 egui::ScrollArea::vertical().show(ui, |ui| {
-                                    if file_cache.is_empty() {
+                                    if !self.wiki_search_query.trim().is_empty() {
+                                        let hits = search_index.search(&self.wiki_search_query, 25);
+                                        if hits.is_empty() {
+                                            ui.add_space(10.0);
+                                            ui.label(egui::RichText::new("No matches.").italics().color(egui::Color32::GRAY));
+                                        } else {
+                                            for hit in hits {
+                                                let label = if hit.title.is_empty() { hit.page_id.clone() } else { hit.title.clone() };
+                                                ui.horizontal(|ui| {
+                                                    if ui.button(egui::RichText::new(&label).strong()).clicked() {
+                                                        self.navigate_to_view_wiki_page(
+                                                            &hit.user_pk,
+                                                            &hit.page_id,
+                                                            &session,
+                                                            pub_storage,
+                                                        );
+                                                    }
+                                                    ui.label(egui::RichText::new(format!("({})", hit.user_pk)).monospace().color(egui::Color32::GRAY));
+                                                });
+                                                ui.label(egui::RichText::new(&hit.snippet).italics().color(egui::Color32::GRAY));
+                                                ui.add_space(8.0);
+                                            }
+                                        }
+                                    } else if file_cache.is_empty() {
                                         ui.add_space(10.0);
                                         ui.label(egui::RichText::new("No wiki posts yet. Create your first one!").italics().color(egui::Color32::GRAY));
                                     } else {
@@ -398,10 +921,13 @@ impl eframe::App for PubkyApp {
                                 });
                             }
                             ViewState::CreateWiki => create_wiki::update(self, &session, ctx, ui),
-                            ViewState::EditWiki => edit_wiki::update(self, &session, ctx, ui),
+                            ViewState::EditWiki => {
+                                edit_wiki::update(self, &session, &pub_storage, ctx, ui)
+                            }
                             ViewState::ViewWiki => {
                                 view_wiki::update(self, &session, &pub_storage, ctx, ui)
                             }
+                            ViewState::DiffMerge => diff_view::update(self, &session, ctx, ui),
                         }
                     }
                     AuthState::Error(ref error) => {
@@ -413,6 +939,16 @@ impl eframe::App for PubkyApp {
             });
         });
     }
+
+    /// Save the current session, if any, so the next launch can skip
+    /// straight to `AuthState::Authenticated` instead of showing a QR code.
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if let AuthState::Authenticated { ref session, .. } = *self.state.lock().unwrap() {
+            if let Err(e) = session_store::save_session(session) {
+                log::error!("Failed to save session: {e}");
+            }
+        }
+    }
 }
 
 async fn initialize_auth() -> Result<(Pubky, PubkyAuthFlow, String)> {
@@ -424,38 +960,80 @@ async fn initialize_auth() -> Result<(Pubky, PubkyAuthFlow, String)> {
     Ok((pubky, flow, auth_url))
 }
 
-pub(crate) async fn create_wiki_post(
+/// Best-effort read path for a stored page body: try the RFC 8188
+/// aes128gcm framing first (the format `create_wiki.rs` writes when
+/// encryption is enabled), and fall back to plain UTF-8 for pages stored
+/// unencrypted.
+fn decode_wiki_body(ikm: &[u8], bytes: &[u8]) -> String {
+    match encrypted_content::open(ikm, bytes) {
+        Ok(plaintext) => String::from_utf8_lossy(&plaintext).into_owned(),
+        Err(_) => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// A webmention-style backlink, written under the *forker's* own storage
+/// when they fork someone else's page, so the original author can discover
+/// downstream edits by polling the people they follow.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ForkMention {
+    pub target_user: String,
+    pub target_page: String,
+    pub source_user: String,
+    pub source_page: String,
+    pub timestamp: u64,
+}
+
+/// Records that `session`'s user forked `target_user`/`target_page` into
+/// `source_page` under their own space, so `discover_inbound_forks` can
+/// find it while polling follows.
+pub(crate) async fn record_fork_mention(
     session: &PubkySession,
-    content: &str,
-    filename: Option<&str>,
-) -> Result<String> {
-    let path = if let Some(fname) = filename {
-        format!("/pub/wiki.app/{}", fname)
-    } else {
-        format!("/pub/wiki.app/{}", Uuid::new_v4())
+    target_user: &str,
+    target_page: &str,
+    source_page: &str,
+) -> Result<()> {
+    let source_user = session.info().public_key().to_string();
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let mention = ForkMention {
+        target_user: target_user.to_string(),
+        target_page: target_page.to_string(),
+        source_user,
+        source_page: source_page.to_string(),
+        timestamp,
     };
 
-    // Create the post with the provided content
-    session.storage().put(&path, content.to_string()).await?;
+    let path = format!("/pub/wiki.app/mentions/{}", Uuid::new_v4());
+    let body = serde_json::to_vec(&mention)?;
+    session.storage().put(&path, body).await?;
 
-    log::info!("Created post at path: {}", path);
+    log::info!("Recorded fork mention at {}", path);
 
-    Ok(path)
+    Ok(())
 }
 
-pub(crate) async fn update_wiki_post(
+/// Writes `content` (plaintext or, when encryption is enabled, an RFC 8188
+/// aes128gcm-sealed blob) to `page_id`'s path, or mints a fresh page id
+/// under `/pub/wiki.app/` when `page_id` is `None` — so Create and Edit
+/// share one write path. Returns the full storage path written to.
+pub(crate) async fn save_wiki_post(
     session: &PubkySession,
-    page_id: &str,
-    content: &str,
-) -> Result<()> {
-    let path = format!("/pub/wiki.app/{}", page_id);
+    page_id: Option<&str>,
+    content: impl Into<Vec<u8>>,
+) -> Result<String> {
+    let path = match page_id {
+        Some(page_id) => format!("/pub/wiki.app/{}", page_id),
+        None => format!("/pub/wiki.app/{}", Uuid::new_v4()),
+    };
 
-    // Update the post with the provided content
-    session.storage().put(&path, content.to_string()).await?;
+    session.storage().put(&path, content.into()).await?;
 
-    log::info!("Updated post at path: {}", path);
+    log::info!("Saved post at path: {}", path);
 
-    Ok(())
+    Ok(path)
 }
 
 pub(crate) async fn delete_wiki_post(session: &PubkySession, page_id: &str) -> Result<()> {
@@ -468,3 +1046,109 @@ pub(crate) async fn delete_wiki_post(session: &PubkySession, page_id: &str) -> R
 
     Ok(())
 }
+
+/// Append one CRDT op to a page's op log at
+/// `/pub/wiki.app/<page_id>/ops/` plus [`rga::Op::log_path`], the append-only path the
+/// collaborative Edit Wiki view polls and merges from every other site.
+pub(crate) async fn append_wiki_op(session: &PubkySession, page_id: &str, op: &rga::Op) -> Result<()> {
+    let path = format!("/pub/wiki.app/{}/ops/{}", page_id, op.log_path());
+    let body = serde_json::to_vec(op)?;
+    session.storage().put(&path, body).await?;
+
+    log::info!("Appended wiki op at {}", path);
+
+    Ok(())
+}
+
+/// Fetch every op recorded by sites other than `own_site` for `page_id`,
+/// so the Edit Wiki view can merge them into the local CRDT document.
+/// Best-effort: a site directory or op entry that can't be listed, fetched
+/// or parsed just contributes nothing, the same as the fork-mention
+/// discovery above.
+pub(crate) fn fetch_remote_wiki_ops(
+    pub_storage: &PublicStorage,
+    rt: &Runtime,
+    owner_pk: &str,
+    page_id: &str,
+    own_site: u64,
+) -> Vec<rga::Op> {
+    let mut ops = Vec::new();
+
+    let ops_url = format!("pubky://{owner_pk}/pub/wiki.app/{page_id}/ops/");
+    let resource: PubkyResource = match ops_url.parse() {
+        Ok(resource) => resource,
+        Err(e) => {
+            log::error!("Invalid op log URL {ops_url}: {e}");
+            return ops;
+        }
+    };
+    let builder = match pub_storage.list(resource) {
+        Ok(builder) => builder.shallow(true),
+        Err(e) => {
+            log::error!("Failed to list op log for {page_id}: {e}");
+            return ops;
+        }
+    };
+    let site_dirs = match rt.block_on(builder.send()) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::error!("Failed to list op log for {page_id}: {e}");
+            return ops;
+        }
+    };
+
+    for dir in site_dirs {
+        let dir_url = dir.to_pubky_url();
+        let site_str = dir_url.trim_end_matches('/').rsplit('/').next().unwrap_or_default();
+        // Deletes are filed under the deleted element's site, so this site's
+        // own directory can also hold other sites' deletes of its elements.
+        let own_dir = site_str.parse::<u64>() == Ok(own_site);
+
+        let resource: PubkyResource = match dir_url.parse() {
+            Ok(resource) => resource,
+            Err(e) => {
+                log::error!("Invalid op log site URL {dir_url}: {e}");
+                continue;
+            }
+        };
+        let builder = match pub_storage.list(resource) {
+            Ok(builder) => builder.shallow(true),
+            Err(e) => {
+                log::error!("Failed to list op log site {dir_url}: {e}");
+                continue;
+            }
+        };
+        let entries = match rt.block_on(builder.send()) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::error!("Failed to list op log site {dir_url}: {e}");
+                continue;
+            }
+        };
+
+        for entry in entries {
+            let op_url = entry.to_pubky_url();
+            if own_dir && !op_url.ends_with(".del") {
+                continue;
+            }
+            let op = match rt.block_on(pub_storage.get(&op_url)) {
+                Ok(response) => match rt.block_on(response.bytes()) {
+                    Ok(bytes) => serde_json::from_slice::<rga::Op>(&bytes).ok(),
+                    Err(e) => {
+                        log::error!("Failed to read op {op_url}: {e}");
+                        None
+                    }
+                },
+                Err(e) => {
+                    log::error!("Failed to fetch op {op_url}: {e}");
+                    None
+                }
+            };
+            if let Some(op) = op {
+                ops.push(op);
+            }
+        }
+    }
+
+    ops
+}
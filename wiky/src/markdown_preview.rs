@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use eframe::egui::{Color32, RichText, Ui};
+use egui_commonmark::{CommonMarkCache, CommonMarkViewer};
+
+const KEYWORD_COLOR: Color32 = Color32::from_rgb(198, 120, 221);
+const STRING_COLOR: Color32 = Color32::from_rgb(152, 195, 121);
+const COMMENT_COLOR: Color32 = Color32::from_rgb(120, 120, 120);
+const NUMBER_COLOR: Color32 = Color32::from_rgb(209, 154, 102);
+const DEFAULT_COLOR: Color32 = Color32::from_rgb(212, 212, 212);
+
+/// Language-agnostic keyword set wide enough to color the handful of
+/// languages wiki pages tend to fence (Rust, JS/TS, Python, shell) without
+/// a per-language grammar.
+const KEYWORDS: &[&str] = &[
+    "fn", "let", "mut", "pub", "struct", "enum", "impl", "match", "if", "else", "for", "while",
+    "loop", "return", "use", "mod", "const", "static", "async", "await", "trait", "type", "where",
+    "break", "continue", "self", "Self", "true", "false", "None", "Some", "Ok", "Err",
+    "def", "class", "import", "from", "as", "try", "except", "finally", "with", "lambda", "yield",
+    "None", "True", "False", "pass", "raise", "in", "is", "not", "and", "or",
+    "function", "var", "export", "interface", "extends", "implements", "new", "this", "typeof",
+    "echo", "fi", "then", "do", "done", "esac", "case",
+];
+
+/// Cached syntax-highlight runs for fenced code blocks, keyed by a hash of
+/// `(lang, code)`, so retyping prose elsewhere doesn't re-tokenize blocks
+/// whose content hasn't changed.
+#[derive(Default)]
+pub(crate) struct HighlightCache {
+    entries: HashMap<u64, Vec<(String, Color32)>>,
+}
+
+impl HighlightCache {
+    fn runs(&mut self, lang: &str, code: &str) -> &[(String, Color32)] {
+        let mut hasher = DefaultHasher::new();
+        lang.hash(&mut hasher);
+        code.hash(&mut hasher);
+        let key = hasher.finish();
+        self.entries.entry(key).or_insert_with(|| tokenize(code))
+    }
+}
+
+/// Split source into (text, color) token runs: comments, quoted strings,
+/// numbers, keywords, and everything else.
+fn tokenize(code: &str) -> Vec<(String, Color32)> {
+    let mut runs: Vec<(String, Color32)> = Vec::new();
+    let chars: Vec<char> = code.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '/' && chars.get(i + 1) == Some(&'/') || c == '#' {
+            let start = i;
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            runs.push((chars[start..i].iter().collect(), COMMENT_COLOR));
+        } else if c == '"' || c == '\'' {
+            let quote = c;
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i] != quote {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i = (i + 1).min(chars.len());
+            runs.push((chars[start..i].iter().collect(), STRING_COLOR));
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                i += 1;
+            }
+            runs.push((chars[start..i].iter().collect(), NUMBER_COLOR));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            let color = if KEYWORDS.contains(&word.as_str()) { KEYWORD_COLOR } else { DEFAULT_COLOR };
+            runs.push((word, color));
+        } else {
+            let start = i;
+            i += 1;
+            runs.push((chars[start..i].iter().collect(), DEFAULT_COLOR));
+        }
+    }
+
+    runs
+}
+
+enum Segment {
+    Prose(String),
+    Code { lang: String, code: String },
+}
+
+/// Split markdown into prose and fenced-code-block segments, in order.
+fn split_segments(markdown: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut prose = String::new();
+    let mut lines = markdown.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        if let Some(lang) = line.trim_start().strip_prefix("```") {
+            if !prose.is_empty() {
+                segments.push(Segment::Prose(std::mem::take(&mut prose)));
+            }
+
+            let lang = lang.trim().to_string();
+            let mut code = String::new();
+            for code_line in lines.by_ref() {
+                if code_line.trim_start().starts_with("```") {
+                    break;
+                }
+                code.push_str(code_line);
+                code.push('\n');
+            }
+            segments.push(Segment::Code { lang, code });
+        } else {
+            prose.push_str(line);
+            prose.push('\n');
+        }
+    }
+    if !prose.is_empty() {
+        segments.push(Segment::Prose(prose));
+    }
+
+    segments
+}
+
+/// Render `content` as rich Markdown, with fenced code blocks tokenized
+/// into colored monospace runs via `hl_cache`.
+pub(crate) fn show(ui: &mut Ui, md_cache: &mut CommonMarkCache, hl_cache: &mut HighlightCache, content: &str) {
+    for segment in split_segments(content) {
+        match segment {
+            Segment::Prose(text) => {
+                if !text.trim().is_empty() {
+                    CommonMarkViewer::new().show(ui, md_cache, &text);
+                }
+            }
+            Segment::Code { lang, code } => {
+                ui.group(|ui| {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.spacing_mut().item_spacing.x = 0.0;
+                        for (text, color) in hl_cache.runs(&lang, &code) {
+                            ui.label(RichText::new(text).monospace().color(*color));
+                        }
+                    });
+                });
+            }
+        }
+    }
+}
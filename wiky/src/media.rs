@@ -0,0 +1,125 @@
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::mpsc::{self, Sender};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use image::imageops::FilterType;
+use pubky::PubkySession;
+use tokio::runtime::Runtime;
+use uuid::Uuid;
+
+/// Longest edge of a generated thumbnail, in pixels.
+const THUMBNAIL_MAX_EDGE: u32 = 512;
+
+/// A queued thumbnail encode, handed off to the background worker so a
+/// large image's resize/re-encode doesn't block the `update` loop.
+pub(crate) struct ThumbnailJob {
+    pub thumb_path: String,
+    pub original_bytes: Vec<u8>,
+    pub format: image::ImageFormat,
+    pub session: PubkySession,
+}
+
+/// Spawns the background thumbnail worker and returns a channel to feed it
+/// jobs from the UI thread. One worker thread is enough: thumbnailing is
+/// rare compared to page views, and this keeps ordering simple.
+pub(crate) fn spawn_thumbnail_worker(rt: Arc<Runtime>) -> Sender<ThumbnailJob> {
+    let (tx, rx) = mpsc::channel::<ThumbnailJob>();
+
+    std::thread::spawn(move || {
+        for job in rx {
+            match generate_thumbnail(&job.original_bytes, job.format) {
+                Ok(thumb_bytes) => {
+                    let put_fut = job.session.storage().put(&job.thumb_path, thumb_bytes);
+                    match rt.block_on(put_fut) {
+                        Ok(_) => log::info!("Uploaded thumbnail {}", job.thumb_path),
+                        Err(e) => {
+                            log::error!("Failed to upload thumbnail {}: {e}", job.thumb_path)
+                        }
+                    }
+                }
+                Err(e) => log::error!("Failed to generate thumbnail {}: {e}", job.thumb_path),
+            }
+        }
+    });
+
+    tx
+}
+
+fn generate_thumbnail(bytes: &[u8], format: image::ImageFormat) -> Result<Vec<u8>> {
+    let decoded = image::load_from_memory(bytes).context("Failed to decode image")?;
+
+    let (width, height) = (decoded.width(), decoded.height());
+    let longest_edge = width.max(height);
+    let thumbnail = if longest_edge > THUMBNAIL_MAX_EDGE {
+        let scale = THUMBNAIL_MAX_EDGE as f32 / longest_edge as f32;
+        let target_width = (width as f32 * scale).round().max(1.0) as u32;
+        let target_height = (height as f32 * scale).round().max(1.0) as u32;
+        decoded.resize(target_width, target_height, FilterType::Lanczos3)
+    } else {
+        decoded
+    };
+
+    let mut buffer = Cursor::new(Vec::new());
+    thumbnail
+        .write_to(&mut buffer, format)
+        .context("Failed to encode thumbnail")?;
+
+    Ok(buffer.into_inner())
+}
+
+/// Uploads `path`'s bytes to `/pub/wiki.app/media/<uuid>.<ext>`, queues a
+/// thumbnail encode for the background worker at the sibling
+/// `<uuid>.thumb.<ext>` path, and returns the markdown image link to
+/// insert into the page. The preview only resolves http(s) image sources,
+/// so this renders as a clickable link rather than an inline image until
+/// the viewer grows a `pubky://` image loader.
+pub(crate) fn upload_image_attachment(
+    session: &PubkySession,
+    rt: &Runtime,
+    thumbnail_tx: &Sender<ThumbnailJob>,
+    path: &Path,
+) -> Result<String> {
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("png")
+        .to_lowercase();
+
+    let format = image::ImageFormat::from_extension(&ext).unwrap_or(image::ImageFormat::Png);
+
+    let original_bytes =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let id = Uuid::new_v4();
+    let original_path = format!("/pub/wiki.app/media/{id}.{ext}");
+    let thumb_path = format!("/pub/wiki.app/media/{id}.thumb.{ext}");
+
+    let put_fut = session
+        .storage()
+        .put(&original_path, original_bytes.clone());
+    rt.block_on(put_fut)
+        .with_context(|| format!("Failed to upload {}", original_path))?;
+
+    thumbnail_tx
+        .send(ThumbnailJob {
+            thumb_path,
+            original_bytes,
+            format,
+            session: session.clone(),
+        })
+        .context("Thumbnail worker is no longer running")?;
+
+    let own_pk = session.info().public_key();
+    Ok(format!("![](pubky://{own_pk}{original_path})"))
+}
+
+/// egui cursor positions are character counts, not byte offsets; convert
+/// before using one as a `String::insert_str` index.
+pub(crate) fn char_index_to_byte_index(s: &str, char_idx: usize) -> usize {
+    s.char_indices()
+        .nth(char_idx)
+        .map(|(byte_idx, _)| byte_idx)
+        .unwrap_or(s.len())
+}
@@ -0,0 +1,220 @@
+//! Incremental Merkle accumulator over wiki page revisions.
+//!
+//! `save_wiki_post` writes a page and `forked_from_page_id` hints at
+//! forking, but there was no verifiable lineage between versions. Each
+//! committed revision is hashed into a new leaf (content + author pubkey +
+//! parent leaf index) and appended here. Rather than rehashing the whole
+//! history on every save, we keep a "frontier" of perfect-subtree roots per
+//! level (the same trick used by append-only transparency logs), so a
+//! commit only touches O(log n) nodes.
+
+use sha2::{Digest, Sha256};
+
+pub type Hash = [u8; 32];
+
+fn hash_leaf(content: &[u8], author_pubkey: &str, parent_leaf: Option<usize>) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update(b"leaf");
+    hasher.update(content);
+    hasher.update(author_pubkey.as_bytes());
+    hasher.update((parent_leaf.map(|i| i as u64).unwrap_or(u64::MAX)).to_le_bytes());
+    hasher.finalize().into()
+}
+
+fn hash_pair(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update(b"node");
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Which side of the running hash a path entry sits on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// A committed revision: where it landed in the leaf order and the root
+/// of the whole history right after it was appended.
+#[derive(Clone, Copy, Debug)]
+pub struct Revision {
+    pub leaf_index: usize,
+    pub root: Hash,
+}
+
+/// Proves that `descendant`'s revision genuinely derives from `ancestor`:
+/// the parent-pointer chain linking the two leaves, plus a Merkle
+/// authentication path for the descendant leaf under the accumulator's root.
+#[derive(Clone, Debug)]
+pub struct AncestryProof {
+    /// Leaf indices from `descendant` back to `ancestor`, inclusive.
+    pub leaf_chain: Vec<usize>,
+    pub leaf_hash: Hash,
+    pub auth_path: Vec<(Hash, Side)>,
+}
+
+/// Append-only Merkle accumulator over wiki revision leaves.
+#[derive(Default)]
+pub struct MerkleAccumulator {
+    /// `layers[0]` holds leaf hashes in insertion order; `layers[k]` holds
+    /// the hashes of completed (both-children-present) nodes at level k.
+    /// A level has a pending "frontier" peak exactly when its length is odd.
+    layers: Vec<Vec<Hash>>,
+    parents: Vec<Option<usize>>,
+}
+
+impl MerkleAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.first().map_or(0, |leaves| leaves.len())
+    }
+
+    /// Hash `content` under `author_pubkey`, chained to `parent_leaf` (the
+    /// revision this one was edited/forked from), and append it as a new
+    /// leaf. O(log n): only the frontier entries touched by carrying the
+    /// new leaf upward are rehashed.
+    pub fn append(&mut self, content: &[u8], author_pubkey: &str, parent_leaf: Option<usize>) -> Revision {
+        let leaf_index = self.len();
+        let leaf_hash = hash_leaf(content, author_pubkey, parent_leaf);
+        self.parents.push(parent_leaf);
+
+        if self.layers.is_empty() {
+            self.layers.push(Vec::new());
+        }
+        self.layers[0].push(leaf_hash);
+
+        let mut idx = leaf_index;
+        let mut level = 0;
+        loop {
+            let sibling_idx = idx ^ 1;
+            if sibling_idx >= self.layers[level].len() {
+                break;
+            }
+            let (a, b) = if idx % 2 == 0 {
+                (self.layers[level][idx], self.layers[level][sibling_idx])
+            } else {
+                (self.layers[level][sibling_idx], self.layers[level][idx])
+            };
+            let parent_hash = hash_pair(&a, &b);
+
+            if level + 1 == self.layers.len() {
+                self.layers.push(Vec::new());
+            }
+            self.layers[level + 1].push(parent_hash);
+
+            idx = self.layers[level + 1].len() - 1;
+            level += 1;
+        }
+
+        Revision { leaf_index, root: self.root() }
+    }
+
+    /// Bag the current frontier peaks (highest level, i.e. earliest and
+    /// largest subtree, first) into a single root.
+    pub fn root(&self) -> Hash {
+        let mut acc: Option<Hash> = None;
+        for level in (0..self.layers.len()).rev() {
+            if self.layers[level].len() % 2 == 1 {
+                let peak = *self.layers[level].last().unwrap();
+                acc = Some(match acc {
+                    None => peak,
+                    Some(prev) => hash_pair(&prev, &peak),
+                });
+            }
+        }
+        acc.unwrap_or([0u8; 32])
+    }
+
+    pub fn leaf_hash(&self, leaf_index: usize) -> Option<Hash> {
+        self.layers.first()?.get(leaf_index).copied()
+    }
+
+    /// Walk the parent chain from `descendant` back to `ancestor` and pair
+    /// it with a Merkle inclusion path for the descendant leaf, so a reader
+    /// can confirm the claimed lineage without trusting the server.
+    pub fn prove_ancestor(&self, ancestor: usize, descendant: usize) -> Option<AncestryProof> {
+        let mut leaf_chain = vec![descendant];
+        let mut current = descendant;
+        while current != ancestor {
+            current = self.parents.get(current)?.clone()?;
+            leaf_chain.push(current);
+        }
+
+        Some(AncestryProof {
+            leaf_chain,
+            leaf_hash: self.leaf_hash(descendant)?,
+            auth_path: self.auth_path(descendant),
+        })
+    }
+
+    /// Authentication path for `leaf_index`: sibling hashes from the leaf up
+    /// to its local frontier peak, then the bagged peaks above and below
+    /// that level, in the same order `root()` combines them.
+    fn auth_path(&self, leaf_index: usize) -> Vec<(Hash, Side)> {
+        let mut path = Vec::new();
+        let mut idx = leaf_index;
+        let mut level = 0;
+
+        loop {
+            let sibling_idx = idx ^ 1;
+            if sibling_idx >= self.layers[level].len() {
+                break;
+            }
+            let side = if idx % 2 == 0 { Side::Right } else { Side::Left };
+            path.push((self.layers[level][sibling_idx], side));
+            idx /= 2;
+            level += 1;
+        }
+        let local_level = level;
+
+        // Peaks above the local level are combined together first, leftmost
+        // (highest level) to rightmost, and attach to the left of our path.
+        let mut prefix: Option<Hash> = None;
+        for l in (local_level + 1..self.layers.len()).rev() {
+            if self.layers[l].len() % 2 == 1 {
+                let peak = *self.layers[l].last().unwrap();
+                prefix = Some(match prefix {
+                    None => peak,
+                    Some(prev) => hash_pair(&prev, &peak),
+                });
+            }
+        }
+        if let Some(prefix) = prefix {
+            path.push((prefix, Side::Left));
+        }
+
+        // Peaks below the local level attach to the right, in descending order.
+        for l in (0..local_level).rev() {
+            if self.layers[l].len() % 2 == 1 {
+                let peak = *self.layers[l].last().unwrap();
+                path.push((peak, Side::Right));
+            }
+        }
+
+        path
+    }
+}
+
+/// Render a hash as lowercase hex for logging.
+pub fn to_hex(hash: &Hash) -> String {
+    hash.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Recompute the root from a leaf hash and its authentication path,
+/// confirming the leaf (and therefore its recorded parent) belongs under
+/// `expected_root` without needing the rest of the tree.
+pub fn verify_proof(expected_root: &Hash, leaf: &Hash, path: &[(Hash, Side)]) -> bool {
+    let mut acc = *leaf;
+    for (sibling, side) in path {
+        acc = match side {
+            Side::Left => hash_pair(sibling, &acc),
+            Side::Right => hash_pair(&acc, sibling),
+        };
+    }
+    &acc == expected_root
+}
@@ -0,0 +1,277 @@
+//! Sequence CRDT (RGA-style) backing collaborative wiki page editing.
+//!
+//! The document is a list of elements, each stamped with a globally unique
+//! `(site, counter)` id. Inserting records the id of the new element's left
+//! neighbour; deleting never removes an element, it only flips a tombstone
+//! flag. An element's position is fully determined by its left neighbour
+//! plus a deterministic tie-break among siblings inserted at the same spot
+//! (comparing ids), so applying a complete set of ops in any order —
+//! network delivery gives no ordering guarantee — converges to the same
+//! list. Re-applying an already-seen op is a no-op, so the merge is also
+//! idempotent.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+/// Uniquely identifies one inserted character: `counter` is that site's
+/// local Lamport-style clock, `site` breaks ties between two sites that
+/// inserted at the same counter value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) struct ElementId {
+    pub site: u64,
+    pub counter: u64,
+}
+
+impl Ord for ElementId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.counter.cmp(&other.counter).then(self.site.cmp(&other.site))
+    }
+}
+
+impl PartialOrd for ElementId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// One CRDT mutation: an insert carries the id of its left neighbour (or
+/// `None` for "insert at the very start"), a delete just names the element
+/// to tombstone. Serialized as-is for the per-page op log.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) enum Op {
+    Insert { id: ElementId, ch: char, left: Option<ElementId> },
+    Delete { id: ElementId },
+}
+
+impl Op {
+    /// The path segment this op is stored under within a page's op log,
+    /// e.g. `ops/<site>/<counter>` for an insert. A delete is filed under the
+    /// deleted element's id as `<site>/<counter>.del`, so it never overwrites
+    /// that element's insert record.
+    pub(crate) fn log_path(&self) -> String {
+        match self {
+            Op::Insert { id, .. } => format!("{}/{}", id.site, id.counter),
+            Op::Delete { id } => format!("{}/{}.del", id.site, id.counter),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Element {
+    id: ElementId,
+    ch: char,
+    tombstone: bool,
+    left: Option<ElementId>,
+}
+
+/// One site's view of a page's CRDT document.
+pub(crate) struct Doc {
+    site: u64,
+    counter: u64,
+    elements: Vec<Element>,
+    /// Ids of inserts already applied, so a replayed insert is a no-op and
+    /// so a pending insert/delete can tell whether its target has landed.
+    applied: HashSet<ElementId>,
+    /// Ops whose dependency (an insert's `left`, or a delete's target)
+    /// hasn't been applied yet; retried whenever new ops land.
+    pending: Vec<Op>,
+    /// Local ops not yet flushed to the homeserver op log.
+    outbox: Vec<Op>,
+}
+
+impl Doc {
+    pub(crate) fn new(site: u64) -> Self {
+        Self { site, counter: 0, elements: Vec::new(), applied: HashSet::new(), pending: Vec::new(), outbox: Vec::new() }
+    }
+
+    /// Rebuild this site's document from page content already persisted on
+    /// the homeserver (i.e. written before this CRDT subsystem existed, or
+    /// by a plain `save_wiki_post` call). Treated as a fresh local
+    /// baseline rather than replayed ops, so it does not get queued for
+    /// the outbox.
+    pub(crate) fn reset(&mut self, site: u64, text: &str) {
+        *self = Doc::new(site);
+        let mut left = None;
+        for ch in text.chars() {
+            self.counter += 1;
+            let id = ElementId { site: self.site, counter: self.counter };
+            self.elements.push(Element { id, ch, tombstone: false, left });
+            self.applied.insert(id);
+            left = Some(id);
+        }
+    }
+
+    pub(crate) fn visible_text(&self) -> String {
+        self.elements.iter().filter(|e| !e.tombstone).map(|e| e.ch).collect()
+    }
+
+    /// Diff `new_text` against the document's current visible text and
+    /// apply the difference as local insert/delete ops, queuing each for
+    /// the outbox. Used once per frame against the `TextEdit` buffer, so a
+    /// single keystroke produces a single op; a paste produces one op per
+    /// inserted character.
+    pub(crate) fn sync_from_text(&mut self, new_text: &str) {
+        let old: Vec<char> = self.visible_text().chars().collect();
+        let new: Vec<char> = new_text.chars().collect();
+
+        let mut prefix = 0;
+        while prefix < old.len() && prefix < new.len() && old[prefix] == new[prefix] {
+            prefix += 1;
+        }
+        let mut suffix = 0;
+        while suffix < old.len() - prefix
+            && suffix < new.len() - prefix
+            && old[old.len() - 1 - suffix] == new[new.len() - 1 - suffix]
+        {
+            suffix += 1;
+        }
+
+        let removed = old.len() - prefix - suffix;
+        for _ in 0..removed {
+            self.local_delete(prefix);
+        }
+        for (i, ch) in new[prefix..new.len() - suffix].iter().enumerate() {
+            self.local_insert(prefix + i, *ch);
+        }
+    }
+
+    /// Drain and return ops queued by `sync_from_text` since the last call,
+    /// for the caller to append to the homeserver op log.
+    pub(crate) fn take_outbox(&mut self) -> Vec<Op> {
+        std::mem::take(&mut self.outbox)
+    }
+
+    fn local_insert(&mut self, visible_idx: usize, ch: char) {
+        let left = if visible_idx == 0 { None } else { self.visible_id_at(visible_idx - 1) };
+        self.counter += 1;
+        let id = ElementId { site: self.site, counter: self.counter };
+        let op = Op::Insert { id, ch, left };
+        self.apply(op.clone());
+        self.outbox.push(op);
+    }
+
+    fn local_delete(&mut self, visible_idx: usize) {
+        let Some(id) = self.visible_id_at(visible_idx) else { return };
+        let op = Op::Delete { id };
+        self.apply(op.clone());
+        self.outbox.push(op);
+    }
+
+    fn visible_id_at(&self, visible_idx: usize) -> Option<ElementId> {
+        self.elements.iter().filter(|e| !e.tombstone).nth(visible_idx).map(|e| e.id)
+    }
+
+    /// Apply a remote op (or a local one, via the two helpers above).
+    /// Idempotent and order-independent: see the module doc comment.
+    pub(crate) fn apply(&mut self, op: Op) {
+        self.pending.push(op);
+        self.drain_pending();
+    }
+
+    pub(crate) fn apply_all(&mut self, ops: impl IntoIterator<Item = Op>) {
+        self.pending.extend(ops);
+        self.drain_pending();
+    }
+
+    fn drain_pending(&mut self) {
+        loop {
+            let round = std::mem::take(&mut self.pending);
+            if round.is_empty() {
+                break;
+            }
+            let mut progressed = false;
+            for op in round {
+                if self.try_apply_one(&op) {
+                    progressed = true;
+                } else {
+                    self.pending.push(op);
+                }
+            }
+            if !progressed {
+                break;
+            }
+        }
+    }
+
+    /// Returns whether `op` was resolved (applied, or already applied) —
+    /// `false` means its dependency hasn't arrived yet and it stays
+    /// pending.
+    fn try_apply_one(&mut self, op: &Op) -> bool {
+        match *op {
+            Op::Insert { id, ch, left } => {
+                if self.applied.contains(&id) {
+                    return true;
+                }
+                if let Some(left_id) = left {
+                    if !self.applied.contains(&left_id) {
+                        return false;
+                    }
+                }
+                self.insert_ordered(Element { id, ch, tombstone: false, left });
+                self.applied.insert(id);
+                true
+            }
+            Op::Delete { id } => match self.elements.iter_mut().find(|e| e.id == id) {
+                Some(el) => {
+                    el.tombstone = true;
+                    true
+                }
+                None => false,
+            },
+        }
+    }
+
+    /// Insert `new` right after its `left` neighbour, then scan rightwards
+    /// comparing each following element's own `left` *position* (not just
+    /// identity) against `new`'s, per the standard RGA integrate algorithm:
+    ///
+    /// - an element whose `left` sits strictly before `new.left` has
+    ///   scanned past everything concurrently inserted at this spot — stop;
+    /// - an element sharing the same `left` (a direct sibling) sorts before
+    ///   `new` only if its id is greater — the deterministic tie-break that
+    ///   makes concurrent inserts at the same spot converge everywhere;
+    ///   otherwise stop;
+    /// - an element whose `left` sits *after* `new.left` is a descendant of
+    ///   some sibling (however deeply nested) rather than a sibling itself
+    ///   — always skip over it, since it's already ordered relative to its
+    ///   own ancestor and has no bearing on where `new` belongs.
+    ///
+    /// Comparing positions rather than assuming a sibling's subtree is a
+    /// contiguous run is what makes this correct: a concurrently-inserted,
+    /// unrelated element can land in the middle of an existing subtree
+    /// (e.g. another top-level insert arriving before a grandchild of an
+    /// earlier top-level insert), and a contiguity assumption would then
+    /// misjudge that grandchild's insertion point and diverge across
+    /// replicas depending on delivery order.
+    fn insert_ordered(&mut self, new: Element) {
+        let pos_of: HashMap<ElementId, usize> =
+            self.elements.iter().enumerate().map(|(i, e)| (e.id, i)).collect();
+        let left_index = |left: Option<ElementId>| -> isize {
+            match left {
+                None => -1,
+                Some(id) => *pos_of.get(&id).expect("insert_ordered's left must already be applied") as isize,
+            }
+        };
+
+        let new_left_index = left_index(new.left);
+        let mut pos = (new_left_index + 1) as usize;
+
+        while pos < self.elements.len() {
+            match left_index(self.elements[pos].left).cmp(&new_left_index) {
+                Ordering::Less => break,
+                Ordering::Equal => {
+                    if self.elements[pos].id > new.id {
+                        pos += 1;
+                    } else {
+                        break;
+                    }
+                }
+                Ordering::Greater => pos += 1,
+            }
+        }
+
+        self.elements.insert(pos, new);
+    }
+}
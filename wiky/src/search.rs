@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use crate::utils::extract_title;
+
+/// Small stopword set so common words don't dominate every query's score.
+const STOPWORDS: &[&str] = &[
+    "the", "a", "an", "and", "or", "of", "to", "in", "is", "it", "for", "on", "with", "as", "this",
+    "that", "be", "are", "was", "were", "at", "by", "from",
+];
+
+/// Lowercase, split on non-alphanumeric boundaries, drop stopwords and
+/// empty tokens.
+fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty() && !STOPWORDS.contains(token))
+        .map(|token| token.to_string())
+        .collect()
+}
+
+#[derive(Clone)]
+struct SearchDoc {
+    user_pk: String,
+    page_id: String,
+    title: String,
+    body: String,
+}
+
+/// A single search result: the matched page's owner/id, its summed TF
+/// score, and a short snippet around the first matching token.
+#[derive(Clone, Debug)]
+pub(crate) struct SearchHit {
+    pub user_pk: String,
+    pub page_id: String,
+    pub title: String,
+    pub score: u32,
+    pub snippet: String,
+}
+
+/// Inverted index over wiki page bodies, built from this account's own
+/// pages and (optionally) followed authors' pages, so pages can be found
+/// by content rather than just by filename/title.
+#[derive(Default, Clone)]
+pub(crate) struct SearchIndex {
+    /// term -> (doc index, term frequency in that doc)
+    postings: HashMap<String, Vec<(usize, u32)>>,
+    docs: Vec<SearchDoc>,
+}
+
+impl SearchIndex {
+    /// Build an index from `(user_pk, page_id, body)` triples.
+    pub(crate) fn build(docs: Vec<(String, String, String)>) -> Self {
+        let mut index = Self::default();
+        for (user_pk, page_id, body) in docs {
+            index.upsert(&user_pk, &page_id, &body);
+        }
+        index
+    }
+
+    /// Insert or replace one page's document and postings in place, so a
+    /// single create/edit doesn't require rebuilding the whole index. A
+    /// page already indexed under `(user_pk, page_id)` keeps its doc slot
+    /// (its old postings are dropped before re-indexing the new body); an
+    /// unseen one is appended.
+    pub(crate) fn upsert(&mut self, user_pk: &str, page_id: &str, body: &str) {
+        let title = extract_title(body).to_string();
+
+        let idx = match self.docs.iter().position(|d| d.user_pk == user_pk && d.page_id == page_id) {
+            Some(idx) => {
+                for postings in self.postings.values_mut() {
+                    postings.retain(|(doc_idx, _)| *doc_idx != idx);
+                }
+                self.docs[idx] = SearchDoc { user_pk: user_pk.to_string(), page_id: page_id.to_string(), title, body: body.to_string() };
+                idx
+            }
+            None => {
+                let idx = self.docs.len();
+                self.docs.push(SearchDoc { user_pk: user_pk.to_string(), page_id: page_id.to_string(), title, body: body.to_string() });
+                idx
+            }
+        };
+
+        let mut term_freq: HashMap<String, u32> = HashMap::new();
+        for token in tokenize(body) {
+            *term_freq.entry(token).or_insert(0) += 1;
+        }
+        for (term, freq) in term_freq {
+            self.postings.entry(term).or_default().push((idx, freq));
+        }
+    }
+
+    /// Score every candidate page by summed term frequency of matching
+    /// query tokens (a simple TF ranking), highest score first.
+    pub(crate) fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        let query_tokens = tokenize(query);
+        if query_tokens.is_empty() {
+            return Vec::new();
+        }
+
+        let mut scores: HashMap<usize, u32> = HashMap::new();
+        for token in &query_tokens {
+            if let Some(postings) = self.postings.get(token) {
+                for (idx, freq) in postings {
+                    *scores.entry(*idx).or_insert(0) += freq;
+                }
+            }
+        }
+
+        let mut hits: Vec<SearchHit> = scores
+            .into_iter()
+            .filter_map(|(idx, score)| {
+                self.docs.get(idx).map(|doc| SearchHit {
+                    user_pk: doc.user_pk.clone(),
+                    page_id: doc.page_id.clone(),
+                    title: doc.title.clone(),
+                    score,
+                    snippet: snippet_around(&doc.body, &query_tokens),
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| b.score.cmp(&a.score));
+        hits.truncate(limit);
+        hits
+    }
+}
+
+/// A window of context around the earliest occurrence of any query token,
+/// for display under a search result.
+fn snippet_around(body: &str, query_tokens: &[String]) -> String {
+    const RADIUS: usize = 40;
+
+    let lower = body.to_lowercase();
+    let match_pos = query_tokens
+        .iter()
+        .filter_map(|token| lower.find(token.as_str()))
+        .min();
+
+    match match_pos {
+        Some(pos) => {
+            let start = floor_char_boundary(body, pos.saturating_sub(RADIUS));
+            let end = ceil_char_boundary(body, (pos + RADIUS).min(body.len()));
+            format!("...{}...", body[start..end].trim())
+        }
+        None => body.chars().take(80).collect(),
+    }
+}
+
+fn floor_char_boundary(s: &str, index: usize) -> usize {
+    let mut i = index.min(s.len());
+    while i > 0 && !s.is_char_boundary(i) {
+        i -= 1;
+    }
+    i
+}
+
+fn ceil_char_boundary(s: &str, index: usize) -> usize {
+    let mut i = index.min(s.len());
+    while i < s.len() && !s.is_char_boundary(i) {
+        i += 1;
+    }
+    i
+}
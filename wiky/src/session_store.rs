@@ -0,0 +1,91 @@
+//! Persists the authenticated session across app restarts so `PubkyApp::new`
+//! can skip the QR handshake on the next launch. Modeled on pubky-cli's
+//! session ticket cache (`pubky-cli/src/util.rs`), but written under the
+//! user's config dir rather than a runtime dir — this one needs to survive
+//! a reboot, not just outlive a single shell session.
+
+use std::{path::PathBuf, str::FromStr};
+
+use anyhow::{Context, Result};
+use pubky::{Pubky, PubkySession, PublicKey};
+use serde::{Deserialize, Serialize};
+
+/// The minimal credentials needed to rehydrate a session: who it belongs to
+/// and the cookie the homeserver issued for it.
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedSession {
+    pubkey: String,
+    cookie: String,
+}
+
+fn config_dir() -> PathBuf {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .unwrap_or_else(std::env::temp_dir);
+    base.join("wiky")
+}
+
+fn session_path() -> PathBuf {
+    config_dir().join("session.json")
+}
+
+/// Writes `session`'s identity and cookie to `<config dir>/wiky/session.json`
+/// with owner-only permissions (0600), creating the directory if needed.
+pub(crate) fn save_session(session: &PubkySession) -> Result<()> {
+    let dir = config_dir();
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create config directory: {}", dir.display()))?;
+
+    let saved = SavedSession {
+        pubkey: session.info().public_key().to_string(),
+        cookie: session.cookie().to_string(),
+    };
+
+    let path = session_path();
+    let json = serde_json::to_vec(&saved).context("Failed to serialize saved session")?;
+    std::fs::write(&path, json)
+        .with_context(|| format!("Failed to write session file: {}", path.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("Failed to set permissions on session file: {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+/// Removes any saved session, e.g. on logout. A missing file is not an error.
+pub(crate) fn clear_session() -> Result<()> {
+    let path = session_path();
+    match std::fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e).with_context(|| format!("Failed to remove session file: {}", path.display())),
+    }
+}
+
+/// Tries to resume a previously saved session against `pubky`. Returns
+/// `Ok(None)` when nothing was saved; an `Err` when something was saved but
+/// is no longer usable (corrupt file, invalid key, or the homeserver no
+/// longer accepts the cookie) — either way the caller should fall back to
+/// the QR flow.
+pub(crate) async fn load_session(pubky: &Pubky) -> Result<Option<PubkySession>> {
+    let path = session_path();
+    let bytes = match std::fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read session file: {}", path.display())),
+    };
+
+    let saved: SavedSession = serde_json::from_slice(&bytes).context("Failed to parse saved session")?;
+    let pubkey = PublicKey::from_str(&saved.pubkey).context("Invalid stored public key")?;
+    let session = pubky
+        .resume_session(&pubkey, &saved.cookie)
+        .await
+        .context("Homeserver rejected saved session")?;
+
+    Ok(Some(session))
+}